@@ -0,0 +1,163 @@
+//! The scalar hex and Base64 codecs the crate started from, kept as the
+//! baseline the table and SIMD backends are measured against.
+//!
+//! They are copied from the first version of `encoding::hex` and
+//! `encoding::base64`, down to the `Vec<char>` per triplet and the linear
+//! charset search, and report invalid input with `None` instead of a
+//! `ParsingError`.
+
+use itertools::Itertools;
+use std::sync::LazyLock;
+
+/// Base64 character set: A-Z, a-z, 0-9, +, /
+static BASE64_CHARSET: LazyLock<[char; 64]> = LazyLock::new(|| {
+    ('A'..='Z')
+        .chain('a'..='z')
+        .chain('0'..='9')
+        .chain(['+', '/'])
+        .collect::<Vec<char>>()
+        .try_into()
+        .expect("total count of characters is 64")
+});
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+    let chars = bytes
+        .iter()
+        .flat_map(|&byte| {
+            // Split byte into two 4-bit nibbles
+            let high = byte >> 4;
+            let low = byte & 0b1111;
+            vec![high, low]
+        })
+        .map(|digit| char::from_digit(digit as u32, 16).expect("nibble is always < 16"));
+
+    String::from_iter(chars).to_uppercase()
+}
+
+pub fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() & 1 != 0 || !hex.chars().all(|char| char.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let bytes = hex
+        .chars()
+        .map(|char| char.to_digit(16).unwrap() as u8)
+        .batching(|it| match it.next() {
+            None => None,
+            Some(x) => match it.next() {
+                Some(y) => Some((x, y)),
+                None => Some((x, 0)),
+            },
+        })
+        .map(|(a, b)| a << 4 | b)
+        .collect_vec();
+
+    Some(bytes)
+}
+
+fn encode_sextet(value: u8) -> char {
+    BASE64_CHARSET[value as usize]
+}
+
+fn encode_triplet(byte_a: u8, byte_b: Option<u8>, byte_c: Option<u8>) -> Vec<char> {
+    let sextet_1 = byte_a >> 2;
+
+    let sextet_2 = (byte_a & 0b0000_0011) << 4 | (byte_b.unwrap_or(0) & 0b1111_0000) >> 4;
+
+    let mut result = vec![encode_sextet(sextet_1), encode_sextet(sextet_2)];
+
+    if byte_b.is_none() {
+        return result;
+    }
+
+    let sextet_3 = (byte_b.unwrap() & 0b0000_1111) << 2 | (byte_c.unwrap_or(0) & 0b1100_0000) >> 6;
+    result.push(encode_sextet(sextet_3));
+
+    if byte_c.is_none() {
+        return result;
+    }
+
+    let sextet_4 = byte_c.unwrap() & 0b0011_1111;
+    result.push(encode_sextet(sextet_4));
+
+    result
+}
+
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let complete_triplets: String = bytes
+        .chunks_exact(3)
+        .flat_map(|chunk| encode_triplet(chunk[0], Some(chunk[1]), Some(chunk[2])))
+        .collect();
+
+    let remainder = bytes.len() % 3;
+    let padding = match remainder {
+        0 => String::new(),
+        1 => {
+            let mut chars = encode_triplet(bytes[bytes.len() - 1], None, None);
+            chars.extend(['=', '=']);
+            chars.into_iter().collect()
+        }
+        2 => {
+            let mut chars =
+                encode_triplet(bytes[bytes.len() - 2], Some(bytes[bytes.len() - 1]), None);
+            chars.push('=');
+            chars.into_iter().collect()
+        }
+        _ => unreachable!(),
+    };
+
+    format!("{}{}", complete_triplets, padding)
+}
+
+fn decode_sextet(value: char) -> Option<u8> {
+    BASE64_CHARSET
+        .iter()
+        .find_position(|&ch| ch.eq(&value))
+        .map(|(idx, _)| idx as u8)
+}
+
+fn decode_quatret(encoded: &[char; 4]) -> Option<Vec<u8>> {
+    let chars = encoded
+        .iter()
+        .map(|&char| {
+            if char == '=' {
+                Some(None)
+            } else {
+                decode_sextet(char).map(Some)
+            }
+        })
+        .collect::<Option<Vec<Option<u8>>>>()?;
+
+    let (first_sextet, second_sextet) = (chars[0]?, chars[1]?);
+
+    let first_byte = first_sextet << 2 | (second_sextet & 0b00110000) >> 4;
+    if chars[2].is_none() {
+        return Some(vec![first_byte]);
+    }
+
+    let third_sextet = chars[2].unwrap();
+    let second_byte = (second_sextet & 0b00001111) << 4 | (third_sextet & 0b111100) >> 2;
+    if chars[3].is_none() {
+        return Some(vec![first_byte, second_byte]);
+    }
+
+    let fourth_sextet = chars[3].unwrap();
+    let third_byte = (third_sextet & 0b11) << 6 | fourth_sextet;
+    Some(vec![first_byte, second_byte, third_byte])
+}
+
+pub fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+
+    encoded
+        .chars()
+        .chunks(4)
+        .into_iter()
+        .try_fold(Vec::new(), |mut acc, chunk| {
+            let decoded = decode_quatret(&chunk.collect_array::<4>().unwrap())?;
+            acc.extend(decoded);
+            Some(acc)
+        })
+}
@@ -0,0 +1,129 @@
+//! Compares the allocating hex and Base64 codecs with the table and SIMD
+//! `_to_slice` backends, on 1 KiB to 100 MiB of random bytes. Each group also
+//! measures the original scalar codecs from [`baseline`], the implementation
+//! the faster backends replaced.
+//!
+//! Run with `cargo bench --bench codecs`; add `--no-default-features` to
+//! measure the tables without SIMD.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use cryptopals::crypto::chacha20::chacha20;
+use cryptopals::encoding::{base64, hex};
+use std::hint::black_box;
+
+mod baseline;
+
+const SIZES: [(usize, &str); 3] = [(1 << 10, "1KiB"), (1 << 20, "1MiB"), (100 << 20, "100MiB")];
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    chacha20(&vec![0; len], &[7; 32], &[0; 12], 0)
+}
+
+/// Runs `bench` for every size, with fewer samples for the largest inputs.
+fn for_each_size(
+    c: &mut Criterion,
+    name: &str,
+    mut bench: impl FnMut(
+        &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+        &[u8],
+        &str,
+    ),
+) {
+    let mut group = c.benchmark_group(name);
+    for (size, label) in SIZES {
+        let bytes = random_bytes(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.sample_size(if size > 1 << 20 { 10 } else { 100 });
+        bench(&mut group, &bytes, label);
+    }
+    group.finish();
+}
+
+fn hex_encode(c: &mut Criterion) {
+    for_each_size(c, "hex_encode", |group, bytes, label| {
+        group.bench_with_input(BenchmarkId::new("baseline", label), bytes, |b, bytes| {
+            b.iter(|| baseline::hex_encode(black_box(bytes)))
+        });
+        group.bench_with_input(BenchmarkId::new("encode", label), bytes, |b, bytes| {
+            b.iter(|| hex::encode(black_box(bytes)))
+        });
+        let mut out = vec![0; 2 * bytes.len()];
+        group.bench_with_input(
+            BenchmarkId::new("encode_to_slice", label),
+            bytes,
+            |b, bytes| b.iter(|| hex::encode_to_slice(black_box(bytes), &mut out)),
+        );
+    });
+}
+
+fn hex_decode(c: &mut Criterion) {
+    for_each_size(c, "hex_decode", |group, bytes, label| {
+        let encoded = hex::encode(bytes);
+        group.bench_with_input(
+            BenchmarkId::new("baseline", label),
+            &encoded,
+            |b, encoded| b.iter(|| baseline::hex_decode(black_box(encoded)).unwrap()),
+        );
+        group.bench_with_input(BenchmarkId::new("decode", label), &encoded, |b, encoded| {
+            b.iter(|| hex::decode(black_box(encoded)).unwrap())
+        });
+        let mut out = vec![0; bytes.len()];
+        group.bench_with_input(
+            BenchmarkId::new("decode_to_slice", label),
+            &encoded,
+            |b, encoded| b.iter(|| hex::decode_to_slice(black_box(encoded), &mut out).unwrap()),
+        );
+    });
+}
+
+fn base64_encode(c: &mut Criterion) {
+    for_each_size(c, "base64_encode", |group, bytes, label| {
+        group.bench_with_input(BenchmarkId::new("baseline", label), bytes, |b, bytes| {
+            b.iter(|| baseline::base64_encode(black_box(bytes)))
+        });
+        group.bench_with_input(BenchmarkId::new("encode", label), bytes, |b, bytes| {
+            b.iter(|| base64::STANDARD.encode(black_box(bytes)))
+        });
+        let mut out = vec![0; base64::STANDARD.encoded_len(bytes.len())];
+        group.bench_with_input(
+            BenchmarkId::new("encode_to_slice", label),
+            bytes,
+            |b, bytes| b.iter(|| base64::STANDARD.encode_to_slice(black_box(bytes), &mut out)),
+        );
+    });
+}
+
+fn base64_decode(c: &mut Criterion) {
+    for_each_size(c, "base64_decode", |group, bytes, label| {
+        let encoded = base64::STANDARD.encode(bytes);
+        group.bench_with_input(
+            BenchmarkId::new("baseline", label),
+            &encoded,
+            |b, encoded| b.iter(|| baseline::base64_decode(black_box(encoded)).unwrap()),
+        );
+        group.bench_with_input(BenchmarkId::new("decode", label), &encoded, |b, encoded| {
+            b.iter(|| base64::STANDARD.decode(black_box(encoded)).unwrap())
+        });
+        let mut out = vec![0; encoded.len() * 3 / 4];
+        group.bench_with_input(
+            BenchmarkId::new("decode_to_slice", label),
+            &encoded,
+            |b, encoded| {
+                b.iter(|| {
+                    base64::STANDARD
+                        .decode_to_slice(black_box(encoded), &mut out)
+                        .unwrap()
+                })
+            },
+        );
+    });
+}
+
+criterion_group!(
+    benches,
+    hex_encode,
+    hex_decode,
+    base64_encode,
+    base64_decode
+);
+criterion_main!(benches);
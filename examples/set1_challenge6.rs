@@ -29,10 +29,7 @@ fn main() {
         .read_to_string(&mut buffer)
         .expect("valid utf8 data");
 
-    // remove new line characters
-    buffer = buffer.replace("\n", "");
-
-    let bytes = base64::decode(&buffer).expect("valid base64 string");
+    let bytes = base64::decode_ignoring_whitespace(&buffer).expect("valid base64 string");
 
     let probable_key_sizes = distance::probable_key_sizes(&bytes, 3, 4, 40);
 
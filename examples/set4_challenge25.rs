@@ -0,0 +1,43 @@
+//! Cryptopals Set 4, Challenge 25: Break "random access read/write" AES CTR
+//!
+//! Challenge: https://cryptopals.com/sets/4/challenges/25
+//!
+//! The challenge's file decrypts to the same lyrics as challenge 6, so
+//! those are encrypted under a random key here.
+
+use cryptopals::attacks::ctr_edit::{EditServer, recover_plaintext};
+use cryptopals::crypto::aes::AesCtr;
+use cryptopals::crypto::chacha20::ChaCha20;
+use cryptopals::util::SplitMix64;
+
+fn main() {
+    let plaintext = std::fs::read("data/answer-1-6.txt").expect("plaintext data file exists");
+
+    let mut rng = SplitMix64::from_entropy();
+    let mut key = [0; 16];
+    rng.fill_bytes(&mut key);
+    let mut server = EditServer::new(AesCtr::new(&key, rng.next_u64()), &plaintext);
+
+    let ciphertext = server.ciphertext().to_vec();
+    let recovered = recover_plaintext(&ciphertext, |ciphertext, offset, newtext| {
+        server.edit(ciphertext, offset, newtext)
+    });
+    println!(
+        "{}",
+        String::from_utf8_lossy(&recovered[..recovered.len().min(200)])
+    );
+    assert_eq!(recovered, plaintext);
+
+    // any seekable stream cipher falls the same way
+    let mut key = [0; 32];
+    let mut nonce = [0; 12];
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+    let mut server = EditServer::new(ChaCha20::new(&key, &nonce, 1), &plaintext);
+
+    let ciphertext = server.ciphertext().to_vec();
+    let recovered = recover_plaintext(&ciphertext, |ciphertext, offset, newtext| {
+        server.edit(ciphertext, offset, newtext)
+    });
+    assert_eq!(recovered, plaintext);
+}
@@ -0,0 +1,22 @@
+//! Cryptopals Set 4, Challenge 27: Recover the key from CBC with IV=Key
+//!
+//! Challenge: https://cryptopals.com/sets/4/challenges/27
+
+use cryptopals::attacks::cbc_iv_key::{Receiver, recover_key};
+use cryptopals::encoding::hex;
+use cryptopals::util::SplitMix64;
+
+fn main() {
+    let mut key = [0; 16];
+    SplitMix64::from_entropy().fill_bytes(&mut key);
+    let receiver = Receiver::new(key);
+
+    let ciphertext = receiver.encrypt(
+        b"comment1=cooking%20MCs;userdata=hello;comment2=%20like%20a%20pound%20of%20bacon",
+    );
+    let recovered =
+        recover_key(&ciphertext, |message| receiver.receive(message)).expect("receiver echoes");
+
+    println!("{}", hex::encode(&recovered));
+    assert_eq!(recovered, key);
+}
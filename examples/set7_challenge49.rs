@@ -0,0 +1,59 @@
+//! Cryptopals Set 7, Challenge 49: CBC-MAC Message Forgery
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/49
+
+use cryptopals::attacks::cbc_mac::{FixedIvBank, IvBank, Transfer, extend_request, forge_sender};
+use cryptopals::util::SplitMix64;
+
+const VICTIM: u64 = 1;
+const ATTACKER: u64 = 3;
+
+fn main() {
+    let mut rng = SplitMix64::from_entropy();
+
+    // the IV travels with the request
+    let mut key = [0; 16];
+    let mut iv = [0; 16];
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut iv);
+    let bank = IvBank::new(key);
+
+    let request = bank.sign(ATTACKER, ATTACKER, 1_000_000, &iv);
+    let forged = forge_sender(&request, VICTIM).expect("single-digit accounts");
+    let transfer = bank.process(&forged).expect("forged request verifies");
+    println!("{:?}", transfer);
+    assert_eq!(
+        transfer,
+        Transfer {
+            from: VICTIM,
+            to: ATTACKER,
+            amount: 1_000_000
+        }
+    );
+
+    // the IV is fixed, but transaction lists can be of any length; a garbage
+    // block containing '&' or ';' would cut the list short, so wait for
+    // another capture if that happens
+    rng.fill_bytes(&mut key);
+    let bank = FixedIvBank::new(key);
+    let own = bank.sign(ATTACKER, &[(ATTACKER, 0), (ATTACKER, 1_000_000)]);
+    let target = Transfer {
+        from: VICTIM,
+        to: ATTACKER,
+        amount: 1_000_000,
+    };
+
+    for amount in 1.. {
+        let captured = bank.sign(VICTIM, &[(2, amount)]);
+        let Some(forged) = extend_request(&captured, &own) else {
+            continue;
+        };
+        let transfers = bank.process(&forged).expect("forged request verifies");
+        assert!(transfers.contains(&target));
+
+        let message = &forged[..forged.len() - 16];
+        println!("{}", String::from_utf8_lossy(message).escape_debug());
+        println!("{:?}", transfers);
+        break;
+    }
+}
@@ -0,0 +1,16 @@
+//! Cryptopals Set 7, Challenge 50: Hashing with CBC-MAC
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/50
+
+use cryptopals::attacks::cbc_mac::{forge_snippet, snippet_hash};
+use cryptopals::encoding::hex;
+
+fn main() {
+    let original = b"alert('MZA who was that?');\n";
+    let forged = forge_snippet(original, b"alert('Ayo, the Wu is back!');//")
+        .expect("a glue block without line breaks");
+
+    println!("{}", hex::encode(&snippet_hash(&forged)).to_lowercase());
+    println!("{}", String::from_utf8_lossy(&forged).escape_debug());
+    assert_eq!(snippet_hash(&forged), snippet_hash(original));
+}
@@ -0,0 +1,36 @@
+//! Cryptopals Set 7, Challenge 51: Compression Ratio Side-Channel Attacks
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/51
+//!
+//! The session id is recovered twice: through AES-CTR, where lengths are
+//! exact, and through AES-CBC, where they are rounded up to whole blocks.
+
+use cryptopals::attacks::compression_oracle::{
+    Cipher, CompressionOracle, recover_secret, recover_secret_blockwise,
+};
+use cryptopals::util::SplitMix64;
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+fn main() {
+    let session_id = b"TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+
+    let ctr = CompressionOracle::new(session_id, Cipher::Ctr, SplitMix64::from_entropy());
+    let recovered = recover_secret(&|body| ctr.length(body), b"sessionid=", BASE64_ALPHABET, 64);
+    println!(
+        "AES-CTR: {} ({} queries)",
+        String::from_utf8_lossy(&recovered),
+        ctr.queries()
+    );
+    assert_eq!(recovered, session_id);
+
+    let cbc = CompressionOracle::new(session_id, Cipher::Cbc, SplitMix64::from_entropy());
+    let recovered =
+        recover_secret_blockwise(&|body| cbc.length(body), b"sessionid=", BASE64_ALPHABET, 64);
+    println!(
+        "AES-CBC: {} ({} queries)",
+        String::from_utf8_lossy(&recovered),
+        cbc.queries()
+    );
+    assert_eq!(recovered, session_id);
+}
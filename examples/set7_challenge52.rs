@@ -0,0 +1,26 @@
+//! Cryptopals Set 7, Challenge 52: Iterated Hash Function Multicollisions
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/52
+
+use cryptopals::analysis::iterated_hash::{TruncatedHash, cascade_collision};
+
+fn main() {
+    let cheap = TruncatedHash::new(16);
+    let expensive = TruncatedHash::new(32);
+
+    let (a, b) = cascade_collision(&cheap, &expensive);
+    println!(
+        "{}-block messages collide in both hashes after {} + {} compression calls",
+        a.len() / 16,
+        cheap.calls(),
+        expensive.calls()
+    );
+    println!(
+        "a birthday attack on 48 bits would take about {}",
+        1u64 << 24
+    );
+
+    assert_ne!(a, b);
+    assert_eq!(cheap.hash(&a), cheap.hash(&b));
+    assert_eq!(expensive.hash(&a), expensive.hash(&b));
+}
@@ -0,0 +1,27 @@
+//! Cryptopals Set 7, Challenge 53: Kelsey and Schneier's Expandable Messages
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/53
+
+use cryptopals::analysis::iterated_hash::{TruncatedHash, second_preimage};
+use cryptopals::util::SplitMix64;
+
+const K: u32 = 12;
+
+fn main() {
+    let hash = TruncatedHash::new(24);
+    let mut target = vec![0; 16 << K];
+    SplitMix64::from_entropy().fill_bytes(&mut target);
+
+    let digest = hash.hash(&target);
+    hash.reset_calls();
+    let forged = second_preimage(&hash, &target, K).expect("target of 2^k blocks");
+
+    println!(
+        "second preimage of a {}-block message after {} compression calls ({} for brute force)",
+        target.len() / 16,
+        hash.calls(),
+        1u64 << hash.bits()
+    );
+    assert_ne!(forged, target);
+    assert_eq!(hash.hash(&forged), digest);
+}
@@ -0,0 +1,33 @@
+//! Cryptopals Set 7, Challenge 54: Kelsey and Kohno's Nostradamus Attack
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/54
+
+use cryptopals::analysis::iterated_hash::{DiamondStructure, TruncatedHash};
+
+const K: u32 = 8;
+
+fn main() {
+    let hash = TruncatedHash::new(24);
+    let diamond = DiamondStructure::new(&hash, K);
+    let prediction = diamond.prediction(&hash, 4);
+    println!(
+        "prediction {:06x}, committed after {} compression calls",
+        prediction,
+        hash.calls()
+    );
+
+    // the season ends; four blocks of results
+    let results = format!(
+        "{:<64}",
+        "Red Sox 4, Yankees 2. Mariners 3, Astros 1. Cubs 5, Mets 0."
+    );
+    hash.reset_calls();
+    let message = diamond
+        .herd(&hash, results.as_bytes())
+        .expect("whole blocks");
+
+    println!("herded in {} compression calls", hash.calls());
+    println!("{}", String::from_utf8_lossy(&message).escape_debug());
+    assert!(message.starts_with(results.as_bytes()));
+    assert_eq!(hash.hash(&message), prediction);
+}
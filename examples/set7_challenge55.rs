@@ -0,0 +1,22 @@
+//! Cryptopals Set 7, Challenge 55: MD4 Collisions
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/55
+//!
+//! A collision takes tens of thousands of attempts, run this with `--release`.
+
+use cryptopals::analysis::md4_collision::find_collision;
+use cryptopals::crypto::md4::md4;
+use cryptopals::encoding::hex;
+use cryptopals::util::random_seed;
+
+fn main() {
+    let seed = random_seed();
+    let (first, second) = find_collision(seed, u64::MAX).expect("the search does not give up");
+
+    println!("{}", hex::encode(&first));
+    println!("{}", hex::encode(&second));
+    println!("md4: {}", hex::encode(&md4(&first)));
+
+    assert_ne!(first, second);
+    assert_eq!(md4(&first), md4(&second));
+}
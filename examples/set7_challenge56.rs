@@ -0,0 +1,37 @@
+//! Cryptopals Set 7, Challenge 56: RC4 Single-Byte Biases
+//!
+//! Challenge: https://cryptopals.com/sets/7/challenges/56
+//!
+//! Collecting enough statistics takes a while, run this with `--release`.
+
+use cryptopals::analysis::rc4_bias::recover_cookie;
+use cryptopals::crypto::rc4::rc4;
+use cryptopals::encoding::base64;
+use cryptopals::util::{random_seed, splitmix64};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+/// Number of encryptions collected for every request padding length.
+const SAMPLES: usize = 1 << 24;
+
+fn main() {
+    let cookie = base64::decode("QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F").expect("valid base64");
+
+    let seed = random_seed();
+    let counter = AtomicU64::new(0);
+    let oracle = |request: &[u8]| {
+        let nonce = counter.fetch_add(2, Ordering::Relaxed);
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&splitmix64(seed ^ nonce).to_le_bytes());
+        key[8..].copy_from_slice(&splitmix64(seed ^ (nonce + 1)).to_le_bytes());
+
+        let plaintext = [request, &cookie].concat();
+        rc4(&plaintext, &key)
+    };
+
+    let threads = thread::available_parallelism().map_or(1, |count| count.get());
+    let recovered = recover_cookie(&oracle, cookie.len(), SAMPLES, threads);
+
+    println!("{}", String::from_utf8_lossy(&recovered));
+    assert_eq!(cookie, recovered);
+}
@@ -0,0 +1,49 @@
+//! Cryptopals Set 8, Challenge 57: Diffie-Hellman Revisited: Small Subgroup Confinement
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/57
+//!
+//! Bob works in the challenge's 512-bit group: `g` generates the subgroup
+//! of prime order `q`, and `p - 1 = j * q` where `j` has small factors
+//! whose product exceeds `q`. Run this with `--release`.
+
+use cryptopals::analysis::bignum::BigUint;
+use cryptopals::analysis::discrete_log::{BigModularGroup, CyclicGroup};
+use cryptopals::analysis::number_theory::{random_below, trial_division};
+use cryptopals::attacks::subgroup_confinement::{Bob, mac, recover_secret};
+use cryptopals::util::{SplitMix64, random_seed};
+
+const PRIME: &str = "7199773997391911030609999317773941274322764333428698921736339643928346453700085358802973900485592910475480089726140708102474957429903531369589969318716771";
+const GENERATOR: &str = "4565356397095740655436854503483826832136106141639563487732438195343690437606117828318042418238184896212352329118608100083187535033402010599512641674644143";
+const ORDER: &str = "236234353446506858198510045061214171961";
+const FACTOR_BOUND: u64 = 1 << 16;
+
+fn decimal(value: &str) -> BigUint {
+    BigUint::from_decimal(value).expect("a decimal number")
+}
+
+fn main() {
+    let prime = decimal(PRIME);
+    let (generator, order) = (decimal(GENERATOR), decimal(ORDER));
+    let group = BigModularGroup::new(prime.clone()).expect("a prime modulus");
+    assert_eq!(group.power_big(&generator, &order), BigUint::one());
+
+    let group_order = prime - BigUint::one();
+    let (factors, cofactor) = trial_division(group_order.clone(), FACTOR_BOUND);
+    println!("p - 1 = {:?} * {}", factors, cofactor);
+
+    let secret = random_below(&order, &mut SplitMix64::new(random_seed()));
+    let bob = Bob::new(group.clone(), secret.clone());
+
+    let (residue, modulus) = recover_secret(
+        &group,
+        &group_order,
+        FACTOR_BOUND,
+        |index| BigUint::from(index + 2),
+        |public| bob.respond(public),
+        mac,
+    );
+
+    println!("x = {} (mod {})", residue, modulus);
+    assert!(modulus > order);
+    assert_eq!(residue, secret);
+}
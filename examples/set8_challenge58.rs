@@ -0,0 +1,74 @@
+//! Cryptopals Set 8, Challenge 58: Pollard's Method for Catching Kangaroos
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/58
+//!
+//! Bob works in the challenge's 512-bit group, where `p - 1 = j * q` and
+//! the small factors of `j` multiply to far less than `q`. The subgroup
+//! confinement attack gives the secret modulo their product `r`, and
+//! Pollard's kangaroo finds the remaining quotient, below about `q / r` or
+//! 2^40, from Bob's public key. Run this with `--release`.
+
+use cryptopals::analysis::bignum::BigUint;
+use cryptopals::analysis::discrete_log::{
+    BigModularGroup, CyclicGroup, kangaroo_parameters, pollard_kangaroo,
+};
+use cryptopals::analysis::number_theory::{random_below, trial_division};
+use cryptopals::attacks::subgroup_confinement::{Bob, mac, recover_remaining, recover_secret};
+use cryptopals::util::{SplitMix64, random_seed};
+
+const PRIME: &str = "11470374874925275658116663507232161402086650258453896274534991676898999262641581519101074740642369848233294239851519212341844337347119899874391456329785623";
+const GENERATOR: &str = "622952335333961296978159266084741085889881358738459939978290179936063635566740258555167783009058567397963466103140082647486611657350811560630587013183357";
+const ORDER: &str = "335062023296420808191071248367701059461";
+const FACTOR_BOUND: u64 = 1 << 16;
+
+// the warm-up: y = g^x with x in [0, 2^20]
+const WARM_UP_TARGET: &str = "7760073848032689505395005705677365876654629189298052775754597607446617558600394076764814236081991643094239886772481052254010323780165093955236429914607119";
+
+fn decimal(value: &str) -> BigUint {
+    BigUint::from_decimal(value).expect("a decimal number")
+}
+
+fn main() {
+    let prime = decimal(PRIME);
+    let (generator, order) = (decimal(GENERATOR), decimal(ORDER));
+    let group = BigModularGroup::new(prime.clone()).expect("a prime modulus");
+
+    let target = decimal(WARM_UP_TARGET);
+    let (lower, upper) = (0, 1 << 20);
+    let (exponent, statistics) = pollard_kangaroo(
+        &group,
+        &generator,
+        &target,
+        lower,
+        upper,
+        &kangaroo_parameters(upper - lower),
+    );
+    println!(
+        "index in [{}, {}] = {:?}, {:?}",
+        lower, upper, exponent, statistics
+    );
+    let exponent = exponent.expect("the warm-up index");
+    assert_eq!(group.power(&generator, exponent), target);
+
+    let group_order = prime - BigUint::one();
+    let (factors, cofactor) = trial_division(group_order.clone(), FACTOR_BOUND);
+    println!("p - 1 = {:?} * {}", factors, cofactor);
+
+    let secret = random_below(&order, &mut SplitMix64::new(random_seed()));
+    let public = group.power_big(&generator, &secret);
+    let bob = Bob::new(group.clone(), secret.clone());
+
+    let partial = recover_secret(
+        &group,
+        &group_order,
+        FACTOR_BOUND,
+        |index| BigUint::from(index + 2),
+        |public| bob.respond(public),
+        mac,
+    );
+    println!("x = {} (mod {})", partial.0, partial.1);
+
+    let recovered = recover_remaining(&group, &generator, &order, &public, &partial);
+    println!("x = {:?}", recovered);
+    assert_eq!(recovered, Some(secret));
+}
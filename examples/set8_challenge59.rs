@@ -0,0 +1,37 @@
+//! Cryptopals Set 8, Challenge 59: Elliptic Curve Diffie-Hellman and Invalid-Curve Attacks
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/59
+//!
+//! Bob uses the challenge's curve over a 128-bit prime and multiplies any
+//! point he is sent by his secret, so points on curves with the same `a`
+//! but another `b` leak his secret modulo the small factors of their
+//! orders.
+
+use cryptopals::analysis::number_theory::trial_division;
+use cryptopals::attacks::invalid_curve::{
+    Bob, CHALLENGE59_WEAK_CURVES, mac, recover_secret, weak_curves,
+};
+use cryptopals::crypto::ec::Domain;
+use cryptopals::util::SplitMix64;
+
+const FACTOR_BOUND: u64 = 1 << 16;
+
+fn main() {
+    let domain = Domain::challenge59();
+
+    let weak_curves = weak_curves(&domain, &CHALLENGE59_WEAK_CURVES);
+    for (curve, order) in &weak_curves {
+        let (factors, cofactor) = trial_division(order.clone(), FACTOR_BOUND);
+        println!("b = {}: order = {:?} * {}", curve.b(), factors, cofactor);
+    }
+
+    let secret = domain.random_secret(&mut SplitMix64::from_entropy());
+    let bob = Bob::new(domain.curve.clone(), secret.clone());
+
+    let (residue, modulus) =
+        recover_secret(&weak_curves, FACTOR_BOUND, |point| bob.respond(point), mac);
+
+    println!("x = {} (mod {})", residue, modulus);
+    assert!(modulus > domain.order);
+    assert_eq!(residue, secret);
+}
@@ -0,0 +1,71 @@
+//! Cryptopals Set 8, Challenge 60: Single-Coordinate Ladders and Insecure Twists
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/60
+//!
+//! Bob's ladder on the challenge's Montgomery curve takes bare
+//! `u`-coordinates, so coordinates of twist points of small order leak his
+//! secret up to sign, points whose order is a product of two of those
+//! primes match up the signs, and a kangaroo over the last 40 bits finds
+//! the rest from his public key. X25519 itself is checked against RFC 7748.
+//!
+//! The kangaroo takes a few minutes; build with `--release`.
+
+use cryptopals::analysis::bignum::BigUint;
+use cryptopals::analysis::number_theory::trial_division;
+use cryptopals::attacks::twist_attack::{
+    Bob, CHALLENGE60_TWIST_ORDER, mac, recover_residues, recover_secret,
+};
+use cryptopals::crypto::ec::Domain;
+use cryptopals::crypto::ec::montgomery::MontgomeryCurve;
+use cryptopals::crypto::ec::x25519::{BASE_POINT, x25519};
+use cryptopals::encoding::hex;
+use cryptopals::util::SplitMix64;
+
+const FACTOR_BOUND: u64 = 1 << 22;
+
+fn main() {
+    // RFC 7748 section 6.1
+    let alice: [u8; 32] =
+        hex::decode("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")
+            .expect("valid hex")
+            .try_into()
+            .expect("32 bytes");
+    let alice_public = x25519(&alice, &BASE_POINT);
+    println!("X25519 public key = {}", hex::encode(&alice_public));
+    assert_eq!(
+        hex::encode(&alice_public),
+        "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+    );
+
+    let domain = Domain::challenge59();
+    let curve = MontgomeryCurve::challenge60();
+    assert_eq!(curve.to_weierstrass(), domain.curve);
+    let (base, _) = curve
+        .from_weierstrass_point(&domain.generator)
+        .expect("the generator is not the identity");
+
+    let twist_order = BigUint::from(CHALLENGE60_TWIST_ORDER);
+    let (factors, cofactor) = trial_division(twist_order.clone(), FACTOR_BOUND);
+    println!("twist order = {:?} * {}", factors, cofactor);
+
+    let secret = domain.random_secret(&mut SplitMix64::from_entropy());
+    let bob = Bob::new(curve.clone(), secret.clone());
+    let public = curve.ladder(&base, &secret);
+
+    let residues = recover_residues(&curve, &twist_order, FACTOR_BOUND, |u| bob.respond(u), mac);
+    for (residue, factor) in residues {
+        println!("x = ±{} (mod {})", residue, factor);
+    }
+
+    let recovered = recover_secret(
+        &domain,
+        &curve,
+        &twist_order,
+        FACTOR_BOUND,
+        &public,
+        |u| bob.respond(u),
+        mac,
+    );
+    println!("x = {:?}", recovered);
+    assert_eq!(recovered, Some(secret));
+}
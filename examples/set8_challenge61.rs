@@ -0,0 +1,46 @@
+//! Cryptopals Set 8, Challenge 61: Duplicate-Signature Key Selection in ECDSA (and RSA)
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/61
+//!
+//! Alice signs a message with ECDSA on P-256. Eve, who may pick her own
+//! domain parameters, makes a key pair on the same curve with another
+//! generator under which Alice's signature verifies too. For RSA, Eve picks primes with
+//! smooth `p - 1` and `q - 1` and solves for the public exponent that takes
+//! the signature to the padded message with Pohlig-Hellman.
+
+use cryptopals::attacks::key_selection::{select_ecdsa_key, select_rsa_key};
+use cryptopals::crypto::ec::Domain;
+use cryptopals::crypto::ec::ecdsa::{self, sign};
+use cryptopals::crypto::rsa::{self, KeyPair};
+use cryptopals::util::SplitMix64;
+
+const MESSAGE: &[u8] = b"I owe Eve one thousand dollars";
+
+fn main() {
+    let mut rng = SplitMix64::from_entropy();
+
+    let domain = Domain::p256();
+    let secret = domain.random_secret(&mut rng);
+    let public = domain.public_key(&secret);
+    let signature = sign(&domain, &secret, MESSAGE, &mut rng);
+    assert!(ecdsa::verify(&domain, &public, MESSAGE, &signature));
+    println!("ECDSA signature = {:?}", signature);
+
+    let (forged, forged_secret) = select_ecdsa_key(&domain, &public, MESSAGE, &signature, &mut rng)
+        .expect("the signature is valid");
+    let forged_public = forged.public_key(&forged_secret);
+    println!("Eve's generator = {:?}", forged.generator);
+    println!("Eve's public key = {:?}", forged_public);
+    assert!(ecdsa::verify(&forged, &forged_public, MESSAGE, &signature));
+
+    let keys = KeyPair::generate(512, &mut rng);
+    let signature = keys.sign(MESSAGE).expect("512 bits fit the encoding");
+    assert!(rsa::verify(&keys.public, MESSAGE, &signature));
+    println!("RSA signature = {:x}", signature);
+
+    let forged = select_rsa_key(&keys.public, MESSAGE, &signature, &mut rng)
+        .expect("the signature is valid");
+    println!("Eve's modulus = {:x}", forged.public.modulus);
+    println!("Eve's exponent = {:x}", forged.public.exponent);
+    assert!(rsa::verify(&forged.public, MESSAGE, &signature));
+}
@@ -0,0 +1,36 @@
+//! Cryptopals Set 8, Challenge 62: Key-Recovery Attacks on ECDSA with Biased Nonces
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/62
+//!
+//! Bob signs with P-256, and his ECDSA nonces always end in eight zero
+//! bits. Each signature then says that a known multiple of his secret, plus
+//! a known offset, is small modulo the group order, and LLL on the lattice
+//! built from forty of them finds the secret. The lattice has 42 dimensions
+//! and takes a few minutes to reduce; build with `--release`.
+
+use cryptopals::attacks::biased_nonce::{Bob, recover_secret};
+use cryptopals::crypto::ec::Domain;
+use cryptopals::util::SplitMix64;
+
+const BIAS_BITS: u32 = 8;
+const SIGNATURES: usize = 40;
+
+fn main() {
+    let domain = Domain::p256();
+    let mut rng = SplitMix64::from_entropy();
+    let secret = domain.random_secret(&mut rng);
+    let bob = Bob::new(domain.clone(), secret.clone(), BIAS_BITS);
+
+    let signatures: Vec<_> = (0..SIGNATURES)
+        .map(|i| {
+            let message = format!("message {i}").into_bytes();
+            let signature = bob.sign(&message, &mut rng);
+            println!("{:?}", signature);
+            (message, signature)
+        })
+        .collect();
+
+    let recovered = recover_secret(&domain, &bob.public_key(), &signatures, BIAS_BITS);
+    println!("d = {:?}", recovered);
+    assert_eq!(recovered, Some(secret));
+}
@@ -0,0 +1,42 @@
+//! Cryptopals Set 8, Challenge 63: Key-Recovery Attacks on GCM with Repeated Nonces
+//!
+//! Challenge: https://cryptopals.com/sets/8/challenges/63
+
+use cryptopals::analysis::gcm_forbidden::{candidate_keys, forge};
+use cryptopals::crypto::aes::Aes128;
+use cryptopals::crypto::gcm::{open, seal};
+use cryptopals::util::SplitMix64;
+
+fn main() {
+    let mut rng = SplitMix64::from_entropy();
+    let mut key = [0; 16];
+    let mut nonce = [0; 12];
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+
+    // a careless server seals every message under the same nonce
+    let messages = [
+        (&b"from=alice"[..], b"pay bob 100 dollars".to_vec()),
+        (&b"from=alice"[..], b"pay carol 250 dollars".to_vec()),
+        (&b"from=dave"[..], b"pay erin 5 dollars".to_vec()),
+    ]
+    .map(|(aad, plaintext)| (aad, seal(&key, &nonce, aad, &plaintext)));
+    let messages: Vec<(&[u8], &[u8])> = messages
+        .iter()
+        .map(|(aad, sealed)| (*aad, sealed.as_slice()))
+        .collect();
+
+    let candidates = candidate_keys(&messages, &mut rng).expect("distinct messages");
+    println!("{} candidate(s) for H", candidates.len());
+    let h = candidates[0];
+    assert_eq!(h.to_be_bytes(), Aes128::new(&key).encrypt_block(&[0; 16]));
+
+    // turn "100" into "900" and claim the message came from dave
+    let mut ciphertext = messages[0].1[..19].to_vec();
+    ciphertext[8] ^= b'1' ^ b'9';
+    let forged = forge(h, messages[0], b"from=dave", &ciphertext).expect("whole message");
+
+    let opened = open(&key, &nonce, b"from=dave", &forged).expect("forged tag verifies");
+    println!("{}", String::from_utf8_lossy(&opened));
+    assert_eq!(opened, b"pay bob 900 dollars");
+}
@@ -0,0 +1,725 @@
+//! Arbitrary-precision unsigned integers.
+//!
+//! [`BigUint`] carries the real parameters of the number-theoretic
+//! challenges, such as the 512-bit Diffie-Hellman prime of challenge 57,
+//! where the machine integers of the toy versions run out. Multiplication is
+//! schoolbook and division is Knuth's Algorithm D (The Art of Computer
+//! Programming, vol. 2, section 4.3.1): quadratic, but plenty for the few
+//! thousand operations an attack makes. Apart from
+//! [`BigUint::conditional_swap`], nothing here runs in constant time.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+
+/// An unsigned integer of any size.
+///
+/// Arithmetic is available through the operators, on values and on
+/// references. Subtraction panics if the result would be negative, and
+/// division panics on a zero divisor, as for the primitive types.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+///
+/// let a = BigUint::from(u128::MAX);
+/// let b = &a * &a;
+/// assert_eq!(b.to_string(), "115792089237316195423570985008687907852589419931798687112530834793049593217025");
+/// assert_eq!(&b / &a, a);
+///
+/// // Fermat's little theorem
+/// let p = BigUint::from(1_000_000_007u64);
+/// assert_eq!(BigUint::from(2u64).pow_mod(&(&p - BigUint::one()), &p), BigUint::one());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    /// Little-endian 64-bit limbs, without trailing zero limbs.
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint::from(1u64)
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of bits needed to write the value; 0 for zero.
+    pub fn bits(&self) -> u64 {
+        self.limbs.last().map_or(0, |&top| {
+            64 * self.limbs.len() as u64 - u64::from(top.leading_zeros())
+        })
+    }
+
+    /// Whether the bit of weight `2^index` is set.
+    pub fn bit(&self, index: u64) -> bool {
+        self.limbs
+            .get((index / 64) as usize)
+            .is_some_and(|&limb| limb >> (index % 64) & 1 == 1)
+    }
+
+    /// The value, if it fits a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low),
+            _ => None,
+        }
+    }
+
+    /// The value, if it fits a `u128`.
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u128),
+            [low, high] => Some((high as u128) << 64 | low as u128),
+            _ => None,
+        }
+    }
+
+    /// Reads a big-endian byte string.
+    pub fn from_be_bytes(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| {
+                let mut limb = [0u8; 8];
+                limb[8 - chunk.len()..].copy_from_slice(chunk);
+                u64::from_be_bytes(limb)
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    /// The big-endian bytes of the value, without leading zeros. Zero has
+    /// no bytes.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let bytes = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect::<Vec<_>>();
+        let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+        bytes[leading_zeros..].to_vec()
+    }
+
+    /// Parses a decimal number, or returns `None` if `decimal` is empty or
+    /// holds anything but digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::analysis::bignum::BigUint;
+    ///
+    /// let n = BigUint::from_decimal("340282366920938463463374607431768211456").unwrap();
+    /// assert_eq!(n, BigUint::from(u128::MAX) + BigUint::one());
+    /// assert_eq!(BigUint::from_decimal("12a"), None);
+    /// ```
+    pub fn from_decimal(decimal: &str) -> Option<Self> {
+        BigUint::from_radix(decimal, 10)
+    }
+
+    /// Parses a hexadecimal number of either case, or returns `None` if
+    /// `hex` is empty or holds anything but hex digits.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        BigUint::from_radix(hex, 16)
+    }
+
+    fn from_radix(digits: &str, radix: u32) -> Option<Self> {
+        if digits.is_empty() {
+            return None;
+        }
+        digits.chars().try_fold(BigUint::zero(), |value, char| {
+            let digit = char.to_digit(radix)?;
+            Some(value.mul_add_u64(u64::from(radix), u64::from(digit)))
+        })
+    }
+
+    /// `self * factor + addend`.
+    fn mul_add_u64(&self, factor: u64, addend: u64) -> Self {
+        let mut carry = addend as u128;
+        let mut limbs = self
+            .limbs
+            .iter()
+            .map(|&limb| {
+                let product = limb as u128 * factor as u128 + carry;
+                carry = product >> 64;
+                product as u64
+            })
+            .collect::<Vec<_>>();
+        limbs.push(carry as u64);
+        BigUint::from_limbs(limbs)
+    }
+
+    /// Divides by a single limb, returning the quotient and the remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+        assert!(divisor != 0, "attempt to divide by zero");
+        let mut remainder = 0u128;
+        let mut quotient = vec![0; self.limbs.len()];
+        for (index, &limb) in self.limbs.iter().enumerate().rev() {
+            let dividend = remainder << 64 | limb as u128;
+            quotient[index] = (dividend / divisor as u128) as u64;
+            remainder = dividend % divisor as u128;
+        }
+        (BigUint::from_limbs(quotient), remainder as u64)
+    }
+
+    /// Divides by `divisor`, returning the quotient and the remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if let [divisor] = divisor.limbs[..] {
+            let (quotient, remainder) = self.div_rem_u64(divisor);
+            return (quotient, BigUint::from(remainder));
+        }
+
+        // normalize so that the divisor's top limb has its high bit set,
+        // which keeps every quotient estimate at most two too large
+        let shift = divisor
+            .limbs
+            .last()
+            .expect("divisor is not zero")
+            .leading_zeros() as usize;
+        let v = (divisor << shift).limbs;
+        let mut u = (self << shift).limbs;
+        u.resize(self.limbs.len() + 1, 0);
+        let n = v.len();
+        let m = u.len() - n - 1;
+
+        let mut quotient = vec![0; m + 1];
+        for j in (0..=m).rev() {
+            let numerator = (u[j + n] as u128) << 64 | u[j + n - 1] as u128;
+            let mut q_hat = numerator / v[n - 1] as u128;
+            let mut r_hat = numerator % v[n - 1] as u128;
+            while q_hat >> 64 != 0
+                || q_hat * v[n - 2] as u128 > (r_hat << 64 | u[j + n - 2] as u128)
+            {
+                q_hat -= 1;
+                r_hat += v[n - 1] as u128;
+                if r_hat >> 64 != 0 {
+                    break;
+                }
+            }
+
+            // u[j..=j + n] -= q_hat * v
+            let (mut borrow, mut carry) = (0i128, 0u128);
+            for i in 0..n {
+                let product = q_hat * v[i] as u128 + carry;
+                carry = product >> 64;
+                let difference = u[i + j] as i128 - borrow - (product as u64) as i128;
+                u[i + j] = difference as u64;
+                borrow = i128::from(difference < 0);
+            }
+            let difference = u[j + n] as i128 - borrow - carry as i128;
+            u[j + n] = difference as u64;
+
+            // the estimate was one too large: add the divisor back
+            if difference < 0 {
+                q_hat -= 1;
+                let mut carry = 0u128;
+                for i in 0..n {
+                    let sum = u[i + j] as u128 + v[i] as u128 + carry;
+                    u[i + j] = sum as u64;
+                    carry = sum >> 64;
+                }
+                u[j + n] = u[j + n].wrapping_add(carry as u64);
+            }
+            quotient[j] = q_hat as u64;
+        }
+
+        u.truncate(n);
+        (
+            BigUint::from_limbs(quotient),
+            BigUint::from_limbs(u) >> shift,
+        )
+    }
+
+    /// `self - other`, or `None` if `other` is larger.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if self < other {
+            return None;
+        }
+        let mut borrow = false;
+        let limbs = self
+            .limbs
+            .iter()
+            .enumerate()
+            .map(|(index, &limb)| {
+                let (difference, under) = limb.overflowing_sub(other.limb(index));
+                let (difference, under_borrow) = difference.overflowing_sub(u64::from(borrow));
+                borrow = under || under_borrow;
+                difference
+            })
+            .collect();
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    /// Swaps the values if `swap` is set, by masking every limb instead of
+    /// branching on `swap`. Both are padded to the same number of limbs
+    /// first, so only their lengths show.
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, swap: bool) {
+        let mask = u64::from(swap).wrapping_neg();
+        let len = a.limbs.len().max(b.limbs.len());
+        a.limbs.resize(len, 0);
+        b.limbs.resize(len, 0);
+        for (a, b) in a.limbs.iter_mut().zip(b.limbs.iter_mut()) {
+            let difference = (*a ^ *b) & mask;
+            *a ^= difference;
+            *b ^= difference;
+        }
+        *a = BigUint::from_limbs(std::mem::take(&mut a.limbs));
+        *b = BigUint::from_limbs(std::mem::take(&mut b.limbs));
+    }
+
+    fn limb(&self, index: usize) -> u64 {
+        self.limbs.get(index).copied().unwrap_or(0)
+    }
+
+    /// `self^exponent mod modulus`, by left-to-right square and multiply.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn pow_mod(&self, exponent: &Self, modulus: &Self) -> Self {
+        let base = self % modulus;
+        let mut result = BigUint::one() % modulus;
+        for index in (0..exponent.bits()).rev() {
+            result = &result * &result % modulus;
+            if exponent.bit(index) {
+                result = &result * &base % modulus;
+            }
+        }
+        result
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let remainder = &a % &b;
+            a = std::mem::replace(&mut b, remainder);
+        }
+        a
+    }
+
+    /// The inverse of `self` modulo `modulus`, or `None` if they share a
+    /// factor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn mod_inverse(&self, modulus: &Self) -> Option<Self> {
+        if let (Some(value), Some(modulus)) = (self.to_u128(), modulus.to_u128()) {
+            return mod_inverse_u128(value, modulus).map(BigUint::from);
+        }
+
+        // the Bezout coefficients of `self` alternate in sign and never
+        // exceed `modulus`, so only their magnitudes are kept
+        let (mut old_r, mut r) = (self % modulus, modulus.clone());
+        let (mut old_s, mut s) = (BigUint::one(), BigUint::zero());
+        let mut negative = false;
+        while !r.is_zero() {
+            let (quotient, remainder) = old_r.div_rem(&r);
+            old_r = std::mem::replace(&mut r, remainder);
+            let next = &old_s + &quotient * &s;
+            old_s = std::mem::replace(&mut s, next);
+            negative = !negative;
+        }
+        if old_r != BigUint::one() {
+            return None;
+        }
+        let old_s = old_s % modulus;
+        Some(if negative && !old_s.is_zero() {
+            modulus - &old_s
+        } else {
+            old_s
+        })
+    }
+}
+
+/// [`BigUint::mod_inverse`] for operands that fit machine words, with the
+/// same alternating-sign bookkeeping.
+fn mod_inverse_u128(value: u128, modulus: u128) -> Option<u128> {
+    let (mut old_r, mut r) = (value % modulus, modulus);
+    let (mut old_s, mut s) = (1u128, 0u128);
+    let mut negative = false;
+    while r != 0 {
+        // most quotients are below four, which comparisons find faster
+        // than a 128-bit division
+        let quotient = if r >> 125 == 0 && old_r < r << 2 {
+            (0..4)
+                .rev()
+                .find(|&quotient| quotient * r <= old_r)
+                .expect("zero always fits")
+        } else {
+            old_r / r
+        };
+        (old_r, r) = (r, old_r - quotient * r);
+        // below the modulus, by the bound on Bezout coefficients
+        (old_s, s) = (s, old_s + quotient * s);
+        negative = !negative;
+    }
+    if old_r != 1 {
+        return None;
+    }
+    let old_s = old_s % modulus;
+    Some(if negative && old_s != 0 {
+        modulus - old_s
+    } else {
+        old_s
+    })
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from_limbs(vec![value])
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        BigUint::from_limbs(vec![value as u64, (value >> 64) as u64])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut carry = false;
+        let limbs = (0..self.limbs.len().max(other.limbs.len()) + 1)
+            .map(|index| {
+                let (sum, over) = self.limb(index).overflowing_add(other.limb(index));
+                let (sum, over_carry) = sum.overflowing_add(u64::from(carry));
+                carry = over || over_carry;
+                sum
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            limbs[i + other.limbs.len()] = carry as u64;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+/// Forwards the operators on values to the ones on references.
+macro_rules! forward_binary_ops {
+    ($($trait:ident $method:ident),*) => {$(
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $trait<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &BigUint) -> BigUint {
+                (&self).$method(other)
+            }
+        }
+
+        impl $trait<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+    )*};
+}
+
+forward_binary_ops!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+        let mut limbs = vec![0; limb_shift];
+        let mut carry = 0;
+        for &limb in &self.limbs {
+            limbs.push(limb << bit_shift | carry);
+            carry = if bit_shift == 0 {
+                0
+            } else {
+                limb >> (64 - bit_shift)
+            };
+        }
+        limbs.push(carry);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+        let limbs = (limb_shift..self.limbs.len())
+            .map(|index| {
+                let high = match (bit_shift, self.limbs.get(index + 1)) {
+                    (0, _) | (_, None) => 0,
+                    (_, Some(&next)) => next << (64 - bit_shift),
+                };
+                self.limbs[index] >> bit_shift | high
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+impl fmt::Display for BigUint {
+    /// Writes the value in decimal.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // peel off 19 decimal digits at a time, the most that fit a limb
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_u64(CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        let mut digits = match chunks.pop() {
+            None => "0".to_string(),
+            Some(top) => top.to_string(),
+        };
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{chunk:019}"));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = match self.limbs.last() {
+            None => "0".to_string(),
+            Some(top) => format!("{top:x}"),
+        };
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{limb:016x}"));
+        }
+        f.pad_integral(true, "0x", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn big(value: u128) -> BigUint {
+        BigUint::from(value)
+    }
+
+    /// A value with up to `limbs` random limbs, biased towards the edge
+    /// cases of long division: all-ones and zero limbs.
+    fn arbitrary_big(limbs: usize) -> impl Strategy<Value = BigUint> {
+        let limb = prop_oneof![any::<u64>(), Just(u64::MAX), Just(0), Just(1 << 63)];
+        proptest::collection::vec(limb, 0..=limbs).prop_map(BigUint::from_limbs)
+    }
+
+    proptest! {
+        #[test]
+        fn matches_u128(a: u64, b: u64, c in 1u128..) {
+            let (a, b) = (a as u128, b as u128);
+            assert_eq!(big(a) + big(b), big(a + b));
+            assert_eq!(big(a) * big(b), big(a * b));
+            assert_eq!(big(a * b) / big(c), big(a * b / c));
+            assert_eq!(big(a * b) % big(c), big(a * b % c));
+            assert_eq!(big(a.max(b)) - big(a.min(b)), big(a.max(b) - a.min(b)));
+            assert_eq!(big(a).cmp(&big(b)), a.cmp(&b));
+        }
+
+        #[test]
+        fn division_inverts_multiplication(a in arbitrary_big(6), b in arbitrary_big(4), r in arbitrary_big(4)) {
+            prop_assume!(!b.is_zero());
+            let r = &r % &b;
+            let (quotient, remainder) = (&a * &b + &r).div_rem(&b);
+            assert_eq!(quotient, a);
+            assert_eq!(remainder, r);
+        }
+
+        #[test]
+        fn shifts_roundtrip(a in arbitrary_big(4), shift in 0usize..200) {
+            let shifted = &a << shift;
+            assert_eq!(shifted.bits(), if a.is_zero() { 0 } else { a.bits() + shift as u64 });
+            assert_eq!(&shifted >> shift, a);
+        }
+
+        #[test]
+        fn formats_and_parses(a in arbitrary_big(4)) {
+            assert_eq!(BigUint::from_decimal(&a.to_string()), Some(a.clone()));
+            assert_eq!(BigUint::from_hex(&format!("{a:x}")), Some(a.clone()));
+            assert_eq!(BigUint::from_be_bytes(&a.to_be_bytes()), a);
+        }
+
+        #[test]
+        fn pow_mod_matches_u128(base: u64, exponent: u32, modulus in 1u64..) {
+            let expected = (0..exponent % 64).fold(1u128 % modulus as u128, |acc, _| acc * base as u128 % modulus as u128);
+            assert_eq!(
+                big(base as u128).pow_mod(&big((exponent % 64) as u128), &big(modulus as u128)),
+                big(expected)
+            );
+        }
+
+        #[test]
+        fn mod_inverse_inverts(a in arbitrary_big(3), m in arbitrary_big(3)) {
+            prop_assume!(m > BigUint::one());
+            match a.mod_inverse(&m) {
+                Some(inverse) => assert_eq!(&a * &inverse % &m, BigUint::one()),
+                None => assert_ne!(a.gcd(&m), BigUint::one()),
+            }
+        }
+
+        #[test]
+        fn conditional_swap_swaps(a in arbitrary_big(4), b in arbitrary_big(2), swap: bool) {
+            let (mut x, mut y) = (a.clone(), b.clone());
+            BigUint::conditional_swap(&mut x, &mut y, swap);
+            if swap {
+                assert_eq!((x, y), (b, a));
+            } else {
+                assert_eq!((x, y), (a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(format!("{:x}", BigUint::zero()), "0");
+        assert_eq!(format!("{:#x}", big(255)), "0xff");
+        assert!(BigUint::zero().to_be_bytes().is_empty());
+        assert_eq!(BigUint::from_be_bytes(&[0, 0, 1, 0]), big(256));
+        assert_eq!(big(1 << 64).to_u64(), None);
+        assert_eq!(big(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(BigUint::from_decimal(""), None);
+        assert_eq!(BigUint::from_hex("DEADbeef"), Some(big(0xDEAD_BEEF)));
+        assert_eq!(big(3).checked_sub(&big(4)), None);
+        assert!(big(5).pow_mod(&BigUint::zero(), &BigUint::one()).is_zero());
+        assert_eq!(big(2).mod_inverse(&big(u128::MAX)), Some(big(1 << 127)));
+        assert_eq!(big(3).mod_inverse(&big(u128::MAX)), None);
+    }
+
+    #[test]
+    fn test_challenge_57_prime() {
+        let p = BigUint::from_decimal("7199773997391911030609999317773941274322764333428698921736339643928346453700085358802973900485592910475480089726140708102474957429903531369589969318716771").unwrap();
+        let g = BigUint::from_decimal("4565356397095740655436854503483826832136106141639563487732438195343690437606117828318042418238184896212352329118608100083187535033402010599512641674644143").unwrap();
+        let q = BigUint::from_decimal("236234353446506858198510045061214171961").unwrap();
+
+        assert_eq!(p.bits(), 512);
+        assert_eq!(g.pow_mod(&q, &p), BigUint::one());
+        assert_eq!((&p - BigUint::one()) % &q, BigUint::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_division_by_zero() {
+        let _ = big(1) / BigUint::zero();
+    }
+}
@@ -0,0 +1,1106 @@
+//! Generic discrete-logarithm solvers.
+//!
+//! The solvers work over any [`CyclicGroup`], so the same code breaks
+//! multiplicative groups modulo a prime and elliptic-curve groups alike:
+//!
+//! - [`baby_step_giant_step`] - deterministic, memory bounded by a table size
+//! - [`pollard_rho`] - memory bounded by the partition count, Floyd or Brent
+//!   cycle detection
+//! - [`pollard_kangaroo`] - constant memory, exponent known to lie in an interval
+//! - [`pohlig_hellman`] - any group order, as long as its prime factors are
+//!   small
+//!
+//! Exponents are `u64`, except for Pohlig-Hellman, whose smooth orders can
+//! be as large as the group, and every solver reports [`Statistics`] so the
+//! time/memory trade-offs of the parameters can be measured.
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::number_theory::{crt, is_prime, is_probable_prime};
+use crate::crypto::ec::{Curve, Point};
+use crate::util::splitmix64;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A finite cyclic group, written multiplicatively.
+pub trait CyclicGroup {
+    type Element: Clone + Eq + Hash;
+
+    /// The neutral element of the group.
+    fn identity(&self) -> Self::Element;
+
+    /// The group operation.
+    fn operate(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    /// The inverse of an element.
+    fn inverse(&self, element: &Self::Element) -> Self::Element;
+
+    /// A stable hash of an element, used to drive pseudo-random walks.
+    ///
+    /// Equal elements must have equal fingerprints.
+    fn fingerprint(&self, element: &Self::Element) -> u64;
+
+    /// Raises an element to a power by square-and-multiply.
+    fn power(&self, base: &Self::Element, exponent: u64) -> Self::Element {
+        let mut result = self.identity();
+        let mut square = base.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.operate(&result, &square);
+            }
+            square = self.operate(&square, &square);
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Raises an element to a power that may not fit a `u64`.
+    fn power_big(&self, base: &Self::Element, exponent: &BigUint) -> Self::Element {
+        let mut result = self.identity();
+        for index in (0..exponent.bits()).rev() {
+            result = self.operate(&result, &result);
+            if exponent.bit(index) {
+                result = self.operate(&result, base);
+            }
+        }
+        result
+    }
+}
+
+/// The multiplicative group of integers modulo a prime below 2^63.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::discrete_log::{CyclicGroup, ModularGroup};
+///
+/// let group = ModularGroup::new(2027).unwrap();
+/// assert_eq!(group.operate(&4, &group.inverse(&4)), 1);
+/// assert!(ModularGroup::new(2025).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModularGroup {
+    modulus: u64,
+}
+
+impl ModularGroup {
+    /// Returns `None` unless `modulus` is an odd prime below 2^63.
+    pub fn new(modulus: u64) -> Option<Self> {
+        (modulus > 2 && modulus < 1 << 63 && is_prime(modulus)).then_some(ModularGroup { modulus })
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+}
+
+impl CyclicGroup for ModularGroup {
+    type Element = u64;
+
+    fn identity(&self) -> u64 {
+        1
+    }
+
+    fn operate(&self, a: &u64, b: &u64) -> u64 {
+        mul_mod(*a, *b, self.modulus)
+    }
+
+    fn inverse(&self, element: &u64) -> u64 {
+        // Fermat's little theorem: a^(p - 2) = a^-1 (mod p)
+        self.power(element, self.modulus - 2)
+    }
+
+    fn fingerprint(&self, element: &u64) -> u64 {
+        *element
+    }
+}
+
+/// The multiplicative group of integers modulo a prime of any size.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::discrete_log::{BigModularGroup, CyclicGroup};
+///
+/// let group = BigModularGroup::new(BigUint::from((1u128 << 89) - 1)).unwrap();
+/// let element = BigUint::from(3u64);
+/// assert_eq!(group.operate(&element, &group.inverse(&element)), BigUint::one());
+/// assert!(BigModularGroup::new(BigUint::from(1u128 << 89)).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigModularGroup {
+    modulus: BigUint,
+}
+
+impl BigModularGroup {
+    /// Returns `None` unless `modulus` is an odd prime, as far as
+    /// [`is_probable_prime`] can tell.
+    pub fn new(modulus: BigUint) -> Option<Self> {
+        (modulus > BigUint::from(2u64) && is_probable_prime(&modulus))
+            .then_some(BigModularGroup { modulus })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+}
+
+impl CyclicGroup for BigModularGroup {
+    type Element = BigUint;
+
+    fn identity(&self) -> BigUint {
+        BigUint::one()
+    }
+
+    fn operate(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % &self.modulus
+    }
+
+    fn inverse(&self, element: &BigUint) -> BigUint {
+        // Fermat's little theorem: a^(p - 2) = a^-1 (mod p)
+        element.pow_mod(&(&self.modulus - BigUint::from(2u64)), &self.modulus)
+    }
+
+    fn fingerprint(&self, element: &BigUint) -> u64 {
+        splitmix64(low_word(element))
+    }
+
+    fn power(&self, base: &BigUint, exponent: u64) -> BigUint {
+        base.pow_mod(&BigUint::from(exponent), &self.modulus)
+    }
+
+    fn power_big(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        base.pow_mod(exponent, &self.modulus)
+    }
+}
+
+/// The points of an elliptic curve. The group is usually written
+/// additively: `operate` adds points and `power` multiplies a point by a
+/// scalar, with the curve's ladder.
+impl CyclicGroup for Curve {
+    type Element = Point;
+
+    fn identity(&self) -> Point {
+        Point::Infinity
+    }
+
+    fn operate(&self, a: &Point, b: &Point) -> Point {
+        self.add(a, b)
+    }
+
+    fn inverse(&self, element: &Point) -> Point {
+        self.negate(element)
+    }
+
+    fn fingerprint(&self, element: &Point) -> u64 {
+        match element {
+            Point::Infinity => 0,
+            Point::Affine { x, y } => splitmix64(low_word(x) ^ low_word(y).rotate_left(32)),
+        }
+    }
+
+    fn power(&self, base: &Point, exponent: u64) -> Point {
+        self.multiply(base, &BigUint::from(exponent))
+    }
+
+    fn power_big(&self, base: &Point, exponent: &BigUint) -> Point {
+        self.multiply(base, exponent)
+    }
+}
+
+/// The low 64 bits of a number, enough to fingerprint it.
+fn low_word(value: &BigUint) -> u64 {
+    let bytes = value.to_be_bytes();
+    bytes[bytes.len().saturating_sub(8)..]
+        .iter()
+        .fold(0, |low, &byte| low << 8 | byte as u64)
+}
+
+/// Work done by a solver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// Group multiplications and inversions, including precomputation.
+    pub group_operations: u64,
+    /// Steps of the main loop (giant steps, walk steps or jumps).
+    pub iterations: u64,
+    /// Times a randomized walk had to start over.
+    pub restarts: u64,
+    /// Elements held in memory at the peak.
+    pub stored_elements: u64,
+}
+
+/// Wraps a group and counts every group operation done through it.
+struct Counting<'a, G> {
+    group: &'a G,
+    operations: Cell<u64>,
+}
+
+impl<'a, G: CyclicGroup> Counting<'a, G> {
+    fn new(group: &'a G) -> Self {
+        Counting {
+            group,
+            operations: Cell::new(0),
+        }
+    }
+
+    /// Stores the operation count in the solver's statistics.
+    fn record(&self, statistics: Statistics) -> Statistics {
+        Statistics {
+            group_operations: self.operations.get(),
+            ..statistics
+        }
+    }
+}
+
+impl<G: CyclicGroup> CyclicGroup for Counting<'_, G> {
+    type Element = G::Element;
+
+    fn identity(&self) -> Self::Element {
+        self.group.identity()
+    }
+
+    fn operate(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
+        self.operations.set(self.operations.get() + 1);
+        self.group.operate(a, b)
+    }
+
+    fn inverse(&self, element: &Self::Element) -> Self::Element {
+        self.operations.set(self.operations.get() + 1);
+        self.group.inverse(element)
+    }
+
+    fn fingerprint(&self, element: &Self::Element) -> u64 {
+        self.group.fingerprint(element)
+    }
+}
+
+/// Cycle-finding strategy for [`pollard_rho`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleDetection {
+    /// Tortoise and hare: three group operations per step.
+    Floyd,
+    /// Power-of-two checkpoints: one group operation per step.
+    Brent,
+}
+
+/// Tuning knobs of [`pollard_rho`]; [`rho_parameters`] picks defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RhoParameters {
+    pub cycle_detection: CycleDetection,
+    /// How many ways the walk splits on an element's fingerprint, at least
+    /// two. One partition squares and each other one multiplies by a stored
+    /// element, so more partitions make the walk more random for more memory.
+    pub partitions: usize,
+    /// Steps a walk may take before it is restarted.
+    pub max_iterations: u64,
+    /// Walks started after the first one before giving up.
+    pub max_restarts: u64,
+    /// Seeds the starting points and multipliers, so runs are reproducible.
+    pub seed: u64,
+}
+
+/// Tuning knobs of [`pollard_kangaroo`]; [`kangaroo_parameters`] picks
+/// defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KangarooParameters {
+    /// Jumps are the powers of two below `2^jump_bits`, between 1 and 63.
+    pub jump_bits: u32,
+    /// Jumps the tame kangaroo makes before it sets its trap. More jumps
+    /// raise the success rate at the cost of time.
+    pub tame_jumps: u64,
+}
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+fn add_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + b as u128) % modulus as u128) as u64
+}
+
+fn sub_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    add_mod(a, modulus - b % modulus, modulus)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Inverse of `a` modulo `modulus`, if they are coprime.
+fn inverse_mod(a: u64, modulus: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (a as i128, modulus as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    (old_r == 1).then(|| old_s.rem_euclid(modulus as i128) as u64)
+}
+
+/// Solves `generator^x = target` with baby-step giant-step.
+///
+/// `order` is the order of `generator` (or any multiple of it). The baby-step
+/// table holds at most `table_size` elements; with a table of `m` elements
+/// the search takes about `order / m` giant steps, so `sqrt(order)` balances
+/// time and memory.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::discrete_log::{baby_step_giant_step, CyclicGroup, ModularGroup};
+///
+/// let group = ModularGroup::new(2027).unwrap();
+/// let target = group.power(&4, 777);
+/// let (exponent, _) = baby_step_giant_step(&group, &4, &target, 1013, 32);
+/// assert_eq!(exponent, Some(777));
+/// ```
+pub fn baby_step_giant_step<G: CyclicGroup>(
+    group: &G,
+    generator: &G::Element,
+    target: &G::Element,
+    order: u64,
+    table_size: usize,
+) -> (Option<u64>, Statistics) {
+    let counting = Counting::new(group);
+    let group = &counting;
+    let mut statistics = Statistics::default();
+    let table_size = (table_size as u64).clamp(1, order.max(1));
+
+    // baby steps: generator^j for 0 <= j < m
+    let mut baby_steps = HashMap::with_capacity(table_size as usize);
+    let mut current = group.identity();
+    for j in 0..table_size {
+        baby_steps.entry(current.clone()).or_insert(j);
+        current = group.operate(&current, generator);
+    }
+    statistics.stored_elements = baby_steps.len() as u64;
+
+    // giant steps: target * generator^(-m i)
+    let giant_stride = group.inverse(&current);
+    let mut giant = target.clone();
+    for i in 0..order.div_ceil(table_size) {
+        statistics.iterations += 1;
+        if let Some(&j) = baby_steps.get(&giant) {
+            return (Some((i * table_size + j) % order), group.record(statistics));
+        }
+        giant = group.operate(&giant, &giant_stride);
+    }
+
+    (None, group.record(statistics))
+}
+
+/// A point of the rho walk, `element = generator^a * target^b`.
+#[derive(Clone)]
+struct WalkPoint<E> {
+    element: E,
+    a: u64,
+    b: u64,
+}
+
+struct RhoWalk<'a, G: CyclicGroup> {
+    group: &'a G,
+    generator: &'a G::Element,
+    target: &'a G::Element,
+    order: u64,
+    /// The multipliers of all partitions but the squaring one.
+    multipliers: Vec<WalkPoint<G::Element>>,
+}
+
+impl<G: CyclicGroup> RhoWalk<'_, G> {
+    /// A random point of the walk, drawn from `state`.
+    fn point(&self, state: u64) -> WalkPoint<G::Element> {
+        let a = splitmix64(state) % self.order;
+        let b = splitmix64(state ^ (1 << 63)) % self.order;
+        WalkPoint {
+            element: self.group.operate(
+                &self.group.power(self.generator, a),
+                &self.group.power(self.target, b),
+            ),
+            a,
+            b,
+        }
+    }
+
+    /// Teske's mixed walk: square the element, or multiply it by one of the
+    /// multipliers, depending on the element's fingerprint.
+    fn step(&self, point: &WalkPoint<G::Element>) -> WalkPoint<G::Element> {
+        let WalkPoint { element, a, b } = point;
+        let partition = self.group.fingerprint(element) % (self.multipliers.len() as u64 + 1);
+        match partition.checked_sub(1) {
+            None => WalkPoint {
+                element: self.group.operate(element, element),
+                a: add_mod(*a, *a, self.order),
+                b: add_mod(*b, *b, self.order),
+            },
+            Some(index) => {
+                let multiplier = &self.multipliers[index as usize];
+                WalkPoint {
+                    element: self.group.operate(element, &multiplier.element),
+                    a: add_mod(*a, multiplier.a, self.order),
+                    b: add_mod(*b, multiplier.b, self.order),
+                }
+            }
+        }
+    }
+}
+
+/// Solves `generator^x = target` with Pollard's rho.
+///
+/// `order` must be the exact order of `generator`; the method works best when
+/// it is prime. Each walk runs for at most `parameters.max_iterations` steps
+/// before it is restarted from a new starting point, up to
+/// `parameters.max_restarts` times.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::discrete_log::{pollard_rho, rho_parameters, CyclicGroup, ModularGroup};
+///
+/// let group = ModularGroup::new(2027).unwrap();
+/// let target = group.power(&4, 777);
+/// let (exponent, _) = pollard_rho(&group, &4, &target, 1013, &rho_parameters(1013));
+/// assert_eq!(exponent, Some(777));
+/// ```
+///
+/// # Panics
+///
+/// Panics if there are fewer than two partitions.
+pub fn pollard_rho<G: CyclicGroup>(
+    group: &G,
+    generator: &G::Element,
+    target: &G::Element,
+    order: u64,
+    parameters: &RhoParameters,
+) -> (Option<u64>, Statistics) {
+    assert!(
+        parameters.partitions >= 2,
+        "the walk needs at least two partitions"
+    );
+
+    let counting = Counting::new(group);
+    let group = &counting;
+    let mut statistics = Statistics::default();
+    let mut walk = RhoWalk {
+        group,
+        generator,
+        target,
+        order,
+        multipliers: Vec::new(),
+    };
+    let seed = splitmix64(parameters.seed);
+    walk.multipliers = (1..parameters.partitions as u64)
+        .map(|partition| walk.point(seed.wrapping_sub(partition)))
+        .collect();
+    statistics.stored_elements = walk.multipliers.len() as u64 + 2;
+
+    for attempt in 0..=parameters.max_restarts {
+        if attempt > 0 {
+            statistics.restarts += 1;
+        }
+
+        let start = walk.point(seed.wrapping_add(attempt));
+        let max_iterations = parameters.max_iterations;
+        let collision = match parameters.cycle_detection {
+            CycleDetection::Floyd => floyd(&walk, start, max_iterations, &mut statistics),
+            CycleDetection::Brent => brent(&walk, start, max_iterations, &mut statistics),
+        };
+
+        if let Some((first, second)) = collision
+            && let Some(exponent) = solve_collision(&walk, &first, &second)
+        {
+            return (Some(exponent), group.record(statistics));
+        }
+    }
+
+    (None, group.record(statistics))
+}
+
+/// Picks [`RhoParameters`] for a generator of the given order.
+///
+/// The walk uses Brent's cycle detection and the 20 partitions Teske found
+/// to behave like a random walk, and each walk may take eight times the
+/// `sqrt(pi * order / 2)` steps expected before a collision.
+pub fn rho_parameters(order: u64) -> RhoParameters {
+    let expected_steps = (std::f64::consts::FRAC_PI_2 * order as f64).sqrt();
+    RhoParameters {
+        cycle_detection: CycleDetection::Brent,
+        partitions: 20,
+        max_iterations: (8.0 * expected_steps).ceil() as u64,
+        max_restarts: 8,
+        seed: 0,
+    }
+}
+
+type Collision<E> = Option<(WalkPoint<E>, WalkPoint<E>)>;
+
+fn floyd<G: CyclicGroup>(
+    walk: &RhoWalk<G>,
+    start: WalkPoint<G::Element>,
+    max_iterations: u64,
+    statistics: &mut Statistics,
+) -> Collision<G::Element> {
+    let mut tortoise = walk.step(&start);
+    let mut hare = walk.step(&tortoise);
+
+    for _ in 0..max_iterations {
+        statistics.iterations += 1;
+        if tortoise.element == hare.element {
+            return Some((tortoise, hare));
+        }
+        tortoise = walk.step(&tortoise);
+        hare = walk.step(&walk.step(&hare));
+    }
+
+    None
+}
+
+fn brent<G: CyclicGroup>(
+    walk: &RhoWalk<G>,
+    start: WalkPoint<G::Element>,
+    max_iterations: u64,
+    statistics: &mut Statistics,
+) -> Collision<G::Element> {
+    let mut checkpoint = start.clone();
+    let mut hare = walk.step(&start);
+    let (mut power, mut length) = (1u64, 1u64);
+
+    for _ in 0..max_iterations {
+        statistics.iterations += 1;
+        if checkpoint.element == hare.element {
+            return Some((checkpoint, hare));
+        }
+        if power == length {
+            checkpoint = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = walk.step(&hare);
+        length += 1;
+    }
+
+    None
+}
+
+/// Turns `g^a1 h^b1 = g^a2 h^b2` into `x = (a1 - a2) / (b2 - b1) (mod n)`.
+///
+/// When `b2 - b1` shares a factor `d` with the order there are `d` candidate
+/// solutions; they are checked one by one if `d` is small.
+fn solve_collision<G: CyclicGroup>(
+    walk: &RhoWalk<G>,
+    first: &WalkPoint<G::Element>,
+    second: &WalkPoint<G::Element>,
+) -> Option<u64> {
+    const MAX_CANDIDATES: u64 = 1 << 16;
+
+    let order = walk.order;
+    let numerator = sub_mod(first.a, second.a, order);
+    let denominator = sub_mod(second.b, first.b, order);
+    if denominator == 0 {
+        return None;
+    }
+
+    let divisor = gcd(denominator, order);
+    if !numerator.is_multiple_of(divisor) || divisor > MAX_CANDIDATES {
+        return None;
+    }
+
+    let reduced_order = order / divisor;
+    let base = mul_mod(
+        numerator / divisor,
+        inverse_mod(denominator / divisor, reduced_order)?,
+        reduced_order,
+    );
+
+    (0..divisor)
+        .map(|k| base + k * reduced_order)
+        .find(|&candidate| walk.group.power(walk.generator, candidate) == *walk.target)
+}
+
+/// Solves `generator^x = target` for `x` in `[lower, upper]` with Pollard's
+/// kangaroo (lambda) method.
+///
+/// Jumps are powers of two below `2^parameters.jump_bits`, picked by the
+/// element's fingerprint, and the tame kangaroo makes
+/// `parameters.tame_jumps` jumps before setting its trap. The walk is
+/// deterministic, so a wild kangaroo that jumps over the trap misses it on
+/// every run with the same parameters; more tame jumps move the trap.
+/// The trap lies at `upper` plus the tame kangaroo's distance, so the walk
+/// also gives up with `None` if that exceeds `u64::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::discrete_log::{kangaroo_parameters, pollard_kangaroo, CyclicGroup, ModularGroup};
+///
+/// let group = ModularGroup::new(2027).unwrap();
+/// let target = group.power(&4, 777);
+/// let parameters = kangaroo_parameters(1000 - 500);
+/// let (exponent, _) = pollard_kangaroo(&group, &4, &target, 500, 1000, &parameters);
+/// assert_eq!(exponent, Some(777));
+/// ```
+pub fn pollard_kangaroo<G: CyclicGroup>(
+    group: &G,
+    generator: &G::Element,
+    target: &G::Element,
+    lower: u64,
+    upper: u64,
+    parameters: &KangarooParameters,
+) -> (Option<u64>, Statistics) {
+    let KangarooParameters {
+        jump_bits,
+        tame_jumps,
+    } = *parameters;
+    assert!(lower <= upper, "interval must not be empty");
+    assert!(
+        (1..64).contains(&jump_bits),
+        "jump_bits must be between 1 and 63"
+    );
+
+    let counting = Counting::new(group);
+    let group = &counting;
+
+    let mut statistics = Statistics::default();
+
+    // generator^(2^i), the table of jump elements
+    let mut jumps = Vec::with_capacity(jump_bits as usize);
+    let mut jump = generator.clone();
+    for _ in 0..jump_bits {
+        jumps.push(jump.clone());
+        jump = group.operate(&jump, &jump);
+    }
+    statistics.stored_elements = jumps.len() as u64 + 2;
+    let jump_index =
+        |element: &G::Element| (group.fingerprint(element) % jump_bits as u64) as usize;
+
+    // the tame kangaroo starts at the upper bound and leaves a trap
+    let mut tame_distance = 0u64;
+    let mut tame = group.power(generator, upper);
+    for _ in 0..tame_jumps {
+        let index = jump_index(&tame);
+        let Some(distance) = tame_distance.checked_add(1 << index) else {
+            return (None, group.record(statistics));
+        };
+        tame_distance = distance;
+        tame = group.operate(&tame, &jumps[index]);
+        statistics.iterations += 1;
+    }
+    let Some(trap) = upper.checked_add(tame_distance) else {
+        return (None, group.record(statistics));
+    };
+
+    // the wild kangaroo starts at the target and runs until it passes the
+    // trap, so trap - wild_distance never drops below lower
+    let mut wild_distance = 0u64;
+    let mut wild = target.clone();
+    while wild_distance <= trap - lower {
+        if wild == tame {
+            return (Some(trap - wild_distance), group.record(statistics));
+        }
+        let index = jump_index(&wild);
+        let Some(distance) = wild_distance.checked_add(1 << index) else {
+            break;
+        };
+        wild_distance = distance;
+        wild = group.operate(&wild, &jumps[index]);
+        statistics.iterations += 1;
+    }
+
+    (None, group.record(statistics))
+}
+
+/// Picks [`KangarooParameters`] for an interval of the given width.
+///
+/// The largest jump is about `sqrt(width)`, and the tame kangaroo makes four
+/// times the mean jump size in jumps, as suggested by Pollard.
+pub fn kangaroo_parameters(width: u64) -> KangarooParameters {
+    let half_bits = (64 - width.max(1).leading_zeros()).div_ceil(2);
+    let jump_bits = half_bits.clamp(1, 63);
+    let mean_jump = ((1u128 << jump_bits) - 1) / jump_bits as u128;
+    KangarooParameters {
+        jump_bits,
+        tame_jumps: (4 * mean_jump).max(1) as u64,
+    }
+}
+
+/// Solves `generator^x = target` with Pohlig-Hellman, for a generator of
+/// smooth order.
+///
+/// `factors` is the factorization of the order of `generator` into
+/// `(prime, multiplicity)` pairs, as [`trial_division`] gives it. The
+/// logarithm is found modulo each prime power one base-`r` digit at a time,
+/// each digit by baby-step giant-step in the subgroup of order `r`, and the
+/// results are joined with the Chinese Remainder Theorem. That takes about
+/// `sqrt(r)` group operations per digit on top of the exponentiations, so
+/// every prime must be small. Returns `x` modulo the order, or `None` if
+/// `target` is not a power of `generator` or `factors` is not the
+/// factorization of its order.
+///
+/// [`trial_division`]: crate::analysis::number_theory::trial_division
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::discrete_log::{pohlig_hellman, CyclicGroup, ModularGroup};
+///
+/// // 7681 - 1 = 2^9 * 3 * 5, and 17 generates the whole group
+/// let group = ModularGroup::new(7681).unwrap();
+/// let target = group.power(&17, 5000);
+/// let (exponent, _) = pohlig_hellman(&group, &17, &target, &[(2, 9), (3, 1), (5, 1)]);
+/// assert_eq!(exponent, Some(BigUint::from(5000u64)));
+/// ```
+pub fn pohlig_hellman<G: CyclicGroup>(
+    group: &G,
+    generator: &G::Element,
+    target: &G::Element,
+    factors: &[(u64, u32)],
+) -> (Option<BigUint>, Statistics) {
+    let counting = Counting::new(group);
+    let group = &counting;
+    let mut statistics = Statistics::default();
+
+    let order = factors
+        .iter()
+        .flat_map(|&(prime, multiplicity)| (0..multiplicity).map(move |_| prime))
+        .fold(BigUint::one(), |order, prime| order * BigUint::from(prime));
+
+    let mut congruences = Vec::with_capacity(factors.len());
+    for &(prime, multiplicity) in factors {
+        // gamma has order prime
+        let gamma = group.power_big(generator, &(&order / BigUint::from(prime)));
+        let table_size = (prime as f64).sqrt().ceil() as usize;
+
+        // x = residue + prime_power * (digit + prime * ...)
+        let mut residue = BigUint::zero();
+        let mut prime_power = BigUint::one();
+        for _ in 0..multiplicity {
+            // (target / generator^residue)^(order / (prime_power * prime))
+            // = gamma^digit
+            let remaining = group.operate(
+                target,
+                &group.inverse(&group.power_big(generator, &residue)),
+            );
+            let exponent = &order / (&prime_power * BigUint::from(prime));
+            let digit_target = group.power_big(&remaining, &exponent);
+
+            let (digit, digit_statistics) =
+                baby_step_giant_step(group, &gamma, &digit_target, prime, table_size);
+            statistics.iterations += digit_statistics.iterations;
+            statistics.stored_elements = statistics
+                .stored_elements
+                .max(digit_statistics.stored_elements);
+            let Some(digit) = digit else {
+                return (None, group.record(statistics));
+            };
+
+            residue = residue + &prime_power * BigUint::from(digit);
+            prime_power = prime_power * BigUint::from(prime);
+        }
+        congruences.push((residue, prime_power));
+    }
+
+    // a factor list that repeats a prime can yield congruences that
+    // contradict each other
+    let Some((exponent, _)) = crt(&congruences) else {
+        return (None, group.record(statistics));
+    };
+    // the digits only show that target^(order / r) lies in the right
+    // subgroups, not that target is a power of generator
+    let found = group.power_big(generator, &exponent) == *target;
+    (found.then_some(exponent), group.record(statistics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ec::Domain;
+
+    // p = 2q + 1 is a safe prime, so 4 generates the subgroup of order q
+    const SMALL_PRIME: u64 = 2027;
+    const SMALL_ORDER: u64 = 1013;
+    const LARGE_PRIME: u64 = 2199023255867;
+    const LARGE_ORDER: u64 = 1099511627933;
+    const GENERATOR: u64 = 4;
+    // y^2 = x^3 + ax + b over GF(p) has prime order, so any point but the
+    // identity generates it
+    const CURVE: (u64, u64, u64) = (390168437, 129670305, 2963424383);
+    const CURVE_ORDER: u64 = 2963420701;
+
+    /// The integers modulo n under addition, to check that nothing assumes
+    /// a multiplicative group.
+    struct AdditiveGroup {
+        modulus: u64,
+    }
+
+    impl CyclicGroup for AdditiveGroup {
+        type Element = u64;
+
+        fn identity(&self) -> u64 {
+            0
+        }
+
+        fn operate(&self, a: &u64, b: &u64) -> u64 {
+            add_mod(*a, *b, self.modulus)
+        }
+
+        fn inverse(&self, element: &u64) -> u64 {
+            sub_mod(0, *element, self.modulus)
+        }
+
+        fn fingerprint(&self, element: &u64) -> u64 {
+            splitmix64(*element)
+        }
+    }
+
+    #[test]
+    fn test_power_and_inverse() {
+        let group = ModularGroup::new(SMALL_PRIME).unwrap();
+
+        assert_eq!(group.power(&GENERATOR, 0), 1);
+        assert_eq!(group.power(&GENERATOR, SMALL_ORDER), 1);
+        assert_eq!(group.operate(&7, &group.inverse(&7)), 1);
+        assert_eq!(inverse_mod(3, 7), Some(5));
+        assert_eq!(inverse_mod(4, 8), None);
+    }
+
+    #[test]
+    fn test_baby_step_giant_step() {
+        let group = ModularGroup::new(SMALL_PRIME).unwrap();
+        for exponent in [0, 1, 500, SMALL_ORDER - 1] {
+            let target = group.power(&GENERATOR, exponent);
+            let (found, _) = baby_step_giant_step(&group, &GENERATOR, &target, SMALL_ORDER, 32);
+            assert_eq!(found, Some(exponent));
+        }
+    }
+
+    #[test]
+    fn test_baby_step_giant_step_trades_memory_for_time() {
+        let group = ModularGroup::new(SMALL_PRIME).unwrap();
+        let target = group.power(&GENERATOR, 1000);
+
+        let (small, small_stats) =
+            baby_step_giant_step(&group, &GENERATOR, &target, SMALL_ORDER, 8);
+        let (large, large_stats) =
+            baby_step_giant_step(&group, &GENERATOR, &target, SMALL_ORDER, 1024);
+
+        assert_eq!(small, Some(1000));
+        assert_eq!(large, Some(1000));
+        assert!(small_stats.stored_elements < large_stats.stored_elements);
+        assert!(small_stats.iterations > large_stats.iterations);
+    }
+
+    #[test]
+    fn test_baby_step_giant_step_not_in_subgroup() {
+        let group = ModularGroup::new(SMALL_PRIME).unwrap();
+        // 2 is a non-residue mod 2027, so it is not a power of 4
+        let (found, _) = baby_step_giant_step(&group, &GENERATOR, &2, SMALL_ORDER, 32);
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_pollard_rho() {
+        let group = ModularGroup::new(LARGE_PRIME).unwrap();
+        let exponent = 123_456_789_012;
+        let target = group.power(&GENERATOR, exponent);
+
+        for cycle_detection in [CycleDetection::Floyd, CycleDetection::Brent] {
+            for partitions in [3, 20] {
+                let parameters = RhoParameters {
+                    cycle_detection,
+                    partitions,
+                    seed: 42,
+                    ..rho_parameters(LARGE_ORDER)
+                };
+                let (found, statistics) =
+                    pollard_rho(&group, &GENERATOR, &target, LARGE_ORDER, &parameters);
+                assert_eq!(found, Some(exponent));
+                assert_eq!(statistics.stored_elements, partitions as u64 + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pollard_rho_generic_group() {
+        let group = AdditiveGroup {
+            modulus: SMALL_ORDER,
+        };
+        let target = group.power(&1, 321);
+
+        let parameters = RhoParameters {
+            seed: 7,
+            ..rho_parameters(SMALL_ORDER)
+        };
+        let (found, _) = pollard_rho(&group, &1, &target, SMALL_ORDER, &parameters);
+        assert_eq!(found, Some(321));
+    }
+
+    #[test]
+    fn test_pollard_rho_gives_up() {
+        let group = ModularGroup::new(LARGE_PRIME).unwrap();
+        let target = group.power(&GENERATOR, 123_456_789_012);
+        let parameters = RhoParameters {
+            max_iterations: 100,
+            max_restarts: 2,
+            ..rho_parameters(LARGE_ORDER)
+        };
+
+        let (found, statistics) =
+            pollard_rho(&group, &GENERATOR, &target, LARGE_ORDER, &parameters);
+        assert_eq!(found, None);
+        assert_eq!(statistics.restarts, 2);
+        assert_eq!(statistics.iterations, 300);
+    }
+
+    #[test]
+    fn test_pollard_kangaroo() {
+        let group = ModularGroup::new(LARGE_PRIME).unwrap();
+        let (lower, upper) = (1 << 30, (1 << 30) + (1 << 22));
+        let exponent = lower + 2_718_281;
+        let target = group.power(&GENERATOR, exponent);
+
+        let parameters = kangaroo_parameters(upper - lower);
+        let (found, statistics) =
+            pollard_kangaroo(&group, &GENERATOR, &target, lower, upper, &parameters);
+
+        assert_eq!(found, Some(exponent));
+        assert!(statistics.iterations < 1 << 16);
+    }
+
+    #[test]
+    fn test_pollard_kangaroo_outside_interval() {
+        let group = ModularGroup::new(LARGE_PRIME).unwrap();
+        let target = group.power(&GENERATOR, 5);
+
+        let (found, _) = pollard_kangaroo(
+            &group,
+            &GENERATOR,
+            &target,
+            1 << 20,
+            (1 << 20) + (1 << 16),
+            &kangaroo_parameters(1 << 16),
+        );
+
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_pollard_kangaroo_trap_past_u64_max() {
+        let group = ModularGroup::new(LARGE_PRIME).unwrap();
+        let (lower, upper) = (u64::MAX - 1000, u64::MAX - 10);
+        let target = group.power(&GENERATOR, u64::MAX - 500);
+
+        let parameters = kangaroo_parameters(upper - lower);
+        let (found, _) = pollard_kangaroo(&group, &GENERATOR, &target, lower, upper, &parameters);
+
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_pohlig_hellman() {
+        // 2027 - 1 = 2 * 1013, and 2 generates the whole group
+        let group = ModularGroup::new(SMALL_PRIME).unwrap();
+        for exponent in [0, 1, 1013, 1500, 2025] {
+            let target = group.power(&2, exponent);
+            let (found, _) = pohlig_hellman(&group, &2, &target, &[(2, 1), (1013, 1)]);
+            assert_eq!(found, Some(BigUint::from(exponent)));
+        }
+
+        // 4 only generates the squares, and 2 is not one
+        let (found, _) = pohlig_hellman(&group, &4, &2, &[(1013, 1)]);
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_pohlig_hellman_wrong_factors() {
+        // 7681 - 1 = 2^9 * 3 * 5, not 2^10 * 3 * 5
+        let group = ModularGroup::new(7681).unwrap();
+        let target = group.power(&17, 5000);
+        let factors = [(2, 9), (2, 1), (3, 1), (5, 1)];
+
+        let (found, _) = pohlig_hellman(&group, &17, &target, &factors);
+
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_pohlig_hellman_big_group() {
+        // 2^89 - 1 is prime and 3 generates its multiplicative group
+        let modulus = BigUint::from((1u128 << 89) - 1);
+        let factors = [
+            (2, 1),
+            (3, 1),
+            (5, 1),
+            (17, 1),
+            (23, 1),
+            (89, 1),
+            (353, 1),
+            (397, 1),
+            (683, 1),
+            (2113, 1),
+            (2931542417, 1),
+        ];
+        let group = BigModularGroup::new(modulus.clone()).unwrap();
+        let generator = BigUint::from(3u64);
+        let exponent = BigUint::from(123_456_789_012_345_678_901u128);
+        let target = group.power_big(&generator, &exponent);
+
+        let (found, statistics) = pohlig_hellman(&group, &generator, &target, &factors);
+        assert_eq!(found, Some(exponent));
+        assert!(statistics.stored_elements <= 54_144);
+    }
+
+    #[test]
+    fn test_solvers_on_a_curve() {
+        let (a, b, p) = CURVE;
+        let curve = Curve::new(a.into(), b.into(), p.into()).unwrap();
+        let generator = curve.lift_x(&BigUint::one()).unwrap();
+        assert_eq!(curve.power(&generator, CURVE_ORDER), Point::Infinity);
+
+        let exponent = 1_234_567_890;
+        let target = curve.power(&generator, exponent);
+
+        let (found, _) = baby_step_giant_step(&curve, &generator, &target, CURVE_ORDER, 1 << 16);
+        assert_eq!(found, Some(exponent));
+
+        let parameters = RhoParameters {
+            seed: 3,
+            ..rho_parameters(CURVE_ORDER)
+        };
+        let (found, _) = pollard_rho(&curve, &generator, &target, CURVE_ORDER, &parameters);
+        assert_eq!(found, Some(exponent));
+
+        let (lower, upper) = (exponent - (1 << 20), exponent + (1 << 20));
+        let (found, _) = pollard_kangaroo(
+            &curve,
+            &generator,
+            &target,
+            lower,
+            upper,
+            &kangaroo_parameters(upper - lower),
+        );
+        assert_eq!(found, Some(exponent));
+    }
+
+    #[test]
+    fn test_pollard_kangaroo_on_the_toy_curve() {
+        let domain = Domain::toy();
+        let order = domain.order.to_u64().unwrap();
+        let (lower, upper) = (order - (1 << 24), order - 1);
+        let exponent = lower + 9_876_543;
+        let target = domain.public_key(&BigUint::from(exponent));
+
+        let (found, statistics) = pollard_kangaroo(
+            &domain.curve,
+            &domain.generator,
+            &target,
+            lower,
+            upper,
+            &kangaroo_parameters(upper - lower),
+        );
+        assert_eq!(found, Some(exponent));
+        assert!(statistics.iterations < 1 << 16);
+    }
+}
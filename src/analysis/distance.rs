@@ -30,7 +30,7 @@ pub fn probable_key_sizes(
                 .chunks_exact(key_size)
                 .take(chunks_to_consider)
                 .tuple_windows()
-                .map(|(a, b)| (hamming_distance(a, b) as f32 / key_size as f32))
+                .map(|(a, b)| hamming_distance(a, b) as f32 / key_size as f32)
                 .collect_vec();
             (
                 key_size,
@@ -104,7 +104,7 @@ mod tests {
             )
         );
 
-        assert_eq!(vec![] as Vec<u32>, probable_key_sizes(&vec![], 3, 2, 20));
+        assert_eq!(vec![] as Vec<u32>, probable_key_sizes(&[], 3, 2, 20));
     }
 
     #[test]
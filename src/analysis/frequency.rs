@@ -39,6 +39,121 @@ pub fn calculate_frequencies(character_set: &HashSet<char>, text: &str) -> BTree
         .collect()
 }
 
+/// Occurrences of each byte value.
+///
+/// Counts are integers, so statistics gathered over millions of samples,
+/// such as the RC4 keystream biases, lose no precision and need no buffer
+/// of the bytes seen. Counts from several sources add up with
+/// [`ByteCounts::merge`].
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::frequency::ByteCounts;
+///
+/// let mut counts = ByteCounts::from(&b"hello"[..]);
+/// counts.add(b'o');
+/// assert_eq!(counts.count(b'l'), 2);
+/// assert_eq!(counts.total(), 6);
+/// assert_eq!(counts.most_frequent(), b'o');
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteCounts {
+    counts: [u64; 256],
+}
+
+impl Default for ByteCounts {
+    fn default() -> Self {
+        ByteCounts { counts: [0; 256] }
+    }
+}
+
+impl From<&[u8]> for ByteCounts {
+    fn from(bytes: &[u8]) -> Self {
+        let mut counts = ByteCounts::default();
+        bytes.iter().for_each(|&byte| counts.add(byte));
+        counts
+    }
+}
+
+impl ByteCounts {
+    pub fn add(&mut self, byte: u8) {
+        self.counts[byte as usize] += 1;
+    }
+
+    /// Adds the counts of `other` to these.
+    pub fn merge(&mut self, other: &ByteCounts) {
+        self.counts
+            .iter_mut()
+            .zip(other.counts)
+            .for_each(|(count, other)| *count += other);
+    }
+
+    pub fn count(&self, byte: u8) -> u64 {
+        self.counts[byte as usize]
+    }
+
+    /// The number of bytes counted.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The byte counted most often; ties go to the larger byte.
+    pub fn most_frequent(&self) -> u8 {
+        (0..=u8::MAX)
+            .max_by_key(|&byte| self.count(byte))
+            .expect("there are 256 byte values")
+    }
+
+    /// Byte frequencies as fractions of the total. Bytes that never occur
+    /// are absent from the map.
+    pub fn frequencies(&self) -> BTreeMap<u8, f32> {
+        let total_count = self.total() as f32;
+        (0..=u8::MAX)
+            .filter(|&byte| self.count(byte) > 0)
+            .map(|byte| (byte, self.count(byte) as f32 / total_count))
+            .collect()
+    }
+}
+
+/// Calculates byte frequencies for a given byte slice.
+///
+/// Returns a map of byte → frequency (as a fraction of total bytes). Bytes
+/// that never occur are absent from the map.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::frequency::calculate_byte_frequencies;
+///
+/// let freqs = calculate_byte_frequencies(&[0xF0, 0xF0, 0x00, 0x01]);
+/// assert_eq!(freqs.get(&0xF0), Some(&0.5));
+/// ```
+pub fn calculate_byte_frequencies(bytes: &[u8]) -> BTreeMap<u8, f32> {
+    ByteCounts::from(bytes).frequencies()
+}
+
+/// Calculates the Shannon entropy of a byte slice, in bits per byte.
+///
+/// Ranges from 0 for a single repeated byte to 8 for uniformly random bytes;
+/// English text sits around 4.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::frequency::shannon_entropy;
+///
+/// assert_eq!(shannon_entropy(b"aaaa"), 0.0);
+/// assert_eq!(shannon_entropy(b"abab"), 1.0);
+/// ```
+pub fn shannon_entropy(bytes: &[u8]) -> f32 {
+    calculate_byte_frequencies(bytes)
+        .values()
+        .map(|&frequency| -frequency * frequency.log2())
+        .sum::<f32>()
+        .max(0.0)
+}
+
 /// Scores text based on how well it matches expected character frequencies.
 ///
 /// Higher scores indicate better matches. This uses the multiplicative
@@ -104,6 +219,38 @@ mod tests {
         assert_eq!(freqs.get(&'z'), None);
     }
 
+    #[test]
+    fn test_calculate_byte_frequencies() {
+        let freqs = calculate_byte_frequencies(&[1, 2, 2, 255, 2]);
+
+        assert_eq!(freqs.get(&1), Some(&0.2));
+        assert_eq!(freqs.get(&2), Some(&0.6));
+        assert_eq!(freqs.get(&255), Some(&0.2));
+        assert_eq!(freqs.get(&0), None);
+        assert!(calculate_byte_frequencies(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_byte_counts_merge() {
+        let mut counts = ByteCounts::from(&[7, 7, 9][..]);
+        counts.merge(&ByteCounts::from(&[9, 9][..]));
+
+        assert_eq!(counts.count(7), 2);
+        assert_eq!(counts.count(9), 3);
+        assert_eq!(counts.total(), 5);
+        assert_eq!(counts.most_frequent(), 9);
+        assert_eq!(ByteCounts::default().total(), 0);
+    }
+
+    #[test]
+    fn test_shannon_entropy() {
+        let all_bytes = (0..=255).collect::<Vec<u8>>();
+
+        assert_eq!(shannon_entropy(&[]), 0.0);
+        assert!((shannon_entropy(&all_bytes) - 8.0).abs() < 1e-4);
+        assert!((shannon_entropy(b"abcd") - 2.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_score_text() {
         let charset = default_charset();
@@ -0,0 +1,144 @@
+//! The "forbidden attack" on AES-GCM under a repeated nonce (challenge 63).
+//!
+//! A GCM tag is `T = GHASH_H(A, C) + s`, where GHASH is a polynomial in the
+//! authentication key `H` whose coefficients are the AAD and ciphertext
+//! blocks, and the mask `s = E_K(J_0)` depends only on the key and nonce.
+//! Two messages sealed under the same nonce share `s`, so `H` is a root of
+//! the sum of their tag polynomials, whose coefficients are all public.
+//! Finding its roots in GF(2^128) leaves a few candidates, and a third
+//! message usually singles out `H`. With `H` and `s` anyone can tag any
+//! ciphertext under that nonce ([Joux's comments on GCM](https://csrc.nist.gov/csrc/media/projects/block-cipher-techniques/documents/bcm/comments/800-38-series-drafts/gcm/joux_comments.pdf)).
+
+use crate::analysis::gf128::Polynomial;
+use crate::crypto::gcm::{TAG_LEN, ghash, ghash_blocks};
+use crate::util::SplitMix64;
+
+/// The polynomial in `H` that evaluates to the mask `s` at the real key:
+/// GHASH of the message plus its tag.
+///
+/// Returns `None` if `sealed` is shorter than a tag.
+pub fn tag_polynomial(aad: &[u8], sealed: &[u8]) -> Option<Polynomial> {
+    let (ciphertext, tag) = sealed.split_at_checked(sealed.len().checked_sub(TAG_LEN)?)?;
+    let tag = u128::from_be_bytes(tag.try_into().expect("tag length"));
+
+    // X_1 H^m + ... + X_m H + T
+    let mut coefficients = ghash_blocks(aad, ciphertext);
+    coefficients.push(tag);
+    coefficients.reverse();
+    Some(Polynomial::new(coefficients))
+}
+
+/// Candidates for the authentication key `H` shared by messages sealed
+/// under one key and nonce, given as `(aad, ciphertext || tag)` pairs.
+///
+/// Every candidate is a common root of the differences between the first
+/// message's tag polynomial and the others'. The real key is always among
+/// them. Returns `None` if there are fewer than two messages, a message is
+/// shorter than a tag, or two messages are identical.
+pub fn candidate_keys(messages: &[(&[u8], &[u8])], rng: &mut SplitMix64) -> Option<Vec<u128>> {
+    let polynomials = messages
+        .iter()
+        .map(|(aad, sealed)| tag_polynomial(aad, sealed))
+        .collect::<Option<Vec<_>>>()?;
+    let (first, others) = polynomials.split_first()?;
+    let (second, rest) = others.split_first()?;
+
+    let difference = first + second;
+    if difference.is_zero() {
+        return None;
+    }
+
+    let candidates = difference
+        .roots(rng)
+        .into_iter()
+        .filter(|&candidate| {
+            rest.iter()
+                .all(|other| first.evaluate(candidate) == other.evaluate(candidate))
+        })
+        .collect();
+    Some(candidates)
+}
+
+/// Tags `ciphertext` under the nonce of a known message, given the
+/// authentication key `h`.
+///
+/// The mask is recovered from the known message as its tag polynomial
+/// evaluated at `h`. Returns `ciphertext || tag`, or `None` if the known
+/// message is shorter than a tag.
+pub fn forge(h: u128, known: (&[u8], &[u8]), aad: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    let mask = tag_polynomial(known.0, known.1)?.evaluate(h);
+
+    let mut forged = ciphertext.to_vec();
+    forged.extend((ghash(h, aad, ciphertext) ^ mask).to_be_bytes());
+    Some(forged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::aes::Aes128;
+    use crate::crypto::gcm::{open, seal};
+
+    const KEY: [u8; 16] = *b"YELLOW SUBMARINE";
+    const NONCE: [u8; 12] = *b"reused nonce";
+
+    fn authentication_key() -> u128 {
+        u128::from_be_bytes(Aes128::new(&KEY).encrypt_block(&[0; 16]))
+    }
+
+    #[test]
+    fn test_tag_polynomial_evaluates_to_the_mask() {
+        let first = seal(&KEY, &NONCE, b"header", b"first message");
+        let second = seal(&KEY, &NONCE, b"", b"a second, longer message");
+
+        let h = authentication_key();
+        assert_eq!(
+            tag_polynomial(b"header", &first).unwrap().evaluate(h),
+            tag_polynomial(b"", &second).unwrap().evaluate(h)
+        );
+    }
+
+    #[test]
+    fn test_recover_key_and_forge() {
+        let messages = [
+            (
+                &b"user=alice"[..],
+                seal(&KEY, &NONCE, b"user=alice", b"transfer 10 to bob"),
+            ),
+            (
+                &b"user=alice"[..],
+                seal(&KEY, &NONCE, b"user=alice", b"transfer 20 to carol"),
+            ),
+            (&b""[..], seal(&KEY, &NONCE, b"", b"log out")),
+        ];
+        let messages: Vec<(&[u8], &[u8])> = messages
+            .iter()
+            .map(|(aad, sealed)| (*aad, sealed.as_slice()))
+            .collect();
+
+        let candidates = candidate_keys(&messages, &mut SplitMix64::new(63)).unwrap();
+        assert_eq!(candidates, [authentication_key()]);
+
+        // flip "10" to "99" in the known plaintext and tag it under new AAD
+        let mut ciphertext = messages[0].1[..18].to_vec();
+        ciphertext[9] ^= b'1' ^ b'9';
+        ciphertext[10] ^= b'0' ^ b'9';
+        let forged = forge(candidates[0], messages[0], b"user=admin", &ciphertext).unwrap();
+
+        assert_eq!(
+            open(&KEY, &NONCE, b"user=admin", &forged).unwrap(),
+            b"transfer 99 to bob"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unusable_input() {
+        let sealed = seal(&KEY, &NONCE, b"", b"message");
+        let mut rng = SplitMix64::new(1);
+
+        assert!(candidate_keys(&[(b"", &sealed)], &mut rng).is_none());
+        assert!(candidate_keys(&[(b"", &sealed), (b"", &sealed)], &mut rng).is_none());
+        assert!(candidate_keys(&[(b"", &sealed), (b"", &sealed[..3])], &mut rng).is_none());
+        assert!(forge(1, (b"", &sealed[..3]), b"", b"").is_none());
+    }
+}
@@ -0,0 +1,312 @@
+//! Recovering the GCM authentication key from truncated tags (challenge 64).
+//!
+//! Changing the ciphertext block that GHASH multiplies by `H^e` by `d`
+//! changes the tag by `d H^e`. Squaring is linear in GF(2^128), so if only
+//! the blocks at `e = 2^i` change, by `d_i`, the tag changes by `A_d h`,
+//! where `A_d = sum M(d_i) S^i` is a bit matrix linear in the bits of the
+//! `d_i` ([`BitMatrix::gf128_multiply_by`], [`BitMatrix::gf128_squaring`]).
+//! Choosing the `d_i` from the kernel of that dependency so that the first
+//! rows of `A_d` vanish leaves only the other tag bits to chance. When such
+//! a forgery is accepted, those other rows are linear equations in `H`; each
+//! batch shrinks the space `H` lies in, which lets the next forgeries zero
+//! more rows, until one candidate is left
+//! ([Ferguson, "Authentication weaknesses in GCM"](https://csrc.nist.gov/csrc/media/projects/block-cipher-techniques/documents/bcm/comments/cwc-gcm/ferguson2.pdf)).
+
+use crate::analysis::gf2::{BitMatrix, BitVector, gf128_multiply};
+use crate::crypto::gcm::{TAG_LEN, ghash};
+use crate::util::SplitMix64;
+
+/// The coefficient of `x^power` in a GF(2^128) element, in GCM's bit order.
+fn coefficient(element: u128, power: usize) -> bool {
+    element >> (127 - power) & 1 == 1
+}
+
+/// Multiplies by `x`, the shift and reduction step of [`gf128_multiply`].
+fn times_x(element: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+    if element & 1 == 1 {
+        (element >> 1) ^ R
+    } else {
+        element >> 1
+    }
+}
+
+/// The first `zeroed` rows of `A_d X` as a linear function of the bits of
+/// `d_1, ..., d_n`, where the columns of `X` are the basis `H` lies in.
+///
+/// Row `c * zeroed + r` is row `r` of `A_d` times basis vector `c`, and
+/// column `128 (i - 1) + j` is the coefficient of `x^j` in `d_i`.
+fn dependency_matrix(basis: &[u128], powers: usize, zeroed: usize) -> BitMatrix {
+    let mut matrix = BitMatrix::zero((zeroed * basis.len()).max(1), 128 * powers);
+    for (column, &vector) in basis.iter().enumerate() {
+        let mut square = vector;
+        for power in 0..powers {
+            // x^j X_c^(2^i), for every j
+            square = gf128_multiply(square, square);
+            let mut product = square;
+            for bit in 0..128 {
+                for row in (0..zeroed).filter(|&row| coefficient(product, row)) {
+                    matrix.set(column * zeroed + row, 128 * power + bit, true);
+                }
+                product = times_x(product);
+            }
+        }
+    }
+    matrix
+}
+
+/// The matrix `A_d = sum M(d_i) S^i`, given `S^1, ..., S^n`.
+fn error_matrix(differences: &[u128], squarings: &[BitMatrix]) -> BitMatrix {
+    differences
+        .iter()
+        .zip(squarings)
+        .map(|(&difference, squaring)| BitMatrix::gf128_multiply_by(difference).multiply(squaring))
+        .reduce(|sum, term| sum.add(&term))
+        .expect("at least one power")
+}
+
+/// A random combination of the kernel vectors, split into `d_1, ..., d_n`.
+fn random_differences(kernel: &[BitVector], powers: usize, rng: &mut SplitMix64) -> Vec<u128> {
+    let mut combination = BitVector::zero(128 * powers);
+    for vector in kernel {
+        if rng.next_u64() & 1 == 1 {
+            combination ^= vector;
+        }
+    }
+    (0..powers)
+        .map(|power| {
+            (0..128).fold(0, |element, bit| {
+                element | (combination.get(128 * power + bit) as u128) << (127 - bit)
+            })
+        })
+        .collect()
+}
+
+/// Recovers the authentication key `H` of a message sealed with its tag
+/// truncated to `tag_len` bytes, from an oracle that says whether a
+/// modified `ciphertext || tag` is accepted.
+///
+/// The ciphertext must be a whole number of blocks; the more there are, the
+/// more rows each forgery can zero. With `2^n - 1` blocks and `t`-bit tags,
+/// the first forgery takes about `2^(t - n + 1)` attempts, and later ones
+/// fewer. Returns `None` if the ciphertext is empty or not whole blocks, the
+/// tag length is not 1 to 16 bytes, or the oracle's answers contradict each
+/// other. An oracle that never accepts keeps this searching.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::gcm_truncated::recover_authentication_key;
+/// use cryptopals::crypto::aes::Aes128;
+/// use cryptopals::crypto::gcm::{open_truncated, seal_truncated};
+/// use cryptopals::util::SplitMix64;
+///
+/// let (key, nonce) = (*b"YELLOW SUBMARINE", *b"fixed nonce!");
+/// let sealed = seal_truncated(&key, &nonce, b"", &[0; 16 * 63], 1);
+/// let accepts = |forged: &[u8]| open_truncated(&key, &nonce, b"", forged, 1).is_some();
+///
+/// let h = recover_authentication_key(&sealed, 1, accepts, &mut SplitMix64::new(0));
+/// assert_eq!(h, Some(u128::from_be_bytes(Aes128::new(&key).encrypt_block(&[0; 16]))));
+/// ```
+pub fn recover_authentication_key<F>(
+    sealed: &[u8],
+    tag_len: usize,
+    mut accepts: F,
+    rng: &mut SplitMix64,
+) -> Option<u128>
+where
+    F: FnMut(&[u8]) -> bool,
+{
+    if !(1..=TAG_LEN).contains(&tag_len) {
+        return None;
+    }
+    let (ciphertext, tag) = sealed.split_at_checked(sealed.len().checked_sub(tag_len)?)?;
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(16) {
+        return None;
+    }
+
+    // the last ciphertext block is multiplied by H^2, the one before by H^3,
+    // and so on, so H^(2^i) reaches back 2^i - 1 blocks
+    let blocks = ciphertext.len() / 16;
+    let powers = (blocks + 1).ilog2() as usize;
+    let tag_bits = 8 * tag_len;
+
+    let squaring = BitMatrix::gf128_squaring();
+    let squarings = std::iter::successors(Some(squaring.clone()), |power| {
+        Some(power.multiply(&squaring))
+    })
+    .take(powers)
+    .collect::<Vec<_>>();
+
+    let mut equations = Vec::new();
+    let mut basis = (0..128)
+        .map(|power| 1 << (127 - power))
+        .collect::<Vec<u128>>();
+    while basis.len() > 1 {
+        // leave the kernel at least one vector
+        let zeroed = (tag_bits - 1).min((128 * powers - 1) / basis.len());
+        let kernel = dependency_matrix(&basis, powers, zeroed).kernel();
+
+        let differences = loop {
+            let differences = random_differences(&kernel, powers, rng);
+            if differences.iter().all(|&difference| difference == 0) {
+                continue;
+            }
+
+            let mut forged = ciphertext.to_vec();
+            for (power, difference) in (1..).zip(&differences) {
+                let start = 16 * (blocks + 1 - (1 << power));
+                for (byte, mask) in forged[start..start + 16]
+                    .iter_mut()
+                    .zip(difference.to_be_bytes())
+                {
+                    *byte ^= mask;
+                }
+            }
+            forged.extend_from_slice(tag);
+            if accepts(&forged) {
+                break differences;
+            }
+        };
+
+        // the tag bits past the zeroed rows did not change either
+        let error = error_matrix(&differences, &squarings);
+        equations.extend((zeroed..tag_bits).map(|row| error.row(row).clone()));
+        basis = BitMatrix::from_rows(equations.clone())
+            .kernel()
+            .iter()
+            .map(BitVector::to_gf128)
+            .collect();
+    }
+    // H is not zero, so the last basis vector is H itself
+    basis.first().copied()
+}
+
+/// Tags `ciphertext` with a `tag_len`-byte tag under the nonce of a known
+/// message, given the authentication key `h`.
+///
+/// The truncated mask is the known tag minus the known message's GHASH.
+/// Returns `ciphertext || tag`, or `None` if the known message is shorter
+/// than a tag or the tag length is not 1 to 16 bytes.
+pub fn forge(
+    h: u128,
+    tag_len: usize,
+    known: (&[u8], &[u8]),
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Option<Vec<u8>> {
+    if !(1..=TAG_LEN).contains(&tag_len) {
+        return None;
+    }
+    let (aad_known, sealed) = known;
+    let (ciphertext_known, tag) = sealed.split_at_checked(sealed.len().checked_sub(tag_len)?)?;
+    let mask = ghash(h, aad_known, ciphertext_known).to_be_bytes();
+
+    let mut forged = ciphertext.to_vec();
+    forged.extend(
+        ghash(h, aad, ciphertext)
+            .to_be_bytes()
+            .iter()
+            .zip(mask.iter().zip(tag))
+            .map(|(byte, (mask, tag))| byte ^ mask ^ tag),
+    );
+    Some(forged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::aes::Aes128;
+    use crate::crypto::gcm::{open_truncated, seal_truncated};
+
+    const KEY: [u8; 16] = *b"YELLOW SUBMARINE";
+    const NONCE: [u8; 12] = *b"fixed nonce!";
+
+    fn authentication_key() -> u128 {
+        u128::from_be_bytes(Aes128::new(&KEY).encrypt_block(&[0; 16]))
+    }
+
+    #[test]
+    fn test_zeroed_rows_vanish() {
+        let squaring = BitMatrix::gf128_squaring();
+        let squarings = vec![squaring.clone(), squaring.multiply(&squaring)];
+        let basis = (0..128)
+            .map(|power| 1 << (127 - power))
+            .collect::<Vec<u128>>();
+
+        // two powers leave room to zero one row
+        let kernel = dependency_matrix(&basis, 2, 1).kernel();
+        assert_eq!(kernel.len(), 128);
+
+        let mut rng = SplitMix64::new(1);
+        for _ in 0..8 {
+            let differences = random_differences(&kernel, 2, &mut rng);
+            let error = error_matrix(&differences, &squarings);
+            assert!(error.row(0).is_zero());
+
+            // the tag difference is d_1 H^2 + d_2 H^4
+            let h = authentication_key();
+            let h2 = gf128_multiply(h, h);
+            let expected = gf128_multiply(differences[0], h2)
+                ^ gf128_multiply(differences[1], gf128_multiply(h2, h2));
+            assert_eq!(
+                error.multiply_vector(&BitVector::from_gf128(h)).to_gf128(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_recovers_key_and_forges() {
+        let aad = b"header";
+        let sealed = seal_truncated(&KEY, &NONCE, aad, &[7; 16 * 255], 2);
+        let queries = std::cell::Cell::new(0);
+        let accepts = |forged: &[u8]| {
+            queries.set(queries.get() + 1);
+            open_truncated(&KEY, &NONCE, aad, forged, 2).is_some()
+        };
+
+        let h = recover_authentication_key(&sealed, 2, accepts, &mut SplitMix64::new(0));
+        assert_eq!(h, Some(authentication_key()));
+        // far fewer than the 2^16 a blind guess at each tag takes per forgery
+        assert!(queries.get() < 1 << 14);
+
+        // the known plaintext gives away the keystream too
+        let ciphertext = b"attack at dawn"
+            .iter()
+            .zip(&sealed)
+            .map(|(byte, sealed)| byte ^ sealed ^ 7)
+            .collect::<Vec<_>>();
+        let forged = forge(h.unwrap(), 2, (aad, &sealed), b"", &ciphertext).unwrap();
+        assert_eq!(
+            open_truncated(&KEY, &NONCE, b"", &forged, 2).unwrap(),
+            b"attack at dawn"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unusable_messages() {
+        let accepts = |_: &[u8]| true;
+        let mut rng = SplitMix64::new(0);
+        let partial = seal_truncated(&KEY, &NONCE, b"", &[0; 20], 4);
+        let empty = seal_truncated(&KEY, &NONCE, b"", b"", 4);
+
+        assert_eq!(
+            recover_authentication_key(&partial, 4, accepts, &mut rng),
+            None
+        );
+        assert_eq!(
+            recover_authentication_key(&empty, 4, accepts, &mut rng),
+            None
+        );
+        assert_eq!(
+            recover_authentication_key(&partial[..3], 4, accepts, &mut rng),
+            None
+        );
+        assert_eq!(
+            recover_authentication_key(&partial, 0, accepts, &mut rng),
+            None
+        );
+        assert_eq!(forge(1, 4, (b"", &partial[..3]), b"", b"text"), None);
+    }
+}
@@ -0,0 +1,482 @@
+//! Polynomials over GF(2^128) and their factorization.
+//!
+//! Field elements are `u128`s in the bit order of GCM (see
+//! [`gf128_multiply`]), so the constant `1` is [`ONE`], the most significant
+//! bit. Polynomials are factored the textbook way (von zur Gathen and
+//! Gerhard, Modern Computer Algebra, chapter 14): square-free factorization,
+//! then distinct-degree factorization, then Cantor–Zassenhaus to split the
+//! factors of equal degree. In characteristic two the splitting uses the
+//! trace `h + h^2 + h^4 + ...` instead of `h^((q^d - 1) / 2)`.
+
+use crate::crypto::gcm::gf128_multiply;
+use crate::util::SplitMix64;
+use std::ops::{Add, Mul, Rem};
+
+/// The multiplicative identity of GF(2^128) in GCM bit order.
+pub const ONE: u128 = 1 << 127;
+
+/// Raises a field element to the given power.
+pub fn pow(base: u128, exponent: u128) -> u128 {
+    (0..128).rev().fold(ONE, |acc, bit| {
+        let squared = gf128_multiply(acc, acc);
+        if exponent >> bit & 1 == 1 {
+            gf128_multiply(squared, base)
+        } else {
+            squared
+        }
+    })
+}
+
+/// The multiplicative inverse, `a^(2^128 - 2)`, or `None` for zero.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::gf128::{inverse, ONE};
+/// use cryptopals::crypto::gcm::gf128_multiply;
+///
+/// let a = 0x66E94BD4EF8A2C3B884CFA59CA342B2E;
+/// assert_eq!(gf128_multiply(a, inverse(a).unwrap()), ONE);
+/// assert_eq!(inverse(0), None);
+/// ```
+pub fn inverse(a: u128) -> Option<u128> {
+    (a != 0).then(|| pow(a, u128::MAX - 1))
+}
+
+/// The unique square root, `a^(2^127)`.
+pub fn sqrt(a: u128) -> u128 {
+    (0..127).fold(a, |acc, _| gf128_multiply(acc, acc))
+}
+
+/// A polynomial with coefficients in GF(2^128).
+///
+/// Coefficients are stored from the constant term up, without trailing
+/// zeros. Addition, multiplication and remainder are available through the
+/// operators on references; remainder panics on a zero divisor.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::gf128::{Polynomial, ONE};
+/// use cryptopals::util::SplitMix64;
+///
+/// // (x + 3)(x + 5) has roots 3 and 5
+/// let product = &Polynomial::new(vec![3, ONE]) * &Polynomial::new(vec![5, ONE]);
+/// let mut roots = product.roots(&mut SplitMix64::new(1));
+/// roots.sort();
+/// assert_eq!(roots, [3, 5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    coefficients: Vec<u128>,
+}
+
+impl Polynomial {
+    /// Creates the polynomial whose coefficient of `x^i` is `coefficients[i]`.
+    pub fn new(mut coefficients: Vec<u128>) -> Self {
+        while coefficients.last() == Some(&0) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial::new(Vec::new())
+    }
+
+    pub fn one() -> Self {
+        Polynomial::new(vec![ONE])
+    }
+
+    /// The polynomial `x`.
+    pub fn x() -> Self {
+        Polynomial::new(vec![0, ONE])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// The coefficients from the constant term up.
+    pub fn coefficients(&self) -> &[u128] {
+        &self.coefficients
+    }
+
+    /// Evaluates the polynomial with Horner's rule.
+    pub fn evaluate(&self, x: u128) -> u128 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0, |acc, &coefficient| gf128_multiply(acc, x) ^ coefficient)
+    }
+
+    /// Divides by the leading coefficient; the zero polynomial stays zero.
+    pub fn monic(&self) -> Polynomial {
+        match self.coefficients.last().copied().and_then(inverse) {
+            Some(scale) => Polynomial::new(
+                self.coefficients
+                    .iter()
+                    .map(|&coefficient| gf128_multiply(coefficient, scale))
+                    .collect(),
+            ),
+            None => self.clone(),
+        }
+    }
+
+    /// Quotient and remainder of the division by `divisor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        let divisor_degree = divisor.degree().expect("divisor is not zero");
+        // factoring divides by monic polynomials, which need no inversion
+        let leading = divisor.coefficients[divisor_degree];
+        let scale = if leading == ONE {
+            ONE
+        } else {
+            inverse(leading).expect("leading term is nonzero")
+        };
+
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![0; remainder.len().saturating_sub(divisor_degree)];
+        for shift in (0..quotient.len()).rev() {
+            let factor = gf128_multiply(remainder[shift + divisor_degree], scale);
+            quotient[shift] = factor;
+            for (idx, &coefficient) in divisor.coefficients.iter().enumerate() {
+                remainder[shift + idx] ^= gf128_multiply(factor, coefficient);
+            }
+        }
+        (Polynomial::new(quotient), Polynomial::new(remainder))
+    }
+
+    /// The monic greatest common divisor; zero only if both are zero.
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let remainder = &a % &b;
+            a = b;
+            b = remainder;
+        }
+        a.monic()
+    }
+
+    /// The formal derivative. In characteristic two the even powers vanish.
+    pub fn derivative(&self) -> Polynomial {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, &coefficient)| if power % 2 == 1 { coefficient } else { 0 })
+                .collect(),
+        )
+    }
+
+    /// The square root of a polynomial with only even powers.
+    fn sqrt(&self) -> Polynomial {
+        debug_assert!(self.derivative().is_zero(), "polynomial is a square");
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .step_by(2)
+                .map(|&c| sqrt(c))
+                .collect(),
+        )
+    }
+
+    fn square_mod(&self, modulus: &Polynomial) -> Polynomial {
+        &(self * self) % modulus
+    }
+
+    /// Factors a monic polynomial into pairwise coprime square-free factors
+    /// with their multiplicities.
+    ///
+    /// The product of `factor^multiplicity` over the result is `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polynomial is not monic.
+    pub fn square_free_factorization(&self) -> Vec<(Polynomial, u32)> {
+        assert!(self.is_monic(), "polynomial is monic");
+
+        let mut factors = Vec::new();
+        let mut c = self.gcd(&self.derivative());
+        let mut w = self.div_rem(&c).0;
+
+        // factors whose multiplicity is odd, peeled off one multiplicity at a time
+        let mut multiplicity = 1;
+        while w != Polynomial::one() {
+            let y = w.gcd(&c);
+            let factor = w.div_rem(&y).0;
+            if factor != Polynomial::one() {
+                factors.push((factor, multiplicity));
+            }
+            c = c.div_rem(&y).0;
+            w = y;
+            multiplicity += 1;
+        }
+
+        // what is left is a square
+        if c != Polynomial::one() {
+            for (factor, multiplicity) in c.sqrt().square_free_factorization() {
+                factors.push((factor, 2 * multiplicity));
+            }
+        }
+        factors
+    }
+
+    /// Splits a monic square-free polynomial into products of its irreducible
+    /// factors of each degree, as `(product, degree)` pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polynomial is not monic.
+    pub fn distinct_degree_factorization(&self) -> Vec<(Polynomial, usize)> {
+        assert!(self.is_monic(), "polynomial is monic");
+
+        let mut factors = Vec::new();
+        let mut rest = self.clone();
+        // x^(q^degree) mod rest
+        let mut frobenius = &Polynomial::x() % &rest;
+        let mut degree = 1;
+        while rest.degree().unwrap_or(0) >= 2 * degree {
+            for _ in 0..128 {
+                frobenius = frobenius.square_mod(&rest);
+            }
+            let factor = rest.gcd(&(&frobenius + &Polynomial::x()));
+            if factor != Polynomial::one() {
+                rest = rest.div_rem(&factor).0;
+                frobenius = &frobenius % &rest;
+                factors.push((factor, degree));
+            }
+            degree += 1;
+        }
+        if let Some(degree @ 1..) = rest.degree() {
+            factors.push((rest, degree));
+        }
+        factors
+    }
+
+    /// Splits a monic square-free polynomial whose irreducible factors all
+    /// have the given degree into those factors (Cantor–Zassenhaus).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polynomial is not monic or `degree` is zero.
+    pub fn equal_degree_factorization(
+        &self,
+        degree: usize,
+        rng: &mut SplitMix64,
+    ) -> Vec<Polynomial> {
+        assert!(self.is_monic(), "polynomial is monic");
+        assert!(degree > 0, "factors have a positive degree");
+
+        let size = self.degree().unwrap_or(0);
+        let count = size / degree;
+        let mut factors = vec![self.clone()];
+        while factors.len() < count {
+            let random = Polynomial::new(
+                (0..size)
+                    .map(|_| (rng.next_u64() as u128) << 64 | rng.next_u64() as u128)
+                    .collect(),
+            );
+
+            // the trace of `random` down to GF(2) is 0 or 1 modulo each factor,
+            // each with probability about one half
+            let mut power = random.clone();
+            let mut trace = random;
+            for _ in 1..128 * degree {
+                power = power.square_mod(self);
+                trace = &trace + &power;
+            }
+
+            factors = factors
+                .into_iter()
+                .flat_map(|factor| {
+                    let common = factor.gcd(&trace);
+                    if factor.degree() == Some(degree)
+                        || common == Polynomial::one()
+                        || common == factor
+                    {
+                        vec![factor]
+                    } else {
+                        let quotient = factor.div_rem(&common).0;
+                        vec![common, quotient]
+                    }
+                })
+                .collect();
+        }
+        factors
+    }
+
+    /// The distinct roots of a nonzero polynomial, in no particular order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polynomial is zero.
+    pub fn roots(&self, rng: &mut SplitMix64) -> Vec<u128> {
+        assert!(!self.is_zero(), "polynomial is not zero");
+
+        self.monic()
+            .square_free_factorization()
+            .into_iter()
+            .flat_map(|(factor, _)| factor.distinct_degree_factorization())
+            .filter(|(_, degree)| *degree == 1)
+            .flat_map(|(product, _)| product.equal_degree_factorization(1, rng))
+            // x + r has the root r
+            .map(|linear| linear.coefficients[0])
+            .collect()
+    }
+
+    fn is_monic(&self) -> bool {
+        self.coefficients.last() == Some(&ONE)
+    }
+}
+
+impl Add<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    // addition in characteristic two is XOR
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: &Polynomial) -> Polynomial {
+        let (long, short) = if self.coefficients.len() >= other.coefficients.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut sum = long.coefficients.clone();
+        sum.iter_mut()
+            .zip(&short.coefficients)
+            .for_each(|(a, b)| *a ^= b);
+        Polynomial::new(sum)
+    }
+}
+
+impl Mul<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut product = vec![0; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in other.coefficients.iter().enumerate() {
+                product[i + j] ^= gf128_multiply(a, b);
+            }
+        }
+        Polynomial::new(product)
+    }
+}
+
+impl Rem<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn rem(self, divisor: &Polynomial) -> Polynomial {
+        self.div_rem(divisor).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn linear(root: u128) -> Polynomial {
+        Polynomial::new(vec![root, ONE])
+    }
+
+    fn power(base: &Polynomial, exponent: u32) -> Polynomial {
+        (0..exponent).fold(Polynomial::one(), |acc, _| &acc * base)
+    }
+
+    fn polynomial(max_degree: usize) -> impl Strategy<Value = Polynomial> {
+        proptest::collection::vec(any::<u128>(), 0..=max_degree + 1).prop_map(Polynomial::new)
+    }
+
+    #[test]
+    fn test_field_identities() {
+        let a = 0x0388DACE60B6A392F328C2B971B2FE78;
+        assert_eq!(pow(a, 0), ONE);
+        assert_eq!(pow(a, 1), a);
+        assert_eq!(pow(a, 3), gf128_multiply(a, gf128_multiply(a, a)));
+        assert_eq!(gf128_multiply(sqrt(a), sqrt(a)), a);
+        // the multiplicative group has order 2^128 - 1
+        assert_eq!(pow(a, u128::MAX), ONE);
+    }
+
+    #[test]
+    fn test_square_free_factorization() {
+        // (x + 1)^3 (x + 2)^2 (x + 3)
+        let f = &(&power(&linear(1), 3) * &power(&linear(2), 2)) * &linear(3);
+        let mut factors = f.square_free_factorization();
+        factors.sort_by_key(|(_, multiplicity)| *multiplicity);
+
+        assert_eq!(factors, [(linear(3), 1), (linear(2), 2), (linear(1), 3)]);
+    }
+
+    #[test]
+    fn test_distinct_degree_factorization() {
+        // x^2 + x + c is irreducible exactly when c has trace 1, so collect a
+        // quadratic with no roots and multiply it by two linear factors
+        let mut rng = SplitMix64::new(5);
+        let quadratic = std::iter::repeat_with(|| {
+            let constant = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            Polynomial::new(vec![constant, ONE, ONE])
+        })
+        .find(|candidate| candidate.roots(&mut SplitMix64::new(1)).is_empty())
+        .unwrap();
+
+        let linears = &linear(7) * &linear(9);
+        let f = &quadratic * &linears;
+        assert_eq!(
+            f.distinct_degree_factorization(),
+            [(linears, 1), (quadratic, 2)]
+        );
+    }
+
+    #[test]
+    fn test_roots_of_repeated_factors() {
+        let f = &(&power(&linear(11), 4) * &linear(13)) * &Polynomial::new(vec![0x1234 << 64]);
+        let mut roots = f.roots(&mut SplitMix64::new(3));
+        roots.sort();
+        assert_eq!(roots, [11, 13]);
+    }
+
+    proptest! {
+        #[test]
+        fn div_rem_reconstructs(a in polynomial(6), b in polynomial(4)) {
+            prop_assume!(!b.is_zero());
+            let (quotient, remainder) = a.div_rem(&b);
+            prop_assert!(remainder.degree() < b.degree());
+            prop_assert_eq!(&(&quotient * &b) + &remainder, a);
+        }
+
+        #[test]
+        fn gcd_divides_both(a in polynomial(5), b in polynomial(5), common in polynomial(2)) {
+            let (a, b) = (&a * &common, &b * &common);
+            let gcd = a.gcd(&b);
+            prop_assume!(!gcd.is_zero());
+            prop_assert!((&a % &gcd).is_zero());
+            prop_assert!((&b % &gcd).is_zero());
+            prop_assert!((&gcd % &common.monic()).is_zero());
+        }
+
+        #[test]
+        fn roots_are_found(roots in proptest::collection::vec(any::<u128>(), 1..5), seed: u64) {
+            let f = roots.iter().fold(Polynomial::one(), |acc, &root| &acc * &linear(root));
+            let mut found = f.roots(&mut SplitMix64::new(seed));
+            found.sort();
+            let mut expected = roots.clone();
+            expected.sort();
+            expected.dedup();
+            prop_assert_eq!(found, expected);
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! Linear algebra over GF(2).
+//!
+//! Bit-level attacks often come down to a linear system over GF(2): every
+//! unknown is a bit, addition is XOR and multiplication is AND. This module
+//! provides bit vectors and bit matrices with row reduction and kernel bases.
+//!
+//! Because squaring and multiplying by a constant are linear maps of
+//! GF(2^128), both have a 128x128 bit matrix. [`BitMatrix::gf128_multiply_by`]
+//! and [`BitMatrix::gf128_squaring`] build them, in the bit order GCM uses
+//! (see [`gf128_multiply`]).
+
+pub use crate::crypto::gcm::gf128_multiply;
+use std::ops::BitXorAssign;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-length vector of bits, packed into words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BitVector {
+    /// The zero vector of `len` bits.
+    pub fn zero(len: usize) -> Self {
+        BitVector {
+            len,
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// The vector of the given bits.
+    pub fn from_bits(bits: &[bool]) -> Self {
+        let mut vector = BitVector::zero(bits.len());
+        for (index, &bit) in bits.iter().enumerate() {
+            vector.set(index, bit);
+        }
+        vector
+    }
+
+    /// The 128 coefficients of a GF(2^128) element, bit `i` holding the
+    /// coefficient of `x^i`.
+    pub fn from_gf128(element: u128) -> Self {
+        let reversed = element.reverse_bits();
+        BitVector {
+            len: 128,
+            words: vec![reversed as u64, (reversed >> 64) as u64],
+        }
+    }
+
+    /// The GF(2^128) element with the coefficients of a 128-bit vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector does not have 128 bits.
+    pub fn to_gf128(&self) -> u128 {
+        assert_eq!(self.len, 128, "a GF(2^128) element has 128 bits");
+        (self.words[0] as u128 | (self.words[1] as u128) << 64).reverse_bits()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "bit index out of bounds");
+        self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, bit: bool) {
+        assert!(index < self.len, "bit index out of bounds");
+        let mask = 1 << (index % WORD_BITS);
+        if bit {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// The number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The inner product, that is the parity of the bits set in both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn dot(&self, other: &BitVector) -> bool {
+        assert_eq!(self.len, other.len, "vector lengths differ");
+        let ones: u32 = self
+            .words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones())
+            .sum();
+        ones % 2 == 1
+    }
+}
+
+impl BitXorAssign<&BitVector> for BitVector {
+    /// Adds another vector of the same length.
+    fn bitxor_assign(&mut self, other: &BitVector) {
+        assert_eq!(self.len, other.len, "vector lengths differ");
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+}
+
+/// A matrix over GF(2), stored as rows of bits.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::gf2::{BitMatrix, BitVector};
+///
+/// // x0 + x1 = 0 and x1 + x2 = 0 leave x0 = x1 = x2 free as one bit
+/// let matrix = BitMatrix::from_rows(vec![
+///     BitVector::from_bits(&[true, true, false]),
+///     BitVector::from_bits(&[false, true, true]),
+/// ]);
+/// assert_eq!(matrix.rank(), 2);
+/// assert_eq!(matrix.kernel(), [BitVector::from_bits(&[true, true, true])]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    columns: usize,
+    rows: Vec<BitVector>,
+}
+
+impl BitMatrix {
+    /// The zero matrix with the given dimensions.
+    pub fn zero(rows: usize, columns: usize) -> Self {
+        BitMatrix {
+            columns,
+            rows: vec![BitVector::zero(columns); rows],
+        }
+    }
+
+    /// The `size` x `size` identity matrix.
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = BitMatrix::zero(size, size);
+        for index in 0..size {
+            matrix.set(index, index, true);
+        }
+        matrix
+    }
+
+    /// The matrix with the given rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no rows or if their lengths differ.
+    pub fn from_rows(rows: Vec<BitVector>) -> Self {
+        let columns = rows.first().expect("a matrix has at least one row").len();
+        assert!(
+            rows.iter().all(|row| row.len() == columns),
+            "rows must have the same length"
+        );
+        BitMatrix { columns, rows }
+    }
+
+    /// The matrix with the given columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no columns or if their lengths differ.
+    pub fn from_columns(columns: Vec<BitVector>) -> Self {
+        BitMatrix::from_rows(columns).transpose()
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.columns
+    }
+
+    pub fn row(&self, row: usize) -> &BitVector {
+        &self.rows[row]
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> bool {
+        self.rows[row].get(column)
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, bit: bool) {
+        self.rows[row].set(column, bit);
+    }
+
+    pub fn transpose(&self) -> BitMatrix {
+        let mut transposed = BitMatrix::zero(self.columns, self.rows.len());
+        for (row_index, row) in self.rows.iter().enumerate() {
+            for column in 0..self.columns {
+                if row.get(column) {
+                    transposed.set(column, row_index, true);
+                }
+            }
+        }
+        transposed
+    }
+
+    /// The product `self * vector`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector length is not the column count.
+    pub fn multiply_vector(&self, vector: &BitVector) -> BitVector {
+        let bits = self
+            .rows
+            .iter()
+            .map(|row| row.dot(vector))
+            .collect::<Vec<_>>();
+        BitVector::from_bits(&bits)
+    }
+
+    /// The product `self * other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column count of `self` is not the row count of `other`.
+    pub fn multiply(&self, other: &BitMatrix) -> BitMatrix {
+        assert_eq!(self.columns, other.rows.len(), "dimensions do not match");
+        // each row of the product sums the rows of `other` picked by a row of `self`
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut product = BitVector::zero(other.columns);
+                for (index, other_row) in other.rows.iter().enumerate() {
+                    if row.get(index) {
+                        product ^= other_row;
+                    }
+                }
+                product
+            })
+            .collect();
+        BitMatrix {
+            columns: other.columns,
+            rows,
+        }
+    }
+
+    /// The sum `self + other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ.
+    pub fn add(&self, other: &BitMatrix) -> BitMatrix {
+        assert_eq!(self.rows.len(), other.rows.len(), "dimensions do not match");
+        let mut sum = self.clone();
+        for (row, other) in sum.rows.iter_mut().zip(&other.rows) {
+            *row ^= other;
+        }
+        sum
+    }
+
+    /// Brings the matrix into reduced row echelon form by Gaussian
+    /// elimination and returns the pivot column of each non-zero row.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for column in 0..self.columns {
+            let next = pivots.len();
+            let Some(pivot) = (next..self.rows.len()).find(|&row| self.rows[row].get(column))
+            else {
+                continue;
+            };
+            self.rows.swap(next, pivot);
+
+            let pivot_row = self.rows[next].clone();
+            for (index, row) in self.rows.iter_mut().enumerate() {
+                if index != next && row.get(column) {
+                    *row ^= &pivot_row;
+                }
+            }
+            pivots.push(column);
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    /// A basis of the kernel, the vectors `v` with `self * v = 0`.
+    pub fn kernel(&self) -> Vec<BitVector> {
+        let mut reduced = self.clone();
+        let pivots = reduced.row_reduce();
+
+        // every free column gives a basis vector, fixing the pivot bits
+        // so that each reduced row sums to zero
+        let mut is_pivot = vec![false; self.columns];
+        pivots.iter().for_each(|&column| is_pivot[column] = true);
+        (0..self.columns)
+            .filter(|&column| !is_pivot[column])
+            .map(|free| {
+                let mut vector = BitVector::zero(self.columns);
+                vector.set(free, true);
+                for (row, &pivot) in reduced.rows.iter().zip(&pivots) {
+                    vector.set(pivot, row.get(free));
+                }
+                vector
+            })
+            .collect()
+    }
+
+    /// The matrix of `y -> constant * y` in GF(2^128), acting on the
+    /// coefficient vectors of [`BitVector::from_gf128`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::analysis::gf2::{gf128_multiply, BitMatrix, BitVector};
+    ///
+    /// let (c, y) = (0x66E94BD4EF8A2C3B884CFA59CA342B2E, 0x0388DACE60B6A392F328C2B971B2FE78);
+    /// let product = BitMatrix::gf128_multiply_by(c).multiply_vector(&BitVector::from_gf128(y));
+    /// assert_eq!(product.to_gf128(), gf128_multiply(c, y));
+    /// ```
+    pub fn gf128_multiply_by(constant: u128) -> BitMatrix {
+        // column i is the image of x^i
+        BitMatrix::from_columns(
+            (0..128)
+                .map(|power| BitVector::from_gf128(gf128_multiply(constant, 1 << (127 - power))))
+                .collect(),
+        )
+    }
+
+    /// The matrix of `y -> y^2` in GF(2^128), which is linear because
+    /// `(a + b)^2 = a^2 + b^2` in characteristic two.
+    pub fn gf128_squaring() -> BitMatrix {
+        BitMatrix::from_columns(
+            (0..128)
+                .map(|power| {
+                    let element = 1 << (127 - power);
+                    BitVector::from_gf128(gf128_multiply(element, element))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn matrix(rows: usize, columns: usize) -> impl Strategy<Value = BitMatrix> {
+        proptest::collection::vec(proptest::collection::vec(any::<bool>(), columns), rows).prop_map(
+            |rows| BitMatrix::from_rows(rows.iter().map(|row| BitVector::from_bits(row)).collect()),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn kernel_is_annihilated(matrix in (1usize..12, 1usize..80).prop_flat_map(|(rows, columns)| matrix(rows, columns))) {
+            let kernel = matrix.kernel();
+            assert_eq!(kernel.len(), matrix.column_count() - matrix.rank());
+            for vector in &kernel {
+                assert!(!vector.is_zero());
+                assert!(matrix.multiply_vector(vector).is_zero());
+            }
+            // the basis vectors are independent
+            if !kernel.is_empty() {
+                assert_eq!(BitMatrix::from_rows(kernel.clone()).rank(), kernel.len());
+            }
+        }
+
+        #[test]
+        fn row_reduce_keeps_the_row_space(matrix in matrix(6, 70)) {
+            let mut reduced = matrix.clone();
+            let pivots = reduced.row_reduce();
+            for (row, &pivot) in pivots.iter().enumerate() {
+                for other in 0..reduced.row_count() {
+                    assert_eq!(reduced.get(other, pivot), other == row);
+                }
+            }
+            // row operations do not change the kernel
+            for vector in matrix.kernel() {
+                assert!(reduced.multiply_vector(&vector).is_zero());
+            }
+        }
+
+        #[test]
+        fn gf128_matrices_match_the_field(constant: u128, element: u128) {
+            let vector = BitVector::from_gf128(element);
+            assert_eq!(vector.to_gf128(), element);
+
+            let product = BitMatrix::gf128_multiply_by(constant).multiply_vector(&vector);
+            assert_eq!(product.to_gf128(), gf128_multiply(constant, element));
+            let square = BitMatrix::gf128_squaring().multiply_vector(&vector);
+            assert_eq!(square.to_gf128(), gf128_multiply(element, element));
+        }
+
+        #[test]
+        fn multiply_composes(a in matrix(5, 7), b in matrix(7, 3), bits in proptest::collection::vec(any::<bool>(), 3)) {
+            let vector = BitVector::from_bits(&bits);
+            assert_eq!(
+                a.multiply(&b).multiply_vector(&vector),
+                a.multiply_vector(&b.multiply_vector(&vector))
+            );
+        }
+    }
+
+    #[test]
+    fn test_gf128_multiply() {
+        // GHASH of test case 2 in the GCM specification: (C * H + L) * H
+        let h = 0x66E94BD4EF8A2C3B884CFA59CA342B2E;
+        let ciphertext = 0x0388DACE60B6A392F328C2B971B2FE78;
+        let lengths = 128;
+        let ghash = gf128_multiply(gf128_multiply(ciphertext, h) ^ lengths, h);
+        assert_eq!(ghash, 0xF38CBB1AD69223DCC3457AE5B6B0F885);
+
+        // 1 is the leading bit, and x^127 * x wraps around to the reduction
+        assert_eq!(gf128_multiply(1 << 127, h), h);
+        assert_eq!(gf128_multiply(1, 1 << 126), 0xE1 << 120);
+    }
+
+    #[test]
+    fn test_squaring_composes_to_powers() {
+        // squaring k times is raising to the power 2^k
+        let squaring = BitMatrix::gf128_squaring();
+        let fourth_power = squaring.multiply(&squaring);
+        let h = 0x66E94BD4EF8A2C3B884CFA59CA342B2E;
+        let square = gf128_multiply(h, h);
+        assert_eq!(
+            fourth_power
+                .multiply_vector(&BitVector::from_gf128(h))
+                .to_gf128(),
+            gf128_multiply(square, square)
+        );
+        assert_eq!(squaring.rank(), 128);
+    }
+
+    #[test]
+    fn test_identity_and_transpose() {
+        let identity = BitMatrix::identity(70);
+        assert_eq!(identity.rank(), 70);
+        assert!(identity.kernel().is_empty());
+        assert_eq!(identity.transpose(), identity);
+
+        let mut matrix = BitMatrix::zero(2, 3);
+        matrix.set(0, 2, true);
+        let transposed = matrix.transpose();
+        assert_eq!((transposed.row_count(), transposed.column_count()), (3, 2));
+        assert!(transposed.get(2, 0));
+        assert_eq!(matrix.add(&matrix), BitMatrix::zero(2, 3));
+    }
+}
@@ -0,0 +1,651 @@
+//! Generic attacks on Merkle–Damgård hashes (challenges 52, 53 and 54).
+//!
+//! [`TruncatedHash`] iterates a deliberately weak compression function: the
+//! state, zero-extended to 128 bits, is the AES-128 key, the message block
+//! is the plaintext, and the first `bits` bits of the ciphertext are the new
+//! state. With a state of 16 to 32 bits the generic attacks, which only rely
+//! on the iterated structure, finish in seconds:
+//!
+//! - Joux multicollisions (Crypto 2004): `n` successive block collisions
+//!   give `2^n` colliding messages for the price of `n` birthday searches,
+//!   which makes cascading two hashes barely stronger than the wider one
+//!   ([`joux_multicollision`], [`cascade_collision`]).
+//! - Kelsey–Schneier expandable messages (Eurocrypt 2005): messages of any
+//!   length in a range, all reaching one state, defeat the length padding
+//!   in a second-preimage search against a long message
+//!   ([`ExpandableMessage`], [`second_preimage`]).
+//! - Kelsey–Kohno herding (Eurocrypt 2006): a diamond structure of
+//!   collisions funnels many states into one, so a hash can be committed
+//!   to before the message it will match is known ([`DiamondStructure`]).
+//!
+//! Every hash counts its compression calls, which is how the attacks'
+//! costs are measured.
+
+use crate::crypto::aes::{Aes128, BLOCK_LEN};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// A message block of the hash.
+pub type Block = [u8; BLOCK_LEN];
+
+/// A Merkle–Damgård hash with a truncated AES compression function.
+///
+/// Messages are padded with `0x80`, zeros and the 64-bit big-endian bit
+/// length, to a multiple of 16 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::iterated_hash::TruncatedHash;
+///
+/// let hash = TruncatedHash::new(16);
+/// assert!(hash.hash(b"YELLOW SUBMARINE") < 1 << 16);
+/// // one message block and one padding block
+/// assert_eq!(hash.calls(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TruncatedHash {
+    bits: u32,
+    calls: Cell<u64>,
+}
+
+impl TruncatedHash {
+    /// Creates a hash with a state of `bits` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `bits` is between 8 and 64.
+    pub fn new(bits: u32) -> Self {
+        assert!((8..=64).contains(&bits), "state is 8 to 64 bits wide");
+        TruncatedHash {
+            bits,
+            calls: Cell::new(0),
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// The initial state: the top `bits` bits of a fixed constant.
+    pub fn initial_state(&self) -> u64 {
+        0x0123_4567_89AB_CDEF >> (64 - self.bits)
+    }
+
+    /// Compression calls made so far.
+    pub fn calls(&self) -> u64 {
+        self.calls.get()
+    }
+
+    pub fn reset_calls(&self) {
+        self.calls.set(0);
+    }
+
+    /// Compresses one block into the state.
+    pub fn compress(&self, state: u64, block: &Block) -> u64 {
+        self.calls.set(self.calls.get() + 1);
+
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&state.to_be_bytes());
+        let output = Aes128::new(&key).encrypt_block(block);
+        u64::from_be_bytes(output[..8].try_into().expect("8 bytes")) >> (64 - self.bits)
+    }
+
+    /// Compresses whole blocks into the state, without padding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length is not a multiple of [`BLOCK_LEN`].
+    pub fn iterate(&self, state: u64, blocks: &[u8]) -> u64 {
+        assert!(
+            blocks.len().is_multiple_of(BLOCK_LEN),
+            "length is a multiple of the block length"
+        );
+        blocks.chunks_exact(BLOCK_LEN).fold(state, |state, block| {
+            self.compress(state, block.try_into().expect("16 bytes"))
+        })
+    }
+
+    /// Compresses the padding of a `len`-byte message that has reached
+    /// `state` after its whole blocks.
+    pub fn finalize(&self, state: u64, tail: &[u8], len: u64) -> u64 {
+        self.iterate(state, &padded_tail(tail, len))
+    }
+
+    /// Hashes the message.
+    pub fn hash(&self, message: &[u8]) -> u64 {
+        let whole = message.len() - message.len() % BLOCK_LEN;
+        let state = self.iterate(self.initial_state(), &message[..whole]);
+        self.finalize(state, &message[whole..], message.len() as u64)
+    }
+}
+
+/// The last partial block of a message followed by its padding.
+fn padded_tail(tail: &[u8], len: u64) -> Vec<u8> {
+    let mut padded = tail.to_vec();
+    padded.push(0x80);
+    while padded.len() % BLOCK_LEN != BLOCK_LEN - 8 {
+        padded.push(0);
+    }
+    padded.extend((len * 8).to_be_bytes());
+    padded
+}
+
+/// The `index`-th block of one of several disjoint enumerations.
+fn block(stream: u64, index: u64) -> Block {
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&stream.to_be_bytes());
+    block[8..].copy_from_slice(&index.to_be_bytes());
+    block
+}
+
+/// Finds blocks `a` and `b` with `compress(from_a, a) == compress(from_b, b)`
+/// by a birthday search that grows a table of outputs from each side.
+///
+/// Returns the two blocks and the state they both lead to. The blocks are
+/// distinct even when the two states are the same. About `2^(bits / 2)`
+/// calls are made on each side.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::iterated_hash::{TruncatedHash, find_collision};
+///
+/// let hash = TruncatedHash::new(16);
+/// let state = hash.initial_state();
+/// let (a, b, next) = find_collision(&hash, state, state);
+/// assert_ne!(a, b);
+/// assert_eq!(hash.compress(state, &a), next);
+/// assert_eq!(hash.compress(state, &b), next);
+/// ```
+pub fn find_collision(hash: &TruncatedHash, from_a: u64, from_b: u64) -> (Block, Block, u64) {
+    let mut seen_a = HashMap::new();
+    let mut seen_b = HashMap::new();
+    for index in 0.. {
+        let (a, b) = (block(0, index), block(1, index));
+        let (state_a, state_b) = (hash.compress(from_a, &a), hash.compress(from_b, &b));
+        if let Some(other) = seen_b.get(&state_a) {
+            return (a, *other, state_a);
+        }
+        seen_a.insert(state_a, a);
+        if let Some(other) = seen_a.get(&state_b) {
+            return (*other, b, state_b);
+        }
+        seen_b.insert(state_b, b);
+    }
+    unreachable!("the state space is finite")
+}
+
+/// `2^n` messages of `n` blocks that all reach the same state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multicollision {
+    /// The two choices of block at each position.
+    pub pairs: Vec<(Block, Block)>,
+    /// The state every message reaches.
+    pub state: u64,
+}
+
+impl Multicollision {
+    /// The message choosing the second block wherever bit `i` of `index`
+    /// is set.
+    pub fn message(&self, index: u64) -> Vec<u8> {
+        self.pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(position, (first, second))| {
+                if index >> position & 1 == 1 {
+                    *second
+                } else {
+                    *first
+                }
+            })
+            .collect()
+    }
+}
+
+/// Builds a multicollision of `2^n` messages from `state` with `n`
+/// successive collisions (challenge 52).
+pub fn joux_multicollision(hash: &TruncatedHash, state: u64, n: usize) -> Multicollision {
+    let mut multicollision = Multicollision {
+        pairs: Vec::with_capacity(n),
+        state,
+    };
+    for _ in 0..n {
+        extend_multicollision(hash, &mut multicollision);
+    }
+    multicollision
+}
+
+fn extend_multicollision(hash: &TruncatedHash, multicollision: &mut Multicollision) {
+    let state = multicollision.state;
+    let (a, b, next) = find_collision(hash, state, state);
+    multicollision.pairs.push((a, b));
+    multicollision.state = next;
+}
+
+/// Finds two messages on which both `cheap` and `expensive` collide, so
+/// that the concatenated hash `cheap(m) || expensive(m)` does too
+/// (challenge 52).
+///
+/// A multicollision of `cheap` with `2^(expensive.bits() / 2)` messages is
+/// searched for a collision of `expensive`, and grown by one collision at a
+/// time until one turns up. All messages have the same length, so the
+/// padding keeps both collisions intact.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::iterated_hash::{TruncatedHash, cascade_collision};
+///
+/// let (cheap, expensive) = (TruncatedHash::new(12), TruncatedHash::new(16));
+/// let (a, b) = cascade_collision(&cheap, &expensive);
+/// assert_ne!(a, b);
+/// assert_eq!(cheap.hash(&a), cheap.hash(&b));
+/// assert_eq!(expensive.hash(&a), expensive.hash(&b));
+/// ```
+pub fn cascade_collision(cheap: &TruncatedHash, expensive: &TruncatedHash) -> (Vec<u8>, Vec<u8>) {
+    let n = expensive.bits().div_ceil(2) as usize;
+    let mut multicollision = joux_multicollision(cheap, cheap.initial_state(), n);
+    loop {
+        if let Some((a, b)) = expensive_collision(expensive, &multicollision) {
+            return (multicollision.message(a), multicollision.message(b));
+        }
+        extend_multicollision(cheap, &mut multicollision);
+    }
+}
+
+/// Hashes every message of the multicollision with `hash`, walking the
+/// tree of shared prefixes so each message costs about two calls, and
+/// returns the indices of two messages that collide.
+fn expensive_collision(
+    hash: &TruncatedHash,
+    multicollision: &Multicollision,
+) -> Option<(u64, u64)> {
+    let len = (multicollision.pairs.len() * BLOCK_LEN) as u64;
+    let mut seen = HashMap::new();
+    let mut stack = vec![(0usize, 0u64, hash.initial_state())];
+    while let Some((depth, index, state)) = stack.pop() {
+        if depth == multicollision.pairs.len() {
+            let digest = hash.finalize(state, &[], len);
+            if let Some(&other) = seen.get(&digest) {
+                return Some((other, index));
+            }
+            seen.insert(digest, index);
+            continue;
+        }
+        let (first, second) = &multicollision.pairs[depth];
+        stack.push((depth + 1, index, hash.compress(state, first)));
+        stack.push((depth + 1, index | 1 << depth, hash.compress(state, second)));
+    }
+    None
+}
+
+/// Messages of every length from `k` to `k + 2^k - 1` blocks that all
+/// reach the same state.
+///
+/// Piece `i` offers a choice between one block and `2^(k - 1 - i) + 1`
+/// blocks that collide; choosing the long form of the pieces whose bits are
+/// set in `len - k` gives a message of `len` blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandableMessage {
+    /// For each piece, its single block, the number of filler blocks before
+    /// the last block of the long form, and that last block.
+    pieces: Vec<(Block, u64, Block)>,
+    /// The state every message reaches.
+    pub state: u64,
+}
+
+/// The filler of the long forms; any fixed block works.
+const FILLER: Block = [0; BLOCK_LEN];
+
+impl ExpandableMessage {
+    /// Builds an expandable message with `k` pieces starting from `state`.
+    ///
+    /// Costs about `2^k` calls for the filler and `k` birthday searches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero or above 32.
+    pub fn new(hash: &TruncatedHash, state: u64, k: u32) -> Self {
+        assert!((1..=32).contains(&k), "1 to 32 pieces");
+
+        let mut pieces = Vec::with_capacity(k as usize);
+        let mut state = state;
+        for piece in 0..k {
+            let filler = 1u64 << (k - 1 - piece);
+            let filled = (0..filler).fold(state, |state, _| hash.compress(state, &FILLER));
+            let (short, long, next) = find_collision(hash, state, filled);
+            pieces.push((short, filler, long));
+            state = next;
+        }
+        ExpandableMessage { pieces, state }
+    }
+
+    /// The range of lengths, in blocks, the message can take.
+    pub fn lengths(&self) -> std::ops::RangeInclusive<u64> {
+        let k = self.pieces.len() as u64;
+        k..=k + (1 << k) - 1
+    }
+
+    /// The message of `len` blocks, or `None` if `len` is out of range.
+    pub fn message(&self, len: u64) -> Option<Vec<u8>> {
+        if !self.lengths().contains(&len) {
+            return None;
+        }
+
+        let extra = len - *self.lengths().start();
+        let k = self.pieces.len();
+        let mut message = Vec::with_capacity(len as usize * BLOCK_LEN);
+        for (piece, (short, filler, long)) in self.pieces.iter().enumerate() {
+            if extra >> (k - 1 - piece) & 1 == 1 {
+                for _ in 0..*filler {
+                    message.extend(FILLER);
+                }
+                message.extend(long);
+            } else {
+                message.extend(short);
+            }
+        }
+        Some(message)
+    }
+}
+
+/// Finds a different message of the same length and hash as `target`
+/// (challenge 53).
+///
+/// `target` must be `2^k` blocks or longer. An expandable message with `k`
+/// pieces is bridged by one block into one of the target's intermediate
+/// states, and then followed by the rest of the target. The bridge takes
+/// about `2^bits / 2^k` calls.
+///
+/// Returns `None` if the target is not a whole number of blocks or is too
+/// short for `k`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::iterated_hash::{TruncatedHash, second_preimage};
+///
+/// let hash = TruncatedHash::new(16);
+/// let target: Vec<u8> = (0..16 * 256).map(|idx| idx as u8).collect();
+/// let forged = second_preimage(&hash, &target, 8).unwrap();
+/// assert_ne!(forged, target);
+/// assert_eq!(forged.len(), target.len());
+/// assert_eq!(hash.hash(&forged), hash.hash(&target));
+/// ```
+pub fn second_preimage(hash: &TruncatedHash, target: &[u8], k: u32) -> Option<Vec<u8>> {
+    let blocks = (target.len() / BLOCK_LEN) as u64;
+    if !target.len().is_multiple_of(BLOCK_LEN) || !(1..=32).contains(&k) || blocks < 1 << k {
+        return None;
+    }
+
+    let expandable = ExpandableMessage::new(hash, hash.initial_state(), k);
+
+    // the state after block `j` (counting from 1) of the target, for every
+    // `j` the expandable message can reach with one bridge block after it
+    let mut states = HashMap::new();
+    let mut state = hash.initial_state();
+    for (j, block) in (1..).zip(target.chunks_exact(BLOCK_LEN)) {
+        state = hash.compress(state, block.try_into().expect("16 bytes"));
+        let prefix = j - 1;
+        if expandable.lengths().contains(&prefix) {
+            states.entry(state).or_insert(j);
+        }
+    }
+
+    let (bridge, j) = (0..).find_map(|index| {
+        let bridge = block(2, index);
+        let j = *states.get(&hash.compress(expandable.state, &bridge))?;
+        Some((bridge, j))
+    })?;
+
+    let mut forged = expandable.message(j - 1)?;
+    forged.extend(bridge);
+    forged.extend(&target[j as usize * BLOCK_LEN..]);
+    Some(forged)
+}
+
+/// A binary tree of collisions funnelling `2^k` leaf states into one root
+/// state (challenge 54).
+#[derive(Debug, Clone)]
+pub struct DiamondStructure {
+    /// Level 0 holds the leaves; each node stores its state and the block
+    /// that takes it to its parent, at index `i / 2` of the next level.
+    levels: Vec<Vec<(u64, Block)>>,
+    leaves: HashMap<u64, usize>,
+    root: u64,
+}
+
+impl DiamondStructure {
+    /// Builds a diamond structure over `2^k` distinct leaf states.
+    ///
+    /// Costs about `2^k` birthday searches, that is `2^(k + bits / 2 + 1)`
+    /// calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is above 20 or `2^k` is more than half the states.
+    pub fn new(hash: &TruncatedHash, k: u32) -> Self {
+        assert!(k <= 20 && k < hash.bits(), "2^k leaves fit in the state");
+
+        // leaves are spread over the state space and distinct
+        let step = 1u64 << (hash.bits() - k);
+        let mut states: Vec<u64> = (0..1u64 << k)
+            .map(|index| index * step + step / 2)
+            .collect();
+        let leaves = states
+            .iter()
+            .enumerate()
+            .map(|(idx, &state)| (state, idx))
+            .collect();
+
+        let mut levels = Vec::with_capacity(k as usize);
+        while states.len() > 1 {
+            let mut level = Vec::with_capacity(states.len());
+            let mut parents = Vec::with_capacity(states.len() / 2);
+            for pair in states.chunks_exact(2) {
+                let (a, b, parent) = find_collision(hash, pair[0], pair[1]);
+                level.push((pair[0], a));
+                level.push((pair[1], b));
+                parents.push(parent);
+            }
+            levels.push(level);
+            states = parents;
+        }
+
+        DiamondStructure {
+            levels,
+            leaves,
+            root: states[0],
+        }
+    }
+
+    /// The state every leaf leads to.
+    pub fn root(&self) -> u64 {
+        self.root
+    }
+
+    /// Depth of the tree, which is the number of blocks from a leaf to the
+    /// root.
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// The blocks from the leaf with the given state to the root, or `None`
+    /// if the state is not a leaf.
+    pub fn path(&self, leaf: u64) -> Option<Vec<u8>> {
+        let mut index = *self.leaves.get(&leaf)?;
+        let mut path = Vec::with_capacity(self.depth() * BLOCK_LEN);
+        for level in &self.levels {
+            path.extend(level[index].1);
+            index /= 2;
+        }
+        Some(path)
+    }
+
+    /// The hash to commit to for messages whose prefix has
+    /// `prefix_blocks` blocks: the root finalized for the whole length,
+    /// prefix, linking block and path.
+    pub fn prediction(&self, hash: &TruncatedHash, prefix_blocks: usize) -> u64 {
+        let len = (prefix_blocks + 1 + self.depth()) * BLOCK_LEN;
+        hash.finalize(self.root, &[], len as u64)
+    }
+
+    /// Extends `prefix` into a message whose hash is
+    /// [`prediction`](Self::prediction) by searching for a block linking
+    /// it to a leaf, which takes about `2^bits / 2^k` calls.
+    ///
+    /// Returns `None` if the prefix is not a whole number of blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::analysis::iterated_hash::{DiamondStructure, TruncatedHash};
+    ///
+    /// let hash = TruncatedHash::new(16);
+    /// let diamond = DiamondStructure::new(&hash, 4);
+    /// let prediction = diamond.prediction(&hash, 2);
+    ///
+    /// let message = diamond.herd(&hash, b"Red Sox 3, Yankees 2 (final)    ").unwrap();
+    /// assert_eq!(hash.hash(&message), prediction);
+    /// ```
+    pub fn herd(&self, hash: &TruncatedHash, prefix: &[u8]) -> Option<Vec<u8>> {
+        if !prefix.len().is_multiple_of(BLOCK_LEN) {
+            return None;
+        }
+
+        let state = hash.iterate(hash.initial_state(), prefix);
+        let (link, leaf) = (0..).find_map(|index| {
+            let link = block(3, index);
+            let leaf = hash.compress(state, &link);
+            self.leaves.contains_key(&leaf).then_some((link, leaf))
+        })?;
+
+        let mut message = prefix.to_vec();
+        message.extend(link);
+        message.extend(self.path(leaf)?);
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(padded_tail(b"", 0).len(), 16);
+        assert_eq!(padded_tail(b"abcdefg", 7).len(), 16);
+        assert_eq!(padded_tail(b"abcdefgh", 8).len(), 32);
+        assert_eq!(padded_tail(b"abc", 35)[8..], 280u64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_width_and_counter() {
+        for bits in [8, 16, 24, 32, 64] {
+            let hash = TruncatedHash::new(bits);
+            let digest = hash.hash(&[7; 36]);
+            assert!(bits == 64 || digest < 1 << bits);
+            assert_eq!(hash.calls(), 3);
+        }
+
+        let hash = TruncatedHash::new(16);
+        hash.hash(b"");
+        hash.reset_calls();
+        assert_eq!(hash.calls(), 0);
+    }
+
+    #[test]
+    fn test_joux_multicollision() {
+        let hash = TruncatedHash::new(16);
+        let multicollision = joux_multicollision(&hash, hash.initial_state(), 4);
+        let digests: Vec<u64> = (0..16)
+            .map(|idx| hash.hash(&multicollision.message(idx)))
+            .collect();
+        assert!(digests.iter().all(|&digest| digest == digests[0]));
+
+        let mut messages: Vec<Vec<u8>> = (0..16).map(|idx| multicollision.message(idx)).collect();
+        messages.sort();
+        messages.dedup();
+        assert_eq!(messages.len(), 16);
+    }
+
+    #[test]
+    fn test_cascade_costs_little_more_than_the_expensive_hash() {
+        let (cheap, expensive) = (TruncatedHash::new(16), TruncatedHash::new(24));
+        let (a, b) = cascade_collision(&cheap, &expensive);
+        let (cheap_calls, expensive_calls) = (cheap.calls(), expensive.calls());
+
+        assert_ne!(a, b);
+        assert_eq!(cheap.hash(&a), cheap.hash(&b));
+        assert_eq!(expensive.hash(&a), expensive.hash(&b));
+        // a generic birthday attack on 40 bits would take about 2^20 calls
+        assert!(cheap_calls + expensive_calls < 1 << 18);
+    }
+
+    #[test]
+    fn test_expandable_message_lengths() {
+        let hash = TruncatedHash::new(16);
+        let expandable = ExpandableMessage::new(&hash, hash.initial_state(), 4);
+        assert_eq!(expandable.lengths(), 4..=19);
+
+        for len in expandable.lengths() {
+            let message = expandable.message(len).unwrap();
+            assert_eq!(message.len(), len as usize * BLOCK_LEN);
+            assert_eq!(
+                hash.iterate(hash.initial_state(), &message),
+                expandable.state
+            );
+        }
+        assert_eq!(expandable.message(3), None);
+        assert_eq!(expandable.message(20), None);
+    }
+
+    #[test]
+    fn test_second_preimage_rejects_short_targets() {
+        let hash = TruncatedHash::new(16);
+        assert_eq!(second_preimage(&hash, &[0; 16 * 15], 4), None);
+        assert_eq!(second_preimage(&hash, &[0; 16 * 16 + 1], 4), None);
+    }
+
+    #[test]
+    fn test_diamond_structure() {
+        let hash = TruncatedHash::new(16);
+        let diamond = DiamondStructure::new(&hash, 3);
+        assert_eq!(diamond.depth(), 3);
+
+        for &leaf in diamond.leaves.keys() {
+            let path = diamond.path(leaf).unwrap();
+            assert_eq!(hash.iterate(leaf, &path), diamond.root());
+        }
+        // leaves sit in the middle of their share of the states
+        assert_eq!(diamond.path(0), None);
+        assert_eq!(diamond.herd(&hash, b"partial"), None);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn herding_matches_the_prediction(prefix in proptest::collection::vec(any::<[u8; 16]>(), 0..3)) {
+            let hash = TruncatedHash::new(16);
+            let diamond = DiamondStructure::new(&hash, 4);
+            let prediction = diamond.prediction(&hash, prefix.len());
+
+            let message = diamond.herd(&hash, &prefix.concat()).unwrap();
+            prop_assert!(message.starts_with(&prefix.concat()));
+            prop_assert_eq!(hash.hash(&message), prediction);
+        }
+
+        #[test]
+        fn second_preimage_matches(seed: u8, extra in 0usize..20) {
+            let hash = TruncatedHash::new(16);
+            let target: Vec<u8> = (0..16 * (64 + extra)).map(|idx| (idx as u8) ^ seed).collect();
+            let forged = second_preimage(&hash, &target, 6).unwrap();
+            prop_assert_ne!(&forged, &target);
+            prop_assert_eq!(forged.len(), target.len());
+            prop_assert_eq!(hash.hash(&forged), hash.hash(&target));
+        }
+    }
+}
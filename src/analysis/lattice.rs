@@ -0,0 +1,361 @@
+//! Lattice basis reduction.
+//!
+//! [`lll`] is the algorithm of [Lenstra, Lenstra and Lovász, "Factoring
+//! polynomials with rational coefficients"](https://doi.org/10.1007/BF01457454)
+//! in exact arithmetic, so the reduced basis never depends on rounding. It
+//! scales the basis to integers and keeps the Gram-Schmidt coefficients as
+//! integers too, updated through reductions and swaps with exact divisions,
+//! as in Cohen, A Course in Computational Algebraic Number Theory,
+//! Algorithm 2.6.7, so no fraction ever needs reducing. Lattices of a few
+//! dozen dimensions with entries of a few hundred bits take seconds.
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::rational::Rational;
+
+/// The inner product of two vectors of the same length.
+///
+/// # Panics
+///
+/// Panics if the lengths differ.
+pub fn inner_product(a: &[Rational], b: &[Rational]) -> Rational {
+    assert_eq!(a.len(), b.len(), "the vectors have different lengths");
+    a.iter()
+        .zip(b)
+        .fold(Rational::zero(), |sum, (x, y)| sum + x * y)
+}
+
+/// `a - scale * b`, for vectors of the same length.
+fn subtract_multiple(a: &[Rational], scale: &Rational, b: &[Rational]) -> Vec<Rational> {
+    a.iter().zip(b).map(|(x, y)| x - scale * y).collect()
+}
+
+/// The Gram-Schmidt orthogonalization of `basis`, without normalization.
+///
+/// Vectors that depend on the ones before them come out as zero.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::lattice::{gram_schmidt, inner_product};
+/// use cryptopals::analysis::rational::Rational;
+///
+/// let basis = [[3, 1], [2, 2]].map(|row| row.map(Rational::from).to_vec());
+/// let orthogonal = gram_schmidt(&basis);
+/// assert_eq!(orthogonal[0], basis[0]);
+/// assert!(inner_product(&orthogonal[0], &orthogonal[1]).is_zero());
+/// ```
+pub fn gram_schmidt(basis: &[Vec<Rational>]) -> Vec<Vec<Rational>> {
+    let mut orthogonal: Vec<Vec<Rational>> = Vec::with_capacity(basis.len());
+    for vector in basis {
+        let projected = orthogonal
+            .iter()
+            .fold(vector.clone(), |projected, previous| {
+                let norm = inner_product(previous, previous);
+                if norm.is_zero() {
+                    return projected;
+                }
+                let coefficient = inner_product(vector, previous) / norm;
+                subtract_multiple(&projected, &coefficient, previous)
+            });
+        orthogonal.push(projected);
+    }
+    orthogonal
+}
+
+/// LLL-reduces the rows of `basis` with parameter `delta`, or returns
+/// `None` if the rows are linearly dependent.
+///
+/// The result spans the same lattice, each Gram-Schmidt coefficient is at
+/// most 1/2 in absolute value, and consecutive Gram-Schmidt vectors satisfy
+/// the Lovász condition `|b*_k|^2 >= (delta - mu_{k,k-1}^2) |b*_{k-1}|^2`.
+/// The first row is then within `(1 / (delta - 1/4))^((n - 1) / 2)` of the
+/// shortest nonzero vector of the lattice, and usually much closer. The
+/// usual `delta` is 3/4, or 99/100 for a stronger reduction.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::lattice::lll;
+/// use cryptopals::analysis::rational::Rational;
+///
+/// let basis = [[1, 1, 1], [-1, 0, 2], [3, 5, 6]].map(|row| row.map(Rational::from).to_vec());
+/// let delta = Rational::from(3) / Rational::from(4);
+/// let reduced = lll(&basis, &delta).unwrap();
+/// let expected = [[0, 1, 0], [1, 0, 1], [-1, 0, 2]].map(|row| row.map(Rational::from).to_vec());
+/// assert_eq!(reduced, expected);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the rows have different lengths or `delta` is not in
+/// `(1/4, 1]`.
+pub fn lll(basis: &[Vec<Rational>], delta: &Rational) -> Option<Vec<Vec<Rational>>> {
+    let quarter = Rational::from(1) / Rational::from(4);
+    assert!(
+        *delta > quarter && *delta <= Rational::from(1),
+        "delta must be in (1/4, 1]"
+    );
+    let n = basis.len();
+    if n == 0 {
+        return Some(Vec::new());
+    }
+
+    // scaling by a common denominator changes neither the Gram-Schmidt
+    // coefficients nor the reduction steps
+    let common = basis.iter().flatten().fold(BigUint::one(), |lcm, entry| {
+        let denominator = entry.denominator();
+        &lcm / lcm.gcd(denominator) * denominator
+    });
+    let common = Rational::from(common);
+    let mut basis: Vec<Vec<Rational>> = basis
+        .iter()
+        .map(|row| row.iter().map(|entry| entry * &common).collect())
+        .collect();
+
+    // d[i + 1] = |b*_0|^2 ... |b*_i|^2 and lambda[i][j] = d[j + 1] mu[i][j]
+    // are integers, and the updates divide them exactly
+    let mut d = vec![Rational::zero(); n + 1];
+    d[0] = Rational::from(1);
+    let mut lambda = vec![vec![Rational::zero(); n]; n];
+
+    // subtracts round(mu[k][l]) b_l from b_k, keeping mu[k][l] within 1/2
+    let reduce = |basis: &mut [Vec<Rational>],
+                  lambda: &mut [Vec<Rational>],
+                  d: &[Rational],
+                  k: usize,
+                  l: usize| {
+        if lambda[k][l].abs() * Rational::from(2) <= d[l + 1] {
+            return;
+        }
+        let rounded = round_quotient(&lambda[k][l], &d[l + 1]);
+        basis[k] = subtract_multiple(&basis[k], &rounded, &basis[l]);
+        lambda[k][l] = &lambda[k][l] - &rounded * &d[l + 1];
+        let (upper, lower) = lambda.split_at_mut(k);
+        for (target, source) in lower[0][..l].iter_mut().zip(&upper[l][..l]) {
+            *target = &*target - &rounded * source;
+        }
+    };
+
+    let (mut k, mut computed) = (0, None);
+    while k < n {
+        if computed.is_none_or(|computed| k > computed) {
+            computed = Some(k);
+            for j in 0..=k {
+                let mut u = inner_product(&basis[k], &basis[j]);
+                for i in 0..j {
+                    u = exact_quotient(&(&d[i + 1] * &u - &lambda[k][i] * &lambda[j][i]), &d[i]);
+                }
+                if j < k {
+                    lambda[k][j] = u;
+                } else {
+                    d[k + 1] = u;
+                }
+            }
+            if d[k + 1].is_zero() {
+                return None;
+            }
+        }
+        if k == 0 {
+            k = 1;
+            continue;
+        }
+
+        reduce(&mut basis, &mut lambda, &d, k, k - 1);
+        // |b*_k|^2 < (delta - mu^2) |b*_{k-1}|^2, times d[k]^2 / d[k - 1]
+        let coefficient = lambda[k][k - 1].clone();
+        if &d[k + 1] * &d[k - 1] < delta * &d[k] * &d[k] - &coefficient * &coefficient {
+            // swap b_k and b_{k-1} and update the orthogonalization
+            basis.swap(k, k - 1);
+            let (upper, lower) = lambda.split_at_mut(k);
+            upper[k - 1][..k - 1].swap_with_slice(&mut lower[0][..k - 1]);
+
+            let product = exact_quotient(
+                &(&d[k - 1] * &d[k + 1] + &coefficient * &coefficient),
+                &d[k],
+            );
+            for row in &mut lambda[k + 1..=computed.expect("row k is computed")] {
+                let t = row[k].clone();
+                row[k] = exact_quotient(&(&d[k + 1] * &row[k - 1] - &coefficient * &t), &d[k]);
+                row[k - 1] = exact_quotient(&(&product * &t + &coefficient * &row[k]), &d[k + 1]);
+            }
+            d[k] = product;
+            k = (k - 1).max(1);
+        } else {
+            for l in (0..k - 1).rev() {
+                reduce(&mut basis, &mut lambda, &d, k, l);
+            }
+            k += 1;
+        }
+    }
+    Some(
+        basis
+            .iter()
+            .map(|row| row.iter().map(|entry| entry / &common).collect())
+            .collect(),
+    )
+}
+
+/// `numerator / denominator` for integers when the division is exact,
+/// without the gcd that dividing [`Rational`]s takes.
+fn exact_quotient(numerator: &Rational, denominator: &Rational) -> Rational {
+    let magnitude = Rational::from(numerator.numerator() / denominator.numerator());
+    if numerator.is_negative() != denominator.is_negative() {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// `numerator / denominator` for integers, rounded to the nearest integer
+/// with halves rounded up like [`Rational::round`], without reducing the
+/// fraction first.
+fn round_quotient(numerator: &Rational, denominator: &Rational) -> Rational {
+    let (magnitude, divisor) = (numerator.numerator(), denominator.numerator());
+    let twice = magnitude + magnitude;
+    if numerator.is_negative() {
+        // -m/d rounds up to -floor((2m + d - 1) / 2d)
+        -Rational::from((twice + divisor - BigUint::one()) / (divisor + divisor))
+    } else {
+        Rational::from((twice + divisor) / (divisor + divisor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fraction(numerator: i64, denominator: i64) -> Rational {
+        Rational::from(numerator) / Rational::from(denominator)
+    }
+
+    fn rows<const N: usize>(rows: &[[Rational; N]]) -> Vec<Vec<Rational>> {
+        rows.iter().map(|row| row.to_vec()).collect()
+    }
+
+    /// The product of the squared Gram-Schmidt norms, the squared volume of
+    /// the lattice.
+    fn gram_determinant(basis: &[Vec<Rational>]) -> Rational {
+        gram_schmidt(basis)
+            .iter()
+            .fold(Rational::from(1), |product, vector| {
+                product * inner_product(vector, vector)
+            })
+    }
+
+    fn assert_reduced(basis: &[Vec<Rational>], delta: &Rational) {
+        let orthogonal = gram_schmidt(basis);
+        let half = fraction(1, 2);
+        let norm = |i: usize| inner_product(&orthogonal[i], &orthogonal[i]);
+        for (k, vector) in basis.iter().enumerate().skip(1) {
+            let mu = |j: usize| inner_product(vector, &orthogonal[j]) / norm(j);
+            for j in 0..k {
+                assert!(mu(j).abs() <= half);
+            }
+            let coefficient = mu(k - 1);
+            assert!(norm(k) >= (delta - &coefficient * &coefficient) * norm(k - 1));
+        }
+    }
+
+    #[test]
+    fn test_challenge_62_example() {
+        let basis = rows(&[
+            [
+                fraction(-2, 1),
+                fraction(0, 1),
+                fraction(2, 1),
+                fraction(0, 1),
+            ],
+            [
+                fraction(1, 2),
+                fraction(-1, 1),
+                fraction(0, 1),
+                fraction(0, 1),
+            ],
+            [
+                fraction(-1, 1),
+                fraction(0, 1),
+                fraction(-2, 1),
+                fraction(1, 2),
+            ],
+            [
+                fraction(-1, 1),
+                fraction(1, 1),
+                fraction(1, 1),
+                fraction(2, 1),
+            ],
+        ]);
+        let expected = rows(&[
+            [
+                fraction(1, 2),
+                fraction(-1, 1),
+                fraction(0, 1),
+                fraction(0, 1),
+            ],
+            [
+                fraction(-1, 1),
+                fraction(0, 1),
+                fraction(-2, 1),
+                fraction(1, 2),
+            ],
+            [
+                fraction(-1, 2),
+                fraction(0, 1),
+                fraction(1, 1),
+                fraction(2, 1),
+            ],
+            [
+                fraction(-3, 2),
+                fraction(-1, 1),
+                fraction(2, 1),
+                fraction(0, 1),
+            ],
+        ]);
+        let delta = fraction(99, 100);
+        assert_eq!(lll(&basis, &delta), Some(expected));
+    }
+
+    #[test]
+    fn test_dependent_rows() {
+        let delta = fraction(3, 4);
+        let basis = rows(&[
+            [fraction(1, 1), fraction(2, 1)],
+            [fraction(2, 1), fraction(4, 1)],
+        ]);
+        assert_eq!(lll(&basis, &delta), None);
+        assert_eq!(lll(&[], &delta), Some(Vec::new()));
+    }
+
+    #[test]
+    #[should_panic(expected = "delta must be in (1/4, 1]")]
+    fn test_rejects_small_delta() {
+        let _ = lll(&rows(&[[fraction(1, 1)]]), &fraction(1, 4));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn reduces_and_preserves_the_lattice(
+            entries in proptest::collection::vec(-1000i64..1000, 16),
+            denominator in 1i64..10,
+        ) {
+            let basis = entries
+                .chunks(4)
+                .map(|row| row.iter().map(|&entry| fraction(entry, denominator)).collect())
+                .collect::<Vec<Vec<_>>>();
+            let delta = fraction(3, 4);
+            let volume = gram_determinant(&basis);
+            match lll(&basis, &delta) {
+                None => prop_assert!(volume.is_zero()),
+                Some(reduced) => {
+                    assert_reduced(&reduced, &delta);
+                    // the same volume, and the rows stay in the lattice
+                    // spanned by the original ones because every step is
+                    // unimodular, so the lattices are equal
+                    prop_assert_eq!(gram_determinant(&reduced), volume);
+                }
+            }
+        }
+    }
+}
@@ -2,5 +2,18 @@
 //!
 //! This module provides tools for analyzing and breaking cryptographic systems.
 
+pub mod bignum;
+pub mod discrete_log;
 pub mod distance;
 pub mod frequency;
+pub mod gcm_forbidden;
+pub mod gcm_truncated;
+pub mod gf128;
+pub mod gf2;
+pub mod iterated_hash;
+pub mod lattice;
+pub mod md4_collision;
+pub mod number_theory;
+pub mod rational;
+pub mod rc4_bias;
+pub mod timing;
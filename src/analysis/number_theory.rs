@@ -0,0 +1,580 @@
+//! Factoring, primality, prime generation and the Chinese Remainder Theorem.
+//!
+//! Attacks on groups of composite order split the order into small primes,
+//! solve the problem modulo each of them and glue the answers back
+//! together. The helpers are generic over [`Integer`]: `u64` and `u128` for
+//! toy parameters, [`BigUint`] for real ones.
+
+use crate::analysis::bignum::BigUint;
+use crate::util::SplitMix64;
+use std::fmt::Debug;
+
+/// The unsigned integers [`trial_division`] and [`crt`] work with.
+///
+/// Methods take references so that [`BigUint`] is not cloned for every
+/// operation.
+pub trait Integer: Clone + Ord + Debug {
+    fn from_u64(value: u64) -> Self;
+
+    /// The value, if it fits a `u128`.
+    fn to_u128(&self) -> Option<u128>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::from_u64(0)
+    }
+
+    /// `self * other`, or `None` if it does not fit the type.
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+    /// The quotient and remainder of `self / divisor`.
+    ///
+    /// Panics if `divisor` is zero.
+    fn div_rem(&self, divisor: &Self) -> (Self, Self);
+
+    /// The quotient and remainder of `self / divisor`, for a small divisor.
+    fn div_rem_u64(&self, divisor: u64) -> (Self, u64);
+
+    /// `a + b mod modulus` for operands below the modulus.
+    fn add_mod(&self, other: &Self, modulus: &Self) -> Self;
+
+    /// `a - b mod modulus` for operands below the modulus.
+    fn sub_mod(&self, other: &Self, modulus: &Self) -> Self;
+
+    /// `a * b mod modulus` for operands below the modulus.
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($type:ty),*) => {$(
+        impl Integer for $type {
+            fn from_u64(value: u64) -> Self {
+                value as $type
+            }
+
+            fn to_u128(&self) -> Option<u128> {
+                Some(*self as u128)
+            }
+
+            fn checked_mul(&self, other: &Self) -> Option<Self> {
+                <$type>::checked_mul(*self, *other)
+            }
+
+            fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+                (self / divisor, self % divisor)
+            }
+
+            fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+                (self / divisor as $type, (self % divisor as $type) as u64)
+            }
+
+            fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+                let (sum, overflowed) = self.overflowing_add(*other);
+                if overflowed || sum >= *modulus {
+                    sum.wrapping_sub(*modulus)
+                } else {
+                    sum
+                }
+            }
+
+            fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+                if self >= other { self - other } else { modulus - (other - self) }
+            }
+
+            fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+                if let Some(product) = <$type>::checked_mul(*self, *other) {
+                    return product % modulus;
+                }
+                // double-and-add, which never leaves the type
+                let (mut result, mut addend, mut factor) = (0, *self, *other);
+                while factor > 0 {
+                    if factor & 1 == 1 {
+                        result = result.add_mod(&addend, modulus);
+                    }
+                    addend = addend.add_mod(&addend, modulus);
+                    factor >>= 1;
+                }
+                result
+            }
+        }
+    )*};
+}
+
+impl_integer!(u64, u128);
+
+impl Integer for BigUint {
+    fn from_u64(value: u64) -> Self {
+        BigUint::from(value)
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        BigUint::to_u128(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        BigUint::is_zero(self)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        BigUint::div_rem(self, divisor)
+    }
+
+    fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+        BigUint::div_rem_u64(self, divisor)
+    }
+
+    fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        (self + other) % modulus
+    }
+
+    fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        (self + modulus - other) % modulus
+    }
+
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        self * other % modulus
+    }
+}
+
+/// Splits off the prime factors of `n` up to `bound` by trial division.
+///
+/// Returns each prime factor found with its multiplicity, in increasing
+/// order, and the cofactor left over. The cofactor is 1 if `n` factored
+/// completely; when it is below `bound^2` it is prime as well.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::number_theory::trial_division;
+///
+/// let (factors, cofactor) = trial_division(2 * 2 * 3 * 1_000_003u64, 100);
+/// assert_eq!(factors, [(2, 2), (3, 1)]);
+/// assert_eq!(cofactor, 1_000_003);
+///
+/// let n = BigUint::from(6u64) * BigUint::from(u128::MAX);
+/// let (factors, _) = trial_division(n, 100);
+/// assert_eq!(factors, [(2, 1), (3, 2), (5, 1), (17, 1)]);
+/// ```
+pub fn trial_division<T: Integer>(n: T, bound: u64) -> (Vec<(u64, u32)>, T) {
+    let mut factors = Vec::new();
+    let mut cofactor = n;
+
+    let candidates = std::iter::once(2).chain((3..).step_by(2));
+    for candidate in candidates.take_while(|&candidate| candidate <= bound) {
+        let square = candidate as u128 * candidate as u128;
+        if let Some(small) = cofactor.to_u128()
+            && square > small
+        {
+            // whatever is left has no smaller factor, so it is prime
+            if small > 1 && small <= bound as u128 {
+                factors.push((small as u64, 1));
+                cofactor = T::from_u64(1);
+            }
+            break;
+        }
+        let mut multiplicity = 0;
+        loop {
+            let (quotient, remainder) = cofactor.div_rem_u64(candidate);
+            if remainder != 0 {
+                break;
+            }
+            cofactor = quotient;
+            multiplicity += 1;
+        }
+        if multiplicity > 0 {
+            factors.push((candidate, multiplicity));
+        }
+    }
+
+    (factors, cofactor)
+}
+
+/// Whether `n` is prime, by Miller-Rabin.
+///
+/// The first twelve primes as bases make the test exact for every `u64`
+/// (Sorenson and Webster, "Strong pseudoprimes to twelve prime bases").
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::number_theory::is_prime;
+///
+/// assert!(is_prime(2) && is_prime(18446744073709551557));
+/// assert!(!is_prime(1) && !is_prime(3215031751));
+/// ```
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&base) = BASES.iter().find(|&&base| n.is_multiple_of(base)) {
+        return n == base;
+    }
+
+    let mul_mod = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+    let (odd, twos) = (
+        (n - 1) >> (n - 1).trailing_zeros(),
+        (n - 1).trailing_zeros(),
+    );
+    BASES.iter().all(|&base| {
+        // base^odd by square-and-multiply
+        let (mut x, mut square, mut exponent) = (1, base, odd);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                x = mul_mod(x, square);
+            }
+            square = mul_mod(square, square);
+            exponent >>= 1;
+        }
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..twos).any(|_| {
+            x = mul_mod(x, x);
+            x == n - 1
+        })
+    })
+}
+
+/// Small primes that candidates are divided by before Miller-Rabin.
+const SIEVE_PRIMES: [u64; 24] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Miller-Rabin rounds of [`is_probable_prime`].
+const MILLER_RABIN_ROUNDS: usize = 32;
+
+/// A uniform value below `bound`, from 64 more random bits than it has.
+///
+/// The extra bits keep the bias of the final reduction below 2^-64.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::number_theory::random_below;
+/// use cryptopals::util::SplitMix64;
+///
+/// let bound = BigUint::from(u128::MAX) * BigUint::from(3u64);
+/// assert!(random_below(&bound, &mut SplitMix64::new(1)) < bound);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `bound` is zero.
+pub fn random_below(bound: &BigUint, rng: &mut SplitMix64) -> BigUint {
+    let mut bytes = vec![0; bound.bits().div_ceil(8) as usize + 8];
+    rng.fill_bytes(&mut bytes);
+    BigUint::from_be_bytes(&bytes) % bound
+}
+
+/// Whether `n` is probably prime, by Miller-Rabin with random bases.
+///
+/// A composite passes each of the rounds with probability at most 1/4, and
+/// the bases are fresh for every call, so no fixed composite fools it.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::number_theory::is_probable_prime;
+///
+/// // 2^127 - 1 is a Mersenne prime, 2^128 + 1 is not prime
+/// assert!(is_probable_prime(&BigUint::from((1u128 << 127) - 1)));
+/// assert!(!is_probable_prime(&(BigUint::from(u128::MAX) + BigUint::from(2u64))));
+/// ```
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    if let Some(n) = n.to_u64() {
+        return is_prime(n);
+    }
+    if !n.bit(0)
+        || SIEVE_PRIMES
+            .iter()
+            .any(|&prime| n.div_rem_u64(prime).1 == 0)
+    {
+        return false;
+    }
+
+    let one = BigUint::one();
+    let minus_one = n - &one;
+    let twos = (0..).find(|&index| minus_one.bit(index)).expect("n > 1");
+    let odd = &minus_one >> twos as usize;
+    let mut rng = SplitMix64::from_entropy();
+    (0..MILLER_RABIN_ROUNDS).all(|_| {
+        // a base in [2, n - 2]
+        let base = random_below(&(n - BigUint::from(3u64)), &mut rng) + BigUint::from(2u64);
+        let mut x = base.pow_mod(&odd, n);
+        if x == one || x == minus_one {
+            return true;
+        }
+        (1..twos).any(|_| {
+            x = &x * &x % n;
+            x == minus_one
+        })
+    })
+}
+
+/// A random prime of exactly `bits` bits, with the top two bits set so that
+/// the product of two of them has exactly twice as many.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::number_theory::{is_probable_prime, random_prime};
+/// use cryptopals::util::SplitMix64;
+///
+/// let prime = random_prime(128, &mut SplitMix64::new(1));
+/// assert_eq!(prime.bits(), 128);
+/// assert!(is_probable_prime(&prime));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `bits` is below 3.
+pub fn random_prime(bits: u64, rng: &mut SplitMix64) -> BigUint {
+    assert!(bits >= 3, "a prime with the top two bits set has 3 bits");
+    let top = (BigUint::one() << (bits - 1) as usize) + (BigUint::one() << (bits - 2) as usize);
+    let low = BigUint::one() << (bits - 2) as usize;
+    loop {
+        let candidate = &top + random_below(&low, rng);
+        let candidate = if candidate.bit(0) {
+            candidate
+        } else {
+            candidate + BigUint::one()
+        };
+        if is_probable_prime(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// Combines congruences `x = residue (mod modulus)` into one.
+///
+/// The moduli need not be coprime. Returns `(residue, modulus)` for the
+/// least common multiple of the moduli, or `None` if the congruences
+/// contradict each other or the combined modulus overflows the type.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::number_theory::crt;
+///
+/// assert_eq!(crt(&[(2u128, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt(&[(1u64, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt(&[(1u64, 4), (2, 6)]), None);
+/// ```
+///
+/// # Panics
+///
+/// Panics if a modulus is zero.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences.iter().try_fold(
+        (T::from_u64(0), T::from_u64(1)),
+        |(residue, modulus), (next_residue, next_modulus)| {
+            assert!(!next_modulus.is_zero(), "moduli must be positive");
+            let reduce = |value: &T| value.div_rem(next_modulus).1;
+            let next_residue = reduce(next_residue);
+
+            // residue + modulus * k = next_residue (mod next_modulus)
+            let (divisor, inverse) = gcd_inverse(&reduce(&modulus), next_modulus);
+            let difference = next_residue.sub_mod(&reduce(&residue), next_modulus);
+            let (difference, remainder) = difference.div_rem(&divisor);
+            if !remainder.is_zero() {
+                return None;
+            }
+            let reduced_modulus = next_modulus.div_rem(&divisor).0;
+            let k = difference.mul_mod(&inverse, &reduced_modulus);
+
+            let combined = modulus.checked_mul(&reduced_modulus)?;
+            // below the combined modulus, since k < reduced_modulus
+            let offset = modulus.mul_mod(&k, &combined);
+            Some((residue.add_mod(&offset, &combined), combined))
+        },
+    )
+}
+
+/// Returns `gcd(a, m)` and the inverse of `a / gcd` modulo `m / gcd`.
+fn gcd_inverse<T: Integer>(a: &T, m: &T) -> (T, T) {
+    let (mut old_r, mut r) = (a.clone(), m.clone());
+    // Bezout coefficients of `a`, kept modulo `m` to stay unsigned
+    let (mut old_s, mut s) = (T::from_u64(1).div_rem(m).1, T::from_u64(0));
+    while !r.is_zero() {
+        let (quotient, remainder) = old_r.div_rem(&r);
+        old_r = std::mem::replace(&mut r, remainder);
+        let step = quotient.div_rem(m).1.mul_mod(&s, m);
+        let next_s = old_s.sub_mod(&step, m);
+        old_s = std::mem::replace(&mut s, next_s);
+    }
+    let reduced = m.div_rem(&old_r).0;
+    let inverse = old_s.div_rem(&reduced).1;
+    (old_r, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn big(value: u128) -> BigUint {
+        BigUint::from(value)
+    }
+
+    proptest! {
+        #[test]
+        fn trial_division_multiplies_back(n in 1u64.., bound in 2u64..5000) {
+            let (factors, cofactor) = trial_division(n, bound);
+            let product = factors
+                .iter()
+                .map(|&(prime, multiplicity)| prime.pow(multiplicity))
+                .product::<u64>();
+            assert_eq!(product * cofactor, n);
+            for &(prime, _) in &factors {
+                assert!(prime <= bound);
+                assert!((2..prime).take_while(|d| d * d <= prime).all(|d| prime % d != 0));
+            }
+            // the cofactor has no factor up to the bound
+            assert!((2..=bound).all(|d| cofactor % d != 0));
+        }
+
+        #[test]
+        fn crt_satisfies_every_congruence(x: u64, moduli in proptest::collection::vec(1u128..1 << 40, 1..4)) {
+            let congruences = moduli
+                .iter()
+                .map(|&modulus| (x as u128 % modulus, modulus))
+                .collect::<Vec<_>>();
+            let (residue, modulus) = crt(&congruences).unwrap();
+            assert!(residue < modulus);
+            for &(expected, m) in &congruences {
+                assert_eq!(residue % m, expected);
+                assert_eq!(modulus % m, 0);
+            }
+            if modulus > x as u128 {
+                assert_eq!(residue, x as u128);
+            }
+
+            let big_congruences = congruences
+                .iter()
+                .map(|&(residue, modulus)| (big(residue), big(modulus)))
+                .collect::<Vec<_>>();
+            assert_eq!(crt(&big_congruences), Some((big(residue), big(modulus))));
+        }
+
+        #[test]
+        fn trial_division_agrees_on_biguint(n in 1u64.., bound in 2u64..5000) {
+            let (factors, cofactor) = trial_division(n, bound);
+            assert_eq!(trial_division(big(n as u128), bound), (factors, big(cofactor as u128)));
+        }
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let sieved = (0..2000u64)
+            .filter(|&n| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            (0..2000).filter(|&n| is_prime(n)).collect::<Vec<_>>(),
+            sieved
+        );
+
+        // strong pseudoprimes to the first few bases
+        for composite in [2047, 1373653, 25326001, 3215031751, 2152302898747] {
+            assert!(!is_prime(composite));
+        }
+        assert!(is_prime((1 << 61) - 1));
+        assert!(!is_prime(u64::MAX));
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        let big = |value: u128| BigUint::from(value);
+        // M127, and a Carmichael number times a large prime
+        assert!(is_probable_prime(&big((1 << 127) - 1)));
+        assert!(!is_probable_prime(&(big(561) * big((1 << 127) - 1))));
+        assert!(!is_probable_prime(
+            &(big((1 << 61) - 1) * big((1 << 89) - 1))
+        ));
+        assert!(is_probable_prime(&big(2)));
+        assert!(!is_probable_prime(&big(1)));
+
+        let mut rng = SplitMix64::new(7);
+        let (p, q) = (random_prime(80, &mut rng), random_prime(80, &mut rng));
+        assert_eq!((p.bits(), q.bits()), (80, 80));
+        assert_eq!((&p * &q).bits(), 160);
+        assert!(!is_probable_prime(&(&p * &q)));
+    }
+
+    #[test]
+    fn test_trial_division() {
+        assert_eq!(trial_division(1u64, 100), (vec![], 1));
+        assert_eq!(trial_division(97u64, 100), (vec![(97, 1)], 1));
+        assert_eq!(trial_division(1u64 << 40, 2), (vec![(2, 40)], 1));
+        // the largest prime below 2^64 has no small factor
+        assert_eq!(
+            trial_division(18446744073709551557u64, 1000),
+            (vec![], 18446744073709551557)
+        );
+        // a composite cofactor above the bound is kept whole
+        assert_eq!(
+            trial_division(6 * 101 * 103u64, 100),
+            (vec![(2, 1), (3, 1)], 10403)
+        );
+    }
+
+    #[test]
+    fn test_challenge_57_parameters() {
+        // p - 1 = j * q for the 512-bit prime of challenge 57
+        let p = BigUint::from_decimal("7199773997391911030609999317773941274322764333428698921736339643928346453700085358802973900485592910475480089726140708102474957429903531369589969318716771").unwrap();
+        let q = BigUint::from_decimal("236234353446506858198510045061214171961").unwrap();
+
+        let (factors, cofactor) = trial_division(&p - BigUint::one(), 1 << 16);
+        assert_eq!(
+            factors,
+            [
+                (2, 1),
+                (3, 2),
+                (5, 1),
+                (109, 1),
+                (7963, 1),
+                (8539, 1),
+                (20641, 1),
+                (38833, 1),
+                (39341, 1),
+                (46337, 1),
+                (51977, 1),
+                (54319, 1),
+                (57529, 1)
+            ]
+        );
+        assert_eq!(cofactor % &q, BigUint::zero());
+
+        // the residues of a secret below q modulo the small factors give it back
+        let secret = &q - big(12345);
+        let congruences = factors
+            .iter()
+            .filter(|&&(_, multiplicity)| multiplicity == 1)
+            .map(|&(factor, _)| (&secret % big(factor as u128), big(factor as u128)))
+            .collect::<Vec<_>>();
+        let (residue, modulus) = crt(&congruences).unwrap();
+        assert!(modulus > q);
+        assert_eq!(residue, secret);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt::<u128>(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(5u64, 3)]), Some((2, 3)));
+        // moduli whose product overflows
+        let large = 1u128 << 100;
+        assert_eq!(crt(&[(1, large - 1), (1, large + 1)]), None);
+        // residues near the top of u128
+        let modulus = u128::MAX - 158; // a prime
+        assert_eq!(
+            crt(&[(modulus - 1, modulus), (0, 1)]),
+            Some((modulus - 1, modulus))
+        );
+    }
+}
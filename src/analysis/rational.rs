@@ -0,0 +1,328 @@
+//! Exact rational numbers.
+//!
+//! [`Rational`] is a sign and a pair of [`BigUint`]s in lowest terms, for
+//! the algorithms whose correctness depends on exact arithmetic, such as the
+//! Gram-Schmidt coefficients of lattice reduction. Every operation with a
+//! fractional result reduces it by a gcd, so the numbers stay as short as
+//! they can be but the arithmetic is far slower than on floating point.
+
+use crate::analysis::bignum::BigUint;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A rational number of any size.
+///
+/// Arithmetic is available through the operators, on values and on
+/// references. Division panics on a zero divisor.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::rational::Rational;
+///
+/// let third = Rational::from(1) / Rational::from(3);
+/// let sum = &third + &third + &third;
+/// assert_eq!(sum, Rational::from(1));
+/// assert_eq!((Rational::from(-7) / Rational::from(2)).to_string(), "-7/2");
+/// assert_eq!((Rational::from(-7) / Rational::from(2)).round(), Rational::from(-3));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    /// Whether the value is below zero; never set for zero.
+    negative: bool,
+    numerator: BigUint,
+    /// Positive and coprime to the numerator.
+    denominator: BigUint,
+}
+
+impl Rational {
+    /// The fraction `numerator / denominator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero.
+    pub fn new(numerator: BigUint, denominator: BigUint) -> Self {
+        Rational::from_parts(false, numerator, denominator)
+    }
+
+    fn from_parts(negative: bool, numerator: BigUint, denominator: BigUint) -> Self {
+        assert!(!denominator.is_zero(), "attempt to divide by zero");
+        if denominator == BigUint::one() {
+            return Rational {
+                negative: negative && !numerator.is_zero(),
+                numerator,
+                denominator,
+            };
+        }
+        let gcd = numerator.gcd(&denominator);
+        Rational {
+            negative: negative && !numerator.is_zero(),
+            numerator: &numerator / &gcd,
+            denominator: &denominator / &gcd,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Rational::from(BigUint::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == BigUint::one()
+    }
+
+    /// The numerator of the absolute value in lowest terms.
+    pub fn numerator(&self) -> &BigUint {
+        &self.numerator
+    }
+
+    /// The denominator in lowest terms, which is positive.
+    pub fn denominator(&self) -> &BigUint {
+        &self.denominator
+    }
+
+    pub fn abs(&self) -> Self {
+        Rational {
+            negative: false,
+            ..self.clone()
+        }
+    }
+
+    /// The greatest integer not above the value.
+    pub fn floor(&self) -> Self {
+        let (quotient, remainder) = self.numerator.div_rem(&self.denominator);
+        let magnitude = if self.negative && !remainder.is_zero() {
+            quotient + BigUint::one()
+        } else {
+            quotient
+        };
+        Rational::from_parts(self.negative, magnitude, BigUint::one())
+    }
+
+    /// The nearest integer, rounding halves up.
+    pub fn round(&self) -> Self {
+        (self + Rational::new(BigUint::one(), BigUint::from(2u64))).floor()
+    }
+}
+
+impl From<BigUint> for Rational {
+    fn from(value: BigUint) -> Self {
+        Rational::new(value, BigUint::one())
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::from_parts(
+            value < 0,
+            BigUint::from(value.unsigned_abs()),
+            BigUint::one(),
+        )
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the magnitudes compare like the cross products
+        let magnitudes =
+            || (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator));
+        match (self.negative, other.negative) {
+            (false, false) => magnitudes(),
+            (true, true) => magnitudes().reverse(),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            negative: !self.negative && !self.is_zero(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        -&self
+    }
+}
+
+impl Add<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational {
+        let left = &self.numerator * &other.denominator;
+        let right = &other.numerator * &self.denominator;
+        let denominator = &self.denominator * &other.denominator;
+        if self.negative == other.negative {
+            return Rational::from_parts(self.negative, left + right, denominator);
+        }
+        // the sign of the one with the larger magnitude wins
+        match left.checked_sub(&right) {
+            Some(difference) => Rational::from_parts(self.negative, difference, denominator),
+            None => Rational::from_parts(other.negative, right - left, denominator),
+        }
+    }
+}
+
+impl Sub<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational {
+        self + &-other
+    }
+}
+
+impl Mul<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn mul(self, other: &Rational) -> Rational {
+        Rational::from_parts(
+            self.negative != other.negative,
+            &self.numerator * &other.numerator,
+            &self.denominator * &other.denominator,
+        )
+    }
+}
+
+impl Div<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn div(self, other: &Rational) -> Rational {
+        Rational::from_parts(
+            self.negative != other.negative,
+            &self.numerator * &other.denominator,
+            &self.denominator * &other.numerator,
+        )
+    }
+}
+
+/// Forwards the operators on values to the ones on references.
+macro_rules! forward_binary_ops {
+    ($($trait:ident $method:ident),*) => {$(
+        impl $trait<Rational> for Rational {
+            type Output = Rational;
+
+            fn $method(self, other: Rational) -> Rational {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $trait<&Rational> for Rational {
+            type Output = Rational;
+
+            fn $method(self, other: &Rational) -> Rational {
+                (&self).$method(other)
+            }
+        }
+
+        impl $trait<Rational> for &Rational {
+            type Output = Rational;
+
+            fn $method(self, other: Rational) -> Rational {
+                self.$method(&other)
+            }
+        }
+    )*};
+}
+
+forward_binary_ops!(Add add, Sub sub, Mul mul, Div div);
+
+impl fmt::Display for Rational {
+    /// Writes the value as `numerator/denominator`, or as an integer.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = if self.is_integer() {
+            self.numerator.to_string()
+        } else {
+            format!("{}/{}", self.numerator, self.denominator)
+        };
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl fmt::Debug for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fraction(numerator: i64, denominator: i64) -> Rational {
+        Rational::from(numerator) / Rational::from(denominator)
+    }
+
+    proptest! {
+        #[test]
+        fn matches_cross_multiplied_integers(
+            a in -1000i64..1000,
+            b in 1i64..1000,
+            c in -1000i64..1000,
+            d in 1i64..1000,
+        ) {
+            let (x, y) = (fraction(a, b), fraction(c, d));
+            prop_assert_eq!(&x + &y, fraction(a * d + c * b, b * d));
+            prop_assert_eq!(&x - &y, fraction(a * d - c * b, b * d));
+            prop_assert_eq!(&x * &y, fraction(a * c, b * d));
+            prop_assert_eq!(x.cmp(&y), (a * d).cmp(&(c * b)));
+            if c != 0 {
+                prop_assert_eq!(&x / &y, fraction(a * d, b * c));
+            }
+        }
+
+        #[test]
+        fn floor_and_round(a in -1000i64..1000, b in 1i64..100) {
+            let x = fraction(a, b);
+            prop_assert_eq!(x.floor(), Rational::from(a.div_euclid(b)));
+            let distance = (&x - x.round()).abs();
+            prop_assert!(distance <= fraction(1, 2));
+            prop_assert!(x.round().is_integer());
+        }
+    }
+
+    #[test]
+    fn test_lowest_terms() {
+        let x = fraction(-6, 4);
+        assert!(x.is_negative());
+        assert_eq!(x.numerator(), &BigUint::from(3u64));
+        assert_eq!(x.denominator(), &BigUint::from(2u64));
+        assert_eq!(format!("{}", x), "-3/2");
+
+        let zero = fraction(-3, 4) + fraction(3, 4);
+        assert!(zero.is_zero() && !zero.is_negative());
+        assert_eq!(zero, Rational::zero());
+        assert_eq!(-Rational::zero(), Rational::zero());
+        assert_eq!(fraction(5, -2).round(), Rational::from(-2));
+        assert_eq!(fraction(5, 2).round(), Rational::from(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_division_by_zero() {
+        let _ = Rational::from(1) / Rational::zero();
+    }
+}
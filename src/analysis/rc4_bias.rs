@@ -0,0 +1,228 @@
+//! Single-byte keystream bias attack on RC4.
+//!
+//! RC4 keystream bytes are not uniformly distributed: the 16th byte leans
+//! towards `0xF0` and the 32nd byte towards `0xE0` (AlFardan et al., 2013).
+//! When the same secret is encrypted under many fresh keys, the most frequent
+//! ciphertext byte at one of those positions is the secret byte XOR the bias.
+
+use crate::analysis::frequency::ByteCounts;
+use std::thread;
+
+/// Index of the 16th keystream byte, which is biased towards [`Z16_BIAS`].
+pub const Z16_INDEX: usize = 15;
+/// Value favoured by the 16th keystream byte.
+pub const Z16_BIAS: u8 = 0xF0;
+/// Index of the 32nd keystream byte, which is biased towards [`Z32_BIAS`].
+pub const Z32_INDEX: usize = 31;
+/// Value favoured by the 32nd keystream byte.
+pub const Z32_BIAS: u8 = 0xE0;
+
+/// Counts the ciphertext bytes seen at the given positions.
+///
+/// The oracle is queried `samples` times with the same request, split across
+/// `threads` worker threads. It must encrypt under a fresh key on every call.
+/// Returns how often each byte value occurred at each position, in the order
+/// of `positions`. Each worker counts into its own [`ByteCounts`], and the
+/// counts are merged at the end.
+///
+/// # Panics
+///
+/// Panics if `samples` or `threads` is zero, or if a ciphertext is shorter
+/// than a position.
+pub fn collect_counts<F>(
+    oracle: &F,
+    request: &[u8],
+    positions: &[usize],
+    samples: usize,
+    threads: usize,
+) -> Vec<ByteCounts>
+where
+    F: Fn(&[u8]) -> Vec<u8> + Sync,
+{
+    assert!(samples > 0, "at least one sample is needed");
+    assert!(threads > 0, "at least one worker thread is needed");
+
+    let per_thread = samples / threads;
+    let remainder = samples % threads;
+
+    let partials = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|worker| {
+                let worker_samples = per_thread + usize::from(worker < remainder);
+                scope.spawn(move || {
+                    let mut counts = vec![ByteCounts::default(); positions.len()];
+                    for _ in 0..worker_samples {
+                        let ciphertext = oracle(request);
+                        positions
+                            .iter()
+                            .zip(counts.iter_mut())
+                            .for_each(|(&position, counts)| counts.add(ciphertext[position]));
+                    }
+                    counts
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("worker thread does not panic"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut merged = vec![ByteCounts::default(); positions.len()];
+    for counts in partials {
+        for (total, partial) in merged.iter_mut().zip(&counts) {
+            total.merge(partial);
+        }
+    }
+
+    merged
+}
+
+/// Recovers a secret appended to attacker-controlled requests.
+///
+/// The oracle encrypts `request || cookie` with RC4 under a fresh key and
+/// returns the ciphertext. Padding the request shifts each cookie byte onto
+/// the 16th or 32nd keystream byte, where the bias gives it away. Each padding
+/// length is queried `samples` times, so the whole attack makes
+/// `16 * samples` queries.
+///
+/// # Panics
+///
+/// Panics if the cookie is longer than 32 bytes, which is the furthest the
+/// two biased positions can reach, or if `samples` or `threads` is zero.
+pub fn recover_cookie<F>(oracle: &F, cookie_len: usize, samples: usize, threads: usize) -> Vec<u8>
+where
+    F: Fn(&[u8]) -> Vec<u8> + Sync,
+{
+    assert!(
+        cookie_len <= Z32_INDEX + 1,
+        "cookie must fit before the 32nd keystream byte"
+    );
+
+    let mut cookie = vec![0u8; cookie_len];
+
+    for padding in 0..=Z16_INDEX {
+        let targets = [(Z16_INDEX, Z16_BIAS), (Z32_INDEX, Z32_BIAS)]
+            .into_iter()
+            .filter(|&(position, _)| position - padding < cookie_len)
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            continue;
+        }
+
+        let request = vec![b'A'; padding];
+        let positions = targets
+            .iter()
+            .map(|&(position, _)| position)
+            .collect::<Vec<_>>();
+        let counts = collect_counts(oracle, &request, &positions, samples, threads);
+
+        for ((position, bias), counts) in targets.into_iter().zip(counts) {
+            cookie[position - padding] = counts.most_frequent() ^ bias;
+        }
+    }
+
+    cookie
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::rc4::rc4;
+    use crate::util::splitmix64;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    /// Mimics a biased keystream: every other query uses the bias value at the
+    /// biased positions, the rest of the keystream is a running counter.
+    fn rigged_oracle(cookie: &[u8]) -> impl Fn(&[u8]) -> Vec<u8> + Sync + '_ {
+        let queries = AtomicUsize::new(0);
+        move |request: &[u8]| {
+            let query = queries.fetch_add(1, Ordering::Relaxed);
+            request
+                .iter()
+                .chain(cookie)
+                .enumerate()
+                .map(|(idx, &byte)| {
+                    let keystream = match idx {
+                        Z16_INDEX if query.is_multiple_of(2) => Z16_BIAS,
+                        Z32_INDEX if query.is_multiple_of(2) => Z32_BIAS,
+                        // odd values never collide with the (even) bias values
+                        _ => (query * 31 + idx * 7) as u8 | 1,
+                    };
+                    byte ^ keystream
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_collect_counts() {
+        let cookie = b"0123456789abcdefghijklmnopqrstuv";
+        let oracle = rigged_oracle(cookie);
+
+        let counts = collect_counts(&oracle, b"", &[Z16_INDEX, Z32_INDEX], 64, 3);
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].count(b'f' ^ Z16_BIAS), 32);
+        assert_eq!(counts[1].count(b'v' ^ Z32_BIAS), 32);
+        assert_eq!(counts[0].total(), 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one sample")]
+    fn test_collect_counts_without_samples() {
+        let oracle = rigged_oracle(b"cookie");
+        collect_counts(&oracle, b"", &[Z16_INDEX], 0, 1);
+    }
+
+    #[test]
+    fn test_recover_cookie() {
+        let cookie = b"BE SURE TO DRINK YOUR OVALTINE";
+        let oracle = rigged_oracle(cookie);
+
+        assert_eq!(recover_cookie(&oracle, cookie.len(), 64, 4), cookie);
+    }
+
+    /// Encrypts `request || cookie` with the real RC4 under a fresh key per
+    /// query.
+    fn rc4_oracle(cookie: &[u8]) -> impl Fn(&[u8]) -> Vec<u8> + Sync + '_ {
+        let queries = AtomicU64::new(0);
+        move |request: &[u8]| {
+            let query = queries.fetch_add(2, Ordering::Relaxed);
+            let mut key = [0u8; 16];
+            key[..8].copy_from_slice(&splitmix64(query).to_le_bytes());
+            key[8..].copy_from_slice(&splitmix64(query + 1).to_le_bytes());
+            rc4(&[request, cookie].concat(), &key)
+        }
+    }
+
+    #[test]
+    fn test_collect_counts_real_rc4() {
+        // the 16th byte needs millions of samples; the 2nd byte is zero twice
+        // as often as any other value (Mantin and Shamir, 2001) and shows
+        // through a few thousand
+        let oracle = rc4_oracle(b"cookie");
+        let counts = collect_counts(&oracle, b"", &[1], 1 << 14, 2);
+
+        assert_eq!(counts[0].most_frequent(), b'o');
+    }
+
+    #[test]
+    #[ignore]
+    fn test_recover_cookie_real_rc4() {
+        // one padding of 2^24 queries; run with `--release`
+        let cookie = b"B";
+        let oracle = rc4_oracle(cookie);
+
+        assert_eq!(recover_cookie(&oracle, cookie.len(), 1 << 24, 4), cookie);
+    }
+
+    #[test]
+    fn test_recover_short_cookie() {
+        let cookie = b"short";
+        let oracle = rigged_oracle(cookie);
+
+        assert_eq!(recover_cookie(&oracle, cookie.len(), 16, 2), cookie);
+    }
+}
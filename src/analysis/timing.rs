@@ -0,0 +1,247 @@
+//! Statistical detection of timing leaks.
+//!
+//! This follows dudect (Reparaz, Balasch and Verbauwhede, 2017): an
+//! operation is timed on two classes of inputs, usually one fixed input and
+//! fresh random ones, and Welch's t-test tells whether the two timing
+//! distributions differ. Interrupts and cache misses only ever add time, so
+//! the slowest measurements are cropped first.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+/// `|t|` above which the timings differ with high confidence, as in dudect.
+pub const LEAK_THRESHOLD: f64 = 4.5;
+
+/// Fraction of the fastest measurements that [`measure`] keeps.
+pub const CROP_PERCENTILE: f64 = 0.9;
+
+/// Which class an input belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Fixed,
+    Random,
+}
+
+/// Running mean and sum of squared deviations, after Welford.
+#[derive(Debug, Clone, Copy, Default)]
+struct Moments {
+    count: usize,
+    mean: f64,
+    squares: f64,
+}
+
+impl Moments {
+    fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+    }
+
+    fn variance(&self) -> f64 {
+        self.squares / (self.count - 1) as f64
+    }
+}
+
+/// Welch's t-test between the timings of the two classes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::timing::{Class, WelchTest};
+///
+/// let mut test = WelchTest::new();
+/// for duration in [1.0, 2.0, 3.0, 4.0, 5.0] {
+///     test.push(Class::Fixed, duration);
+///     test.push(Class::Random, duration + 5.0);
+/// }
+/// assert_eq!(test.t(), -5.0);
+/// assert!(test.is_leaky());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WelchTest {
+    fixed: Moments,
+    random: Moments,
+}
+
+impl WelchTest {
+    pub fn new() -> Self {
+        WelchTest::default()
+    }
+
+    /// Adds one measurement, in any unit.
+    pub fn push(&mut self, class: Class, duration: f64) {
+        match class {
+            Class::Fixed => self.fixed.push(duration),
+            Class::Random => self.random.push(duration),
+        }
+    }
+
+    /// Number of measurements in both classes.
+    pub fn samples(&self) -> usize {
+        self.fixed.count + self.random.count
+    }
+
+    /// The t statistic, positive if the fixed class is slower. Zero until
+    /// both classes have two measurements.
+    pub fn t(&self) -> f64 {
+        if self.fixed.count < 2 || self.random.count < 2 {
+            return 0.0;
+        }
+        let error = (self.fixed.variance() / self.fixed.count as f64
+            + self.random.variance() / self.random.count as f64)
+            .sqrt();
+        if error == 0.0 {
+            return 0.0;
+        }
+        (self.fixed.mean - self.random.mean) / error
+    }
+
+    /// Whether `|t|` exceeds [`LEAK_THRESHOLD`].
+    pub fn is_leaky(&self) -> bool {
+        self.t().abs() > LEAK_THRESHOLD
+    }
+}
+
+/// Times `operation` once per input, in order, and tests the fastest
+/// [`CROP_PERCENTILE`] of the measurements.
+///
+/// The classes should be interleaved at random, so that changes in the
+/// machine's speed affect both alike. Build and run with optimizations for
+/// meaningful results.
+pub fn measure<T>(inputs: &[(Class, T)], mut operation: impl FnMut(&T)) -> WelchTest {
+    let timings: Vec<(Class, f64)> = inputs
+        .iter()
+        .map(|(class, input)| {
+            let start = Instant::now();
+            operation(black_box(input));
+            (*class, start.elapsed().as_nanos() as f64)
+        })
+        .collect();
+
+    let mut durations: Vec<f64> = timings.iter().map(|&(_, duration)| duration).collect();
+    durations.sort_by(f64::total_cmp);
+    let crop = durations
+        .get((durations.len() as f64 * CROP_PERCENTILE) as usize)
+        .or(durations.last())
+        .copied()
+        .unwrap_or(0.0);
+
+    let mut test = WelchTest::new();
+    for (class, duration) in timings {
+        if duration <= crop {
+            test.push(class, duration);
+        }
+    }
+    test
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::chacha20::chacha20;
+    use crate::encoding::{base64, hex};
+
+    /// Inputs of `len` bytes in both classes, in a random order: zeros for
+    /// the fixed class and fresh random bytes for the other.
+    fn classed_inputs(
+        count: usize,
+        len: usize,
+        encode: fn(&[u8]) -> String,
+    ) -> Vec<(Class, String)> {
+        let random = chacha20(&vec![0; count * (len + 1)], &[42; 32], &[0; 12], 0);
+        random
+            .chunks_exact(len + 1)
+            .map(|chunk| match chunk[0] & 1 {
+                0 => (Class::Fixed, encode(&vec![0; len])),
+                _ => (Class::Random, encode(&chunk[1..])),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_welch_t() {
+        let mut test = WelchTest::new();
+        assert_eq!(test.t(), 0.0);
+        for duration in [10.0, 12.0, 11.0, 13.0] {
+            test.push(Class::Fixed, duration);
+            test.push(Class::Random, duration);
+        }
+        assert_eq!(test.samples(), 8);
+        assert_eq!(test.t(), 0.0);
+
+        // means 2 and 4, variances 1 and 4, three samples each
+        let mut test = WelchTest::new();
+        for (fixed, random) in [(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)] {
+            test.push(Class::Fixed, fixed);
+            test.push(Class::Random, random);
+        }
+        assert!((test.t() + 2.0 / (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert!(!test.is_leaky());
+    }
+
+    #[test]
+    fn test_measure_crops_slow_outliers() {
+        let inputs: Vec<(Class, u64)> = (0..100)
+            .map(|idx| {
+                (
+                    if idx % 2 == 0 {
+                        Class::Fixed
+                    } else {
+                        Class::Random
+                    },
+                    idx,
+                )
+            })
+            .collect();
+        let test = measure(&inputs, |&idx| {
+            if idx == 99 {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        });
+        // fast measurements tie often, so only the slow one is sure to go
+        assert!(test.samples() < inputs.len());
+        assert!(test.samples() >= 90);
+    }
+
+    // Timing tests are slow and need optimizations to be meaningful:
+    // `cargo test --release -- --ignored timing`
+
+    #[test]
+    #[ignore]
+    fn timing_detects_early_exit() {
+        // compares against the fixed input and stops at the first
+        // difference, so only that class runs to the end
+        fn leaky_equals(a: &[u8], b: &[u8]) -> bool {
+            for (x, y) in a.iter().zip(b) {
+                if black_box(x) != black_box(y) {
+                    return false;
+                }
+            }
+            a.len() == b.len()
+        }
+
+        let inputs = classed_inputs(100_000, 192, base64::encode);
+        let fixed = base64::encode(&[0; 192]);
+        let test = measure(&inputs, |encoded| {
+            black_box(leaky_equals(encoded.as_bytes(), fixed.as_bytes()));
+        });
+        assert!(test.is_leaky(), "t = {}", test.t());
+    }
+
+    #[test]
+    #[ignore]
+    fn timing_constant_time_codecs() {
+        let inputs = classed_inputs(100_000, 192, base64::encode);
+        let test = measure(&inputs, |encoded| {
+            black_box(base64::STANDARD.decode_constant_time(encoded).unwrap());
+        });
+        assert!(!test.is_leaky(), "base64 decoding: t = {}", test.t());
+
+        let inputs = classed_inputs(100_000, 192, hex::encode);
+        let test = measure(&inputs, |encoded| {
+            black_box(hex::decode_constant_time(encoded).unwrap());
+        });
+        assert!(!test.is_leaky(), "hex decoding: t = {}", test.t());
+    }
+}
@@ -0,0 +1,302 @@
+//! Key recovery from ECDSA signatures with biased nonces (challenge 62).
+//!
+//! If the low `l` bits of every nonce are zero, each signature `(r, s)` of a
+//! message with scalar `h` gives `k / 2^l = t d + u (mod q)` with
+//! `t = r / (s 2^l)` and `u = h / (s 2^l)`, and the left side is below
+//! `q / 2^l`. Recovering `d` from such samples is the hidden number problem
+//! of [Boneh and Venkatesan, "Hardness of computing the most significant
+//! bits of secret keys in Diffie-Hellman and related
+//! schemes"](https://doi.org/10.1007/3-540-68697-5_11), and
+//! [Howgrave-Graham and Smart, "Lattice attacks on digital signature
+//! schemes"](https://doi.org/10.1023/A:1011214926272) solve it for DSA by
+//! finding a short vector with [`lll`].
+//!
+//! [`solve_hidden_number`] works for any source of samples, such as nonces
+//! whose top bits leak through timing; [`recover_secret`] turns biased
+//! ECDSA signatures into samples.
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::lattice::lll;
+use crate::analysis::number_theory::random_below;
+use crate::analysis::rational::Rational;
+use crate::crypto::ec::ecdsa::{Signature, message_scalar, sign_with_nonce};
+use crate::crypto::ec::field::Field;
+use crate::crypto::ec::{Domain, Point};
+use crate::util::SplitMix64;
+
+/// A signer whose nonces have `bias_bits` low bits set to zero.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::biased_nonce::Bob;
+/// use cryptopals::crypto::ec::Domain;
+/// use cryptopals::crypto::ec::ecdsa::verify;
+/// use cryptopals::util::SplitMix64;
+///
+/// let domain = Domain::toy();
+/// let bob = Bob::new(domain.clone(), BigUint::from(1234u64), 8);
+/// let signature = bob.sign(b"hi mom", &mut SplitMix64::new(1));
+/// assert!(verify(&domain, &bob.public_key(), b"hi mom", &signature));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bob {
+    domain: Domain,
+    secret: BigUint,
+    bias_bits: u32,
+}
+
+impl Bob {
+    /// # Panics
+    ///
+    /// Panics if `bias_bits` leaves no nonces below the order.
+    pub fn new(domain: Domain, secret: BigUint, bias_bits: u32) -> Self {
+        assert!(
+            !Self::nonces(&domain, bias_bits).is_zero(),
+            "the bias leaves no nonces"
+        );
+        Bob {
+            domain,
+            secret,
+            bias_bits,
+        }
+    }
+
+    /// How many nonces below the order have `bias_bits` low zero bits.
+    fn nonces(domain: &Domain, bias_bits: u32) -> BigUint {
+        (&domain.order - BigUint::one()) >> bias_bits as usize
+    }
+
+    pub fn public_key(&self) -> Point {
+        self.domain.public_key(&self.secret)
+    }
+
+    /// Signs `message` with a nonce whose low bits are zero.
+    pub fn sign(&self, message: &[u8], rng: &mut SplitMix64) -> Signature {
+        let nonces = Self::nonces(&self.domain, self.bias_bits);
+        loop {
+            let nonce = (random_below(&nonces, rng) + BigUint::one()) << self.bias_bits as usize;
+            if let Some(signature) = sign_with_nonce(&self.domain, &self.secret, message, &nonce) {
+                return signature;
+            }
+        }
+    }
+}
+
+/// Solves the hidden number problem: finds the `x` for which every sample
+/// `(t, u)` has `t x + u (mod order)` below `order / 2^known_bits`.
+///
+/// Subtracting `c = order / 2^(l+1)` from every `u` centers the small values
+/// on zero, within `c` of it. The samples and `order` then span a lattice
+/// with the rows `order * e_i`, `(t_1, ..., t_n, 1 / 2^(l+1), 0)` and
+/// `(u_1 - c, ..., u_n - c, 0, c)`, in which the combination with `x` and 1
+/// is unusually short. Its next-to-last entry is `x / 2^(l+1)`, so every row
+/// of the reduced basis that ends in `±c` gives a candidate. Returns the
+/// candidates, which the caller checks against whatever `x` is known to
+/// satisfy; with too few samples the right one is usually missing.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::number_theory::random_below;
+/// use cryptopals::attacks::biased_nonce::solve_hidden_number;
+/// use cryptopals::util::SplitMix64;
+///
+/// // samples t x + u = b with b below 2^31, for a 47-bit prime
+/// let order = BigUint::from(140737488355213u64);
+/// let x = BigUint::from(123456789012345u64);
+/// let mut rng = SplitMix64::new(1);
+/// let samples: Vec<_> = (0..8)
+///     .map(|_| {
+///         let t = random_below(&order, &mut rng);
+///         let b = random_below(&(BigUint::one() << 31), &mut rng);
+///         let u = (b + &order - &t * &x % &order) % &order;
+///         (t, u)
+///     })
+///     .collect();
+/// assert!(solve_hidden_number(&order, &samples, 16).contains(&x));
+/// ```
+pub fn solve_hidden_number(
+    order: &BigUint,
+    samples: &[(BigUint, BigUint)],
+    known_bits: u32,
+) -> Vec<BigUint> {
+    let n = samples.len();
+    let scale = Rational::from(BigUint::one() << (known_bits as usize + 1));
+    let modulus = Rational::from(order.clone());
+    let center = order >> (known_bits as usize + 1);
+    let ct = Rational::from(1) / &scale;
+    let cu = Rational::from(center.clone());
+
+    let mut basis: Vec<Vec<Rational>> = (0..n)
+        .map(|i| {
+            let mut row = vec![Rational::zero(); n + 2];
+            row[i] = modulus.clone();
+            row
+        })
+        .collect();
+    let mut last_rows = [vec![Rational::zero(); n + 2], vec![Rational::zero(); n + 2]];
+    for (i, (t, u)) in samples.iter().enumerate() {
+        last_rows[0][i] = Rational::from(t.clone());
+        last_rows[1][i] = Rational::from((u + order - &center % order) % order);
+    }
+    last_rows[0][n] = ct;
+    last_rows[1][n + 1] = cu.clone();
+    basis.extend(last_rows);
+
+    let delta = Rational::from(99) / Rational::from(100);
+    let Some(reduced) = lll(&basis, &delta) else {
+        return Vec::new();
+    };
+
+    let mut candidates = Vec::new();
+    for row in reduced {
+        if row[n + 1].abs() != cu {
+            continue;
+        }
+        // x / 2^(l+1), or its negation if the row is the negated combination
+        let value = &row[n] * &scale;
+        if !value.is_integer() {
+            continue;
+        }
+        let residue = value.numerator() % order;
+        let candidate = if value.is_negative() == row[n + 1].is_negative() {
+            residue
+        } else {
+            (order - residue) % order
+        };
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Recovers the secret behind `public` from signatures of messages by a
+/// signer whose nonces have `bias_bits` low bits set to zero, or returns
+/// `None` if the signatures are too few.
+///
+/// Each bit of bias gives about one bit of the secret, and the lattice
+/// needs some slack: for the 46-bit toy order and 8 bits of bias, a dozen
+/// signatures are plenty, and for P-256 about 40 are.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::biased_nonce::{Bob, recover_secret};
+/// use cryptopals::crypto::ec::Domain;
+/// use cryptopals::util::SplitMix64;
+///
+/// let domain = Domain::toy();
+/// let secret = BigUint::from(31337u64);
+/// let bob = Bob::new(domain.clone(), secret.clone(), 8);
+/// let mut rng = SplitMix64::new(1);
+/// let signatures: Vec<_> = (0..12u8)
+///     .map(|i| (vec![i], bob.sign(&[i], &mut rng)))
+///     .collect();
+/// assert_eq!(recover_secret(&domain, &bob.public_key(), &signatures, 8), Some(secret));
+/// ```
+pub fn recover_secret(
+    domain: &Domain,
+    public: &Point,
+    signatures: &[(Vec<u8>, Signature)],
+    bias_bits: u32,
+) -> Option<BigUint> {
+    let field = Field::new(domain.order.clone()).expect("the group order is an odd prime");
+    let scale = field.power(&field.element(2), &BigUint::from(bias_bits as u64));
+
+    let samples = signatures
+        .iter()
+        .map(|(message, signature)| {
+            // k / 2^l = (r / (s 2^l)) d + h / (s 2^l)
+            let divisor = field.inverse(&field.multiply(&signature.s, &scale))?;
+            let t = field.multiply(&field.reduce(&signature.r), &divisor);
+            let u = field.multiply(&message_scalar(domain, message), &divisor);
+            Some((t, u))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    solve_hidden_number(&domain.order, &samples, bias_bits)
+        .into_iter()
+        .find(|candidate| domain.public_key(candidate) == *public)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn signatures(bob: &Bob, count: u8, rng: &mut SplitMix64) -> Vec<(Vec<u8>, Signature)> {
+        (0..count)
+            .map(|i| {
+                let message = format!("message {i}").into_bytes();
+                let signature = bob.sign(&message, rng);
+                (message, signature)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nonces_are_biased() {
+        let domain = Domain::toy();
+        let bob = Bob::new(domain.clone(), BigUint::from(1234u64), 8);
+        let mut rng = SplitMix64::new(1);
+        let field = Field::new(domain.order.clone()).unwrap();
+        for (message, signature) in signatures(&bob, 16, &mut rng) {
+            // k = (h + r d) / s
+            let nonce = field.multiply(
+                &field.add(
+                    &message_scalar(&domain, &message),
+                    &field.multiply(&signature.r, &field.element(1234)),
+                ),
+                &field.inverse(&signature.s).unwrap(),
+            );
+            assert!((0..8).all(|bit| !nonce.bit(bit)));
+        }
+    }
+
+    #[test]
+    fn test_too_few_signatures() {
+        let domain = Domain::toy();
+        let bob = Bob::new(domain.clone(), BigUint::from(1234567u64), 8);
+        let mut rng = SplitMix64::new(2);
+        let signatures = signatures(&bob, 2, &mut rng);
+        assert_eq!(
+            recover_secret(&domain, &bob.public_key(), &signatures, 8),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recovers_a_p256_key() {
+        // a wide bias keeps the lattice small
+        let domain = Domain::p256();
+        let mut rng = SplitMix64::new(3);
+        let secret = domain.random_secret(&mut rng);
+        let bob = Bob::new(domain.clone(), secret.clone(), 32);
+        let signatures = signatures(&bob, 10, &mut rng);
+        assert_eq!(
+            recover_secret(&domain, &bob.public_key(), &signatures, 32),
+            Some(secret)
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2))]
+
+        #[test]
+        fn recovers_any_secret(seed: u64) {
+            let domain = Domain::toy();
+            let mut rng = SplitMix64::new(seed);
+            let secret = domain.random_secret(&mut rng);
+            let bob = Bob::new(domain.clone(), secret.clone(), 8);
+            let signatures = signatures(&bob, 12, &mut rng);
+
+            let recovered = recover_secret(&domain, &bob.public_key(), &signatures, 8);
+            prop_assert_eq!(recovered, Some(secret));
+        }
+    }
+}
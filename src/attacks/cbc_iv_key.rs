@@ -0,0 +1,148 @@
+//! Recovering the key from CBC with IV = key (challenge 27).
+//!
+//! CBC decryption computes `P_i = D(C_i) ^ C_(i-1)` with `C_0 = IV`. If the
+//! IV is the key, submitting `C_1 || 0 || C_1` decrypts to `P_1`, garbage,
+//! and `P_1 ^ key`, so XORing the first and third plaintext blocks gives
+//! the key. All it takes is a receiver that reveals the plaintext when it
+//! rejects a message, such as [`Receiver`] does for high ASCII.
+
+use crate::crypto::aes::{BLOCK_LEN, cbc_decrypt, cbc_encrypt};
+use crate::crypto::pkcs7;
+
+/// How [`Receiver`] answers a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// The message decrypted to ASCII with valid padding; carries the
+    /// plaintext.
+    Accepted(Vec<u8>),
+    /// The message decrypted to bytes above 0x7F; the error echoes the
+    /// whole decryption, padding included.
+    HighAscii(Vec<u8>),
+    /// The message decrypted to ASCII but its padding is malformed, or it is
+    /// not a whole number of blocks.
+    BadPadding,
+}
+
+/// A receiver that uses its AES key as the CBC IV, and complains about
+/// non-ASCII plaintext by echoing it.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::cbc_iv_key::{Receiver, Response};
+///
+/// let receiver = Receiver::new(*b"YELLOW SUBMARINE");
+/// let ciphertext = receiver.encrypt(b"comment1=cooking%20MCs");
+/// assert_eq!(receiver.receive(&ciphertext), Response::Accepted(b"comment1=cooking%20MCs".to_vec()));
+/// ```
+#[derive(Clone)]
+pub struct Receiver {
+    key: [u8; 16],
+}
+
+impl Receiver {
+    pub fn new(key: [u8; 16]) -> Self {
+        Receiver { key }
+    }
+
+    /// The sender: pads and encrypts under IV = key.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        cbc_encrypt(&self.key, &self.key, &pkcs7::pad(plaintext, BLOCK_LEN))
+    }
+
+    /// Decrypts the message and checks that it is ASCII before unpadding.
+    pub fn receive(&self, ciphertext: &[u8]) -> Response {
+        if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_LEN) {
+            return Response::BadPadding;
+        }
+
+        let decrypted = cbc_decrypt(&self.key, &self.key, ciphertext);
+        if !decrypted.is_ascii() {
+            return Response::HighAscii(decrypted);
+        }
+        match pkcs7::unpad(&decrypted, BLOCK_LEN) {
+            Some(plaintext) => Response::Accepted(plaintext.to_vec()),
+            None => Response::BadPadding,
+        }
+    }
+}
+
+/// Recovers the key from the first block of a ciphertext and the
+/// receiver's answer to one forged message.
+///
+/// Returns `None` if the ciphertext is shorter than a block or the receiver
+/// does not echo the forged message, which happens when it decrypts to
+/// ASCII by chance.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::cbc_iv_key::{Receiver, recover_key};
+///
+/// let receiver = Receiver::new(*b"YELLOW SUBMARINE");
+/// let ciphertext = receiver.encrypt(b"comment1=cooking%20MCs;userdata=x;comment2=%20like%20a%20pound%20of%20bacon");
+/// assert_eq!(recover_key(&ciphertext, |message| receiver.receive(message)), Some(*b"YELLOW SUBMARINE"));
+/// ```
+pub fn recover_key(ciphertext: &[u8], receive: impl Fn(&[u8]) -> Response) -> Option<[u8; 16]> {
+    let first = ciphertext.get(..BLOCK_LEN)?;
+
+    let forged = [first, &[0; BLOCK_LEN], first].concat();
+    match receive(&forged) {
+        Response::HighAscii(plaintext) => Some(std::array::from_fn(|idx| {
+            plaintext[idx] ^ plaintext[2 * BLOCK_LEN + idx]
+        })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MESSAGE: &[u8] =
+        b"comment1=cooking%20MCs;userdata=hi;comment2=%20like%20a%20pound%20of%20bacon";
+
+    #[test]
+    fn test_receiver_responses() {
+        let key = *b"YELLOW SUBMARINE";
+        let receiver = Receiver::new(key);
+        let mut ciphertext = receiver.encrypt(MESSAGE);
+
+        assert_eq!(receiver.receive(&ciphertext[..20]), Response::BadPadding);
+        assert_eq!(receiver.receive(&[]), Response::BadPadding);
+
+        // ASCII without padding
+        let unpadded = cbc_encrypt(&key, &key, b"sixteen byte msg");
+        assert_eq!(receiver.receive(&unpadded), Response::BadPadding);
+
+        // scrambling a block decrypts it to bytes above 0x7F
+        ciphertext[0] ^= 0x80;
+        assert!(matches!(
+            receiver.receive(&ciphertext),
+            Response::HighAscii(_)
+        ));
+    }
+
+    #[test]
+    fn test_short_ciphertext() {
+        let receiver = Receiver::new(*b"YELLOW SUBMARINE");
+        let ciphertext = receiver.encrypt(b"short");
+        let receive = |message: &[u8]| receiver.receive(message);
+
+        assert_eq!(
+            recover_key(&ciphertext, receive),
+            Some(*b"YELLOW SUBMARINE")
+        );
+        assert_eq!(recover_key(&ciphertext[..15], receive), None);
+    }
+
+    proptest! {
+        #[test]
+        fn recovers_any_key(key: [u8; 16]) {
+            let receiver = Receiver::new(key);
+            let ciphertext = receiver.encrypt(MESSAGE);
+            prop_assert_eq!(recover_key(&ciphertext, |message| receiver.receive(message)), Some(key));
+        }
+    }
+}
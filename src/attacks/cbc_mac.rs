@@ -0,0 +1,446 @@
+//! CBC-MAC message forgery (challenges 49 and 50).
+//!
+//! CBC-MAC is the last block of the CBC encryption of a message. It is a
+//! secure MAC for messages of one fixed length under a fixed IV, and
+//! breaks as soon as either is relaxed:
+//!
+//! - If the IV travels with the message, flipping bits of the IV flips the
+//!   same bits of the first plaintext block without changing the tag.
+//! - If messages of any length are accepted, the tag `t` of one message is
+//!   the chaining value after it. Appending a second message whose first
+//!   block is XORed with `t` reproduces the second message's tag.
+//!
+//! Both forgeries run here against a local bank: [`IvBank`] accepts an
+//! attacker-controlled IV and [`FixedIvBank`] accepts transaction lists of
+//! any length. [`forge_snippet`] turns the length extension into a
+//! collision for CBC-MAC used as a hash.
+
+use crate::crypto::aes::{BLOCK_LEN, cbc_encrypt};
+use crate::crypto::pkcs7;
+
+/// The key [`snippet_hash`] uses, from challenge 50.
+pub const SNIPPET_KEY: [u8; 16] = *b"YELLOW SUBMARINE";
+
+/// Computes the AES-CBC-MAC of the PKCS#7-padded message.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::cbc_mac::cbc_mac;
+///
+/// let tag = cbc_mac(b"YELLOW SUBMARINE", &[0; 16], b"from=1&to=2&amount=10");
+/// assert_ne!(tag, cbc_mac(b"YELLOW SUBMARINE", &[1; 16], b"from=1&to=2&amount=10"));
+/// ```
+pub fn cbc_mac(key: &[u8; 16], iv: &[u8; 16], message: &[u8]) -> [u8; 16] {
+    let ciphertext = cbc_encrypt(key, iv, &pkcs7::pad(message, BLOCK_LEN));
+    ciphertext[ciphertext.len() - BLOCK_LEN..]
+        .try_into()
+        .expect("padding adds at least one block")
+}
+
+/// CBC-MAC under [`SNIPPET_KEY`] and a zero IV, used as a hash function.
+pub fn snippet_hash(message: &[u8]) -> [u8; 16] {
+    cbc_mac(&SNIPPET_KEY, &[0; 16], message)
+}
+
+fn xor_block(a: &[u8], b: &[u8]) -> [u8; 16] {
+    std::array::from_fn(|idx| a[idx] ^ b[idx])
+}
+
+/// Splits `key=value` pairs separated by `&`.
+fn fields(message: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    message.split(|&byte| byte == b'&').filter_map(|field| {
+        let separator = field.iter().position(|&byte| byte == b'=')?;
+        Some((&field[..separator], &field[separator + 1..]))
+    })
+}
+
+fn field<'a>(message: &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+    fields(message).find_map(|(key, value)| (key == name).then_some(value))
+}
+
+fn number(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(digits).ok()?.parse().ok()
+}
+
+/// A transfer a bank has authorized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: u64,
+    pub to: u64,
+    pub amount: u64,
+}
+
+/// The bank of challenge 49's first protocol.
+///
+/// Requests are `from=#{from}&to=#{to}&amount=#{amount} || IV || MAC`. The
+/// web client signs transfers for the logged-in account only, and the API
+/// server trusts any request whose MAC verifies under the IV it carries.
+#[derive(Clone)]
+pub struct IvBank {
+    key: [u8; 16],
+}
+
+impl IvBank {
+    pub fn new(key: [u8; 16]) -> Self {
+        IvBank { key }
+    }
+
+    /// The web client: signs a transfer out of the account `from`, which the
+    /// client has authenticated.
+    pub fn sign(&self, from: u64, to: u64, amount: u64, iv: &[u8; 16]) -> Vec<u8> {
+        let mut request = format!("from={from}&to={to}&amount={amount}").into_bytes();
+        let tag = cbc_mac(&self.key, iv, &request);
+        request.extend(iv);
+        request.extend(tag);
+        request
+    }
+
+    /// The API server: returns the transfer if the request verifies and
+    /// parses.
+    pub fn process(&self, request: &[u8]) -> Option<Transfer> {
+        let (message, tail) = request.split_at_checked(request.len().checked_sub(32)?)?;
+        let (iv, tag) = tail.split_at(BLOCK_LEN);
+        if cbc_mac(&self.key, iv.try_into().expect("16 bytes"), message) != tag {
+            return None;
+        }
+
+        Some(Transfer {
+            from: number(field(message, b"from")?)?,
+            to: number(field(message, b"to")?)?,
+            amount: number(field(message, b"amount")?)?,
+        })
+    }
+}
+
+/// Rewrites the sender of an [`IvBank`] request to `victim`, keeping its
+/// MAC valid by adjusting the IV.
+///
+/// Returns `None` if the request is too short, does not start with the
+/// sender, the sender does not fit in the first block, or `victim` has a
+/// different number of digits.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::cbc_mac::{IvBank, Transfer, forge_sender};
+///
+/// let bank = IvBank::new(*b"bank secret key!");
+/// // the attacker, account 3, pays themselves
+/// let request = bank.sign(3, 3, 1_000_000, &[0; 16]);
+/// let forged = forge_sender(&request, 1).unwrap();
+/// assert_eq!(bank.process(&forged), Some(Transfer { from: 1, to: 3, amount: 1_000_000 }));
+/// ```
+pub fn forge_sender(request: &[u8], victim: u64) -> Option<Vec<u8>> {
+    let message_len = request.len().checked_sub(32)?;
+    let sender_len = request[..message_len]
+        .strip_prefix(b"from=")?
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    let victim = victim.to_string().into_bytes();
+
+    let start = b"from=".len();
+    if victim.len() != sender_len || start + sender_len > BLOCK_LEN {
+        return None;
+    }
+
+    let mut forged = request.to_vec();
+    forged[start..start + victim.len()].copy_from_slice(&victim);
+    // P_1 xor IV is what gets encrypted, so the IV absorbs the change
+    for idx in start..start + victim.len() {
+        forged[message_len + idx] ^= request[idx] ^ forged[idx];
+    }
+    Some(forged)
+}
+
+/// The bank of challenge 49's second protocol.
+///
+/// Requests are `from=#{from}&tx_list=#{to}:#{amount}(;#{to}:#{amount})* || MAC`
+/// with a fixed zero IV. Transactions that do not parse are skipped, which
+/// is what lets a forged list with a garbage block through.
+#[derive(Clone)]
+pub struct FixedIvBank {
+    key: [u8; 16],
+}
+
+impl FixedIvBank {
+    pub fn new(key: [u8; 16]) -> Self {
+        FixedIvBank { key }
+    }
+
+    /// The web client: signs transactions out of the account `from`.
+    pub fn sign(&self, from: u64, transactions: &[(u64, u64)]) -> Vec<u8> {
+        let list = transactions
+            .iter()
+            .map(|(to, amount)| format!("{to}:{amount}"))
+            .collect::<Vec<_>>()
+            .join(";");
+        let mut request = format!("from={from}&tx_list={list}").into_bytes();
+        let tag = cbc_mac(&self.key, &[0; 16], &request);
+        request.extend(tag);
+        request
+    }
+
+    /// The API server: returns the transfers if the request verifies and
+    /// names a sender.
+    pub fn process(&self, request: &[u8]) -> Option<Vec<Transfer>> {
+        let (message, tag) = request.split_at_checked(request.len().checked_sub(BLOCK_LEN)?)?;
+        if cbc_mac(&self.key, &[0; 16], message) != tag {
+            return None;
+        }
+
+        let from = number(field(message, b"from")?)?;
+        let transfers = field(message, b"tx_list")?
+            .split(|&byte| byte == b';')
+            .filter_map(|transaction| {
+                let separator = transaction.iter().position(|&byte| byte == b':')?;
+                Some(Transfer {
+                    from,
+                    to: number(&transaction[..separator])?,
+                    amount: number(&transaction[separator + 1..])?,
+                })
+            })
+            .collect();
+        Some(transfers)
+    }
+}
+
+/// Appends the attacker's own signed request to a captured one, producing
+/// a request carrying both messages under the attacker's MAC.
+///
+/// The captured message is followed by its padding, then by the attacker's
+/// message with the first block XORed with the captured tag. That block
+/// turns into garbage, so the attacker's message should start with a block
+/// they can spare, such as `from=3&tx_list=3:0;3:1000000` whose real
+/// transaction starts after byte 16.
+///
+/// The garbage block lands inside the captured transaction list, where a
+/// `&` or `;` in it would end the list or the transaction early and could
+/// drop the attacker's transfer. The block depends only on the two tags, so
+/// the attacker can wait for another capture, or re-sign their own request,
+/// until it has neither.
+///
+/// Returns `None` if either request is shorter than a tag plus one block,
+/// or if the garbage block contains `&` or `;`.
+pub fn extend_request(captured: &[u8], own: &[u8]) -> Option<Vec<u8>> {
+    let (captured_message, captured_tag) =
+        captured.split_at_checked(captured.len().checked_sub(BLOCK_LEN)?)?;
+    let (own_message, own_tag) = own.split_at_checked(own.len().checked_sub(BLOCK_LEN)?)?;
+    if own_message.len() < BLOCK_LEN {
+        return None;
+    }
+
+    let garbage = xor_block(&own_message[..BLOCK_LEN], captured_tag);
+    if garbage.iter().any(|&byte| byte == b'&' || byte == b';') {
+        return None;
+    }
+
+    let mut forged = pkcs7::pad(captured_message, BLOCK_LEN);
+    forged.extend(garbage);
+    forged.extend(&own_message[BLOCK_LEN..]);
+    forged.extend(own_tag);
+    Some(forged)
+}
+
+/// Spaces tried after the replacement before giving up on a glue block
+/// without line breaks.
+const MAX_SPACES: usize = 256;
+
+/// Builds a message starting with `replacement` whose [`snippet_hash`]
+/// equals that of `original`.
+///
+/// The replacement is padded, then followed by a glue block that brings the
+/// CBC state to what it is after the original's first block, then by the
+/// rest of the original. For JavaScript, ending the replacement with `//`
+/// comments the rest out; spaces are inserted before the padding until the
+/// glue block has no `\r` or `\n` to end that comment.
+///
+/// Returns `None` if the original is shorter than a block, or no glue block
+/// without line breaks turns up.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::cbc_mac::{forge_snippet, snippet_hash};
+///
+/// let original = b"alert('MZA who was that?');\n";
+/// let forged = forge_snippet(original, b"alert('Ayo, the Wu is back!');//").unwrap();
+/// assert!(forged.starts_with(b"alert('Ayo, the Wu is back!');"));
+/// assert_eq!(snippet_hash(&forged), snippet_hash(original));
+/// ```
+pub fn forge_snippet(original: &[u8], replacement: &[u8]) -> Option<Vec<u8>> {
+    let first_block = original.get(..BLOCK_LEN)?;
+
+    (0..MAX_SPACES).find_map(|spaces| {
+        let mut forged = replacement.to_vec();
+        // keep the comment marker last so the padding stays inside it
+        let marker = if forged.ends_with(b"//") { 2 } else { 0 };
+        let position = forged.len() - marker;
+        forged.splice(position..position, std::iter::repeat_n(b' ', spaces));
+
+        // CBC-MAC of the padded prefix is the chaining value after it
+        let state = cbc_mac(&SNIPPET_KEY, &[0; 16], &forged);
+        let glue = xor_block(&state, first_block);
+        if glue.iter().any(|byte| matches!(byte, b'\n' | b'\r')) {
+            return None;
+        }
+
+        let mut forged = pkcs7::pad(&forged, BLOCK_LEN);
+        forged.extend(glue);
+        forged.extend(&original[BLOCK_LEN..]);
+        Some(forged)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+    use crate::util::SplitMix64;
+    use proptest::prelude::*;
+
+    const KEY: [u8; 16] = *b"bank secret key!";
+
+    #[test]
+    fn test_snippet_hash_vector() {
+        // challenge 50
+        assert_eq!(
+            hex::encode(&snippet_hash(b"alert('MZA who was that?');\n")).to_lowercase(),
+            "296b8d7cb78a243dda4d0a61d33bbdd1"
+        );
+    }
+
+    #[test]
+    fn test_iv_bank_rejects_tampering() {
+        let bank = IvBank::new(KEY);
+        let request = bank.sign(3, 4, 100, &[7; 16]);
+        assert_eq!(
+            bank.process(&request),
+            Some(Transfer {
+                from: 3,
+                to: 4,
+                amount: 100
+            })
+        );
+
+        let mut changed = request.clone();
+        changed[5] = b'1';
+        assert_eq!(bank.process(&changed), None);
+        assert_eq!(bank.process(&request[..31]), None);
+    }
+
+    #[test]
+    fn test_forge_sender_needs_matching_digits() {
+        let request = IvBank::new(KEY).sign(3, 3, 100, &[0; 16]);
+        assert_eq!(forge_sender(&request, 12), None);
+        assert_eq!(forge_sender(&request[..20], 1), None);
+
+        // a sender running past the first block is out of reach
+        let long = IvBank::new(KEY).sign(123456789012, 3, 100, &[0; 16]);
+        assert_eq!(forge_sender(&long, 987654321012), None);
+    }
+
+    #[test]
+    fn test_fixed_iv_bank_skips_malformed_transactions() {
+        let bank = FixedIvBank::new(KEY);
+        let mut message = b"from=2&tx_list=3:10;garbage;4:x;5:20".to_vec();
+        message.extend(cbc_mac(&KEY, &[0; 16], &message));
+
+        assert_eq!(
+            bank.process(&message).unwrap(),
+            [
+                Transfer {
+                    from: 2,
+                    to: 3,
+                    amount: 10
+                },
+                Transfer {
+                    from: 2,
+                    to: 5,
+                    amount: 20
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extend_request() {
+        let mut rng = SplitMix64::new(49);
+        let mut key = [0; 16];
+        rng.fill_bytes(&mut key);
+        let bank = FixedIvBank::new(key);
+
+        // the victim, account 1, pays account 2; the attacker, account 3,
+        // signs a payment to themselves
+        let captured = bank.sign(1, &[(2, 5), (4, 7)]);
+        let own = bank.sign(3, &[(3, 0), (3, 1_000_000)]);
+        let forged = extend_request(&captured, &own).unwrap();
+
+        let transfers = bank.process(&forged).unwrap();
+        assert_eq!(transfers[0].from, 1);
+        assert!(transfers.contains(&Transfer {
+            from: 1,
+            to: 3,
+            amount: 1_000_000
+        }));
+        assert_eq!(extend_request(&captured, &own[..20]), None);
+    }
+
+    #[test]
+    fn test_extend_request_rejects_separators_in_garbage() {
+        let mut rng = SplitMix64::new(49);
+        let mut key = [0; 16];
+        rng.fill_bytes(&mut key);
+        let bank = FixedIvBank::new(key);
+        let own = bank.sign(3, &[(3, 0), (3, 1_000_000)]);
+        let target = Transfer {
+            from: 1,
+            to: 3,
+            amount: 1_000_000,
+        };
+
+        let mut rejected = 0;
+        for amount in 0..64 {
+            let captured = bank.sign(1, &[(2, amount)]);
+            match extend_request(&captured, &own) {
+                Some(forged) => assert!(bank.process(&forged).unwrap().contains(&target)),
+                None => {
+                    let tag = &captured[captured.len() - BLOCK_LEN..];
+                    let garbage = xor_block(&own[..BLOCK_LEN], tag);
+                    assert!(garbage.iter().any(|&byte| byte == b'&' || byte == b';'));
+                    rejected += 1;
+                }
+            }
+        }
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn test_forge_snippet_rejects_short_originals() {
+        assert_eq!(forge_snippet(b"alert(1)", b"alert(2)//"), None);
+    }
+
+    proptest! {
+        #[test]
+        fn iv_forgery_works_for_any_key(key: [u8; 16], iv: [u8; 16], attacker in 1u64..10, victim in 1u64..10, amount: u64) {
+            let bank = IvBank::new(key);
+            let forged = forge_sender(&bank.sign(attacker, attacker, amount, &iv), victim).unwrap();
+            prop_assert_eq!(
+                bank.process(&forged),
+                Some(Transfer { from: victim, to: attacker, amount })
+            );
+        }
+
+        #[test]
+        fn snippet_forgery_collides(original in proptest::collection::vec(any::<u8>(), 16..64), replacement in proptest::collection::vec(any::<u8>(), 0..40)) {
+            let forged = forge_snippet(&original, &replacement).unwrap();
+            // spaces go before a trailing comment marker
+            prop_assert!(forged.starts_with(&replacement[..replacement.len().saturating_sub(2)]));
+            prop_assert_eq!(snippet_hash(&forged), snippet_hash(&original));
+        }
+    }
+}
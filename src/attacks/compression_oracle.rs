@@ -0,0 +1,419 @@
+//! Compression-ratio side channel (CRIME).
+//!
+//! A request that is compressed before it is encrypted leaks through its
+//! length: text that repeats something already in the request compresses
+//! into a back-reference, anything else costs a literal. When the attacker's
+//! body is sent along with a secret header, guessing the header one byte at
+//! a time and keeping the guess with the shortest ciphertext recovers it
+//! (challenge 51).
+//!
+//! [`CompressionOracle`] plays the victim. Under AES-CTR, or any other
+//! stream cipher, the ciphertext is as long as the compressed request and
+//! [`recover_secret`] reads the difference directly. Under AES-CBC lengths
+//! are rounded up to whole blocks, so [`recover_secret_blockwise`] first
+//! pads the request until it sits just past a block boundary, where saving a
+//! literal drops a whole block.
+
+use crate::compression::deflate::compress;
+use crate::crypto::aes::{self, BLOCK_LEN};
+use crate::crypto::pkcs7;
+use crate::util::SplitMix64;
+use std::cell::{Cell, RefCell};
+
+/// Paddings tried before a position is given up on.
+const MAX_PADDING: usize = 32;
+
+/// Low filler bytes [`recover_secret_blockwise`] needs on top of its
+/// stand-in.
+const LOW_FILLER: usize = 7 + MAX_PADDING_BITS / 8;
+
+/// Padding bits tried before a position is given up on: two blocks, so that
+/// the search always crosses a boundary.
+const MAX_PADDING_BITS: usize = 2 * 8 * BLOCK_LEN;
+
+/// The cipher a [`CompressionOracle`] encrypts requests with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cipher {
+    /// AES-CTR: the ciphertext is as long as the compressed request.
+    Ctr,
+    /// AES-CBC with PKCS#7 padding: the ciphertext length is a whole number
+    /// of blocks.
+    Cbc,
+}
+
+/// A victim that compresses its requests, encrypts them under a fresh key
+/// and nonce or IV each time, and reveals only the ciphertext length.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::compression_oracle::{Cipher, CompressionOracle};
+/// use cryptopals::util::SplitMix64;
+///
+/// let oracle = CompressionOracle::new(b"c2VjcmV0", Cipher::Cbc, SplitMix64::new(0));
+/// assert_eq!(oracle.length(b"hello") % 16, 0);
+/// assert_eq!(oracle.queries(), 1);
+/// ```
+pub struct CompressionOracle {
+    session_id: Vec<u8>,
+    cipher: Cipher,
+    rng: RefCell<SplitMix64>,
+    queries: Cell<u64>,
+}
+
+impl CompressionOracle {
+    pub fn new(session_id: &[u8], cipher: Cipher, rng: SplitMix64) -> Self {
+        CompressionOracle {
+            session_id: session_id.to_vec(),
+            cipher,
+            rng: RefCell::new(rng),
+            queries: Cell::new(0),
+        }
+    }
+
+    /// The length of the request carrying `body`, compressed and encrypted.
+    pub fn length(&self, body: &[u8]) -> usize {
+        self.queries.set(self.queries.get() + 1);
+
+        let compressed = compress(&format_request(&self.session_id, body));
+        let mut rng = self.rng.borrow_mut();
+        let mut key = [0; 16];
+        rng.fill_bytes(&mut key);
+        match self.cipher {
+            Cipher::Ctr => aes::ctr(&compressed, &key, rng.next_u64()).len(),
+            Cipher::Cbc => {
+                let mut iv = [0; 16];
+                rng.fill_bytes(&mut iv);
+                aes::cbc_encrypt(&key, &iv, &pkcs7::pad(&compressed, BLOCK_LEN)).len()
+            }
+        }
+    }
+
+    /// How many times [`length`](Self::length) has been called.
+    pub fn queries(&self) -> u64 {
+        self.queries.get()
+    }
+}
+
+/// Formats a request carrying a session cookie, as the victim sends it.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::compression_oracle::format_request;
+///
+/// let request = format_request(b"c2VjcmV0", b"hello");
+/// assert!(request.starts_with(b"POST / HTTP/1.1\nHost: hapless.com\n"));
+/// assert!(request.ends_with(b"Content-Length: 5\nhello"));
+/// ```
+pub fn format_request(session_id: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut request = b"POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid=".to_vec();
+    request.extend_from_slice(session_id);
+    request.extend_from_slice(format!("\nContent-Length: {}\n", body.len()).as_bytes());
+    request.extend_from_slice(body);
+    request
+}
+
+/// Recovers the secret that follows `prefix` in requests whose body the
+/// attacker controls.
+///
+/// `oracle` returns the length of the compressed and encrypted request for a
+/// given body. Each guess sends the prefix, the bytes recovered so far and one
+/// candidate from `alphabet`; the right candidate extends the back-reference
+/// to the secret and comes out shortest. Lengths are whole bytes, so
+/// candidates often tie. Padding the body with bytes that occur nowhere else
+/// shifts where the compressed bits fall until one candidate stands out.
+///
+/// Recovery stops after `max_len` bytes, or once no padding separates the
+/// candidates, which happens when the secret has ended.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::compression_oracle::{format_request, recover_secret};
+/// use cryptopals::compression::deflate::compress;
+///
+/// // without encryption, the compressed length is all the oracle shows
+/// let oracle = |body: &[u8]| compress(&format_request(b"dG9wIHNlY3JldA==", body)).len();
+/// let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+///
+/// let secret = recover_secret(&oracle, b"sessionid=", alphabet, 64);
+/// assert_eq!(secret, b"dG9wIHNlY3JldA==");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `alphabet` is empty.
+pub fn recover_secret<F>(oracle: &F, prefix: &[u8], alphabet: &[u8], max_len: usize) -> Vec<u8>
+where
+    F: Fn(&[u8]) -> usize,
+{
+    assert!(!alphabet.is_empty(), "the alphabet must not be empty");
+
+    let filler = filler(prefix, alphabet);
+    let mut secret = Vec::new();
+    while secret.len() < max_len {
+        let next = (0..=filler.len()).find_map(|padding| {
+            unique_shortest(oracle, &filler[..padding], prefix, &secret, alphabet)
+        });
+
+        match next {
+            Some(byte) => secret.push(byte),
+            None => break,
+        }
+    }
+    secret
+}
+
+/// Recovers the secret that follows `prefix` when the oracle's lengths are
+/// rounded up to whole cipher blocks.
+///
+/// Guessing works as in [`recover_secret`], but a guess that saves a few bits
+/// rarely changes the number of blocks. Before each guess, the body is padded
+/// one bit at a time, mixing filler bytes that cost eight and nine bits, with
+/// a byte that cannot extend the secret standing in for the candidate, until
+/// the length grows by a block. There the request has just spilled into a
+/// new block, and only the right candidate, which compresses better than the
+/// stand-in, pulls it back. If no candidate stands out, the search moves on
+/// to the next boundary.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::compression_oracle::{Cipher, CompressionOracle, recover_secret_blockwise};
+/// use cryptopals::util::SplitMix64;
+///
+/// let oracle = CompressionOracle::new(b"dG9wIHNlY3JldA==", Cipher::Cbc, SplitMix64::new(0));
+/// let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+///
+/// let secret = recover_secret_blockwise(&|body| oracle.length(body), b"sessionid=", alphabet, 64);
+/// assert_eq!(secret, b"dG9wIHNlY3JldA==");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `alphabet` is empty, or if it and `prefix` leave fewer than 40
+/// bytes below 0x90 unused.
+pub fn recover_secret_blockwise<F>(
+    oracle: &F,
+    prefix: &[u8],
+    alphabet: &[u8],
+    max_len: usize,
+) -> Vec<u8>
+where
+    F: Fn(&[u8]) -> usize,
+{
+    assert!(!alphabet.is_empty(), "the alphabet must not be empty");
+
+    // bytes below 0x90 cost eight bits under the fixed Huffman codes: the
+    // first stands in for a wrong candidate, the rest pad a byte at a time
+    let low = (0..0x90)
+        .filter(|byte| !alphabet.contains(byte) && !prefix.contains(byte))
+        .take(LOW_FILLER + 1)
+        .collect::<Vec<_>>();
+    assert!(
+        low.len() > LOW_FILLER,
+        "too few bytes below 0x90 are left for padding"
+    );
+    let (stand_in, low) = (low[0], &low[1..]);
+    let high = filler(prefix, alphabet);
+
+    let mut secret = Vec::new();
+    while secret.len() < max_len {
+        let length = |bits: usize| {
+            let padding = padding_bits(bits, low, &high);
+            oracle(&guess_body(&padding, prefix, &secret, stand_in))
+        };
+
+        let mut previous = length(0);
+        let next = (1..=MAX_PADDING_BITS).find_map(|bits| {
+            let current = length(bits);
+            let crossed = current > previous;
+            previous = current;
+            if crossed {
+                let padding = padding_bits(bits, low, &high);
+                unique_shortest(oracle, &padding, prefix, &secret, alphabet)
+            } else {
+                None
+            }
+        });
+
+        match next {
+            Some(byte) => secret.push(byte),
+            None => break,
+        }
+    }
+    secret
+}
+
+/// Padding that costs seven bytes and `bits` bits under the fixed Huffman
+/// codes: up to seven of the bytes are high ones, each a bit dearer.
+fn padding_bits(bits: usize, low: &[u8], high: &[u8]) -> Vec<u8> {
+    let (bytes, dear) = (7 + bits / 8, bits % 8);
+    let mut padding = high[..dear].to_vec();
+    padding.extend_from_slice(&low[..bytes - dear]);
+    padding
+}
+
+/// Bytes that occur nowhere else in the request, to pad the body with.
+fn filler(prefix: &[u8], alphabet: &[u8]) -> Vec<u8> {
+    // high bytes cost nine bits under the fixed Huffman codes, so each one
+    // moves the rest of the request by a bit
+    (0..=u8::MAX)
+        .rev()
+        .filter(|byte| !alphabet.contains(byte) && !prefix.contains(byte))
+        .take(MAX_PADDING)
+        .collect()
+}
+
+/// The padding, the prefix, the secret recovered so far and one guess.
+fn guess_body(padding: &[u8], prefix: &[u8], secret: &[u8], candidate: u8) -> Vec<u8> {
+    let mut body = padding.to_vec();
+    body.extend_from_slice(prefix);
+    body.extend_from_slice(secret);
+    body.push(candidate);
+    body
+}
+
+/// The candidate whose guess alone comes out shortest, if there is one.
+fn unique_shortest<F>(
+    oracle: &F,
+    padding: &[u8],
+    prefix: &[u8],
+    secret: &[u8],
+    alphabet: &[u8],
+) -> Option<u8>
+where
+    F: Fn(&[u8]) -> usize,
+{
+    let lengths = alphabet
+        .iter()
+        .map(|&candidate| {
+            let length = oracle(&guess_body(padding, prefix, secret, candidate));
+            (length, candidate)
+        })
+        .collect::<Vec<_>>();
+
+    let shortest = lengths.iter().map(|&(length, _)| length).min()?;
+    let mut best = lengths.iter().filter(|&&(length, _)| length == shortest);
+    match (best.next(), best.next()) {
+        (Some(&(_, candidate)), None) => Some(candidate),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::chacha20::chacha20;
+    use crate::crypto::rc4::rc4;
+    use proptest::prelude::*;
+
+    const SESSION_ID: &[u8] = b"TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+    const BASE64_ALPHABET: &[u8] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+    /// Fresh key material for every query.
+    fn key_bytes(rng: &RefCell<SplitMix64>) -> [u8; 44] {
+        let mut bytes = [0; 44];
+        rng.borrow_mut().fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_recover_through_aes_ctr() {
+        let oracle = CompressionOracle::new(SESSION_ID, Cipher::Ctr, SplitMix64::new(0));
+        let secret = recover_secret(
+            &|body| oracle.length(body),
+            b"sessionid=",
+            BASE64_ALPHABET,
+            64,
+        );
+        assert_eq!(secret, SESSION_ID);
+    }
+
+    #[test]
+    fn test_recover_through_aes_cbc() {
+        let oracle = CompressionOracle::new(SESSION_ID, Cipher::Cbc, SplitMix64::new(0));
+        let secret = recover_secret_blockwise(
+            &|body| oracle.length(body),
+            b"sessionid=",
+            BASE64_ALPHABET,
+            64,
+        );
+        assert_eq!(secret, SESSION_ID);
+    }
+
+    #[test]
+    fn test_oracle_lengths() {
+        let body = b"sessionid=TmV2";
+        let expected = compress(&format_request(SESSION_ID, body)).len();
+
+        let ctr = CompressionOracle::new(SESSION_ID, Cipher::Ctr, SplitMix64::new(1));
+        assert_eq!(ctr.length(body), expected);
+
+        let cbc = CompressionOracle::new(SESSION_ID, Cipher::Cbc, SplitMix64::new(1));
+        assert_eq!(cbc.length(body), (expected / BLOCK_LEN + 1) * BLOCK_LEN);
+        assert_eq!(cbc.queries(), 1);
+    }
+
+    #[test]
+    fn test_recover_through_chacha20() {
+        let rng = RefCell::new(SplitMix64::new(0));
+        let oracle = |body: &[u8]| {
+            let bytes = key_bytes(&rng);
+            let key = bytes[..32].try_into().unwrap();
+            let nonce = bytes[32..44].try_into().unwrap();
+            chacha20(&compress(&format_request(SESSION_ID, body)), key, nonce, 1).len()
+        };
+
+        assert_eq!(
+            recover_secret(&oracle, b"sessionid=", BASE64_ALPHABET, 64),
+            SESSION_ID
+        );
+    }
+
+    #[test]
+    fn test_recover_through_rc4() {
+        let rng = RefCell::new(SplitMix64::new(0));
+        let oracle = |body: &[u8]| {
+            rc4(
+                &compress(&format_request(SESSION_ID, body)),
+                &key_bytes(&rng)[..16],
+            )
+            .len()
+        };
+
+        assert_eq!(
+            recover_secret(&oracle, b"sessionid=", BASE64_ALPHABET, 64),
+            SESSION_ID
+        );
+    }
+
+    #[test]
+    fn test_stops_at_max_len() {
+        let oracle = |body: &[u8]| compress(&format_request(SESSION_ID, body)).len();
+        assert_eq!(
+            recover_secret(&oracle, b"sessionid=", BASE64_ALPHABET, 8),
+            &SESSION_ID[..8]
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn recovers_any_session_id(session_id in "[A-Za-z0-9+/]{4,16}") {
+            let oracle = |body: &[u8]| compress(&format_request(session_id.as_bytes(), body)).len();
+            let secret = recover_secret(&oracle, b"sessionid=", BASE64_ALPHABET, 64);
+            assert_eq!(secret, session_id.as_bytes());
+        }
+
+        #[test]
+        fn recovers_any_session_id_through_cbc(session_id in "[A-Za-z0-9+/]{4,16}", seed: u64) {
+            let oracle = CompressionOracle::new(session_id.as_bytes(), Cipher::Cbc, SplitMix64::new(seed));
+            let secret = recover_secret_blockwise(&|body| oracle.length(body), b"sessionid=", BASE64_ALPHABET, 64);
+            assert_eq!(secret, session_id.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Breaking a random-access read/write API over a stream cipher
+//! (challenge 25).
+//!
+//! A server that lets clients re-encrypt part of a stored ciphertext in
+//! place reuses the keystream at that offset for the new text. Since
+//! `newtext ^ keystream` comes back, editing in known bytes reveals the
+//! keystream, and editing in the ciphertext itself reveals the plaintext
+//! outright. [`EditServer`] works with any [`SeekableStream`], such as
+//! [`AesCtr`](crate::crypto::aes::AesCtr) or
+//! [`ChaCha20`](crate::crypto::chacha20::ChaCha20).
+
+use crate::crypto::stream::SeekableStream;
+
+/// A server that stores a ciphertext and exposes
+/// [`SeekableStream::edit`] under its secret key.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::ctr_edit::EditServer;
+/// use cryptopals::crypto::aes::AesCtr;
+///
+/// let mut server = EditServer::new(AesCtr::new(b"YELLOW SUBMARINE", 0), b"attack at dawn");
+/// let ciphertext = server.ciphertext().to_vec();
+/// let edited = server.edit(&ciphertext, 10, b"dusk");
+/// assert_eq!(edited.len(), ciphertext.len());
+/// assert_eq!(edited[..10], ciphertext[..10]);
+/// ```
+#[derive(Clone)]
+pub struct EditServer<S> {
+    stream: S,
+    ciphertext: Vec<u8>,
+}
+
+impl<S: SeekableStream> EditServer<S> {
+    /// Encrypts the plaintext from the start of the keystream.
+    pub fn new(mut stream: S, plaintext: &[u8]) -> Self {
+        stream.seek(0);
+        let ciphertext = stream.apply_keystream(plaintext);
+        EditServer { stream, ciphertext }
+    }
+
+    /// The stored ciphertext, which anyone may read.
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.ciphertext
+    }
+
+    /// The public edit API: re-encrypts `ciphertext` with the plaintext at
+    /// `offset` replaced by `newtext`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is past the end of the ciphertext.
+    pub fn edit(&mut self, ciphertext: &[u8], offset: usize, newtext: &[u8]) -> Vec<u8> {
+        self.stream.edit(ciphertext, offset, newtext)
+    }
+}
+
+/// Recovers the plaintext under `ciphertext` with one call to `edit`.
+///
+/// Editing the ciphertext in as the new plaintext at offset 0 returns
+/// `ciphertext ^ keystream`, which is the plaintext.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::ctr_edit::{EditServer, recover_plaintext};
+/// use cryptopals::crypto::chacha20::ChaCha20;
+///
+/// let mut server = EditServer::new(ChaCha20::new(&[7; 32], &[1; 12], 1), b"attack at dawn");
+/// let ciphertext = server.ciphertext().to_vec();
+/// let plaintext = recover_plaintext(&ciphertext, |ciphertext, offset, newtext| {
+///     server.edit(ciphertext, offset, newtext)
+/// });
+/// assert_eq!(plaintext, b"attack at dawn");
+/// ```
+pub fn recover_plaintext(
+    ciphertext: &[u8],
+    mut edit: impl FnMut(&[u8], usize, &[u8]) -> Vec<u8>,
+) -> Vec<u8> {
+    edit(ciphertext, 0, ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::aes::AesCtr;
+    use crate::crypto::xor::xor_bytes;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_known_bytes_reveal_the_keystream() {
+        let plaintext = b"Ladies and Gentlemen of the class of '99";
+        let mut server = EditServer::new(AesCtr::new(b"YELLOW SUBMARINE", 9), plaintext);
+        let ciphertext = server.ciphertext().to_vec();
+
+        let keystream = server.edit(&ciphertext, 0, &vec![0; ciphertext.len()]);
+        assert_eq!(xor_bytes(&ciphertext, &keystream), plaintext);
+    }
+
+    proptest! {
+        #[test]
+        fn recovers_any_plaintext(key: [u8; 16], nonce: u64, plaintext in proptest::collection::vec(any::<u8>(), 0..100)) {
+            let mut server = EditServer::new(AesCtr::new(&key, nonce), &plaintext);
+            let ciphertext = server.ciphertext().to_vec();
+            let recovered = recover_plaintext(&ciphertext, |ciphertext, offset, newtext| {
+                server.edit(ciphertext, offset, newtext)
+            });
+            prop_assert_eq!(recovered, plaintext);
+        }
+    }
+}
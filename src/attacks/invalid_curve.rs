@@ -0,0 +1,217 @@
+//! The invalid-curve attack on ECDH (challenge 59).
+//!
+//! Adding points on `y^2 = x^3 + ax + b` never involves `b`. A party that
+//! multiplies whatever point it is sent by its secret, without checking that
+//! the point is on its curve, ends up computing on the curve with the same
+//! `a` that the point does lie on. Such curves have other orders, often with
+//! small factors, and a point of small order `r` confines the shared secret
+//! to `r` values just as in [`crate::attacks::subgroup_confinement`]. The
+//! residues from a few curves determine the secret by the Chinese Remainder
+//! Theorem ([Biehl, Meyer and Müller, "Differential fault attacks on
+//! elliptic curve cryptosystems"](https://doi.org/10.1007/3-540-44598-6_8)).
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::number_theory::crt;
+use crate::attacks::subgroup_confinement;
+use crate::crypto::ec::{Curve, Domain, Point};
+use crate::crypto::sha256::hmac_sha256;
+
+/// Curves `y^2 = x^3 + ax + b` with the `a` and field of
+/// [`Domain::toy`], as `(b, order)`. The small factors of their orders
+/// multiply to more than the toy group order.
+pub const TOY_WEAK_CURVES: [(u64, u128); 4] = [
+    // 2^2 * 383 * ...
+    (2, 220513544284156),
+    // 479 * 3613 * ...
+    (3, 220513513930933),
+    // 2 * 13 * 79 * ...
+    (5, 220513536950174),
+    // 2 * 3^2 * 17 * 43 * ...
+    (6, 220513540449474),
+];
+
+/// The curves of challenge 59, with the `a` and field of
+/// [`Domain::challenge59`], as `(b, order)`. Their prime factors below
+/// 2^16 multiply to more than the group order.
+pub const CHALLENGE59_WEAK_CURVES: [(u64, u128); 3] = [
+    // 2^2 * 3 * 11 * 23 * 31 * 89 * 4999 * 28411 * 45361 * ...
+    (210, 233970423115425145550826547352470124412),
+    // 2 * 5 * 7 * 11 * 61 * 12157 * 34693 * ...
+    (504, 233970423115425145544350131142039591210),
+    // 2^2 * 7 * 23 * 37 * 67 * 607 * 1979 * 13327 * 13799 * ...
+    (727, 233970423115425145545378039958152057148),
+];
+
+/// The curves `(b, order)` of `parameters`, with the `a` and field of
+/// `domain`.
+///
+/// # Panics
+///
+/// Panics if some `b` gives a singular curve or is not below the modulus.
+pub fn weak_curves(domain: &Domain, parameters: &[(u64, u128)]) -> Vec<(Curve, BigUint)> {
+    let (a, p) = (domain.curve.a(), domain.curve.field().modulus());
+    parameters
+        .iter()
+        .map(|&(b, order)| {
+            let curve = Curve::new(a.clone(), BigUint::from(b), p.clone())
+                .expect("weak curves are not singular");
+            (curve, BigUint::from(order))
+        })
+        .collect()
+}
+
+/// A party that answers any point with a message authenticated under the
+/// ECDH shared point, without validating the point.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::invalid_curve::{Bob, mac};
+/// use cryptopals::crypto::ec::Domain;
+///
+/// let domain = Domain::toy();
+/// let secret = BigUint::from(777u64);
+/// let bob = Bob::new(domain.curve.clone(), secret.clone());
+/// let (message, tag) = bob.respond(&domain.generator);
+/// assert_eq!(tag, mac(&domain.public_key(&secret), &message));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bob {
+    curve: Curve,
+    secret: BigUint,
+}
+
+impl Bob {
+    pub fn new(curve: Curve, secret: BigUint) -> Self {
+        Bob { curve, secret }
+    }
+
+    /// Returns a message and its tag under `secret * public`.
+    pub fn respond(&self, public: &Point) -> (Vec<u8>, [u8; 32]) {
+        let shared = self.curve.multiply(public, &self.secret);
+        let message = b"crazy flamboyant for the rap enjoyment".to_vec();
+        let tag = mac(&shared, &message);
+        (message, tag)
+    }
+}
+
+/// HMAC-SHA256 keyed with both coordinates of a shared point, big-endian,
+/// as [`Bob`] uses it. The point at infinity gives an empty key.
+pub fn mac(shared: &Point, message: &[u8]) -> [u8; 32] {
+    match shared {
+        Point::Infinity => hmac_sha256(&[], message),
+        Point::Affine { x, y } => {
+            hmac_sha256(&[x.to_be_bytes(), y.to_be_bytes()].concat(), message)
+        }
+    }
+}
+
+/// Recovers a secret from its residues modulo the small factors of the
+/// orders of `weak_curves`, given as `(curve, order)`.
+///
+/// Points of small order are found on each weak curve and sent to `oracle`
+/// by [`subgroup_confinement::recover_secret`], with prime factors up to
+/// `bound`. Returns `(residue, modulus)` with `secret = residue (mod
+/// modulus)`, which is the secret itself once the modulus exceeds it.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::invalid_curve::{Bob, TOY_WEAK_CURVES, mac, recover_secret, weak_curves};
+/// use cryptopals::crypto::ec::Domain;
+///
+/// let domain = Domain::toy();
+/// let bob = Bob::new(domain.curve.clone(), BigUint::from(31337u64));
+///
+/// // 2 * 383 from the first curve alone pins down a small secret
+/// let weak = weak_curves(&domain, &TOY_WEAK_CURVES[..1]);
+/// let (residue, modulus) = recover_secret(&weak, 1 << 12, |point| bob.respond(point), mac);
+/// assert_eq!((residue, modulus), (BigUint::from(31337u64 % 766), BigUint::from(766u64)));
+/// ```
+pub fn recover_secret<T: PartialEq>(
+    weak_curves: &[(Curve, BigUint)],
+    bound: u64,
+    oracle: impl Fn(&Point) -> (Vec<u8>, T),
+    mac: impl Fn(&Point, &[u8]) -> T,
+) -> (BigUint, BigUint) {
+    let congruences = weak_curves
+        .iter()
+        .map(|(curve, order)| {
+            subgroup_confinement::recover_secret(
+                curve,
+                order,
+                bound,
+                // half the x-coordinates lift; the others give the identity,
+                // which is skipped
+                |index| {
+                    curve
+                        .lift_x(&BigUint::from(index))
+                        .unwrap_or(Point::Infinity)
+                },
+                &oracle,
+                &mac,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    crt(&congruences).expect("residues of one secret are consistent")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_weak_curve_orders() {
+        for (domain, parameters) in [
+            (Domain::toy(), &TOY_WEAK_CURVES[..]),
+            (Domain::challenge59(), &CHALLENGE59_WEAK_CURVES[..]),
+        ] {
+            for (curve, order) in weak_curves(&domain, parameters) {
+                let point = (1u64..)
+                    .find_map(|x| curve.lift_x(&BigUint::from(x)))
+                    .unwrap();
+                assert!(!domain.curve.contains(&point));
+                assert_eq!(curve.multiply(&point, &order), Point::Infinity);
+                // Hasse's bound: |order - (p + 1)| <= 2 sqrt(p)
+                let p_plus_one = curve.field().modulus() + &BigUint::one();
+                let trace = if order > p_plus_one {
+                    &order - &p_plus_one
+                } else {
+                    &p_plus_one - &order
+                };
+                assert!(&trace * &trace <= BigUint::from(4u64) * curve.field().modulus());
+            }
+        }
+    }
+
+    #[test]
+    fn test_validating_ecdh_is_immune() {
+        let domain = Domain::toy();
+        let (curve, order) = weak_curves(&domain, &TOY_WEAK_CURVES).swap_remove(0);
+        let point = curve.lift_x(&BigUint::one()).unwrap();
+        let small = curve.multiply(&point, &(order / BigUint::from(383u64)));
+        assert_eq!(domain.shared_secret(&BigUint::from(12345u64), &small), None);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn recovers_any_secret(seed: u64) {
+            let domain = Domain::toy();
+            let secret = domain.random_secret(&mut SplitMix64::new(seed));
+            let bob = Bob::new(domain.curve.clone(), secret.clone());
+
+            let weak = weak_curves(&domain, &TOY_WEAK_CURVES);
+            let (residue, modulus) =
+                recover_secret(&weak, 1 << 12, |point| bob.respond(point), mac);
+            prop_assert!(modulus > domain.order);
+            prop_assert_eq!(residue, secret);
+        }
+    }
+}
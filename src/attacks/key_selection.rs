@@ -0,0 +1,298 @@
+//! Duplicate-signature key selection (challenge 61).
+//!
+//! A signature binds a message to a key only as far as the verifier fixes
+//! every parameter of the key. Given a signature by someone else, an
+//! attacker who may choose their own domain parameters can make a key pair
+//! under which the same signature verifies, and then claim the message
+//! (Blake-Wilson and Menezes, "Unknown key-share attacks on the
+//! station-to-station (STS) protocol", PKC 1999).
+//!
+//! For ECDSA the attacker picks their secret first and then the generator
+//! that makes it work. For RSA they pick primes `p` and `q` for which
+//! `p - 1` and `q - 1` are smooth, so that the discrete logarithm of the
+//! padded message to the base of the signature is easy with
+//! [`pohlig_hellman`], and that logarithm is the public exponent.
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::discrete_log::{BigModularGroup, pohlig_hellman};
+use crate::analysis::number_theory::{crt, is_prime, is_probable_prime};
+use crate::crypto::ec::ecdsa::{self, Signature, message_scalar};
+use crate::crypto::ec::field::Field;
+use crate::crypto::ec::{Domain, Point};
+use crate::crypto::rsa::{self, KeyPair, PublicKey};
+use crate::util::SplitMix64;
+use std::collections::HashSet;
+
+/// The bound on the prime factors of `p - 1` and `q - 1` for RSA.
+const SMOOTHNESS_BOUND: u64 = 1 << 16;
+
+/// Returns domain parameters on the same curve and a secret key under which
+/// `signature` verifies for `message`, or `None` if the signature is not
+/// valid under `public`.
+///
+/// With `u1 = e / s` and `u2 = r / s`, verification computes
+/// `R = u1 G + u2 Q`. For a chosen secret `d'`, the generator
+/// `G' = R / (u1 + u2 d')` gives `u1 G' + u2 d' G' = R` again.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::key_selection::select_ecdsa_key;
+/// use cryptopals::crypto::ec::Domain;
+/// use cryptopals::crypto::ec::ecdsa::{sign, verify};
+/// use cryptopals::util::SplitMix64;
+///
+/// let domain = Domain::toy();
+/// let mut rng = SplitMix64::new(1);
+/// let secret = BigUint::from(1234u64);
+/// let public = domain.public_key(&secret);
+/// let signature = sign(&domain, &secret, b"hi mom", &mut rng);
+///
+/// let (forged, secret) = select_ecdsa_key(&domain, &public, b"hi mom", &signature, &mut rng).unwrap();
+/// assert_ne!(forged.public_key(&secret), public);
+/// assert!(verify(&forged, &forged.public_key(&secret), b"hi mom", &signature));
+/// ```
+pub fn select_ecdsa_key(
+    domain: &Domain,
+    public: &Point,
+    message: &[u8],
+    signature: &Signature,
+    rng: &mut SplitMix64,
+) -> Option<(Domain, BigUint)> {
+    if !ecdsa::verify(domain, public, message, signature) {
+        return None;
+    }
+    let field = Field::new(domain.order.clone()).expect("the group order is an odd prime");
+
+    let s_inverse = field.inverse(&signature.s).expect("s is not zero");
+    let u1 = field.multiply(&message_scalar(domain, message), &s_inverse);
+    let u2 = field.multiply(&signature.r, &s_inverse);
+    let point = domain.curve.add(
+        &domain.curve.multiply(&domain.generator, &u1),
+        &domain.curve.multiply(public, &u2),
+    );
+
+    loop {
+        let secret = domain.random_secret(rng);
+        let Some(t_inverse) = field.inverse(&field.add(&u1, &field.multiply(&u2, &secret))) else {
+            continue;
+        };
+        let forged = Domain {
+            generator: domain.curve.multiply(&point, &t_inverse),
+            ..domain.clone()
+        };
+        return Some((forged, secret));
+    }
+}
+
+/// Returns a key pair under which `signature` verifies for `message`, or
+/// `None` if the signature is not valid under `public`.
+///
+/// The new modulus is longer than the old one, so it exceeds the signature,
+/// and the message is padded to its length.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::attacks::key_selection::select_rsa_key;
+/// use cryptopals::crypto::rsa::{KeyPair, verify};
+/// use cryptopals::util::SplitMix64;
+///
+/// let mut rng = SplitMix64::new(1);
+/// let keys = KeyPair::generate(512, &mut rng);
+/// let signature = keys.sign(b"hi mom").unwrap();
+///
+/// let forged = select_rsa_key(&keys.public, b"hi mom", &signature, &mut rng).unwrap();
+/// assert_ne!(forged.public, keys.public);
+/// assert!(verify(&forged.public, b"hi mom", &signature));
+/// ```
+pub fn select_rsa_key(
+    public: &PublicKey,
+    message: &[u8],
+    signature: &BigUint,
+    rng: &mut SplitMix64,
+) -> Option<KeyPair> {
+    if !rsa::verify(public, message, signature) {
+        return None;
+    }
+    // both primes exceed 2^bits, so their product exceeds the old modulus
+    let bits = public.modulus.bits().div_ceil(2);
+
+    loop {
+        let mut used = HashSet::new();
+        let (p, p_factors) = smooth_prime(bits, signature, &mut used, rng);
+        let (q, q_factors) = smooth_prime(bits, signature, &mut used, rng);
+        let modulus = &p * &q;
+        let Some(padded) = rsa::encode(message, modulus.bits().div_ceil(8) as usize) else {
+            continue;
+        };
+
+        let mut congruences = Vec::with_capacity(2);
+        for (prime, factors) in [(&p, &p_factors), (&q, &q_factors)] {
+            let group = BigModularGroup::new(prime.clone()).expect("the prime passed the test");
+            let generator = signature % prime;
+            let (exponent, _) = pohlig_hellman(&group, &generator, &(&padded % prime), factors);
+            let Some(exponent) = exponent else {
+                break;
+            };
+            congruences.push((exponent, prime - BigUint::one()));
+        }
+        if congruences.len() < 2 {
+            continue;
+        }
+
+        // both moduli are even, so the exponents may disagree modulo 2
+        let Some((exponent, _)) = crt(&congruences) else {
+            continue;
+        };
+        if let Some(keys) = KeyPair::from_primes(&p, &q, exponent) {
+            return Some(keys);
+        }
+    }
+}
+
+/// A prime `p = 2 r_1 ... r_k + 1` of more than `bits` bits with distinct
+/// odd primes `r_i` below [`SMOOTHNESS_BOUND`] that are not in `used`, of
+/// which `base` is a primitive root, and the factorization of `p - 1`.
+fn smooth_prime(
+    bits: u64,
+    base: &BigUint,
+    used: &mut HashSet<u64>,
+    rng: &mut SplitMix64,
+) -> (BigUint, Vec<(u64, u32)>) {
+    loop {
+        let mut factors = vec![(2, 1)];
+        let mut half = BigUint::one();
+        while half.bits() < bits {
+            let factor = rng.below(SMOOTHNESS_BOUND);
+            if factor > 2
+                && is_prime(factor)
+                && !used.contains(&factor)
+                && factors.iter().all(|&(known, _)| known != factor)
+            {
+                factors.push((factor, 1));
+                half = half * BigUint::from(factor);
+            }
+        }
+        let prime = (half << 1) + BigUint::one();
+        if !is_probable_prime(&prime) {
+            continue;
+        }
+
+        let one = BigUint::one();
+        let order = &prime - &one;
+        let is_primitive_root = !(base % &prime).is_zero()
+            && factors
+                .iter()
+                .all(|&(factor, _)| base.pow_mod(&(&order / BigUint::from(factor)), &prime) != one);
+        if is_primitive_root {
+            used.extend(
+                factors
+                    .iter()
+                    .map(|&(factor, _)| factor)
+                    .filter(|&factor| factor != 2),
+            );
+            return (prime, factors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ec::ecdsa::{sign, verify};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_rejects_invalid_signatures() {
+        let domain = Domain::toy();
+        let mut rng = SplitMix64::new(2);
+        let secret = BigUint::from(1234u64);
+        let public = domain.public_key(&secret);
+        let signature = sign(&domain, &secret, b"message", &mut rng);
+        assert!(select_ecdsa_key(&domain, &public, b"other", &signature, &mut rng).is_none());
+
+        let keys = KeyPair::generate(512, &mut rng);
+        let signature = keys.sign(b"message").unwrap();
+        assert!(select_rsa_key(&keys.public, b"other", &signature, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_selects_a_p256_key() {
+        let domain = Domain::p256();
+        let mut rng = SplitMix64::new(4);
+        let secret = domain.random_secret(&mut rng);
+        let public = domain.public_key(&secret);
+        let signature = sign(&domain, &secret, b"message", &mut rng);
+
+        let (forged, forged_secret) =
+            select_ecdsa_key(&domain, &public, b"message", &signature, &mut rng).unwrap();
+        let forged_public = forged.public_key(&forged_secret);
+        assert_ne!(forged_public, public);
+        assert!(verify(&forged, &forged_public, b"message", &signature));
+    }
+
+    #[test]
+    fn test_smooth_prime() {
+        let mut rng = SplitMix64::new(3);
+        let mut used = HashSet::new();
+        let base = BigUint::from(7u64);
+        let (first, first_factors) = smooth_prime(100, &base, &mut used, &mut rng);
+        let (second, second_factors) = smooth_prime(100, &base, &mut used, &mut rng);
+
+        for (prime, factors) in [(&first, &first_factors), (&second, &second_factors)] {
+            assert!(prime.bits() > 100);
+            let product = factors
+                .iter()
+                .fold(BigUint::one(), |product, &(factor, _)| {
+                    product * BigUint::from(factor)
+                });
+            assert_eq!(product + BigUint::one(), *prime);
+        }
+        // only 2 is shared
+        assert!(
+            first_factors
+                .iter()
+                .skip(1)
+                .all(|factor| !second_factors.contains(factor))
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn ecdsa_signatures_verify_under_the_selected_key(seed: u64, message: Vec<u8>) {
+            let domain = Domain::toy();
+            let mut rng = SplitMix64::new(seed);
+            let secret = domain.random_secret(&mut rng);
+            let signature = sign(&domain, &secret, &message, &mut rng);
+
+            let (forged, forged_secret) = select_ecdsa_key(
+                &domain,
+                &domain.public_key(&secret),
+                &message,
+                &signature,
+                &mut rng,
+            ).unwrap();
+            prop_assert!(domain.curve.contains(&forged.generator));
+            prop_assert!(verify(&forged, &forged.public_key(&forged_secret), &message, &signature));
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2))]
+
+        #[test]
+        fn rsa_signatures_verify_under_the_selected_key(seed: u64, message: Vec<u8>) {
+            let mut rng = SplitMix64::new(seed);
+            let keys = KeyPair::generate(512, &mut rng);
+            let signature = keys.sign(&message).unwrap();
+
+            let forged = select_rsa_key(&keys.public, &message, &signature, &mut rng).unwrap();
+            prop_assert!(forged.public.modulus > keys.public.modulus);
+            prop_assert!(rsa::verify(&forged.public, &message, &signature));
+        }
+    }
+}
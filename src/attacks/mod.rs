@@ -0,0 +1,14 @@
+//! End-to-end attacks on protocols.
+//!
+//! Where [`crate::analysis`] holds the cryptanalytic tools, this module
+//! pairs attacks with the local servers they fool.
+
+pub mod biased_nonce;
+pub mod cbc_iv_key;
+pub mod cbc_mac;
+pub mod compression_oracle;
+pub mod ctr_edit;
+pub mod invalid_curve;
+pub mod key_selection;
+pub mod subgroup_confinement;
+pub mod twist_attack;
@@ -0,0 +1,356 @@
+//! Diffie-Hellman subgroup-confinement attack.
+//!
+//! Bob computes a shared secret `K = h^x` from whatever public key `h` he is
+//! sent and authenticates a message with it. If the group order has small
+//! prime factors `r`, an attacker can send an element `h` of order `r`: `K`
+//! then takes one of only `r` values, and trying them all against the tag
+//! reveals `x mod r`. The residues are combined with the Chinese Remainder
+//! Theorem (challenge 57).
+//!
+//! When the small factors do not cover the whole secret, `x = n + m * r`
+//! with `n` and `r` known and `m` small, so Pollard's kangaroo finds `m` from
+//! Bob's public key `y` as the logarithm of `y * g^-n` to the base `g^r`
+//! (challenge 58).
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::discrete_log::{CyclicGroup, kangaroo_parameters, pollard_kangaroo};
+use crate::analysis::number_theory::{crt, trial_division};
+use crate::crypto::sha256::hmac_sha256;
+
+/// Candidates tried for each small factor before it is given up on.
+const MAX_CANDIDATES: u64 = 1 << 10;
+
+/// A party that answers any public key with a message authenticated under
+/// the shared secret, without checking which subgroup the key lies in.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::discrete_log::{CyclicGroup, ModularGroup};
+/// use cryptopals::attacks::subgroup_confinement::{Bob, mac};
+///
+/// let group = ModularGroup::new(2027).unwrap();
+/// let bob = Bob::new(group, BigUint::from(777u64));
+/// let (message, tag) = bob.respond(&4);
+/// assert_eq!(tag, mac(&group.power(&4, 777), &message));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bob<G> {
+    group: G,
+    secret: BigUint,
+}
+
+impl<G> Bob<G>
+where
+    G: CyclicGroup,
+    G::Element: Into<BigUint>,
+{
+    pub fn new(group: G, secret: BigUint) -> Self {
+        Bob { group, secret }
+    }
+
+    /// Returns a message and its tag under `public^secret`.
+    pub fn respond(&self, public: &G::Element) -> (Vec<u8>, [u8; 32]) {
+        let shared = self.group.power_big(public, &self.secret);
+        let message = b"crazy flamboyant for the rap enjoyment".to_vec();
+        let tag = mac(&shared, &message);
+        (message, tag)
+    }
+}
+
+/// HMAC-SHA256 keyed with the big-endian bytes of a shared secret, as
+/// [`Bob`] uses it.
+pub fn mac<E: Clone + Into<BigUint>>(shared: &E, message: &[u8]) -> [u8; 32] {
+    hmac_sha256(&shared.clone().into().to_be_bytes(), message)
+}
+
+/// An element of prime order `factor`, raising the candidates
+/// `element(0)`, `element(1)`, ... to the power `group_order / factor`
+/// until one is not the identity.
+fn element_of_order<G: CyclicGroup>(
+    group: &G,
+    group_order: &BigUint,
+    factor: u64,
+    element: &impl Fn(u64) -> G::Element,
+) -> Option<G::Element> {
+    let cofactor = group_order / BigUint::from(factor);
+    (0..MAX_CANDIDATES)
+        .map(|index| group.power_big(&element(index), &cofactor))
+        .find(|candidate| *candidate != group.identity())
+}
+
+/// Recovers the victim's secret exponent modulo the small prime factors of
+/// the group order.
+///
+/// `group_order` is the order of the whole group that public keys are taken
+/// from, and its prime factors up to `bound` are used. `element` enumerates
+/// group elements, from which elements of small order are derived. `oracle`
+/// answers a public key with a message and its tag, and `mac` computes the
+/// tag a shared secret would give.
+///
+/// Returns `(residue, modulus)` with `secret = residue (mod modulus)`. The
+/// secret is fully recovered once `modulus` exceeds it; otherwise the
+/// remaining bits can be searched for with [`recover_remaining`].
+/// Each factor `r` costs one oracle query and at most `r` group operations
+/// and MACs.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::discrete_log::ModularGroup;
+/// use cryptopals::attacks::subgroup_confinement::{Bob, mac, recover_secret};
+///
+/// // 2027 - 1 = 2 * 1013
+/// let group = ModularGroup::new(2027).unwrap();
+/// let bob = Bob::new(group, BigUint::from(777u64));
+/// let (residue, modulus) = recover_secret(
+///     &group,
+///     &BigUint::from(2026u64),
+///     1013,
+///     |index| index + 2,
+///     |public| bob.respond(public),
+///     mac,
+/// );
+/// assert_eq!((residue, modulus), (BigUint::from(777u64), BigUint::from(2026u64)));
+/// ```
+pub fn recover_secret<G, T>(
+    group: &G,
+    group_order: &BigUint,
+    bound: u64,
+    element: impl Fn(u64) -> G::Element,
+    oracle: impl Fn(&G::Element) -> (Vec<u8>, T),
+    mac: impl Fn(&G::Element, &[u8]) -> T,
+) -> (BigUint, BigUint)
+where
+    G: CyclicGroup,
+    T: PartialEq,
+{
+    let (factors, _) = trial_division(group_order.clone(), bound);
+
+    let congruences = factors
+        .iter()
+        .filter_map(|&(factor, _)| {
+            let confined = element_of_order(group, group_order, factor, &element)?;
+            let (message, tag) = oracle(&confined);
+
+            // the shared secret is confined^(secret mod factor)
+            let mut shared = group.identity();
+            for residue in 0..factor {
+                if mac(&shared, &message) == tag {
+                    return Some((BigUint::from(residue), BigUint::from(factor)));
+                }
+                shared = group.operate(&shared, &confined);
+            }
+            None
+        })
+        .collect::<Vec<_>>();
+
+    crt(&congruences).expect("residues modulo distinct primes are consistent")
+}
+
+/// Tame jump counts tried by [`recover_remaining`], as multiples of the
+/// default, each moving the trap a wild kangaroo may have jumped over.
+const TRAP_DISTANCES: [u64; 4] = [1, 2, 3, 5];
+
+/// Finds the secret exponent of `public = generator^secret` given
+/// `secret = residue (mod modulus)`, as returned by [`recover_secret`].
+///
+/// `order` is the order of `generator`. The unknown quotient is at most
+/// `order / modulus`, which takes Pollard's kangaroo about the square root
+/// of that many group operations. Returns `None` if every kangaroo misses,
+/// which means the residue is wrong or, rarely, that the walks were
+/// unlucky, and also if the quotient does not fit a `u64`, far more than a
+/// kangaroo can search.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::analysis::discrete_log::{CyclicGroup, ModularGroup};
+/// use cryptopals::attacks::subgroup_confinement::recover_remaining;
+///
+/// // 4 generates the subgroup of order 1013
+/// let group = ModularGroup::new(2027).unwrap();
+/// let public = group.power(&4, 777);
+/// let partial = (BigUint::from(777u64 % 10), BigUint::from(10u64));
+/// let secret = recover_remaining(&group, &4, &BigUint::from(1013u64), &public, &partial);
+/// assert_eq!(secret, Some(BigUint::from(777u64)));
+/// ```
+pub fn recover_remaining<G: CyclicGroup>(
+    group: &G,
+    generator: &G::Element,
+    order: &BigUint,
+    public: &G::Element,
+    partial: &(BigUint, BigUint),
+) -> Option<BigUint> {
+    recover_remaining_any(
+        group,
+        generator,
+        order,
+        &[(public.clone(), partial.clone())],
+    )
+}
+
+/// [`recover_remaining`] for several guesses `(public, (residue,
+/// modulus))`, when only one of them is expected to be right.
+///
+/// Every guess gets a kangaroo before any gets its trap moved, so a wrong
+/// guess costs one kangaroo rather than one per trap distance. Returns the
+/// first secret found.
+pub fn recover_remaining_any<G: CyclicGroup>(
+    group: &G,
+    generator: &G::Element,
+    order: &BigUint,
+    guesses: &[(G::Element, (BigUint, BigUint))],
+) -> Option<BigUint> {
+    // public * g^-residue = (g^modulus)^m for a quotient m up to upper
+    let mut searches = Vec::with_capacity(guesses.len());
+    for (public, (residue, modulus)) in guesses {
+        if modulus >= order {
+            let secret = residue % order;
+            if group.power_big(generator, &secret) == *public {
+                return Some(secret);
+            }
+            continue;
+        }
+        let base = group.power_big(generator, modulus);
+        let target = group.operate(public, &group.inverse(&group.power_big(generator, residue)));
+        let Some(upper) = (order - BigUint::one())
+            .checked_sub(residue)
+            .and_then(|span| (span / modulus).to_u64())
+        else {
+            continue;
+        };
+        searches.push((base, target, upper, residue, modulus));
+    }
+
+    TRAP_DISTANCES.iter().find_map(|&multiple| {
+        searches
+            .iter()
+            .find_map(|(base, target, upper, residue, modulus)| {
+                let mut parameters = kangaroo_parameters(*upper);
+                parameters.tame_jumps *= multiple;
+                let (quotient, _) = pollard_kangaroo(group, base, target, 0, *upper, &parameters);
+                quotient.map(|quotient| *residue + BigUint::from(quotient) * *modulus)
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::discrete_log::ModularGroup;
+    use proptest::prelude::*;
+
+    // p - 1 = 2 * 53 * 547 * 601 * 2939 * q, and g generates the subgroup
+    // of prime order q
+    const PRIME: u64 = 1718257541434531583;
+    const ORDER: u64 = 16777259;
+    const GENERATOR: u64 = 1053674166658224433;
+
+    fn big(value: u64) -> BigUint {
+        BigUint::from(value)
+    }
+
+    fn attack(secret: u64) -> (BigUint, BigUint) {
+        let group = ModularGroup::new(PRIME).unwrap();
+        let bob = Bob::new(group, big(secret));
+        recover_secret(
+            &group,
+            &big(PRIME - 1),
+            1 << 16,
+            |index| index + 2,
+            |public| bob.respond(public),
+            mac,
+        )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn recovers_any_secret(secret in 0..ORDER) {
+            let (residue, modulus) = attack(secret);
+            assert_eq!(modulus, big(2 * 53 * 547 * 601 * 2939));
+            assert_eq!(residue, big(secret));
+        }
+    }
+
+    #[test]
+    fn test_bob_uses_the_public_key() {
+        let group = ModularGroup::new(PRIME).unwrap();
+        let bob = Bob::new(group, big(12345));
+        let public = group.power(&GENERATOR, 678);
+        let (message, tag) = bob.respond(&public);
+
+        assert_eq!(tag, mac(&group.power(&GENERATOR, 12345 * 678), &message));
+        assert_ne!(tag, mac(&public, &message));
+    }
+
+    #[test]
+    fn test_small_order_elements() {
+        let group = ModularGroup::new(PRIME).unwrap();
+        for factor in [2, 53, 547, 601, 2939] {
+            let element =
+                element_of_order(&group, &big(PRIME - 1), factor, &|index| index + 2).unwrap();
+            assert_ne!(element, 1);
+            assert_eq!(group.power(&element, factor), 1);
+        }
+    }
+
+    #[test]
+    fn test_recover_remaining() {
+        let group = ModularGroup::new(PRIME).unwrap();
+        let secret = 9_876_543;
+        let public = group.power(&GENERATOR, secret);
+
+        // with a low bound the kangaroo searches about 2^17 quotients
+        let bob = Bob::new(group, big(secret));
+        let partial = recover_secret(
+            &group,
+            &big(PRIME - 1),
+            100,
+            |index| index + 2,
+            |public| bob.respond(public),
+            mac,
+        );
+        let order = big(ORDER);
+        assert_eq!(
+            recover_remaining(&group, &GENERATOR, &order, &public, &partial),
+            Some(big(secret))
+        );
+        assert_eq!(
+            recover_remaining(&group, &GENERATOR, &order, &public, &attack(secret)),
+            Some(big(secret))
+        );
+        assert_eq!(
+            recover_remaining(
+                &group,
+                &GENERATOR,
+                &order,
+                &public,
+                &(big((secret + 1) % 106), big(106))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_partial_recovery() {
+        // with a low bound only 2 and 53 are used
+        let group = ModularGroup::new(PRIME).unwrap();
+        let bob = Bob::new(group, big(1_000_000));
+        let (residue, modulus) = recover_secret(
+            &group,
+            &big(PRIME - 1),
+            100,
+            |index| index + 2,
+            |public| bob.respond(public),
+            mac,
+        );
+        assert_eq!(modulus, big(106));
+        assert_eq!(residue, big(1_000_000 % 106));
+    }
+}
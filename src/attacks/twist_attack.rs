@@ -0,0 +1,345 @@
+//! The twist attack on x-only ECDH (challenge 60).
+//!
+//! A Montgomery ladder that takes a bare `u`-coordinate cannot be sent a
+//! point off the curve, but every `u` is the coordinate of a point on either
+//! the curve or its quadratic twist, and the ladder computes on whichever it
+//! is. When the twist order has small factors `r`, twist points of order `r`
+//! confine the shared secret as in [`crate::attacks::subgroup_confinement`].
+//!
+//! Two things differ. A `u`-coordinate stands for both `P` and `-P`, so each
+//! leak gives the secret modulo `r` only up to sign. Points of order
+//! `r_1 r_2` tell whether two signs agree, which leaves the secret modulo
+//! the product up to one overall sign. And the small factors of the twist
+//! order do not cover the secret, so Pollard's kangaroo finds the rest from
+//! the public key for both signs ([Fouque, Lercier, Réal and Valette, "Fault
+//! attack on elliptic curve Montgomery ladder
+//! implementation"](https://doi.org/10.1109/FDTC.2008.15)).
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::number_theory::{crt, trial_division};
+use crate::attacks::subgroup_confinement::recover_remaining_any;
+use crate::crypto::ec::montgomery::MontgomeryCurve;
+use crate::crypto::ec::{Curve, Domain, Point};
+use crate::crypto::sha256::hmac_sha256;
+
+/// The order of the twist of [`MontgomeryCurve::toy`],
+/// `2^2 * 3 * 457 * 1657 * 24266953`.
+pub const TOY_TWIST_ORDER: u128 = 220513510707564;
+
+/// The order of the twist of [`MontgomeryCurve::challenge60`],
+/// `2^2 * 11 * 107 * 197 * 1621 * 105143 * 405373 * 2323367 *
+/// 1571528514013`.
+pub const CHALLENGE60_TWIST_ORDER: u128 = 233970423115425145549737651362517029924;
+
+/// Twist coordinates tried when looking for a point of a given order.
+const MAX_CANDIDATES: u64 = 1 << 10;
+
+/// A party that answers any `u`-coordinate with a message authenticated
+/// under the x-only shared secret, as X25519 does, without checking which
+/// curve the coordinate belongs to.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::twist_attack::{Bob, mac};
+/// use cryptopals::crypto::ec::montgomery::MontgomeryCurve;
+///
+/// let curve = MontgomeryCurve::toy();
+/// let (u, secret) = (BigUint::from(4u64), BigUint::from(777u64));
+/// let bob = Bob::new(curve.clone(), secret.clone());
+/// let (message, tag) = bob.respond(&u);
+/// assert_eq!(tag, mac(&curve.ladder(&u, &secret), &message));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bob {
+    curve: MontgomeryCurve,
+    secret: BigUint,
+}
+
+impl Bob {
+    pub fn new(curve: MontgomeryCurve, secret: BigUint) -> Self {
+        Bob { curve, secret }
+    }
+
+    /// Returns a message and its tag under `ladder(u, secret)`.
+    pub fn respond(&self, u: &BigUint) -> (Vec<u8>, [u8; 32]) {
+        let shared = self.curve.ladder(u, &self.secret);
+        let message = b"crazy flamboyant for the rap enjoyment".to_vec();
+        let tag = mac(&shared, &message);
+        (message, tag)
+    }
+}
+
+/// HMAC-SHA256 keyed with the big-endian bytes of a shared `u`-coordinate,
+/// as [`Bob`] uses it.
+pub fn mac(shared: &BigUint, message: &[u8]) -> [u8; 32] {
+    hmac_sha256(&shared.to_be_bytes(), message)
+}
+
+/// The twist of `curve` with its Weierstrass form, in which twist points
+/// are added.
+fn twist_group(curve: &MontgomeryCurve) -> (MontgomeryCurve, Curve) {
+    let twist = curve.twist();
+    let weierstrass = twist.to_weierstrass();
+    (twist, weierstrass)
+}
+
+/// The `u`-coordinate of a twist point, 0 for the point at infinity as the
+/// ladder gives it.
+fn twist_u(twist: &MontgomeryCurve, point: &Point) -> BigUint {
+    twist
+        .from_weierstrass_point(point)
+        .map_or_else(BigUint::zero, |(u, _)| u)
+}
+
+/// A point of the twist whose order is the product of the distinct primes
+/// `factors`, all of which divide `twist_order`.
+fn twist_point_of_order(
+    (twist, weierstrass): &(MontgomeryCurve, Curve),
+    twist_order: &BigUint,
+    factors: &[u64],
+) -> Option<Point> {
+    let order = factors.iter().fold(BigUint::one(), |order, &factor| {
+        order * BigUint::from(factor)
+    });
+    let cofactor = twist_order / &order;
+    (1..MAX_CANDIDATES)
+        .map(BigUint::from)
+        .filter_map(|u| {
+            let v = twist.lift_u(&u)?;
+            Some(weierstrass.multiply(&twist.to_weierstrass_point(&u, &v), &cofactor))
+        })
+        .find(|point| {
+            factors.iter().all(|&factor| {
+                weierstrass.multiply(point, &(&order / BigUint::from(factor))) != Point::Infinity
+            })
+        })
+}
+
+/// Recovers the secret modulo the small odd prime factors of the twist
+/// order, up to sign.
+///
+/// For each prime factor `r` of `twist_order` up to `bound`, a twist point
+/// of order `r` is sent to `oracle`, and the `u`-coordinates of its
+/// multiples, added up on the twist, are tried against the tag with `mac`.
+/// Returns `(n, r)` pairs with `secret = ±n (mod r)` and `n <= r / 2`. The
+/// factor 2 is skipped: the ladder sends the point `(0, 0)` of order two to
+/// 0 whatever the scalar.
+pub fn recover_residues<T: PartialEq>(
+    curve: &MontgomeryCurve,
+    twist_order: &BigUint,
+    bound: u64,
+    oracle: impl Fn(&BigUint) -> (Vec<u8>, T),
+    mac: impl Fn(&BigUint, &[u8]) -> T,
+) -> Vec<(u64, u64)> {
+    let twist = twist_group(curve);
+    let (factors, _) = trial_division(twist_order.clone(), bound);
+
+    factors
+        .iter()
+        .filter(|&&(factor, _)| factor != 2)
+        .filter_map(|&(factor, _)| {
+            let confined = twist_point_of_order(&twist, twist_order, &[factor])?;
+            let (message, tag) = oracle(&twist_u(&twist.0, &confined));
+
+            // k and r - k give the same u-coordinate
+            let mut shared = Point::Infinity;
+            for residue in 0..=factor / 2 {
+                if mac(&twist_u(&twist.0, &shared), &message) == tag {
+                    return Some((residue, factor));
+                }
+                shared = twist.1.add(&shared, &confined);
+            }
+            None
+        })
+        .collect()
+}
+
+/// Joins the residues of [`recover_residues`] into `(n, m)` with
+/// `secret = ±n (mod m)`.
+///
+/// One residue is taken as positive. For every other one, a twist point of
+/// order the product of the two primes is sent to `oracle`, and the tag
+/// tells whether the second residue has the same sign as the first: the
+/// other choice gives a different `u`-coordinate.
+pub fn resolve_signs<T: PartialEq>(
+    curve: &MontgomeryCurve,
+    twist_order: &BigUint,
+    residues: &[(u64, u64)],
+    oracle: impl Fn(&BigUint) -> (Vec<u8>, T),
+    mac: impl Fn(&BigUint, &[u8]) -> T,
+) -> Option<(BigUint, BigUint)> {
+    let twist = twist_group(curve);
+    let congruence = |residue: u64, factor: u64| (BigUint::from(residue), BigUint::from(factor));
+    // a zero residue has no sign to compare with
+    let pivot = residues.iter().find(|&&(residue, _)| residue != 0);
+    let Some(&(pivot, pivot_factor)) = pivot else {
+        let congruences = residues
+            .iter()
+            .map(|&(residue, factor)| congruence(residue, factor))
+            .collect::<Vec<_>>();
+        return crt(&congruences);
+    };
+
+    let mut congruences = Vec::with_capacity(residues.len());
+    for &(residue, factor) in residues {
+        if residue == 0 || factor == pivot_factor {
+            congruences.push(congruence(residue, factor));
+            continue;
+        }
+        let confined = twist_point_of_order(&twist, twist_order, &[pivot_factor, factor])?;
+        let (message, tag) = oracle(&twist_u(&twist.0, &confined));
+        let (same_signs, _) = crt(&[congruence(pivot, pivot_factor), congruence(residue, factor)])?;
+        let shared = twist.1.multiply(&confined, &same_signs);
+        congruences.push(if mac(&twist_u(&twist.0, &shared), &message) == tag {
+            congruence(residue, factor)
+        } else {
+            congruence(factor - residue, factor)
+        });
+    }
+    crt(&congruences)
+}
+
+/// Recovers the secret behind the `u`-coordinate `public` of a public key
+/// on the Montgomery form `curve` of `domain`.
+///
+/// The residues of [`recover_residues`], joined by [`resolve_signs`], leave
+/// two candidates for the secret modulo their product. `public` lifts to the
+/// public key or its negation, so a kangaroo runs for each candidate and
+/// each lift, with [`recover_remaining_any`]; the residues come from the
+/// secret itself and rule out `order - secret`. Returns `None` if no
+/// kangaroo lands.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::attacks::twist_attack::{Bob, TOY_TWIST_ORDER, mac, recover_secret};
+/// use cryptopals::crypto::ec::Domain;
+/// use cryptopals::crypto::ec::montgomery::MontgomeryCurve;
+///
+/// let (domain, curve) = (Domain::toy(), MontgomeryCurve::toy());
+/// let (base, _) = curve.from_weierstrass_point(&domain.generator).unwrap();
+/// let secret = BigUint::from(31337u64);
+/// let bob = Bob::new(curve.clone(), secret.clone());
+///
+/// let public = curve.ladder(&base, &secret);
+/// let twist_order = BigUint::from(TOY_TWIST_ORDER);
+/// let recovered = recover_secret(&domain, &curve, &twist_order, 1 << 12, &public, |u| bob.respond(u), mac);
+/// assert_eq!(recovered, Some(secret));
+/// ```
+pub fn recover_secret<T: PartialEq>(
+    domain: &Domain,
+    curve: &MontgomeryCurve,
+    twist_order: &BigUint,
+    bound: u64,
+    public: &BigUint,
+    oracle: impl Fn(&BigUint) -> (Vec<u8>, T),
+    mac: impl Fn(&BigUint, &[u8]) -> T,
+) -> Option<BigUint> {
+    let residues = recover_residues(curve, twist_order, bound, &oracle, &mac);
+    let (residue, modulus) = resolve_signs(curve, twist_order, &residues, &oracle, &mac)?;
+
+    let weierstrass = curve.to_weierstrass();
+    let lift = curve.to_weierstrass_point(public, &curve.lift_u(public)?);
+    let negated = weierstrass.negate(&lift);
+    let opposite = (&modulus - &residue) % &modulus;
+
+    let guesses = [lift, negated]
+        .into_iter()
+        .flat_map(|target| {
+            [residue.clone(), opposite.clone()]
+                .map(|residue| (target.clone(), (residue, modulus.clone())))
+        })
+        .collect::<Vec<_>>();
+    recover_remaining_any(&weierstrass, &domain.generator, &domain.order, &guesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::SplitMix64;
+    use proptest::prelude::*;
+
+    fn base_point(domain: &Domain, curve: &MontgomeryCurve) -> BigUint {
+        curve.from_weierstrass_point(&domain.generator).unwrap().0
+    }
+
+    #[test]
+    fn test_twist_orders() {
+        for (domain, curve, twist_order) in [
+            (Domain::toy(), MontgomeryCurve::toy(), TOY_TWIST_ORDER),
+            (
+                Domain::challenge59(),
+                MontgomeryCurve::challenge60(),
+                CHALLENGE60_TWIST_ORDER,
+            ),
+        ] {
+            let p = curve.field().modulus();
+            let u = (1u64..)
+                .map(BigUint::from)
+                .find(|u| !curve.contains_u(u))
+                .unwrap();
+
+            let twist_order = BigUint::from(twist_order);
+            assert!(curve.ladder(&u, &twist_order).is_zero());
+            // the curve and its twist together have 2p + 2 points
+            assert_eq!(
+                &domain.order * BigUint::from(domain.cofactor) + twist_order,
+                (p + &BigUint::one()) << 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_residues_up_to_sign() {
+        let curve = MontgomeryCurve::toy();
+        let twist_order = BigUint::from(TOY_TWIST_ORDER);
+        let secret = 1_000_003;
+        let bob = Bob::new(curve.clone(), BigUint::from(secret));
+
+        let residues = recover_residues(&curve, &twist_order, 1 << 12, |u| bob.respond(u), mac);
+        assert_eq!(
+            residues
+                .iter()
+                .map(|&(_, factor)| factor)
+                .collect::<Vec<_>>(),
+            [3, 457, 1657]
+        );
+        for &(residue, factor) in &residues {
+            let expected = secret % factor;
+            assert_eq!(residue, expected.min(factor - expected));
+        }
+
+        // one sign is left
+        let (residue, modulus) =
+            resolve_signs(&curve, &twist_order, &residues, |u| bob.respond(u), mac).unwrap();
+        assert_eq!(modulus, BigUint::from(3u64 * 457 * 1657));
+        let expected = BigUint::from(secret) % &modulus;
+        assert!(residue == expected || residue == &modulus - &expected);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2))]
+
+        #[test]
+        fn recovers_any_secret(seed: u64) {
+            let (domain, curve) = (Domain::toy(), MontgomeryCurve::toy());
+            let secret = domain.random_secret(&mut SplitMix64::new(seed));
+            let bob = Bob::new(curve.clone(), secret.clone());
+            let public = curve.ladder(&base_point(&domain, &curve), &secret);
+
+            let recovered = recover_secret(
+                &domain,
+                &curve,
+                &BigUint::from(TOY_TWIST_ORDER),
+                1 << 12,
+                &public,
+                |u| bob.respond(u),
+                mac,
+            );
+            prop_assert_eq!(recovered, Some(secret));
+        }
+    }
+}
@@ -0,0 +1,779 @@
+//! DEFLATE compression.
+//!
+//! This module implements the compressor side of
+//! [RFC 1951](https://datatracker.ietf.org/doc/html/rfc1951). LZ77 with hash
+//! chains turns the input into literals and back-references within a 32 KiB
+//! window. Each block of those tokens is then written stored, with the fixed
+//! Huffman codes, or with dynamic Huffman codes sent in its header, whichever
+//! is shortest. The output is a raw DEFLATE stream, without a zlib or gzip
+//! wrapper.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const WINDOW_SIZE: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// Earlier positions with the same hash looked at for each match.
+const MAX_CHAIN: usize = 128;
+/// The most bits hashed prefixes are reduced to.
+const MAX_HASH_BITS: u32 = 15;
+/// Tokens per block, so that each block gets codes fitted to its own data.
+const BLOCK_TOKENS: usize = 1 << 14;
+const END_OF_BLOCK: usize = 256;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order code length code lengths are sent in, most used first.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// How a block of compressed data is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockType {
+    /// The bytes as they are.
+    Stored,
+    /// Huffman codes fixed by the specification.
+    Fixed,
+    /// Huffman codes built for the block and sent in its header.
+    Dynamic,
+}
+
+/// A literal byte, or a copy of earlier output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Literal(u8),
+    Match { length: u16, distance: u16 },
+}
+
+impl Token {
+    /// The literal/length symbol, with the extra bits that follow it.
+    fn symbol(&self) -> (usize, u8, u16) {
+        match *self {
+            Token::Literal(byte) => (byte as usize, 0, 0),
+            Token::Match { length, .. } => {
+                let code = LENGTH_BASE.partition_point(|&base| base <= length) - 1;
+                (
+                    257 + code,
+                    LENGTH_EXTRA_BITS[code],
+                    length - LENGTH_BASE[code],
+                )
+            }
+        }
+    }
+
+    /// The distance symbol and its extra bits, for matches.
+    fn distance_symbol(&self) -> Option<(usize, u8, u16)> {
+        match *self {
+            Token::Literal(_) => None,
+            Token::Match { distance, .. } => {
+                let code = DISTANCE_BASE.partition_point(|&base| base <= distance) - 1;
+                Some((
+                    code,
+                    DISTANCE_EXTRA_BITS[code],
+                    distance - DISTANCE_BASE[code],
+                ))
+            }
+        }
+    }
+
+    /// How many input bytes the token stands for.
+    fn len(&self) -> usize {
+        match *self {
+            Token::Literal(_) => 1,
+            Token::Match { length, .. } => length as usize,
+        }
+    }
+}
+
+/// Writes bits least significant first, as DEFLATE packs them.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, count: u8) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count as u32;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which goes most significant bit first.
+    fn write_code(&mut self, code: u16, length: u8) {
+        let reversed = code.reverse_bits() >> (16 - length as u32);
+        self.write_bits(reversed as u32, length);
+    }
+
+    fn align(&mut self) {
+        if self.count > 0 {
+            self.write_bits(0, 8 - self.count as u8);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+/// Compresses data, picking the shortest encoding for each block.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::compression::deflate::compress;
+///
+/// let text = b"to be or not to be, that is the question: to be or not to be";
+/// assert!(compress(text).len() < text.len());
+/// ```
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    compress_blocks(data, None)
+}
+
+/// Compresses data with every block encoded the same way.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::compression::deflate::{BlockType, compress_with};
+///
+/// // a final stored block: its length, the length inverted, then the bytes
+/// let stored = compress_with(b"abc", BlockType::Stored);
+/// assert_eq!(stored, [0x01, 0x03, 0x00, 0xFC, 0xFF, b'a', b'b', b'c']);
+/// ```
+pub fn compress_with(data: &[u8], block_type: BlockType) -> Vec<u8> {
+    compress_blocks(data, Some(block_type))
+}
+
+fn compress_blocks(data: &[u8], block_type: Option<BlockType>) -> Vec<u8> {
+    let tokens = tokenize(data);
+    let mut writer = BitWriter::default();
+
+    let mut start = 0;
+    let mut blocks = tokens.chunks(BLOCK_TOKENS).peekable();
+    // an empty input still needs one final block
+    let empty: &[Token] = &[];
+    if blocks.peek().is_none() {
+        write_block(&mut writer, empty, &[], true, block_type);
+    }
+    while let Some(block) = blocks.next() {
+        let end = start + block.iter().map(Token::len).sum::<usize>();
+        let is_final = blocks.peek().is_none();
+        write_block(&mut writer, block, &data[start..end], is_final, block_type);
+        start = end;
+    }
+
+    writer.finish()
+}
+
+/// Splits the input into literals and the longest back-references found
+/// along hash chains of three-byte prefixes.
+fn tokenize(data: &[u8]) -> Vec<Token> {
+    // short inputs get a table that is quick to set up
+    let hash_bits = data
+        .len()
+        .max(2)
+        .next_power_of_two()
+        .ilog2()
+        .min(MAX_HASH_BITS);
+    let hash = |position: usize| {
+        let prefix =
+            u32::from_le_bytes([data[position], data[position + 1], data[position + 2], 0]);
+        (prefix.wrapping_mul(0x9E37_79B1) >> (32 - hash_bits)) as usize
+    };
+    // the latest position with each hash, and the one before each position
+    let mut head = vec![usize::MAX; 1 << hash_bits];
+    let mut previous = vec![usize::MAX; data.len()];
+    let insert = |head: &mut [usize], previous: &mut [usize], position: usize| {
+        if position + MIN_MATCH <= data.len() {
+            let bucket = hash(position);
+            previous[position] = head[bucket];
+            head[bucket] = position;
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while position < data.len() {
+        let (mut best_length, mut best_distance) = (0, 0);
+        if position + MIN_MATCH <= data.len() {
+            let max_length = MAX_MATCH.min(data.len() - position);
+            let mut candidate = head[hash(position)];
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || position - candidate > WINDOW_SIZE {
+                    break;
+                }
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[position..position + max_length])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if length > best_length {
+                    (best_length, best_distance) = (length, position - candidate);
+                    if length == max_length {
+                        break;
+                    }
+                }
+                candidate = previous[candidate];
+            }
+        }
+
+        if best_length >= MIN_MATCH {
+            tokens.push(Token::Match {
+                length: best_length as u16,
+                distance: best_distance as u16,
+            });
+            for position in position..position + best_length {
+                insert(&mut head, &mut previous, position);
+            }
+            position += best_length;
+        } else {
+            tokens.push(Token::Literal(data[position]));
+            insert(&mut head, &mut previous, position);
+            position += 1;
+        }
+    }
+    tokens
+}
+
+/// Huffman code lengths of at most `limit` bits for the given symbol
+/// frequencies, with at least two codes so that every code is complete.
+///
+/// When the optimal code is too deep, the frequencies are halved and the
+/// code rebuilt, which flattens it until it fits.
+fn code_lengths(frequencies: &[u32], limit: u8) -> Vec<u8> {
+    let mut frequencies = frequencies.to_vec();
+    for index in 0..2 {
+        if frequencies
+            .iter()
+            .filter(|&&frequency| frequency > 0)
+            .count()
+            < 2
+        {
+            frequencies[index] = frequencies[index].max(1);
+        }
+    }
+
+    loop {
+        // merge the two lightest trees until one is left, tracking the
+        // symbols under each tree to deepen them at every merge
+        let mut lengths = vec![0u8; frequencies.len()];
+        let mut heap = frequencies
+            .iter()
+            .enumerate()
+            .filter(|&(_, &frequency)| frequency > 0)
+            .map(|(symbol, &frequency)| Reverse((frequency as u64, vec![symbol])))
+            .collect::<BinaryHeap<_>>();
+        while heap.len() > 1 {
+            let Reverse((weight_a, mut symbols)) = heap.pop().expect("two trees");
+            let Reverse((weight_b, other)) = heap.pop().expect("two trees");
+            symbols.extend(other);
+            symbols.iter().for_each(|&symbol| lengths[symbol] += 1);
+            heap.push(Reverse((weight_a + weight_b, symbols)));
+        }
+
+        if lengths.iter().all(|&length| length <= limit) {
+            return lengths;
+        }
+        for frequency in frequencies.iter_mut().filter(|frequency| **frequency > 0) {
+            *frequency = frequency.div_ceil(2);
+        }
+    }
+}
+
+/// The canonical Huffman codes for the given code lengths.
+fn canonical_codes(lengths: &[u8]) -> Vec<u16> {
+    let max_length = lengths.iter().copied().max().unwrap_or(0) as usize;
+    let mut length_counts = vec![0u16; max_length + 1];
+    lengths
+        .iter()
+        .filter(|&&length| length > 0)
+        .for_each(|&length| length_counts[length as usize] += 1);
+
+    let mut next_code = vec![0u16; max_length + 1];
+    let mut code = 0;
+    for length in 1..=max_length {
+        code = (code + length_counts[length - 1]) << 1;
+        next_code[length] = code;
+    }
+
+    lengths
+        .iter()
+        .map(|&length| {
+            if length == 0 {
+                return 0;
+            }
+            let code = next_code[length as usize];
+            next_code[length as usize] += 1;
+            code
+        })
+        .collect()
+}
+
+fn fixed_lengths() -> (Vec<u8>, Vec<u8>) {
+    let literal_lengths = (0..288)
+        .map(|symbol| match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        })
+        .collect();
+    (literal_lengths, vec![5; 30])
+}
+
+/// A run-length encoded code length, with the extra bits of symbols 16-18.
+type CodeLengthSymbol = (usize, u8, u16);
+
+/// Run-length encodes code lengths with the repeat symbols 16, 17 and 18.
+fn run_length_encode(lengths: &[u8]) -> Vec<CodeLengthSymbol> {
+    let mut symbols = Vec::new();
+    let mut index = 0;
+    while index < lengths.len() {
+        let length = lengths[index];
+        let mut run = lengths[index..]
+            .iter()
+            .take_while(|&&other| other == length)
+            .count();
+        index += run;
+
+        if length == 0 {
+            while run >= 11 {
+                let repeat = run.min(138);
+                symbols.push((18, 7, (repeat - 11) as u16));
+                run -= repeat;
+            }
+            if run >= 3 {
+                symbols.push((17, 3, (run - 3) as u16));
+                run = 0;
+            }
+        } else {
+            symbols.push((length as usize, 0, 0));
+            run -= 1;
+            while run >= 3 {
+                let repeat = run.min(6);
+                symbols.push((16, 2, (repeat - 3) as u16));
+                run -= repeat;
+            }
+        }
+        symbols.extend(std::iter::repeat_n((length as usize, 0, 0), run));
+    }
+    symbols
+}
+
+/// The header of a dynamic block, ready to be written or measured.
+struct DynamicHeader {
+    literal_count: usize,
+    distance_count: usize,
+    code_length_lengths: Vec<u8>,
+    code_length_count: usize,
+    symbols: Vec<CodeLengthSymbol>,
+}
+
+impl DynamicHeader {
+    fn new(literal_lengths: &[u8], distance_lengths: &[u8]) -> Self {
+        let used = |lengths: &[u8], minimum: usize| {
+            lengths
+                .iter()
+                .rposition(|&length| length > 0)
+                .map_or(minimum, |last| (last + 1).max(minimum))
+        };
+        let literal_count = used(literal_lengths, 257);
+        let distance_count = used(distance_lengths, 1);
+
+        let all_lengths = [
+            &literal_lengths[..literal_count],
+            &distance_lengths[..distance_count],
+        ]
+        .concat();
+        let symbols = run_length_encode(&all_lengths);
+
+        let mut frequencies = [0u32; 19];
+        symbols
+            .iter()
+            .for_each(|&(symbol, _, _)| frequencies[symbol] += 1);
+        let code_length_lengths = code_lengths(&frequencies, 7);
+        let code_length_count = CODE_LENGTH_ORDER
+            .iter()
+            .rposition(|&symbol| code_length_lengths[symbol] > 0)
+            .map_or(4, |last| (last + 1).max(4));
+
+        DynamicHeader {
+            literal_count,
+            distance_count,
+            code_length_lengths,
+            code_length_count,
+            symbols,
+        }
+    }
+
+    fn bit_len(&self) -> usize {
+        let symbols: usize = self
+            .symbols
+            .iter()
+            .map(|&(symbol, extra_bits, _)| {
+                (self.code_length_lengths[symbol] + extra_bits) as usize
+            })
+            .sum();
+        5 + 5 + 4 + 3 * self.code_length_count + symbols
+    }
+
+    fn write(&self, writer: &mut BitWriter) {
+        writer.write_bits((self.literal_count - 257) as u32, 5);
+        writer.write_bits((self.distance_count - 1) as u32, 5);
+        writer.write_bits((self.code_length_count - 4) as u32, 4);
+        for &symbol in &CODE_LENGTH_ORDER[..self.code_length_count] {
+            writer.write_bits(self.code_length_lengths[symbol] as u32, 3);
+        }
+
+        let codes = canonical_codes(&self.code_length_lengths);
+        for &(symbol, extra_bits, extra) in &self.symbols {
+            writer.write_code(codes[symbol], self.code_length_lengths[symbol]);
+            writer.write_bits(extra as u32, extra_bits);
+        }
+    }
+}
+
+/// The bits the tokens take with the given code lengths, end of block
+/// included.
+fn tokens_bit_len(tokens: &[Token], literal_lengths: &[u8], distance_lengths: &[u8]) -> usize {
+    let tokens: usize = tokens
+        .iter()
+        .map(|token| {
+            let (symbol, extra_bits, _) = token.symbol();
+            let distance = token
+                .distance_symbol()
+                .map_or(0, |(symbol, extra_bits, _)| {
+                    (distance_lengths[symbol] + extra_bits) as usize
+                });
+            (literal_lengths[symbol] + extra_bits) as usize + distance
+        })
+        .sum();
+    tokens + literal_lengths[END_OF_BLOCK] as usize
+}
+
+fn write_block(
+    writer: &mut BitWriter,
+    tokens: &[Token],
+    data: &[u8],
+    is_final: bool,
+    block_type: Option<BlockType>,
+) {
+    let mut literal_frequencies = [0u32; 286];
+    let mut distance_frequencies = [0u32; 30];
+    for token in tokens {
+        literal_frequencies[token.symbol().0] += 1;
+        if let Some((symbol, _, _)) = token.distance_symbol() {
+            distance_frequencies[symbol] += 1;
+        }
+    }
+    literal_frequencies[END_OF_BLOCK] += 1;
+
+    let dynamic_lengths = (
+        code_lengths(&literal_frequencies, 15),
+        code_lengths(&distance_frequencies, 15),
+    );
+    let header = DynamicHeader::new(&dynamic_lengths.0, &dynamic_lengths.1);
+    let fixed_lengths = fixed_lengths();
+
+    let block_type = block_type.unwrap_or_else(|| {
+        // the three-bit block header is common to all and left out
+        let dynamic =
+            header.bit_len() + tokens_bit_len(tokens, &dynamic_lengths.0, &dynamic_lengths.1);
+        let fixed = tokens_bit_len(tokens, &fixed_lengths.0, &fixed_lengths.1);
+        let stored = 8 + data.len().div_ceil(u16::MAX as usize).max(1) * 40 + 8 * data.len();
+        [
+            (stored, BlockType::Stored),
+            (fixed, BlockType::Fixed),
+            (dynamic, BlockType::Dynamic),
+        ]
+        .into_iter()
+        .min_by_key(|&(bits, _)| bits)
+        .map(|(_, block_type)| block_type)
+        .expect("three candidates")
+    });
+
+    let (literal_lengths, distance_lengths) = match block_type {
+        BlockType::Stored => return write_stored(writer, data, is_final),
+        BlockType::Fixed => {
+            writer.write_bits(is_final as u32, 1);
+            writer.write_bits(0b01, 2);
+            fixed_lengths
+        }
+        BlockType::Dynamic => {
+            writer.write_bits(is_final as u32, 1);
+            writer.write_bits(0b10, 2);
+            header.write(writer);
+            dynamic_lengths
+        }
+    };
+
+    let literal_codes = canonical_codes(&literal_lengths);
+    let distance_codes = canonical_codes(&distance_lengths);
+    for token in tokens {
+        let (symbol, extra_bits, extra) = token.symbol();
+        writer.write_code(literal_codes[symbol], literal_lengths[symbol]);
+        writer.write_bits(extra as u32, extra_bits);
+        if let Some((symbol, extra_bits, extra)) = token.distance_symbol() {
+            writer.write_code(distance_codes[symbol], distance_lengths[symbol]);
+            writer.write_bits(extra as u32, extra_bits);
+        }
+    }
+    writer.write_code(literal_codes[END_OF_BLOCK], literal_lengths[END_OF_BLOCK]);
+}
+
+/// Writes the bytes as stored blocks of at most 65535 bytes each.
+fn write_stored(writer: &mut BitWriter, data: &[u8], is_final: bool) {
+    let mut chunks = data.chunks(u16::MAX as usize).collect::<Vec<_>>();
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+    let mut chunks = chunks.into_iter().peekable();
+    while let Some(chunk) = chunks.next() {
+        writer.write_bits((is_final && chunks.peek().is_none()) as u32, 1);
+        writer.write_bits(0b00, 2);
+        writer.align();
+        let length = chunk.len() as u16;
+        writer.write_bits(length as u32, 16);
+        writer.write_bits(!length as u32, 16);
+        writer.bytes.extend_from_slice(chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    /// Reads bits least significant first.
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: u8) -> u32 {
+            (0..count).fold(0, |value, index| {
+                let byte = self.bytes[self.position / 8];
+                let bit = (byte >> (self.position % 8)) & 1;
+                self.position += 1;
+                value | (bit as u32) << index
+            })
+        }
+
+        /// Decodes one symbol by reading a code a bit at a time.
+        fn symbol(&mut self, decoder: &Decoder) -> usize {
+            let (mut code, mut length) = (0u16, 0u8);
+            loop {
+                code = code << 1 | self.bits(1) as u16;
+                length += 1;
+                assert!(length <= 15, "no symbol matches");
+                if let Some(&symbol) = decoder.get(&(length, code)) {
+                    return symbol;
+                }
+            }
+        }
+    }
+
+    /// Symbols by code length and canonical code.
+    type Decoder = HashMap<(u8, u16), usize>;
+
+    fn decoder(lengths: &[u8]) -> Decoder {
+        let codes = canonical_codes(lengths);
+        (0..lengths.len())
+            .filter(|&symbol| lengths[symbol] > 0)
+            .map(|symbol| ((lengths[symbol], codes[symbol]), symbol))
+            .collect()
+    }
+
+    /// A minimal inflater, to check the compressor against.
+    fn decompress(compressed: &[u8]) -> Vec<u8> {
+        let mut reader = BitReader {
+            bytes: compressed,
+            position: 0,
+        };
+        let mut output = Vec::new();
+        loop {
+            let is_final = reader.bits(1) == 1;
+            let (literal_lengths, distance_lengths) = match reader.bits(2) {
+                0b00 => {
+                    reader.position = reader.position.div_ceil(8) * 8;
+                    let length = reader.bits(16);
+                    assert_eq!(reader.bits(16), !length & 0xFFFF);
+                    let start = reader.position / 8;
+                    output.extend_from_slice(&compressed[start..start + length as usize]);
+                    reader.position += 8 * length as usize;
+                    if is_final {
+                        return output;
+                    }
+                    continue;
+                }
+                0b01 => fixed_lengths(),
+                0b10 => read_dynamic_lengths(&mut reader),
+                _ => panic!("reserved block type"),
+            };
+            let (literals, distances) = (decoder(&literal_lengths), decoder(&distance_lengths));
+
+            loop {
+                let symbol = reader.symbol(&literals);
+                match symbol {
+                    0..=255 => output.push(symbol as u8),
+                    END_OF_BLOCK => break,
+                    _ => {
+                        let code = symbol - 257;
+                        let length = LENGTH_BASE[code] as usize
+                            + reader.bits(LENGTH_EXTRA_BITS[code]) as usize;
+                        let code = reader.symbol(&distances);
+                        let distance = DISTANCE_BASE[code] as usize
+                            + reader.bits(DISTANCE_EXTRA_BITS[code]) as usize;
+                        for _ in 0..length {
+                            output.push(output[output.len() - distance]);
+                        }
+                    }
+                }
+            }
+            if is_final {
+                return output;
+            }
+        }
+    }
+
+    fn read_dynamic_lengths(reader: &mut BitReader) -> (Vec<u8>, Vec<u8>) {
+        let literal_count = reader.bits(5) as usize + 257;
+        let distance_count = reader.bits(5) as usize + 1;
+        let code_length_count = reader.bits(4) as usize + 4;
+        let mut code_length_lengths = [0u8; 19];
+        for &symbol in &CODE_LENGTH_ORDER[..code_length_count] {
+            code_length_lengths[symbol] = reader.bits(3) as u8;
+        }
+
+        let code_lengths = decoder(&code_length_lengths);
+        let mut lengths = Vec::new();
+        while lengths.len() < literal_count + distance_count {
+            match reader.symbol(&code_lengths) {
+                16 => {
+                    let previous = *lengths.last().unwrap();
+                    let repeat = 3 + reader.bits(2) as usize;
+                    lengths.extend(std::iter::repeat_n(previous, repeat));
+                }
+                17 => lengths.extend(std::iter::repeat_n(0, 3 + reader.bits(3) as usize)),
+                18 => lengths.extend(std::iter::repeat_n(0, 11 + reader.bits(7) as usize)),
+                length => lengths.push(length as u8),
+            }
+        }
+        let distance_lengths = lengths.split_off(literal_count);
+        (lengths, distance_lengths)
+    }
+
+    /// Text with repeats at all distances, and runs.
+    fn compressible() -> impl Strategy<Value = Vec<u8>> {
+        proptest::collection::vec(
+            prop_oneof![
+                proptest::collection::vec(b'a'..b'e', 0..20),
+                proptest::collection::vec(any::<u8>(), 0..20),
+                (any::<u8>(), 0usize..600).prop_map(|(byte, count)| vec![byte; count]),
+            ],
+            0..40,
+        )
+        .prop_map(|chunks| chunks.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn valid_deflate_roundtrip(data in compressible()) {
+            assert_eq!(decompress(&compress(&data)), data);
+            for block_type in [BlockType::Stored, BlockType::Fixed, BlockType::Dynamic] {
+                assert_eq!(decompress(&compress_with(&data, block_type)), data);
+            }
+        }
+
+        #[test]
+        fn code_lengths_are_limited_and_complete(frequencies in proptest::collection::vec(0u32..1 << 20, 2..300)) {
+            // deep enough for every symbol to get a code
+            let limit = 7.max(frequencies.len().next_power_of_two().ilog2() as u8);
+            let lengths = code_lengths(&frequencies, limit);
+            assert!(lengths.iter().all(|&length| length <= limit));
+            for (&frequency, &length) in frequencies.iter().zip(&lengths) {
+                assert!(frequency == 0 || length > 0);
+            }
+            // Kraft's sum is exactly one for a complete code
+            let kraft: u64 = lengths
+                .iter()
+                .filter(|&&length| length > 0)
+                .map(|&length| 1u64 << (32 - length))
+                .sum();
+            assert_eq!(kraft, 1 << 32);
+        }
+    }
+
+    #[test]
+    fn test_long_inputs_span_several_blocks() {
+        // long enough for several blocks, and for stored blocks to split
+        let data = (0..200_000u32)
+            .map(|index| (index.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect::<Vec<_>>();
+        for block_type in [BlockType::Stored, BlockType::Dynamic] {
+            assert_eq!(decompress(&compress_with(&data, block_type)), data);
+        }
+        let text = b"all work and no play makes jack a dull boy\n".repeat(5000);
+        let compressed = compress(&text);
+        assert!(compressed.len() < text.len() / 50);
+        assert_eq!(decompress(&compressed), text);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        for block_type in [BlockType::Stored, BlockType::Fixed, BlockType::Dynamic] {
+            assert!(decompress(&compress_with(b"", block_type)).is_empty());
+        }
+        // the fixed code for end of block is seven zero bits
+        assert_eq!(compress(b""), [0x03, 0x00]);
+    }
+
+    #[test]
+    fn test_fixed_block() {
+        // "a" is 0x30 + 0x61 in eight bits, and the stream ends with the
+        // seven-bit end of block code
+        assert_eq!(compress_with(b"a", BlockType::Fixed), [0x4B, 0x04, 0x00]);
+    }
+
+    #[test]
+    fn test_matches_are_found() {
+        let tokens = tokenize(b"abcabcabcabc");
+        assert_eq!(
+            tokens[3..],
+            [Token::Match {
+                length: 9,
+                distance: 3
+            }]
+        );
+        let symbol = Token::Match {
+            length: 258,
+            distance: 32768,
+        };
+        assert_eq!(symbol.symbol(), (285, 0, 0));
+        assert_eq!(symbol.distance_symbol(), Some((29, 13, 8191)));
+    }
+}
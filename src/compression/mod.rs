@@ -0,0 +1,6 @@
+//! Compression.
+//!
+//! [`deflate`] implements the DEFLATE compressor that the compression
+//! side-channel attacks of challenge 51 measure.
+
+pub mod deflate;
@@ -0,0 +1,448 @@
+//! AES-128 block cipher and the ECB, CBC and CTR modes of operation.
+//!
+//! The block cipher follows [FIPS 197](https://doi.org/10.6028/NIST.FIPS.197-upd1).
+//! The S-box is derived at compile time from inversion in GF(2^8) and the
+//! affine map, rather than pasted in as a table. This is a byte-oriented
+//! implementation: the S-box lookups are not constant time.
+//!
+//! The ECB and CBC functions work on whole blocks and do not pad; combine
+//! them with [`crate::crypto::pkcs7`]. CTR uses the Cryptopals layout of
+//! [challenge 18](https://cryptopals.com/sets/3/challenges/18): a 64-bit
+//! little-endian nonce followed by a 64-bit little-endian block counter.
+
+use crate::crypto::stream::{SeekableStream, xor_with_blocks};
+
+/// Length of an AES block in bytes.
+pub const BLOCK_LEN: usize = 16;
+
+/// Multiplies two elements of GF(2^8) modulo `x^8 + x^4 + x^3 + x + 1`.
+const fn gf256_multiply(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1B } else { 0 };
+        b >>= 1;
+    }
+    product
+}
+
+const fn sbox() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut value = 0;
+    while value < 256 {
+        // a^254 is the inverse of a, and maps 0 to 0
+        let mut inverse = 1u8;
+        let mut power = 0;
+        while power < 254 {
+            inverse = gf256_multiply(inverse, value as u8);
+            power += 1;
+        }
+        table[value] = inverse
+            ^ inverse.rotate_left(1)
+            ^ inverse.rotate_left(2)
+            ^ inverse.rotate_left(3)
+            ^ inverse.rotate_left(4)
+            ^ 0x63;
+        value += 1;
+    }
+    table
+}
+
+const fn inverse_sbox(sbox: &[u8; 256]) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut value = 0;
+    while value < 256 {
+        table[sbox[value] as usize] = value as u8;
+        value += 1;
+    }
+    table
+}
+
+const SBOX: [u8; 256] = sbox();
+const INVERSE_SBOX: [u8; 256] = inverse_sbox(&SBOX);
+
+/// Round constants for the AES-128 key schedule.
+const ROUND_CONSTANTS: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1B, 0x36];
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    state
+        .iter_mut()
+        .zip(round_key)
+        .for_each(|(byte, key)| *byte ^= key);
+}
+
+// The state is stored column by column, so byte `4 * column + row` is at
+// row `row` of column `column`, as in FIPS 197 Section 3.4.
+
+fn shift_rows(state: &mut [u8; 16]) {
+    let original = *state;
+    for column in 0..4 {
+        for row in 1..4 {
+            state[4 * column + row] = original[4 * ((column + row) % 4) + row];
+        }
+    }
+}
+
+fn inverse_shift_rows(state: &mut [u8; 16]) {
+    let original = *state;
+    for column in 0..4 {
+        for row in 1..4 {
+            state[4 * ((column + row) % 4) + row] = original[4 * column + row];
+        }
+    }
+}
+
+/// Multiplies by `x` in GF(2^8).
+fn xtime(byte: u8) -> u8 {
+    (byte << 1) ^ if byte & 0x80 != 0 { 0x1B } else { 0 }
+}
+
+fn mix_columns(state: &mut [u8; 16]) {
+    for column in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = column.try_into().expect("4 bytes");
+        // 2a + 3b + c + d = a + (a + b + c + d) + 2(a + b)
+        let total = a0 ^ a1 ^ a2 ^ a3;
+        column[0] = a0 ^ total ^ xtime(a0 ^ a1);
+        column[1] = a1 ^ total ^ xtime(a1 ^ a2);
+        column[2] = a2 ^ total ^ xtime(a2 ^ a3);
+        column[3] = a3 ^ total ^ xtime(a3 ^ a0);
+    }
+}
+
+fn inverse_mix_columns(state: &mut [u8; 16]) {
+    // the inverse polynomial is the forward one times {04}y^2 + {05} (The
+    // Design of Rijndael, section 4.1.3), which maps a to a + 4(a + c)
+    for column in state.chunks_exact_mut(4) {
+        let even = xtime(xtime(column[0] ^ column[2]));
+        let odd = xtime(xtime(column[1] ^ column[3]));
+        column[0] ^= even;
+        column[1] ^= odd;
+        column[2] ^= even;
+        column[3] ^= odd;
+    }
+    mix_columns(state);
+}
+
+/// AES-128 with an expanded key.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::aes::Aes128;
+///
+/// let cipher = Aes128::new(b"YELLOW SUBMARINE");
+/// let ciphertext = cipher.encrypt_block(b"sixteen byte msg");
+/// assert_eq!(&cipher.decrypt_block(&ciphertext), b"sixteen byte msg");
+/// ```
+#[derive(Clone)]
+pub struct Aes128 {
+    round_keys: [[u8; 16]; 11],
+}
+
+impl Aes128 {
+    /// Expands the 128-bit key into the 11 round keys.
+    pub fn new(key: &[u8; 16]) -> Self {
+        let mut round_keys = [*key; 11];
+        for (round, constant) in ROUND_CONSTANTS.iter().enumerate() {
+            let previous = round_keys[round];
+            let mut word: [u8; 4] = previous[12..].try_into().expect("4 bytes");
+            word.rotate_left(1);
+            word = word.map(|byte| SBOX[byte as usize]);
+            word[0] ^= constant;
+
+            let next = &mut round_keys[round + 1];
+            for column in 0..4 {
+                for row in 0..4 {
+                    word[row] ^= previous[4 * column + row];
+                }
+                next[4 * column..4 * column + 4].copy_from_slice(&word);
+            }
+        }
+        Aes128 { round_keys }
+    }
+
+    /// Encrypts one block.
+    pub fn encrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut state = *block;
+        add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1..11 {
+            state = state.map(|byte| SBOX[byte as usize]);
+            shift_rows(&mut state);
+            if round != 10 {
+                mix_columns(&mut state);
+            }
+            add_round_key(&mut state, &self.round_keys[round]);
+        }
+        state
+    }
+
+    /// Decrypts one block.
+    pub fn decrypt_block(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut state = *block;
+        for round in (1..11).rev() {
+            add_round_key(&mut state, &self.round_keys[round]);
+            if round != 10 {
+                inverse_mix_columns(&mut state);
+            }
+            inverse_shift_rows(&mut state);
+            state = state.map(|byte| INVERSE_SBOX[byte as usize]);
+        }
+        add_round_key(&mut state, &self.round_keys[0]);
+        state
+    }
+}
+
+fn blocks(bytes: &[u8]) -> impl Iterator<Item = &[u8; 16]> {
+    assert!(
+        bytes.len().is_multiple_of(BLOCK_LEN),
+        "length is a multiple of the block length"
+    );
+    bytes
+        .chunks_exact(BLOCK_LEN)
+        .map(|block| block.try_into().expect("16 bytes"))
+}
+
+/// Encrypts whole blocks in ECB mode.
+///
+/// # Panics
+///
+/// Panics if the length is not a multiple of [`BLOCK_LEN`].
+pub fn ecb_encrypt(key: &[u8; 16], plaintext: &[u8]) -> Vec<u8> {
+    let cipher = Aes128::new(key);
+    blocks(plaintext)
+        .flat_map(|block| cipher.encrypt_block(block))
+        .collect()
+}
+
+/// Decrypts whole blocks in ECB mode.
+///
+/// # Panics
+///
+/// Panics if the length is not a multiple of [`BLOCK_LEN`].
+pub fn ecb_decrypt(key: &[u8; 16], ciphertext: &[u8]) -> Vec<u8> {
+    let cipher = Aes128::new(key);
+    blocks(ciphertext)
+        .flat_map(|block| cipher.decrypt_block(block))
+        .collect()
+}
+
+/// Encrypts whole blocks in CBC mode.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::aes::{cbc_decrypt, cbc_encrypt};
+/// use cryptopals::crypto::pkcs7;
+///
+/// let padded = pkcs7::pad(b"attack at dawn", 16);
+/// let ciphertext = cbc_encrypt(b"YELLOW SUBMARINE", &[0; 16], &padded);
+/// let decrypted = cbc_decrypt(b"YELLOW SUBMARINE", &[0; 16], &ciphertext);
+/// assert_eq!(pkcs7::unpad(&decrypted, 16).unwrap(), b"attack at dawn");
+/// ```
+///
+/// # Panics
+///
+/// Panics if the length is not a multiple of [`BLOCK_LEN`].
+pub fn cbc_encrypt(key: &[u8; 16], iv: &[u8; 16], plaintext: &[u8]) -> Vec<u8> {
+    let cipher = Aes128::new(key);
+    let mut previous = *iv;
+    let mut ciphertext = Vec::with_capacity(plaintext.len());
+    for block in blocks(plaintext) {
+        let mut input = *block;
+        add_round_key(&mut input, &previous);
+        previous = cipher.encrypt_block(&input);
+        ciphertext.extend(previous);
+    }
+    ciphertext
+}
+
+/// Decrypts whole blocks in CBC mode.
+///
+/// # Panics
+///
+/// Panics if the length is not a multiple of [`BLOCK_LEN`].
+pub fn cbc_decrypt(key: &[u8; 16], iv: &[u8; 16], ciphertext: &[u8]) -> Vec<u8> {
+    let cipher = Aes128::new(key);
+    let mut previous = iv;
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    for block in blocks(ciphertext) {
+        let mut output = cipher.decrypt_block(block);
+        add_round_key(&mut output, previous);
+        plaintext.extend(output);
+        previous = block;
+    }
+    plaintext
+}
+
+/// Seekable AES-128 CTR keystream.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::aes::AesCtr;
+/// use cryptopals::crypto::stream::SeekableStream;
+///
+/// let mut cipher = AesCtr::new(b"YELLOW SUBMARINE", 0);
+/// let ciphertext = cipher.apply_keystream(b"attack at dawn");
+///
+/// let edited = cipher.edit(&ciphertext, 10, b"dusk");
+/// cipher.seek(0);
+/// assert_eq!(cipher.apply_keystream(&edited), b"attack at dusk");
+/// ```
+#[derive(Clone)]
+pub struct AesCtr {
+    cipher: Aes128,
+    nonce: u64,
+    position: u64,
+}
+
+impl AesCtr {
+    /// Creates a keystream whose first block uses counter 0.
+    pub fn new(key: &[u8; 16], nonce: u64) -> Self {
+        AesCtr {
+            cipher: Aes128::new(key),
+            nonce,
+            position: 0,
+        }
+    }
+}
+
+impl SeekableStream for AesCtr {
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn seek(&mut self, offset: u64) {
+        self.position = offset;
+    }
+
+    fn apply_keystream(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let output = xor_with_blocks(bytes, self.position, |counter| {
+            let mut input = [0u8; 16];
+            input[..8].copy_from_slice(&self.nonce.to_le_bytes());
+            input[8..].copy_from_slice(&counter.to_le_bytes());
+            self.cipher.encrypt_block(&input)
+        });
+        self.position += bytes.len() as u64;
+        output
+    }
+}
+
+/// Encrypts or decrypts the bytes in CTR mode, starting at block 0.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::aes::ctr;
+///
+/// let ciphertext = ctr(b"attack at dawn", b"YELLOW SUBMARINE", 7);
+/// assert_eq!(ctr(&ciphertext, b"YELLOW SUBMARINE", 7), b"attack at dawn");
+/// ```
+pub fn ctr(bytes: &[u8], key: &[u8; 16], nonce: u64) -> Vec<u8> {
+    AesCtr::new(key, nonce).apply_keystream(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{base64, hex};
+    use proptest::prelude::*;
+
+    fn block(hex: &str) -> [u8; 16] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_sbox() {
+        assert_eq!(SBOX[0x00], 0x63);
+        assert_eq!(SBOX[0x53], 0xED);
+        assert_eq!(SBOX[0xFF], 0x16);
+        assert_eq!(INVERSE_SBOX[0x63], 0x00);
+    }
+
+    #[test]
+    fn test_fips_197_vector() {
+        // FIPS 197 Appendix C.1
+        let cipher = Aes128::new(&block("000102030405060708090a0b0c0d0e0f"));
+        let plaintext = block("00112233445566778899aabbccddeeff");
+        let ciphertext = cipher.encrypt_block(&plaintext);
+
+        assert_eq!(
+            hex::encode(&ciphertext).to_lowercase(),
+            "69c4e0d86a7b0430d8cdb78070b4c55a"
+        );
+        assert_eq!(cipher.decrypt_block(&ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_key_expansion() {
+        // FIPS 197 Appendix A.1
+        let cipher = Aes128::new(&block("2b7e151628aed2a6abf7158809cf4f3c"));
+        assert_eq!(
+            hex::encode(&cipher.round_keys[10]).to_lowercase(),
+            "d014f9a8c9ee2589e13f0cc8b6630ca6"
+        );
+    }
+
+    #[test]
+    fn test_cbc_vector() {
+        // NIST SP 800-38A F.2.1, first two blocks
+        let key = block("2b7e151628aed2a6abf7158809cf4f3c");
+        let iv = block("000102030405060708090a0b0c0d0e0f");
+        let plaintext =
+            hex::decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51")
+                .unwrap();
+
+        let ciphertext = cbc_encrypt(&key, &iv, &plaintext);
+        assert_eq!(
+            hex::encode(&ciphertext).to_lowercase(),
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2"
+        );
+        assert_eq!(cbc_decrypt(&key, &iv, &ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_ctr_challenge_18() {
+        let ciphertext = base64::decode(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        )
+        .unwrap();
+        assert_eq!(
+            ctr(&ciphertext, b"YELLOW SUBMARINE", 0),
+            b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+        );
+    }
+
+    #[test]
+    fn test_ctr_seek_matches_full_keystream() {
+        let mut cipher = AesCtr::new(b"YELLOW SUBMARINE", 3);
+        let full = cipher.apply_keystream(&[0; 100]);
+
+        for offset in [0, 1, 15, 16, 17, 50] {
+            cipher.seek(offset as u64);
+            assert_eq!(cipher.apply_keystream(&[0; 40]), full[offset..offset + 40]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of the block length")]
+    fn test_ecb_rejects_partial_blocks() {
+        ecb_encrypt(&[0; 16], b"short");
+    }
+
+    proptest! {
+        #[test]
+        fn ecb_round_trips(key in any::<[u8; 16]>(), blocks in proptest::collection::vec(any::<[u8; 16]>(), 0..8)) {
+            let plaintext = blocks.concat();
+            prop_assert_eq!(ecb_decrypt(&key, &ecb_encrypt(&key, &plaintext)), plaintext);
+        }
+
+        #[test]
+        fn cbc_round_trips(key in any::<[u8; 16]>(), iv in any::<[u8; 16]>(), blocks in proptest::collection::vec(any::<[u8; 16]>(), 0..8)) {
+            let plaintext = blocks.concat();
+            prop_assert_eq!(cbc_decrypt(&key, &iv, &cbc_encrypt(&key, &iv, &plaintext)), plaintext);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! ChaCha20 stream cipher.
+//!
+//! This module implements ChaCha20 as specified in
+//! [RFC 8439 Section 2.4](https://datatracker.ietf.org/doc/html/rfc8439#section-2.4):
+//! a 256-bit key, a 96-bit nonce and an explicit 32-bit block counter.
+
+use crate::crypto::stream::{SeekableStream, xor_with_blocks};
+
+/// "expand 32-byte k" as little-endian words.
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [u32; N] {
+    std::array::from_fn(|idx| {
+        u32::from_le_bytes(bytes[4 * idx..4 * idx + 4].try_into().expect("4 bytes"))
+    })
+}
+
+/// Computes one 64-byte ChaCha20 keystream block.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::chacha20::chacha20_block;
+///
+/// let block = chacha20_block(&[0; 32], 0, &[0; 12]);
+/// assert_eq!(block[..4], [0x76, 0xB8, 0xE0, 0xAD]);
+/// ```
+pub fn chacha20_block(key: &[u8; 32], counter: u32, nonce: &[u8; 12]) -> [u8; 64] {
+    let key: [u32; 8] = words(key);
+    let nonce: [u32; 3] = words(nonce);
+
+    let mut initial = [0u32; 16];
+    initial[..4].copy_from_slice(&CONSTANTS);
+    initial[4..12].copy_from_slice(&key);
+    initial[12] = counter;
+    initial[13..].copy_from_slice(&nonce);
+
+    let mut state = initial;
+    for _ in 0..10 {
+        // column rounds
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        // diagonal rounds
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut block = [0u8; 64];
+    for (idx, (word, initial)) in state.iter().zip(initial).enumerate() {
+        block[4 * idx..4 * idx + 4].copy_from_slice(&word.wrapping_add(initial).to_le_bytes());
+    }
+    block
+}
+
+/// Seekable ChaCha20 keystream.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::chacha20::ChaCha20;
+/// use cryptopals::crypto::stream::SeekableStream;
+///
+/// let mut cipher = ChaCha20::new(&[7; 32], &[1; 12], 1);
+/// let ciphertext = cipher.apply_keystream(b"attack at dawn");
+///
+/// let edited = cipher.edit(&ciphertext, 10, b"dusk");
+/// cipher.seek(0);
+/// assert_eq!(cipher.apply_keystream(&edited), b"attack at dusk");
+/// ```
+#[derive(Clone)]
+pub struct ChaCha20 {
+    key: [u8; 32],
+    nonce: [u8; 12],
+    initial_counter: u32,
+    position: u64,
+}
+
+impl ChaCha20 {
+    /// Creates a keystream whose first block uses `initial_counter`.
+    pub fn new(key: &[u8; 32], nonce: &[u8; 12], initial_counter: u32) -> Self {
+        ChaCha20 {
+            key: *key,
+            nonce: *nonce,
+            initial_counter,
+            position: 0,
+        }
+    }
+}
+
+impl SeekableStream for ChaCha20 {
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn seek(&mut self, offset: u64) {
+        self.position = offset;
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the 32-bit block counter would overflow.
+    fn apply_keystream(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let output = xor_with_blocks(bytes, self.position, |index| {
+            let counter = u32::try_from(index)
+                .ok()
+                .and_then(|index| index.checked_add(self.initial_counter))
+                .expect("block counter does not overflow");
+            chacha20_block(&self.key, counter, &self.nonce)
+        });
+        self.position += bytes.len() as u64;
+        output
+    }
+}
+
+/// Encrypts or decrypts the bytes with ChaCha20, starting at block `counter`.
+pub fn chacha20(bytes: &[u8], key: &[u8; 32], nonce: &[u8; 12], counter: u32) -> Vec<u8> {
+    ChaCha20::new(key, nonce, counter).apply_keystream(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    fn rfc_key() -> [u8; 32] {
+        std::array::from_fn(|idx| idx as u8)
+    }
+
+    #[test]
+    fn test_block_function() {
+        // RFC 8439 Section 2.3.2
+        let nonce = hex::decode("000000090000004a00000000").unwrap();
+        let block = chacha20_block(&rfc_key(), 1, &nonce.try_into().unwrap());
+
+        assert_eq!(
+            hex::encode(&block).to_lowercase(),
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+        );
+    }
+
+    #[test]
+    fn test_encryption() {
+        // RFC 8439 Section 2.4.2
+        let nonce = hex::decode("000000000000004a00000000").unwrap();
+        let ciphertext = chacha20(SUNSCREEN, &rfc_key(), &nonce.try_into().unwrap(), 1);
+
+        assert_eq!(
+            hex::encode(&ciphertext).to_lowercase(),
+            "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+             f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+             07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+             5af90bbf74a35be6b40b8eedf2785e42874d"
+        );
+    }
+
+    #[test]
+    fn test_seek_matches_full_keystream() {
+        let mut cipher = ChaCha20::new(&rfc_key(), &[9; 12], 0);
+        let full = cipher.apply_keystream(&[0; 200]);
+
+        for offset in [0, 1, 63, 64, 65, 150] {
+            cipher.seek(offset as u64);
+            assert_eq!(cipher.apply_keystream(&[0; 50]), full[offset..offset + 50]);
+        }
+    }
+
+    #[test]
+    fn test_edit_recovers_plaintext_under_nonce_reuse() {
+        let mut cipher = ChaCha20::new(&rfc_key(), &[3; 12], 1);
+        let ciphertext = cipher.apply_keystream(SUNSCREEN);
+
+        // editing in zeros hands back the keystream
+        let keystream = cipher.edit(&ciphertext, 0, &vec![0; ciphertext.len()]);
+        assert_eq!(
+            crate::crypto::xor::xor_bytes(&ciphertext, &keystream),
+            SUNSCREEN
+        );
+    }
+}
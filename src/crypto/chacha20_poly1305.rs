@@ -0,0 +1,129 @@
+//! ChaCha20-Poly1305 authenticated encryption.
+//!
+//! This module implements the AEAD construction from
+//! [RFC 8439 Section 2.8](https://datatracker.ietf.org/doc/html/rfc8439#section-2.8).
+//! The Poly1305 key is the first half of ChaCha20 block 0, and the payload is
+//! encrypted from block 1 onwards.
+
+use crate::crypto::chacha20::{chacha20, chacha20_block};
+use crate::crypto::poly1305::poly1305;
+
+/// Length of the authentication tag appended to the ciphertext.
+pub const TAG_LEN: usize = 16;
+
+fn pad16(data: &mut Vec<u8>) {
+    let padding = (16 - data.len() % 16) % 16;
+    data.extend(std::iter::repeat_n(0, padding));
+}
+
+fn compute_tag(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    let one_time_key: [u8; 32] = chacha20_block(key, 0, nonce)[..32]
+        .try_into()
+        .expect("block is longer than 32 bytes");
+
+    let mut mac_data = aad.to_vec();
+    pad16(&mut mac_data);
+    mac_data.extend_from_slice(ciphertext);
+    pad16(&mut mac_data);
+    mac_data.extend((aad.len() as u64).to_le_bytes());
+    mac_data.extend((ciphertext.len() as u64).to_le_bytes());
+
+    poly1305(&one_time_key, &mac_data)
+}
+
+/// Encrypts and authenticates the plaintext, and authenticates the AAD.
+///
+/// Returns `ciphertext || tag`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::chacha20_poly1305::{open, seal};
+///
+/// let sealed = seal(&[1; 32], &[2; 12], b"header", b"secret");
+/// assert_eq!(open(&[1; 32], &[2; 12], b"header", &sealed).unwrap(), b"secret");
+/// assert!(open(&[1; 32], &[2; 12], b"other header", &sealed).is_none());
+/// ```
+pub fn seal(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let mut sealed = chacha20(plaintext, key, nonce, 1);
+    let tag = compute_tag(key, nonce, aad, &sealed);
+    sealed.extend(tag);
+    sealed
+}
+
+/// Verifies and decrypts `ciphertext || tag`.
+///
+/// Returns `None` if the input is shorter than a tag or the tag does not
+/// match; the tag comparison does not exit early.
+pub fn open(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8], sealed: &[u8]) -> Option<Vec<u8>> {
+    let (ciphertext, tag) = sealed.split_at_checked(sealed.len().checked_sub(TAG_LEN)?)?;
+
+    let expected = compute_tag(key, nonce, aad, ciphertext);
+    let difference = expected
+        .iter()
+        .zip(tag)
+        .fold(0, |acc, (a, b)| acc | (a ^ b));
+
+    (difference == 0).then(|| chacha20(ciphertext, key, nonce, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    fn rfc_parameters() -> ([u8; 32], [u8; 12], Vec<u8>) {
+        let key = std::array::from_fn(|idx| 0x80 + idx as u8);
+        let nonce = hex::decode("070000004041424344454647")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let aad = hex::decode("50515253c0c1c2c3c4c5c6c7").unwrap();
+        (key, nonce, aad)
+    }
+
+    #[test]
+    fn test_rfc_vector() {
+        // RFC 8439 Section 2.8.2
+        let (key, nonce, aad) = rfc_parameters();
+        let sealed = seal(&key, &nonce, &aad, SUNSCREEN);
+
+        assert_eq!(
+            hex::encode(&sealed).to_lowercase(),
+            "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+             3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+             92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+             3ff4def08e4b7a9de576d26586cec64b6116\
+             1ae10b594f09e26a7e902ecbd0600691"
+        );
+        assert_eq!(open(&key, &nonce, &aad, &sealed).unwrap(), SUNSCREEN);
+    }
+
+    #[test]
+    fn test_open_rejects_tampering() {
+        let (key, nonce, aad) = rfc_parameters();
+        let sealed = seal(&key, &nonce, &aad, SUNSCREEN);
+
+        let mut flipped_ciphertext = sealed.clone();
+        flipped_ciphertext[0] ^= 1;
+        assert!(open(&key, &nonce, &aad, &flipped_ciphertext).is_none());
+
+        let mut flipped_tag = sealed.clone();
+        *flipped_tag.last_mut().unwrap() ^= 0x80;
+        assert!(open(&key, &nonce, &aad, &flipped_tag).is_none());
+
+        assert!(open(&key, &nonce, b"", &sealed).is_none());
+        assert!(open(&key, &nonce, &aad, &sealed[..TAG_LEN - 1]).is_none());
+    }
+
+    #[test]
+    fn test_empty_plaintext() {
+        let (key, nonce, aad) = rfc_parameters();
+        let sealed = seal(&key, &nonce, &aad, b"");
+
+        assert_eq!(sealed.len(), TAG_LEN);
+        assert_eq!(open(&key, &nonce, &aad, &sealed).unwrap(), b"");
+    }
+}
@@ -0,0 +1,216 @@
+//! ECDSA signatures.
+//!
+//! This follows [SEC 1](https://www.secg.org/sec1-v2.pdf) sections 4.1.3
+//! and 4.1.4 with SHA-256, on any [`Domain`], P-256 included. Scalars live
+//! in the field modulo the prime group order, and the nonce of every
+//! signature must be secret, uniform and never reused: [`sign_with_nonce`]
+//! exists for the attacks on nonces that are not.
+
+use super::field::Field;
+use super::{Domain, Point};
+use crate::analysis::bignum::BigUint;
+use crate::crypto::sha256::sha256;
+use crate::util::SplitMix64;
+
+/// An ECDSA signature `(r, s)`, both in `[1, order)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+/// The field of scalars modulo the group order.
+fn scalars(domain: &Domain) -> Field {
+    Field::new(domain.order.clone()).expect("the group order is an odd prime")
+}
+
+/// Whether a scalar is in `[1, order)`.
+fn in_range(domain: &Domain, scalar: &BigUint) -> bool {
+    !scalar.is_zero() && *scalar < domain.order
+}
+
+/// The SHA-256 digest of `message` as a scalar: its leftmost bits, as many
+/// as the group order has, reduced modulo the order.
+pub fn message_scalar(domain: &Domain, message: &[u8]) -> BigUint {
+    let digest = BigUint::from_be_bytes(&sha256(message));
+    let excess = 256u64.saturating_sub(domain.order.bits());
+    (digest >> excess as usize) % &domain.order
+}
+
+/// Signs `message` with a fresh nonce from `rng`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::ec::Domain;
+/// use cryptopals::crypto::ec::ecdsa::{sign, verify};
+/// use cryptopals::util::SplitMix64;
+///
+/// let domain = Domain::toy();
+/// let mut rng = SplitMix64::new(1);
+/// let secret = domain.random_secret(&mut rng);
+/// let signature = sign(&domain, &secret, b"hi mom", &mut rng);
+/// assert!(verify(&domain, &domain.public_key(&secret), b"hi mom", &signature));
+/// assert!(!verify(&domain, &domain.public_key(&secret), b"hi dad", &signature));
+/// ```
+pub fn sign(domain: &Domain, secret: &BigUint, message: &[u8], rng: &mut SplitMix64) -> Signature {
+    loop {
+        let nonce = domain.random_secret(rng);
+        if let Some(signature) = sign_with_nonce(domain, secret, message, &nonce) {
+            return signature;
+        }
+    }
+}
+
+/// Signs `message` with the given nonce, or returns `None` if the nonce
+/// gives `r = 0` or `s = 0` and another one must be picked.
+///
+/// # Panics
+///
+/// Panics if the nonce is not in `[1, order)`.
+pub fn sign_with_nonce(
+    domain: &Domain,
+    secret: &BigUint,
+    message: &[u8],
+    nonce: &BigUint,
+) -> Option<Signature> {
+    assert!(in_range(domain, nonce), "the nonce must be in [1, order)");
+    let field = scalars(domain);
+
+    let Point::Affine { x, .. } = domain.public_key(nonce) else {
+        unreachable!("a nonce below the order does not give the identity");
+    };
+    let r = field.reduce(&x);
+    // s = (e + r d) / k
+    let e = message_scalar(domain, message);
+    let s = field.multiply(
+        &field.add(&e, &field.multiply(&r, &field.reduce(secret))),
+        &field.inverse(nonce)?,
+    );
+    (!r.is_zero() && !s.is_zero()).then_some(Signature { r, s })
+}
+
+/// Whether `signature` is a valid signature of `message` under `public`.
+///
+/// The public key is validated first, and `r` and `s` must be in
+/// `[1, order)`.
+pub fn verify(domain: &Domain, public: &Point, message: &[u8], signature: &Signature) -> bool {
+    let Signature { r, s } = signature;
+    if !in_range(domain, r) || !in_range(domain, s) || !domain.is_valid_public_key(public) {
+        return false;
+    }
+    let field = scalars(domain);
+
+    // R = (e / s) G + (r / s) Q
+    let s_inverse = field.inverse(s).expect("s is not zero");
+    let u1 = field.multiply(&message_scalar(domain, message), &s_inverse);
+    let u2 = field.multiply(r, &s_inverse);
+    let point = domain.curve.add(
+        &domain.curve.multiply(&domain.generator, &u1),
+        &domain.curve.multiply(public, &u2),
+    );
+    matches!(point, Point::Affine { x, .. } if field.reduce(&x) == *r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_rejects_malformed_signatures() {
+        let domain = Domain::toy();
+        let mut rng = SplitMix64::new(3);
+        let secret = domain.random_secret(&mut rng);
+        let public = domain.public_key(&secret);
+        let signature = sign(&domain, &secret, b"message", &mut rng);
+
+        for forged in [
+            Signature {
+                r: BigUint::zero(),
+                ..signature.clone()
+            },
+            Signature {
+                s: BigUint::zero(),
+                ..signature.clone()
+            },
+            Signature {
+                s: &signature.s + &domain.order,
+                ..signature.clone()
+            },
+            Signature {
+                s: &domain.order - &signature.s,
+                r: &signature.r + BigUint::one(),
+            },
+        ] {
+            assert!(!verify(&domain, &public, b"message", &forged));
+        }
+        assert!(!verify(&domain, &Point::Infinity, b"message", &signature));
+        assert!(!verify(
+            &domain,
+            &domain.public_key(&(&secret + BigUint::one())),
+            b"message",
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_nonce_determines_the_signature() {
+        let domain = Domain::toy();
+        let (secret, nonce) = (BigUint::from(1234u64), BigUint::from(5678u64));
+        let first = sign_with_nonce(&domain, &secret, b"one", &nonce).unwrap();
+        let second = sign_with_nonce(&domain, &secret, b"two", &nonce).unwrap();
+        assert_eq!(first.r, second.r);
+        assert_ne!(first.s, second.s);
+    }
+
+    #[test]
+    fn test_p256_known_answer() {
+        // RFC 6979 appendix A.2.5, with SHA-256 and the message "sample"
+        let domain = Domain::p256();
+        let hex = |value: &str| BigUint::from_hex(value).unwrap();
+        let secret = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let public = Point::Affine {
+            x: hex("60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6"),
+            y: hex("7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"),
+        };
+        let nonce = hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60");
+        assert_eq!(domain.public_key(&secret), public);
+
+        let signature = sign_with_nonce(&domain, &secret, b"sample", &nonce).unwrap();
+        assert_eq!(
+            signature,
+            Signature {
+                r: hex("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716"),
+                s: hex("f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"),
+            }
+        );
+        assert!(verify(&domain, &public, b"sample", &signature));
+        assert!(!verify(&domain, &public, b"samples", &signature));
+    }
+
+    #[test]
+    fn test_message_scalar_takes_leftmost_bits() {
+        let domain = Domain::toy();
+        let digest = sha256(b"abc");
+        // the order has 46 bits
+        let leftmost = u64::from_be_bytes(digest[..8].try_into().unwrap()) >> 18;
+        assert_eq!(
+            message_scalar(&domain, b"abc"),
+            BigUint::from(leftmost) % &domain.order
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn signatures_verify(seed: u64, message: Vec<u8>) {
+            let domain = Domain::toy();
+            let mut rng = SplitMix64::new(seed);
+            let secret = domain.random_secret(&mut rng);
+            let signature = sign(&domain, &secret, &message, &mut rng);
+            prop_assert!(verify(&domain, &domain.public_key(&secret), &message, &signature));
+        }
+    }
+}
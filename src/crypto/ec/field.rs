@@ -0,0 +1,201 @@
+//! Arithmetic modulo a prime.
+//!
+//! Residues are [`BigUint`]s, so the same code carries the toy curves the
+//! tests run on and the 128- and 256-bit curves of the challenges. The same
+//! type serves the coordinates of curve points and, for signatures, scalars
+//! modulo the group order.
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::number_theory::is_probable_prime;
+
+/// The integers modulo an odd prime `p`.
+///
+/// Operands must be below the modulus.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::crypto::ec::field::Field;
+///
+/// let field = Field::new(BigUint::from(2027u64)).unwrap();
+/// let five = field.element(5);
+/// assert_eq!(field.multiply(&field.inverse(&five).unwrap(), &five), BigUint::one());
+///
+/// let four = field.element(4);
+/// let root = field.sqrt(&four).unwrap();
+/// assert_eq!(field.square(&root), four);
+/// assert!(Field::new(BigUint::from(2025u64)).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Field {
+    modulus: BigUint,
+}
+
+impl Field {
+    /// Returns `None` unless `modulus` is an odd prime, as far as
+    /// [`is_probable_prime`] can tell.
+    pub fn new(modulus: BigUint) -> Option<Self> {
+        (modulus > BigUint::from(2u64) && is_probable_prime(&modulus)).then_some(Field { modulus })
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Reduces any integer into the field.
+    pub fn reduce(&self, value: &BigUint) -> BigUint {
+        value % &self.modulus
+    }
+
+    /// A small constant as a field element.
+    pub fn element(&self, value: u64) -> BigUint {
+        BigUint::from(value) % &self.modulus
+    }
+
+    pub fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let sum = a + b;
+        if sum >= self.modulus {
+            sum - &self.modulus
+        } else {
+            sum
+        }
+    }
+
+    pub fn subtract(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if a >= b { a - b } else { a + &self.modulus - b }
+    }
+
+    pub fn negate(&self, a: &BigUint) -> BigUint {
+        self.subtract(&BigUint::zero(), a)
+    }
+
+    pub fn multiply(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % &self.modulus
+    }
+
+    pub fn square(&self, a: &BigUint) -> BigUint {
+        self.multiply(a, a)
+    }
+
+    /// `base^exponent` by square-and-multiply.
+    pub fn power(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        base.pow_mod(exponent, &self.modulus)
+    }
+
+    /// The multiplicative inverse, by the extended Euclidean algorithm;
+    /// `None` for zero.
+    pub fn inverse(&self, a: &BigUint) -> Option<BigUint> {
+        a.mod_inverse(&self.modulus)
+    }
+
+    /// Whether `a` is a square, by Euler's criterion. Zero is a square.
+    pub fn is_square(&self, a: &BigUint) -> bool {
+        let half = (&self.modulus - BigUint::one()) >> 1;
+        a.is_zero() || self.power(a, &half) == BigUint::one()
+    }
+
+    /// A square root of `a`, or `None` if it has none.
+    ///
+    /// This is the Tonelli-Shanks algorithm, which takes a single
+    /// exponentiation when `p = 3 (mod 4)`. Of the two roots `r` and `p - r`
+    /// the smaller is returned.
+    pub fn sqrt(&self, a: &BigUint) -> Option<BigUint> {
+        if a.is_zero() {
+            return Some(BigUint::zero());
+        }
+        if !self.is_square(a) {
+            return None;
+        }
+
+        // p - 1 = odd * 2^twos, and z is any non-square
+        let one = BigUint::one();
+        let minus_one = &self.modulus - &one;
+        let twos = (0..).find(|&index| minus_one.bit(index)).expect("p > 1");
+        let odd = &minus_one >> twos as usize;
+        let z = (2..)
+            .map(|z| self.element(z))
+            .find(|z| !self.is_square(z))
+            .expect("half the residues are non-squares");
+
+        let mut c = self.power(&z, &odd);
+        let mut t = self.power(a, &odd);
+        let mut root = self.power(a, &((&odd + &one) >> 1));
+        let mut m = twos;
+        while t != one {
+            // the least i with t^(2^i) = 1
+            let mut i = 0;
+            let mut t_power = t.clone();
+            while t_power != one {
+                t_power = self.square(&t_power);
+                i += 1;
+            }
+            let b = (0..m - i - 1).fold(c, |b, _| self.square(&b));
+            root = self.multiply(&root, &b);
+            c = self.square(&b);
+            t = self.multiply(&t, &c);
+            m = i;
+        }
+        let other = self.negate(&root);
+        Some(root.min(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // 2^32 - 2^20 + 1 has p - 1 divisible by 2^20, the slow case for
+    // Tonelli-Shanks; 2^61 - 1 is 3 mod 4, and 2^127 - 1 does not fit a
+    // machine word
+    const PRIMES: [u128; 4] = [4293918721, (1 << 61) - 1, 220513532931199, (1 << 127) - 1];
+
+    fn field(index: usize) -> Field {
+        Field::new(BigUint::from(PRIMES[index])).unwrap()
+    }
+
+    #[test]
+    fn test_rejects_unsuitable_moduli() {
+        let new = |modulus: u128| Field::new(BigUint::from(modulus));
+        assert!(new(2).is_none());
+        assert!(new(91).is_none());
+        assert!(new(1 << 127).is_none());
+        assert!(new(3).is_some());
+    }
+
+    proptest! {
+        #[test]
+        fn field_laws_hold(index in 0usize..4, a: u128, b: u128) {
+            let field = field(index);
+            let a = field.reduce(&BigUint::from(a));
+            let b = field.reduce(&BigUint::from(b));
+
+            prop_assert_eq!(field.subtract(&field.add(&a, &b), &b), a.clone());
+            prop_assert!(field.add(&a, &field.negate(&a)).is_zero());
+            if let Some(inverse) = field.inverse(&a) {
+                prop_assert_eq!(field.multiply(&a, &inverse), BigUint::one());
+            }
+            prop_assert_eq!(
+                field.power(&a, &BigUint::from(3u64)),
+                field.multiply(&a, &field.square(&a))
+            );
+        }
+
+        #[test]
+        fn sqrt_finds_the_smaller_root(index in 0usize..4, a: u128) {
+            let field = field(index);
+            let square = field.square(&field.reduce(&BigUint::from(a)));
+
+            let root = field.sqrt(&square).unwrap();
+            prop_assert_eq!(field.square(&root), square.clone());
+            prop_assert!(root <= field.negate(&root) || root.is_zero());
+
+            // a square times a non-square is a non-square
+            let non_square = (2..).map(|z| field.element(z)).find(|z| !field.is_square(z)).unwrap();
+            if !square.is_zero() {
+                prop_assert_eq!(field.sqrt(&field.multiply(&square, &non_square)), None);
+            }
+        }
+    }
+}
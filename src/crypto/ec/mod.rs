@@ -0,0 +1,595 @@
+//! Elliptic curves in short Weierstrass form, and ECDH.
+//!
+//! A curve `y^2 = x^3 + ax + b` over GF(p) is a group under the chord and
+//! tangent rule, with the point at infinity as identity
+//! ([SEC 1](https://www.secg.org/sec1-v2.pdf) section 2.2). Coordinates are
+//! [`BigUint`]s: [`Domain::toy`] is a 48-bit curve on which the tests run
+//! quickly, [`Domain::challenge59`] is the 128-bit curve the challenges
+//! attack, and [`Domain::p256`] is the standard curve they sign with.
+//!
+//! Affine addition needs a field inversion. [`Jacobian`] coordinates, with
+//! `x = X / Z^2` and `y = Y / Z^3`, defer it to a single inversion at the
+//! end, which is how [`Curve::multiply`] runs its ladder. ECDH validates the
+//! peer's point before using it; the invalid-curve attack in
+//! [`crate::attacks::invalid_curve`] shows what skipping that costs.
+//!
+//! [`montgomery`] gives the same curves in Montgomery form with an x-only
+//! ladder, and [`x25519`] runs that ladder on Curve25519 over a 255-bit
+//! field.
+//!
+//! [`ecdsa`] signs with the same domain parameters.
+
+pub mod ecdsa;
+pub mod field;
+pub mod montgomery;
+pub mod x25519;
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::number_theory::random_below;
+use crate::util::SplitMix64;
+use field::Field;
+
+/// A point of a curve in affine coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Point {
+    /// The point at infinity, the identity of the group.
+    Infinity,
+    Affine {
+        x: BigUint,
+        y: BigUint,
+    },
+}
+
+/// A point in Jacobian coordinates `(X, Y, Z)`, standing for the affine
+/// point `(X / Z^2, Y / Z^3)`, or for the point at infinity when `Z = 0`.
+///
+/// Many triples stand for the same point, so compare points after
+/// [`Curve::to_affine`].
+#[derive(Debug, Clone)]
+pub struct Jacobian {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub z: BigUint,
+}
+
+impl Jacobian {
+    /// The point at infinity.
+    fn infinity() -> Self {
+        Jacobian {
+            x: BigUint::one(),
+            y: BigUint::one(),
+            z: BigUint::zero(),
+        }
+    }
+}
+
+/// The curve `y^2 = x^3 + ax + b` over a prime field.
+///
+/// Addition never looks at `b`, so the arithmetic of a curve works just as
+/// well on the points of any curve with the same `a` and field.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::crypto::ec::{Curve, Point};
+///
+/// let big = |value: u64| BigUint::from(value);
+/// // y^2 = x^3 + 2x + 3 over GF(97)
+/// let curve = Curve::new(big(2), big(3), big(97)).unwrap();
+/// let point = Point::Affine { x: big(3), y: big(6) };
+/// assert!(curve.contains(&point));
+/// assert_eq!(curve.add(&point, &point), Point::Affine { x: big(80), y: big(10) });
+/// assert_eq!(curve.multiply(&point, &big(5)), Point::Infinity);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Curve {
+    field: Field,
+    a: BigUint,
+    b: BigUint,
+}
+
+impl Curve {
+    /// Returns `None` unless `p` is an odd prime, `a` and `b` are below it,
+    /// and the curve is not singular (`4a^3 + 27b^2 != 0`).
+    pub fn new(a: BigUint, b: BigUint, p: BigUint) -> Option<Self> {
+        let field = Field::new(p)?;
+        if a >= *field.modulus() || b >= *field.modulus() {
+            return None;
+        }
+        let discriminant = field.add(
+            &field.multiply(&field.element(4), &field.multiply(&a, &field.square(&a))),
+            &field.multiply(&field.element(27), &field.square(&b)),
+        );
+        (!discriminant.is_zero()).then_some(Curve { field, a, b })
+    }
+
+    pub fn a(&self) -> &BigUint {
+        &self.a
+    }
+
+    pub fn b(&self) -> &BigUint {
+        &self.b
+    }
+
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+
+    /// `x^3 + ax + b`, the value `y^2` must take.
+    fn right_side(&self, x: &BigUint) -> BigUint {
+        let field = &self.field;
+        let cubic = field.multiply(&field.add(&field.square(x), &self.a), x);
+        field.add(&cubic, &self.b)
+    }
+
+    /// Whether the point lies on the curve. The point at infinity always
+    /// does.
+    pub fn contains(&self, point: &Point) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine { x, y } => {
+                x < self.field.modulus()
+                    && y < self.field.modulus()
+                    && self.field.square(y) == self.right_side(x)
+            }
+        }
+    }
+
+    /// The point with x-coordinate `x` and the smaller of its two
+    /// y-coordinates, or `None` if there is none.
+    pub fn lift_x(&self, x: &BigUint) -> Option<Point> {
+        let x = self.field.reduce(x);
+        let y = self.field.sqrt(&self.right_side(&x))?;
+        Some(Point::Affine { x, y })
+    }
+
+    pub fn negate(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine {
+                x: x.clone(),
+                y: self.field.negate(y),
+            },
+        }
+    }
+
+    /// Adds two points with the chord and tangent rule.
+    pub fn add(&self, p: &Point, q: &Point) -> Point {
+        let field = &self.field;
+        let (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) = (p, q) else {
+            return if *p == Point::Infinity {
+                q.clone()
+            } else {
+                p.clone()
+            };
+        };
+        if x1 == x2 && field.add(y1, y2).is_zero() {
+            return Point::Infinity;
+        }
+
+        let slope = if x1 == x2 {
+            // the tangent: (3x^2 + a) / 2y
+            let numerator = field.add(
+                &field.multiply(&field.element(3), &field.square(x1)),
+                &self.a,
+            );
+            let denominator = field.add(y1, y1);
+            field.multiply(
+                &numerator,
+                &field.inverse(&denominator).expect("y is not zero"),
+            )
+        } else {
+            let denominator = field.subtract(x2, x1);
+            field.multiply(
+                &field.subtract(y2, y1),
+                &field.inverse(&denominator).expect("x1 != x2"),
+            )
+        };
+        let x = field.subtract(&field.subtract(&field.square(&slope), x1), x2);
+        let y = field.subtract(&field.multiply(&slope, &field.subtract(x1, &x)), y1);
+        Point::Affine { x, y }
+    }
+
+    pub fn to_jacobian(&self, point: &Point) -> Jacobian {
+        match point {
+            Point::Infinity => Jacobian::infinity(),
+            Point::Affine { x, y } => Jacobian {
+                x: x.clone(),
+                y: y.clone(),
+                z: BigUint::one(),
+            },
+        }
+    }
+
+    pub fn to_affine(&self, point: &Jacobian) -> Point {
+        let field = &self.field;
+        let Some(z_inverse) = field.inverse(&point.z) else {
+            return Point::Infinity;
+        };
+        let z2_inverse = field.square(&z_inverse);
+        Point::Affine {
+            x: field.multiply(&point.x, &z2_inverse),
+            y: field.multiply(&point.y, &field.multiply(&z2_inverse, &z_inverse)),
+        }
+    }
+
+    /// Doubles a point in Jacobian coordinates, without inversions.
+    pub fn jacobian_double(&self, point: &Jacobian) -> Jacobian {
+        let field = &self.field;
+        let Jacobian { x, y, z } = point;
+
+        // S = 4XY^2, M = 3X^2 + aZ^4
+        let y2 = field.square(y);
+        let s = field.multiply(&field.element(4), &field.multiply(x, &y2));
+        let m = field.add(
+            &field.multiply(&field.element(3), &field.square(x)),
+            &field.multiply(&self.a, &field.square(&field.square(z))),
+        );
+        let x3 = field.subtract(&field.square(&m), &field.add(&s, &s));
+        let y3 = field.subtract(
+            &field.multiply(&m, &field.subtract(&s, &x3)),
+            &field.multiply(&field.element(8), &field.square(&y2)),
+        );
+        // Z3 = 2YZ, zero for points of order two
+        let z3 = field.multiply(&field.add(y, y), z);
+        Jacobian {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// Adds two points in Jacobian coordinates, without inversions.
+    pub fn jacobian_add(&self, p: &Jacobian, q: &Jacobian) -> Jacobian {
+        let field = &self.field;
+        if p.z.is_zero() {
+            return q.clone();
+        }
+        if q.z.is_zero() {
+            return p.clone();
+        }
+
+        let (z1_2, z2_2) = (field.square(&p.z), field.square(&q.z));
+        let u1 = field.multiply(&p.x, &z2_2);
+        let u2 = field.multiply(&q.x, &z1_2);
+        let s1 = field.multiply(&p.y, &field.multiply(&z2_2, &q.z));
+        let s2 = field.multiply(&q.y, &field.multiply(&z1_2, &p.z));
+        let h = field.subtract(&u2, &u1);
+        let r = field.subtract(&s2, &s1);
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.jacobian_double(p)
+            } else {
+                Jacobian::infinity()
+            };
+        }
+
+        let h2 = field.square(&h);
+        let h3 = field.multiply(&h2, &h);
+        let u1h2 = field.multiply(&u1, &h2);
+        let x3 = field.subtract(
+            &field.subtract(&field.square(&r), &h3),
+            &field.add(&u1h2, &u1h2),
+        );
+        let y3 = field.subtract(
+            &field.multiply(&r, &field.subtract(&u1h2, &x3)),
+            &field.multiply(&s1, &h3),
+        );
+        let z3 = field.multiply(&h, &field.multiply(&p.z, &q.z));
+        Jacobian {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// `scalar * point`, by a Montgomery ladder in Jacobian coordinates.
+    ///
+    /// The ladder does one addition and one doubling for each bit, over one
+    /// more bit than the field modulus has (or the whole scalar, if it is
+    /// longer), so scalars below the group order all take the same number
+    /// of steps. The registers are swapped by masking their limbs, not by
+    /// branching on the bits of the scalar.
+    pub fn multiply(&self, point: &Point, scalar: &BigUint) -> Point {
+        let bits = scalar.bits().max(self.field.modulus().bits() + 1);
+        let mut low = Jacobian::infinity();
+        let mut high = self.to_jacobian(point);
+        for bit in (0..bits).rev() {
+            let swap = scalar.bit(bit);
+            conditional_swap(&mut low, &mut high, swap);
+            high = self.jacobian_add(&low, &high);
+            low = self.jacobian_double(&low);
+            conditional_swap(&mut low, &mut high, swap);
+        }
+        self.to_affine(&low)
+    }
+}
+
+/// Swaps the points if `swap` is set, coordinate by coordinate.
+fn conditional_swap(a: &mut Jacobian, b: &mut Jacobian, swap: bool) {
+    BigUint::conditional_swap(&mut a.x, &mut b.x, swap);
+    BigUint::conditional_swap(&mut a.y, &mut b.y, swap);
+    BigUint::conditional_swap(&mut a.z, &mut b.z, swap);
+}
+
+/// Parses the decimal constants of the named domains.
+fn decimal(value: &str) -> BigUint {
+    BigUint::from_decimal(value).expect("a decimal constant")
+}
+
+/// Parses the hexadecimal constants of the named domains.
+fn hex(value: &str) -> BigUint {
+    BigUint::from_hex(value).expect("a hexadecimal constant")
+}
+
+/// Domain parameters: a curve and a generator of prime order, the group
+/// ECDH and ECDSA work in.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::crypto::ec::Domain;
+///
+/// let domain = Domain::toy();
+/// let (alice, bob) = (BigUint::from(123_456_789u64), BigUint::from(987_654_321u64));
+/// let shared = domain.shared_secret(&alice, &domain.public_key(&bob));
+/// assert_eq!(shared, domain.shared_secret(&bob, &domain.public_key(&alice)));
+/// assert!(shared.is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Domain {
+    pub curve: Curve,
+    pub generator: Point,
+    /// The prime order of the generator.
+    pub order: BigUint,
+    /// The number of points on the curve divided by `order`.
+    pub cofactor: u64,
+}
+
+impl Domain {
+    /// A toy curve over a 48-bit prime with a generator of 46-bit prime
+    /// order.
+    ///
+    /// It is `y^2 = x^3 + ax + b` with `a` and `b` chosen to make it the
+    /// Montgomery curve `v^2 = u^3 + 1563u^2 + u`, and the curve has four
+    /// times as many points as the generator's order. Curves with the same
+    /// `a` but other `b` have orders with small factors, and so does the
+    /// twist.
+    pub fn toy() -> Self {
+        let big = |value: u64| BigUint::from(value);
+        Domain {
+            curve: Curve::new(big(220513532116877), big(282841001), big(220513532931199))
+                .expect("the toy curve is valid"),
+            generator: Point::Affine {
+                x: big(187397087005097),
+                y: big(204845807391460),
+            },
+            order: big(55128388788709),
+            cofactor: 4,
+        }
+    }
+
+    /// The curve `y^2 = x^3 - 95051x + 11279326` over a 128-bit prime, with
+    /// the generator `(182, ...)` of 125-bit prime order, as given in
+    /// challenge 59. It is the Montgomery curve
+    /// `v^2 = u^3 + 534u^2 + u` of challenge 60.
+    pub fn challenge59() -> Self {
+        let p = decimal("233970423115425145524320034830162017933");
+        Domain {
+            curve: Curve::new(&p - BigUint::from(95051u64), BigUint::from(11279326u64), p)
+                .expect("the challenge curve is valid"),
+            generator: Point::Affine {
+                x: BigUint::from(182u64),
+                y: decimal("85518893674295321206118380980485522083"),
+            },
+            order: decimal("29246302889428143187362802287225875743"),
+            cofactor: 8,
+        }
+    }
+
+    /// The NIST curve P-256, also known as secp256r1
+    /// ([SEC 2](https://www.secg.org/sec2-v2.pdf) section 2.4.2), whose
+    /// group of points has prime order.
+    pub fn p256() -> Self {
+        let p = hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        Domain {
+            curve: Curve::new(
+                &p - BigUint::from(3u64),
+                hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+                p,
+            )
+            .expect("P-256 is valid"),
+            generator: Point::Affine {
+                x: hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+                y: hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            },
+            order: hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+            cofactor: 1,
+        }
+    }
+
+    /// A secret key, uniform in `[1, order)`.
+    pub fn random_secret(&self, rng: &mut SplitMix64) -> BigUint {
+        random_below(&(&self.order - BigUint::one()), rng) + BigUint::one()
+    }
+
+    pub fn public_key(&self, secret: &BigUint) -> Point {
+        self.curve.multiply(&self.generator, secret)
+    }
+
+    /// Whether a received public key is a point of the generator's group:
+    /// not the point at infinity, on the curve, and of the right order
+    /// (SEC 1 section 3.2.2.1).
+    pub fn is_valid_public_key(&self, point: &Point) -> bool {
+        *point != Point::Infinity
+            && self.curve.contains(point)
+            && self.curve.multiply(point, &self.order) == Point::Infinity
+    }
+
+    /// The ECDH shared point `secret * peer`, or `None` if the peer's key
+    /// is invalid.
+    pub fn shared_secret(&self, secret: &BigUint, peer: &Point) -> Option<Point> {
+        if !self.is_valid_public_key(peer) {
+            return None;
+        }
+        match self.curve.multiply(peer, secret) {
+            Point::Infinity => None,
+            shared => Some(shared),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn big(value: u64) -> BigUint {
+        BigUint::from(value)
+    }
+
+    fn small_curve() -> Curve {
+        Curve::new(big(2), big(3), big(97)).unwrap()
+    }
+
+    /// `scalar * point` by repeated affine addition.
+    fn repeated_addition(curve: &Curve, point: &Point, scalar: u64) -> Point {
+        (0..scalar).fold(Point::Infinity, |sum, _| curve.add(&sum, point))
+    }
+
+    #[test]
+    fn test_rejects_bad_parameters() {
+        // 4 * (-3)^3 + 27 * 2^2 = 0
+        assert!(Curve::new(big(94), big(2), big(97)).is_none());
+        assert!(Curve::new(big(2), big(3), big(91)).is_none());
+        assert!(Curve::new(big(2), big(97), big(97)).is_none());
+    }
+
+    #[test]
+    fn test_small_curve_group() {
+        let curve = small_curve();
+        let points = std::iter::once(Point::Infinity)
+            .chain((0..97).flat_map(|x| {
+                curve
+                    .lift_x(&big(x))
+                    .into_iter()
+                    .flat_map(|point| [curve.negate(&point), point])
+            }))
+            .collect::<std::collections::HashSet<_>>();
+        // Hasse: |#E - 98| <= 2 sqrt(97)
+        assert_eq!(points.len(), 100);
+
+        for point in &points {
+            assert!(curve.contains(point));
+            assert_eq!(curve.multiply(point, &big(100)), Point::Infinity);
+            for scalar in [0, 1, 2, 3, 7, 50, 99] {
+                assert_eq!(
+                    curve.multiply(point, &big(scalar)),
+                    repeated_addition(&curve, point, scalar)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_jacobian_matches_affine() {
+        let curve = small_curve();
+        let p = curve.lift_x(&big(3)).unwrap();
+        let q = curve.lift_x(&big(10)).unwrap();
+        let (jp, jq) = (curve.to_jacobian(&p), curve.to_jacobian(&q));
+
+        assert_eq!(
+            curve.to_affine(&curve.jacobian_add(&jp, &jq)),
+            curve.add(&p, &q)
+        );
+        assert_eq!(
+            curve.to_affine(&curve.jacobian_add(&jp, &jp)),
+            curve.add(&p, &p)
+        );
+        assert_eq!(
+            curve.to_affine(&curve.jacobian_double(&jp)),
+            curve.add(&p, &p)
+        );
+        let negated = curve.to_jacobian(&curve.negate(&p));
+        assert_eq!(
+            curve.to_affine(&curve.jacobian_add(&jp, &negated)),
+            Point::Infinity
+        );
+    }
+
+    #[test]
+    fn test_named_domains() {
+        for domain in [Domain::toy(), Domain::challenge59(), Domain::p256()] {
+            assert!(domain.curve.contains(&domain.generator));
+            assert!(domain.is_valid_public_key(&domain.generator));
+            assert_ne!(
+                domain
+                    .curve
+                    .multiply(&domain.generator, &big(domain.cofactor)),
+                Point::Infinity
+            );
+
+            // every point times the group order is the identity
+            let point = (1..).find_map(|x| domain.curve.lift_x(&big(x))).unwrap();
+            assert_eq!(
+                domain
+                    .curve
+                    .multiply(&point, &(&domain.order * big(domain.cofactor))),
+                Point::Infinity
+            );
+            // and only with no cofactor is every point a valid key
+            assert_eq!(domain.is_valid_public_key(&point), domain.cofactor == 1);
+        }
+    }
+
+    #[test]
+    fn test_shared_secret_rejects_invalid_keys() {
+        let domain = Domain::toy();
+        let off_curve = Point::Affine {
+            x: big(1),
+            y: big(1),
+        };
+        let weak = Curve::new(
+            domain.curve.a().clone(),
+            big(2),
+            domain.curve.field().modulus().clone(),
+        )
+        .unwrap()
+        .lift_x(&big(2))
+        .unwrap();
+
+        assert!(!domain.curve.contains(&off_curve));
+        assert_eq!(domain.shared_secret(&big(5), &off_curve), None);
+        assert_eq!(domain.shared_secret(&big(5), &weak), None);
+        assert_eq!(domain.shared_secret(&big(5), &Point::Infinity), None);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn multiply_is_a_homomorphism(a: u128, b: u128) {
+            let domain = Domain::toy();
+            let curve = &domain.curve;
+            let (a, b) = (BigUint::from(a) % &domain.order, BigUint::from(b) % &domain.order);
+
+            let sum = curve.add(&domain.public_key(&a), &domain.public_key(&b));
+            prop_assert_eq!(sum, domain.public_key(&((&a + &b) % &domain.order)));
+            let product = curve.multiply(&domain.public_key(&a), &b);
+            prop_assert_eq!(product, domain.public_key(&(&a * &b % &domain.order)));
+        }
+
+        #[test]
+        fn ecdh_agrees(seed: u64) {
+            for domain in [Domain::toy(), Domain::challenge59()] {
+                let mut rng = SplitMix64::new(seed);
+                let (alice, bob) = (domain.random_secret(&mut rng), domain.random_secret(&mut rng));
+                let shared = domain.shared_secret(&alice, &domain.public_key(&bob)).unwrap();
+                prop_assert_eq!(Some(shared.clone()), domain.shared_secret(&bob, &domain.public_key(&alice)));
+                prop_assert!(domain.curve.contains(&shared));
+            }
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Montgomery curves and the x-only ladder.
+//!
+//! A curve `Bv^2 = u^3 + Au^2 + u` supports scalar multiplication on the
+//! `u`-coordinate alone ([Montgomery, "Speeding the Pollard and elliptic
+//! curve methods of factorization"](https://doi.org/10.1090/S0025-5718-1987-0866113-7)),
+//! which is how X25519 works. The ladder never checks that `u` belongs to
+//! the curve: a `u` that does not lifts to the quadratic twist, and the
+//! ladder computes on the twist instead. The twist attack in
+//! [`crate::attacks::twist_attack`] exploits that.
+//!
+//! Every Montgomery curve is birationally equivalent to a short Weierstrass
+//! curve by `x = u/B + A/3B`, `y = v/B`, which gives the full group law.
+
+use super::field::Field;
+use super::{Curve, Point};
+use crate::analysis::bignum::BigUint;
+
+/// The curve `Bv^2 = u^3 + Au^2 + u` over a prime field.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::analysis::bignum::BigUint;
+/// use cryptopals::crypto::ec::{Domain, montgomery::MontgomeryCurve};
+///
+/// let domain = Domain::toy();
+/// let curve = MontgomeryCurve::toy();
+/// assert_eq!(curve.to_weierstrass(), domain.curve);
+///
+/// let scalar = BigUint::from(1000u64);
+/// let (u, _) = curve.from_weierstrass_point(&domain.generator).unwrap();
+/// let (public, _) = curve.from_weierstrass_point(&domain.public_key(&scalar)).unwrap();
+/// assert_eq!(curve.ladder(&u, &scalar), public);
+/// assert!(curve.ladder(&u, &domain.order).is_zero());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MontgomeryCurve {
+    field: Field,
+    a: BigUint,
+    b: BigUint,
+}
+
+impl MontgomeryCurve {
+    /// Returns `None` unless `p` is an odd prime, `a` and `b` are below it,
+    /// and the curve is not singular (`B != 0` and `A^2 != 4`).
+    pub fn new(a: BigUint, b: BigUint, p: BigUint) -> Option<Self> {
+        let field = Field::new(p)?;
+        if a >= *field.modulus()
+            || b >= *field.modulus()
+            || b.is_zero()
+            || field.square(&a) == field.element(4)
+        {
+            return None;
+        }
+        Some(MontgomeryCurve { field, a, b })
+    }
+
+    /// The toy curve `v^2 = u^3 + 1563u^2 + u`, the Montgomery form of
+    /// [`Domain::toy`](super::Domain::toy).
+    pub fn toy() -> Self {
+        MontgomeryCurve::new(
+            BigUint::from(1563u64),
+            BigUint::one(),
+            BigUint::from(220513532931199u64),
+        )
+        .expect("the toy curve is valid")
+    }
+
+    /// The curve `v^2 = u^3 + 534u^2 + u` of challenge 60, the Montgomery
+    /// form of [`Domain::challenge59`](super::Domain::challenge59), on which
+    /// the generator has `u = 4`.
+    pub fn challenge60() -> Self {
+        MontgomeryCurve::new(
+            BigUint::from(534u64),
+            BigUint::one(),
+            BigUint::from_decimal("233970423115425145524320034830162017933")
+                .expect("a decimal constant"),
+        )
+        .expect("the challenge curve is valid")
+    }
+
+    /// The quadratic twist `nBv^2 = u^3 + Au^2 + u`, for the least
+    /// non-square `n`.
+    ///
+    /// Every `u` that is not on the curve is on its twist, and since the
+    /// ladder never looks at `B` it computes the same on both. The
+    /// Weierstrass form of the twist gives the group law the ladder follows
+    /// for such `u`.
+    pub fn twist(&self) -> Self {
+        let field = &self.field;
+        let non_square = (2..)
+            .map(|n| field.element(n))
+            .find(|n| !field.is_square(n))
+            .expect("half the residues are non-squares");
+        MontgomeryCurve {
+            field: field.clone(),
+            a: self.a.clone(),
+            b: field.multiply(&self.b, &non_square),
+        }
+    }
+
+    pub fn a(&self) -> &BigUint {
+        &self.a
+    }
+
+    pub fn b(&self) -> &BigUint {
+        &self.b
+    }
+
+    pub fn field(&self) -> &Field {
+        &self.field
+    }
+
+    /// `(u^3 + Au^2 + u) / B`, the value `v^2` must take.
+    fn right_side(&self, u: &BigUint) -> BigUint {
+        let field = &self.field;
+        let quadratic = field.multiply(&field.add(u, &self.a), u);
+        let cubic = field.multiply(&field.add(&quadratic, &BigUint::one()), u);
+        field.multiply(&cubic, &field.inverse(&self.b).expect("B is not zero"))
+    }
+
+    /// Whether `u` is the coordinate of a point of the curve rather than of
+    /// its twist. Every `u` is one or the other, and `u = 0` is both.
+    pub fn contains_u(&self, u: &BigUint) -> bool {
+        self.field
+            .is_square(&self.right_side(&self.field.reduce(u)))
+    }
+
+    /// The `v` with the smaller value for a `u` on the curve, or `None` if
+    /// `u` lies on the twist.
+    pub fn lift_u(&self, u: &BigUint) -> Option<BigUint> {
+        self.field.sqrt(&self.right_side(&self.field.reduce(u)))
+    }
+
+    /// The `u`-coordinate of `scalar` times a point with coordinate `u`.
+    ///
+    /// This is the ladder of RFC 7748 section 5: the registers are swapped
+    /// by the bits of the scalar, over one more bit than the field modulus
+    /// has (or the whole scalar, if it is longer), and the point at infinity
+    /// comes out as 0. `u` may belong to the twist.
+    pub fn ladder(&self, u: &BigUint, scalar: &BigUint) -> BigUint {
+        let field = &self.field;
+        let u = field.reduce(u);
+        // (A - 2) / 4
+        let a24 = field.multiply(
+            &field.subtract(&self.a, &field.element(2)),
+            &field.inverse(&field.element(4)).expect("p is odd"),
+        );
+
+        let (mut x2, mut z2) = (BigUint::one(), BigUint::zero());
+        let (mut x3, mut z3) = (u.clone(), BigUint::one());
+        let mut swap = false;
+        let bits = scalar.bits().max(field.modulus().bits() + 1);
+        for bit in (0..bits).rev() {
+            let k = scalar.bit(bit);
+            swap ^= k;
+            BigUint::conditional_swap(&mut x2, &mut x3, swap);
+            BigUint::conditional_swap(&mut z2, &mut z3, swap);
+            swap = k;
+
+            let a = field.add(&x2, &z2);
+            let aa = field.square(&a);
+            let b = field.subtract(&x2, &z2);
+            let bb = field.square(&b);
+            let e = field.subtract(&aa, &bb);
+            let c = field.add(&x3, &z3);
+            let d = field.subtract(&x3, &z3);
+            let da = field.multiply(&d, &a);
+            let cb = field.multiply(&c, &b);
+            x3 = field.square(&field.add(&da, &cb));
+            z3 = field.multiply(&u, &field.square(&field.subtract(&da, &cb)));
+            z2 = field.multiply(&e, &field.add(&aa, &field.multiply(&a24, &e)));
+            x2 = field.multiply(&aa, &bb);
+        }
+        BigUint::conditional_swap(&mut x2, &mut x3, swap);
+        BigUint::conditional_swap(&mut z2, &mut z3, swap);
+
+        // the point at infinity has z2 = 0, which has no inverse
+        field
+            .inverse(&z2)
+            .map_or_else(BigUint::zero, |inverse| field.multiply(&x2, &inverse))
+    }
+
+    /// The equivalent curve `y^2 = x^3 + ax + b`, with
+    /// `a = (3 - A^2) / 3B^2` and `b = (2A^3 - 9A) / 27B^3`.
+    pub fn to_weierstrass(&self) -> Curve {
+        let field = &self.field;
+        let inverse = |value: &BigUint| field.inverse(value).expect("p > 3");
+        let (a, b) = (&self.a, &self.b);
+
+        let a2 = field.square(a);
+        let b2 = field.square(b);
+        let weierstrass_a = field.multiply(
+            &field.subtract(&field.element(3), &a2),
+            &inverse(&field.multiply(&field.element(3), &b2)),
+        );
+        let weierstrass_b = field.multiply(
+            &field.subtract(
+                &field.multiply(&field.element(2), &field.multiply(&a2, a)),
+                &field.multiply(&field.element(9), a),
+            ),
+            &inverse(&field.multiply(&field.element(27), &field.multiply(&b2, b))),
+        );
+        Curve::new(weierstrass_a, weierstrass_b, field.modulus().clone())
+            .expect("equivalent curves are not singular")
+    }
+
+    /// `A / 3`, the shift between `u` and `Bx`.
+    fn shift(&self) -> BigUint {
+        let field = &self.field;
+        field.multiply(&self.a, &field.inverse(&field.element(3)).expect("p > 3"))
+    }
+
+    /// Maps a point `(u, v)` to [`to_weierstrass`](Self::to_weierstrass).
+    pub fn to_weierstrass_point(&self, u: &BigUint, v: &BigUint) -> Point {
+        let field = &self.field;
+        let b_inverse = field.inverse(&self.b).expect("B is not zero");
+        Point::Affine {
+            x: field.multiply(&field.add(&field.reduce(u), &self.shift()), &b_inverse),
+            y: field.multiply(&field.reduce(v), &b_inverse),
+        }
+    }
+
+    /// Maps a point of [`to_weierstrass`](Self::to_weierstrass) back to
+    /// `(u, v)`, or `None` for the point at infinity, which has no
+    /// coordinates.
+    pub fn from_weierstrass_point(&self, point: &Point) -> Option<(BigUint, BigUint)> {
+        let Point::Affine { x, y } = point else {
+            return None;
+        };
+        let field = &self.field;
+        Some((
+            field.subtract(&field.multiply(x, &self.b), &self.shift()),
+            field.multiply(y, &self.b),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ec::Domain;
+    use proptest::prelude::*;
+
+    fn big(value: u64) -> BigUint {
+        BigUint::from(value)
+    }
+
+    #[test]
+    fn test_rejects_bad_parameters() {
+        let new = |a, b, p| MontgomeryCurve::new(big(a), big(b), big(p));
+        assert!(new(2, 1, 97).is_none());
+        assert!(new(95, 1, 97).is_none());
+        assert!(new(3, 0, 97).is_none());
+        assert!(new(3, 1, 91).is_none());
+        assert!(new(3, 1, 97).is_some());
+    }
+
+    #[test]
+    fn test_points_map_between_forms() {
+        let curve = MontgomeryCurve::toy();
+        let weierstrass = curve.to_weierstrass();
+        for u in (1..50).map(big) {
+            let Some(v) = curve.lift_u(&u) else {
+                assert!(!curve.contains_u(&u));
+                continue;
+            };
+            let point = curve.to_weierstrass_point(&u, &v);
+            assert!(weierstrass.contains(&point));
+            assert_eq!(curve.from_weierstrass_point(&point), Some((u, v)));
+        }
+        assert_eq!(curve.from_weierstrass_point(&Point::Infinity), None);
+    }
+
+    #[test]
+    fn test_challenge60_curve() {
+        let domain = Domain::challenge59();
+        let curve = MontgomeryCurve::challenge60();
+        assert_eq!(curve.to_weierstrass(), domain.curve);
+
+        let (u, _) = curve.from_weierstrass_point(&domain.generator).unwrap();
+        assert_eq!(u, big(4));
+        assert!(curve.ladder(&u, &domain.order).is_zero());
+    }
+
+    #[test]
+    fn test_twist_holds_the_other_coordinates() {
+        let curve = MontgomeryCurve::toy();
+        let twist = curve.twist();
+        let weierstrass = twist.to_weierstrass();
+        for u in (1..50).map(big) {
+            assert_ne!(curve.contains_u(&u), twist.contains_u(&u));
+            let Some(v) = twist.lift_u(&u) else {
+                continue;
+            };
+            // the ladder on the curve follows the group of the twist
+            let point = twist.to_weierstrass_point(&u, &v);
+            let product = weierstrass.multiply(&point, &big(12345));
+            let expected = twist
+                .from_weierstrass_point(&product)
+                .map_or(BigUint::zero(), |(u, _)| u);
+            assert_eq!(curve.ladder(&u, &big(12345)), expected);
+        }
+    }
+
+    #[test]
+    fn test_ladder_handles_the_identity() {
+        let curve = MontgomeryCurve::toy();
+        // (0, 0) has order two
+        assert!(curve.ladder(&big(0), &big(1)).is_zero());
+        assert!(curve.ladder(&big(0), &big(2)).is_zero());
+        assert!(curve.ladder(&big(5), &big(0)).is_zero());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn ladder_matches_the_weierstrass_group(u in 1u64..1 << 20, scalar: u64) {
+            let curve = MontgomeryCurve::toy();
+            let weierstrass = curve.to_weierstrass();
+            let (u, scalar) = (big(u), big(scalar));
+            prop_assume!(curve.contains_u(&u));
+
+            let point = curve.to_weierstrass_point(&u, &curve.lift_u(&u).unwrap());
+            let product = weierstrass.multiply(&point, &scalar);
+            let expected = curve.from_weierstrass_point(&product).map_or(BigUint::zero(), |(u, _)| u);
+            prop_assert_eq!(curve.ladder(&u, &scalar), expected);
+        }
+
+        #[test]
+        fn ladder_composes_on_the_twist(u in 1u64..1 << 20, a in 1u64..1 << 32, b in 1u64..1 << 32) {
+            let curve = MontgomeryCurve::toy();
+            let (u, a, b) = (big(u), big(a), big(b));
+            prop_assume!(!curve.contains_u(&u));
+            prop_assert_eq!(curve.ladder(&curve.ladder(&u, &a), &b), curve.ladder(&u, &(&a * &b)));
+        }
+
+        #[test]
+        fn toy_generator_has_prime_order(scalar in 1u64..55128388788709) {
+            let domain = Domain::toy();
+            let curve = MontgomeryCurve::toy();
+            let (u, _) = curve.from_weierstrass_point(&domain.generator).unwrap();
+            prop_assert!(!curve.ladder(&u, &big(scalar)).is_zero());
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! X25519 key agreement.
+//!
+//! This module implements the X25519 function of
+//! [RFC 7748](https://datatracker.ietf.org/doc/html/rfc7748): the x-only
+//! ladder of [`super::montgomery`] on Curve25519, `v^2 = u^3 + 486662u^2 + u`
+//! over GF(2^255 - 19). Field elements are kept in five 51-bit limbs so that
+//! products fit in `u128`, and the ladder swaps with masks, but no effort is
+//! made to keep the final inversion or the compiler constant-time.
+
+const LIMB_MASK: u64 = (1 << 51) - 1;
+
+/// `(A - 2) / 4` for `A = 486662`.
+const A24: u64 = 121665;
+
+/// The `u`-coordinate of the base point, 9.
+pub const BASE_POINT: [u8; 32] = {
+    let mut base = [0; 32];
+    base[0] = 9;
+    base
+};
+
+/// An element of GF(2^255 - 19) in five 51-bit limbs, least significant
+/// first. Limbs may exceed 51 bits between reductions.
+#[derive(Debug, Clone, Copy)]
+struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    const ZERO: FieldElement = FieldElement([0; 5]);
+    const ONE: FieldElement = FieldElement([1, 0, 0, 0, 0]);
+
+    /// Decodes 32 little-endian bytes, ignoring the top bit as RFC 7748
+    /// requires. Values from `p` to `2^255 - 1` are accepted unreduced.
+    fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load = |offset: usize| {
+            u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("8 bytes"))
+        };
+        FieldElement([
+            load(0) & LIMB_MASK,
+            (load(6) >> 3) & LIMB_MASK,
+            (load(12) >> 6) & LIMB_MASK,
+            (load(19) >> 1) & LIMB_MASK,
+            (load(24) >> 12) & LIMB_MASK,
+        ])
+    }
+
+    /// Encodes the fully reduced value in 32 little-endian bytes.
+    fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = self.carry().carry().0;
+
+        // subtract p if the value is at least p: q = 1 exactly when
+        // value + 19 >= 2^255
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+        limbs[4] &= LIMB_MASK;
+
+        let mut bytes = [0u8; 32];
+        let mut accumulator = 0u128;
+        let mut bits = 0;
+        let mut index = 0;
+        for limb in limbs {
+            accumulator |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 && index < 32 {
+                bytes[index] = accumulator as u8;
+                accumulator >>= 8;
+                bits -= 8;
+                index += 1;
+            }
+        }
+        bytes[index] = accumulator as u8;
+        bytes
+    }
+
+    /// Brings every limb below 2^51 plus a little, folding the carry out of
+    /// the top limb back in as `2^255 = 19 (mod p)`.
+    fn carry(self) -> Self {
+        let mut limbs = self.0;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+        limbs[0] += 19 * (limbs[4] >> 51);
+        limbs[4] &= LIMB_MASK;
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LIMB_MASK;
+        FieldElement(limbs)
+    }
+
+    fn add(self, other: Self) -> Self {
+        let mut limbs = self.0;
+        for (limb, other) in limbs.iter_mut().zip(other.0) {
+            *limb += other;
+        }
+        FieldElement(limbs).carry()
+    }
+
+    fn subtract(self, other: Self) -> Self {
+        // add 2p so that no limb goes negative
+        const TWO_P: [u64; 5] = [
+            0xf_ffff_ffff_ffda,
+            0xf_ffff_ffff_fffe,
+            0xf_ffff_ffff_fffe,
+            0xf_ffff_ffff_fffe,
+            0xf_ffff_ffff_fffe,
+        ];
+        let mut limbs = self.0;
+        for ((limb, other), two_p) in limbs.iter_mut().zip(other.0).zip(TWO_P) {
+            *limb = *limb + two_p - other;
+        }
+        FieldElement(limbs).carry()
+    }
+
+    fn multiply(self, other: Self) -> Self {
+        let (a, b) = (self.0, other.0);
+        // limbs past the top wrap around multiplied by 19
+        let b19 = [b[1] * 19, b[2] * 19, b[3] * 19, b[4] * 19];
+        let product = |x: u64, y: u64| x as u128 * y as u128;
+
+        let wide = [
+            product(a[0], b[0])
+                + product(a[1], b19[3])
+                + product(a[2], b19[2])
+                + product(a[3], b19[1])
+                + product(a[4], b19[0]),
+            product(a[0], b[1])
+                + product(a[1], b[0])
+                + product(a[2], b19[3])
+                + product(a[3], b19[2])
+                + product(a[4], b19[1]),
+            product(a[0], b[2])
+                + product(a[1], b[1])
+                + product(a[2], b[0])
+                + product(a[3], b19[3])
+                + product(a[4], b19[2]),
+            product(a[0], b[3])
+                + product(a[1], b[2])
+                + product(a[2], b[1])
+                + product(a[3], b[0])
+                + product(a[4], b19[3]),
+            product(a[0], b[4])
+                + product(a[1], b[3])
+                + product(a[2], b[2])
+                + product(a[3], b[1])
+                + product(a[4], b[0]),
+        ];
+
+        let mut limbs = [0u64; 5];
+        let mut carry = 0u128;
+        for (limb, wide) in limbs.iter_mut().zip(wide) {
+            let sum = wide + carry;
+            *limb = sum as u64 & LIMB_MASK;
+            carry = sum >> 51;
+        }
+        // the carry out of the top limb is below 2^60, so 19 times it is
+        // folded in as a u128
+        let bottom = limbs[0] as u128 + 19 * carry;
+        limbs[0] = bottom as u64 & LIMB_MASK;
+        limbs[1] += (bottom >> 51) as u64;
+        FieldElement(limbs).carry()
+    }
+
+    fn square(self) -> Self {
+        self.multiply(self)
+    }
+
+    fn multiply_small(self, small: u64) -> Self {
+        self.multiply(FieldElement([small, 0, 0, 0, 0]))
+    }
+
+    /// The inverse by Fermat's little theorem, `self^(p - 2)`; zero maps to
+    /// zero.
+    fn invert(self) -> Self {
+        // p - 2 = 2^255 - 21: bits 0, 1, 3 and 5 to 254 are set
+        let mut result = FieldElement::ONE;
+        for bit in (0..255).rev() {
+            result = result.square();
+            if bit >= 5 || bit == 3 || bit <= 1 {
+                result = result.multiply(self);
+            }
+        }
+        result
+    }
+
+    /// Swaps the elements if `swap` is 1 and leaves them if it is 0.
+    fn conditional_swap(a: &mut Self, b: &mut Self, swap: u64) {
+        let mask = swap.wrapping_neg();
+        for (a, b) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let difference = (*a ^ *b) & mask;
+            *a ^= difference;
+            *b ^= difference;
+        }
+    }
+}
+
+/// Clamps a scalar as RFC 7748 section 5 describes: a multiple of the
+/// cofactor 8, with bit 254 set and bit 255 clear.
+fn clamp(scalar: &[u8; 32]) -> [u8; 32] {
+    let mut clamped = *scalar;
+    clamped[0] &= 248;
+    clamped[31] &= 127;
+    clamped[31] |= 64;
+    clamped
+}
+
+/// The X25519 function: the `u`-coordinate of the clamped `scalar` times
+/// the point with coordinate `u`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::ec::x25519::{BASE_POINT, x25519};
+///
+/// let (alice, bob) = ([1; 32], [2; 32]);
+/// let (alice_public, bob_public) = (x25519(&alice, &BASE_POINT), x25519(&bob, &BASE_POINT));
+/// assert_eq!(x25519(&alice, &bob_public), x25519(&bob, &alice_public));
+/// ```
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let scalar = clamp(scalar);
+    let x1 = FieldElement::from_bytes(u);
+    let (mut x2, mut z2, mut x3, mut z3) =
+        (FieldElement::ONE, FieldElement::ZERO, x1, FieldElement::ONE);
+
+    let mut swap = 0;
+    for bit in (0..255).rev() {
+        let k = ((scalar[bit / 8] >> (bit % 8)) & 1) as u64;
+        swap ^= k;
+        FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+        FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+        swap = k;
+
+        let a = x2.add(z2);
+        let aa = a.square();
+        let b = x2.subtract(z2);
+        let bb = b.square();
+        let e = aa.subtract(bb);
+        let c = x3.add(z3);
+        let d = x3.subtract(z3);
+        let da = d.multiply(a);
+        let cb = c.multiply(b);
+        x3 = da.add(cb).square();
+        z3 = x1.multiply(da.subtract(cb).square());
+        x2 = aa.multiply(bb);
+        z2 = e.multiply(aa.add(e.multiply_small(A24)));
+    }
+    FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+    FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+
+    x2.multiply(z2.invert()).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    fn bytes(hex_string: &str) -> [u8; 32] {
+        hex::decode(hex_string).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rfc7748_vectors() {
+        // section 5.2
+        let vectors = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            ),
+        ];
+        for (scalar, u, expected) in vectors {
+            assert_eq!(x25519(&bytes(scalar), &bytes(u)), bytes(expected));
+        }
+    }
+
+    #[test]
+    fn test_rfc7748_iterations() {
+        // section 5.2: k, u = X25519(k, u), k
+        let (mut k, mut u) = (BASE_POINT, BASE_POINT);
+        for iteration in 1..=1000 {
+            (k, u) = (x25519(&k, &u), k);
+            if iteration == 1 {
+                assert_eq!(
+                    k,
+                    bytes("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            bytes("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+    }
+
+    #[test]
+    #[ignore = "a million ladders take minutes"]
+    fn test_rfc7748_million_iterations() {
+        let (mut k, mut u) = (BASE_POINT, BASE_POINT);
+        for _ in 0..1_000_000 {
+            (k, u) = (x25519(&k, &u), k);
+        }
+        assert_eq!(
+            k,
+            bytes("7c3911e0ab2586fd864497297e575e6f3bc601c0883c30df5f4dd2d24f665424")
+        );
+    }
+
+    #[test]
+    fn test_rfc7748_diffie_hellman() {
+        // section 6.1
+        let alice = bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let alice_public = x25519(&alice, &BASE_POINT);
+        let bob_public = x25519(&bob, &BASE_POINT);
+
+        assert_eq!(
+            alice_public,
+            bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_public,
+            bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+        let shared = bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519(&alice, &bob_public), shared);
+        assert_eq!(x25519(&bob, &alice_public), shared);
+    }
+
+    #[test]
+    fn test_field_encoding_reduces() {
+        // p encodes as zero and p + 1 as one
+        let mut p = [0xff; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(FieldElement::from_bytes(&p).to_bytes(), [0; 32]);
+        p[0] = 0xee;
+        assert_eq!(
+            FieldElement::from_bytes(&p).to_bytes(),
+            FieldElement::ONE.to_bytes()
+        );
+
+        let x = FieldElement::from_bytes(&[0x5a; 32]);
+        assert_eq!(
+            x.multiply(x.invert()).to_bytes(),
+            FieldElement::ONE.to_bytes()
+        );
+    }
+}
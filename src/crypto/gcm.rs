@@ -0,0 +1,315 @@
+//! AES-GCM authenticated encryption.
+//!
+//! This module implements GCM with AES-128 and 96-bit nonces as specified in
+//! [NIST SP 800-38D](https://doi.org/10.6028/NIST.SP.800-38D). The payload is
+//! encrypted in CTR mode from counter block 2, and the tag is GHASH of the
+//! AAD and ciphertext masked with the encryption of counter block 1.
+//!
+//! GHASH evaluates a polynomial at the authentication key `H = E_K(0^128)`,
+//! which is what the nonce-reuse attack in
+//! [`crate::analysis::gcm_forbidden`] exploits.
+
+use crate::crypto::aes::Aes128;
+
+/// Length of the authentication tag appended to the ciphertext.
+pub const TAG_LEN: usize = 16;
+
+/// Multiplies two elements of GF(2^128) as GCM does.
+///
+/// Elements are 16-byte blocks read as big-endian `u128`s. The first bit of
+/// the block, the most significant one, is the coefficient of `x^0`, and the
+/// field is reduced by `x^128 + x^7 + x^2 + x + 1`, as in
+/// [NIST SP 800-38D](https://doi.org/10.6028/NIST.SP.800-38D) Section 6.3.
+pub fn gf128_multiply(a: u128, b: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+
+    let mut product = 0;
+    let mut multiple = b;
+    for index in 0..128 {
+        if a >> (127 - index) & 1 == 1 {
+            product ^= multiple;
+        }
+        // multiply by x, reducing if x^127 overflows
+        multiple = if multiple & 1 == 1 {
+            (multiple >> 1) ^ R
+        } else {
+            multiple >> 1
+        };
+    }
+    product
+}
+
+fn push_padded(blocks: &mut Vec<u128>, bytes: &[u8]) {
+    blocks.extend(bytes.chunks(16).map(|chunk| {
+        let mut block = [0u8; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        u128::from_be_bytes(block)
+    }));
+}
+
+/// The blocks GHASH absorbs: the AAD and the ciphertext, each zero-padded to
+/// whole blocks, then their lengths in bits.
+pub fn ghash_blocks(aad: &[u8], ciphertext: &[u8]) -> Vec<u128> {
+    let mut blocks = Vec::with_capacity(aad.len().div_ceil(16) + ciphertext.len().div_ceil(16) + 1);
+    push_padded(&mut blocks, aad);
+    push_padded(&mut blocks, ciphertext);
+    blocks.push(((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8));
+    blocks
+}
+
+/// GHASH of the AAD and ciphertext under the authentication key `h`.
+///
+/// With blocks `X_1, ..., X_m` from [`ghash_blocks`] this is
+/// `X_1 H^m + X_2 H^(m-1) + ... + X_m H`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::gcm::ghash;
+///
+/// // test case 2 of the GCM specification
+/// let h = 0x66E94BD4EF8A2C3B884CFA59CA342B2E;
+/// let ciphertext = 0x0388DACE60B6A392F328C2B971B2FE78u128.to_be_bytes();
+/// assert_eq!(ghash(h, b"", &ciphertext), 0xF38CBB1AD69223DCC3457AE5B6B0F885);
+/// ```
+pub fn ghash(h: u128, aad: &[u8], ciphertext: &[u8]) -> u128 {
+    ghash_blocks(aad, ciphertext)
+        .into_iter()
+        .fold(0, |acc, block| gf128_multiply(acc ^ block, h))
+}
+
+fn counter_block(nonce: &[u8; 12], counter: u32) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..12].copy_from_slice(nonce);
+    block[12..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
+
+fn apply_keystream(cipher: &Aes128, nonce: &[u8; 12], bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .chunks(16)
+        .zip(2u32..)
+        .flat_map(|(chunk, counter)| {
+            let keystream = cipher.encrypt_block(&counter_block(nonce, counter));
+            chunk
+                .iter()
+                .zip(keystream)
+                .map(|(byte, key)| byte ^ key)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn compute_tag(cipher: &Aes128, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    let h = u128::from_be_bytes(cipher.encrypt_block(&[0; 16]));
+    let mask = u128::from_be_bytes(cipher.encrypt_block(&counter_block(nonce, 1)));
+    (ghash(h, aad, ciphertext) ^ mask).to_be_bytes()
+}
+
+/// Encrypts and authenticates the plaintext, and authenticates the AAD.
+///
+/// Returns `ciphertext || tag`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::gcm::{open, seal};
+///
+/// let sealed = seal(&[1; 16], &[2; 12], b"header", b"secret");
+/// assert_eq!(open(&[1; 16], &[2; 12], b"header", &sealed).unwrap(), b"secret");
+/// assert!(open(&[1; 16], &[2; 12], b"other header", &sealed).is_none());
+/// ```
+///
+/// # Panics
+///
+/// Panics if the plaintext is longer than the 2^32 - 2 blocks one nonce
+/// can encrypt.
+pub fn seal(key: &[u8; 16], nonce: &[u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    seal_truncated(key, nonce, aad, plaintext, TAG_LEN)
+}
+
+/// Verifies and decrypts `ciphertext || tag`.
+///
+/// Returns `None` if the input is shorter than a tag or the tag does not
+/// match; the tag comparison does not exit early.
+pub fn open(key: &[u8; 16], nonce: &[u8; 12], aad: &[u8], sealed: &[u8]) -> Option<Vec<u8>> {
+    open_truncated(key, nonce, aad, sealed, TAG_LEN)
+}
+
+/// Like [`seal`], but keeps only the first `tag_len` bytes of the tag.
+///
+/// SP 800-38D allows tags as short as four bytes for some applications;
+/// [`crate::analysis::gcm_truncated`] shows what that costs.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::gcm::{open_truncated, seal, seal_truncated};
+///
+/// let sealed = seal_truncated(&[1; 16], &[2; 12], b"", b"secret", 4);
+/// assert_eq!(sealed[..], seal(&[1; 16], &[2; 12], b"", b"secret")[..6 + 4]);
+/// assert_eq!(open_truncated(&[1; 16], &[2; 12], b"", &sealed, 4).unwrap(), b"secret");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `tag_len` is 0 or more than [`TAG_LEN`], or if the plaintext is
+/// longer than the 2^32 - 2 blocks one nonce can encrypt.
+pub fn seal_truncated(
+    key: &[u8; 16],
+    nonce: &[u8; 12],
+    aad: &[u8],
+    plaintext: &[u8],
+    tag_len: usize,
+) -> Vec<u8> {
+    assert!(
+        (1..=TAG_LEN).contains(&tag_len),
+        "tag length must be 1 to 16 bytes"
+    );
+    assert!(
+        plaintext.len().div_ceil(16) < u32::MAX as usize - 1,
+        "plaintext fits under one nonce"
+    );
+
+    let cipher = Aes128::new(key);
+    let mut sealed = apply_keystream(&cipher, nonce, plaintext);
+    let tag = compute_tag(&cipher, nonce, aad, &sealed);
+    sealed.extend(&tag[..tag_len]);
+    sealed
+}
+
+/// Like [`open`], for tags truncated to `tag_len` bytes.
+///
+/// # Panics
+///
+/// Panics if `tag_len` is 0 or more than [`TAG_LEN`].
+pub fn open_truncated(
+    key: &[u8; 16],
+    nonce: &[u8; 12],
+    aad: &[u8],
+    sealed: &[u8],
+    tag_len: usize,
+) -> Option<Vec<u8>> {
+    assert!(
+        (1..=TAG_LEN).contains(&tag_len),
+        "tag length must be 1 to 16 bytes"
+    );
+
+    let (ciphertext, tag) = sealed.split_at_checked(sealed.len().checked_sub(tag_len)?)?;
+    if ciphertext.len().div_ceil(16) >= u32::MAX as usize - 1 {
+        return None;
+    }
+
+    let cipher = Aes128::new(key);
+    let expected = compute_tag(&cipher, nonce, aad, ciphertext);
+    let difference = expected
+        .iter()
+        .zip(tag)
+        .fold(0, |acc, (a, b)| acc | (a ^ b));
+
+    (difference == 0).then(|| apply_keystream(&cipher, nonce, ciphertext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+    use proptest::prelude::*;
+
+    fn test_case_4() -> ([u8; 16], [u8; 12], Vec<u8>, Vec<u8>) {
+        let key = hex::decode("feffe9928665731c6d6a8f9467308308")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let nonce = hex::decode("cafebabefacedbaddecaf888")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let plaintext = hex::decode(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        )
+        .unwrap();
+        (key, nonce, aad, plaintext)
+    }
+
+    #[test]
+    fn test_zero_key_vectors() {
+        // test cases 1 and 2 of the GCM specification
+        assert_eq!(
+            hex::encode(&seal(&[0; 16], &[0; 12], b"", b"")).to_lowercase(),
+            "58e2fccefa7e3061367f1d57a4e7455a"
+        );
+        assert_eq!(
+            hex::encode(&seal(&[0; 16], &[0; 12], b"", &[0; 16])).to_lowercase(),
+            "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf"
+        );
+    }
+
+    #[test]
+    fn test_vector_with_aad() {
+        // test case 4 of the GCM specification
+        let (key, nonce, aad, plaintext) = test_case_4();
+        let sealed = seal(&key, &nonce, &aad, &plaintext);
+
+        assert_eq!(
+            hex::encode(&sealed).to_lowercase(),
+            "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+             21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091\
+             5bc94fbc3221a5db94fae95ae7121a47"
+        );
+        assert_eq!(open(&key, &nonce, &aad, &sealed).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_open_rejects_tampering() {
+        let (key, nonce, aad, plaintext) = test_case_4();
+        let sealed = seal(&key, &nonce, &aad, &plaintext);
+
+        let mut flipped_ciphertext = sealed.clone();
+        flipped_ciphertext[0] ^= 1;
+        assert!(open(&key, &nonce, &aad, &flipped_ciphertext).is_none());
+
+        let mut flipped_tag = sealed.clone();
+        *flipped_tag.last_mut().unwrap() ^= 0x80;
+        assert!(open(&key, &nonce, &aad, &flipped_tag).is_none());
+
+        assert!(open(&key, &nonce, b"", &sealed).is_none());
+        assert!(open(&key, &nonce, &aad, &sealed[..TAG_LEN - 1]).is_none());
+    }
+
+    #[test]
+    fn test_truncated_tags() {
+        let (key, nonce, aad, plaintext) = test_case_4();
+        let sealed = seal(&key, &nonce, &aad, &plaintext);
+
+        for tag_len in [4, 8, 12] {
+            let truncated = seal_truncated(&key, &nonce, &aad, &plaintext, tag_len);
+            assert_eq!(truncated[..], sealed[..plaintext.len() + tag_len]);
+            assert_eq!(
+                open_truncated(&key, &nonce, &aad, &truncated, tag_len).unwrap(),
+                plaintext
+            );
+            assert!(open_truncated(&key, &nonce, b"", &truncated, tag_len).is_none());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn gf128_multiply_distributes(a: u128, b: u128, c: u128) {
+            prop_assert_eq!(gf128_multiply(a, b), gf128_multiply(b, a));
+            prop_assert_eq!(
+                gf128_multiply(a, b ^ c),
+                gf128_multiply(a, b) ^ gf128_multiply(a, c)
+            );
+        }
+
+        #[test]
+        fn open_inverts_seal(key: [u8; 16], nonce: [u8; 12], aad in proptest::collection::vec(any::<u8>(), 0..40), plaintext in proptest::collection::vec(any::<u8>(), 0..80)) {
+            let sealed = seal(&key, &nonce, &aad, &plaintext);
+            prop_assert_eq!(sealed.len(), plaintext.len() + TAG_LEN);
+            prop_assert_eq!(open(&key, &nonce, &aad, &sealed), Some(plaintext));
+        }
+    }
+}
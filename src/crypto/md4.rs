@@ -0,0 +1,153 @@
+//! MD4 hash function.
+//!
+//! This module implements MD4 as specified in
+//! [RFC 1320](https://datatracker.ietf.org/doc/html/rfc1320). MD4 is broken,
+//! and the step-by-step view of its compression function is exposed to the
+//! crate for the collision attack.
+
+/// The chaining values `A`, `B`, `C` and `D`.
+pub(crate) const INITIAL_STATE: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+/// Added in every step of the three rounds.
+const ROUND_CONSTANTS: [u32; 3] = [0, 0x5a827999, 0x6ed9eba1];
+
+/// Left rotations of the four steps repeated through each round.
+pub(crate) const SHIFTS: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+
+/// The message word used by each step of each round.
+const WORD_ORDER: [[usize; 16]; 3] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+    [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+];
+
+/// The round 1 function: `y` where `x` is set, `z` elsewhere.
+pub(crate) fn f(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (!x & z)
+}
+
+/// The round 2 function: the majority of each bit.
+fn g(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (x & z) | (y & z)
+}
+
+/// The round 3 function: the parity of each bit.
+fn h(x: u32, y: u32, z: u32) -> u32 {
+    x ^ y ^ z
+}
+
+/// The message words of a block, read little-endian.
+pub(crate) fn words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+    }
+    words
+}
+
+/// The value written by each of the 48 steps, after the four initial
+/// values in the order `A`, `D`, `C`, `B` that the steps update them in.
+///
+/// Step `i` replaces the value four places before it, mixing in the three
+/// values just before it.
+pub(crate) fn step_values(state: &[u32; 4], words: &[u32; 16]) -> [u32; 52] {
+    let [a, b, c, d] = *state;
+    let mut values = [0u32; 52];
+    values[..4].copy_from_slice(&[a, d, c, b]);
+
+    for step in 0..48 {
+        let round = step / 16;
+        let idx = step + 4;
+        let function = [f, g, h][round];
+        let (x, y, z) = (values[idx - 1], values[idx - 2], values[idx - 3]);
+        values[idx] = values[idx - 4]
+            .wrapping_add(function(x, y, z))
+            .wrapping_add(words[WORD_ORDER[round][step % 16]])
+            .wrapping_add(ROUND_CONSTANTS[round])
+            .rotate_left(SHIFTS[round][step % 4]);
+    }
+    values
+}
+
+fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let values = step_values(state, &words(block));
+    // the last values written to A, B, C and D
+    let last = [values[48], values[51], values[50], values[49]];
+    for (word, value) in state.iter_mut().zip(last) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// Computes the MD4 digest of a message.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::md4::md4;
+///
+/// let digest = md4(b"abc");
+/// assert_eq!(digest[..4], [0xA4, 0x48, 0x01, 0x7A]);
+/// ```
+pub fn md4(message: &[u8]) -> [u8; 16] {
+    // pad like SHA-256, but with the length in little-endian bits
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    padded.extend(std::iter::repeat_n(0, (119 - message.len() % 64) % 64));
+    padded.extend((message.len() as u64).wrapping_mul(8).to_le_bytes());
+
+    let mut state = INITIAL_STATE;
+    for block in padded.chunks_exact(64) {
+        compress(&mut state, block);
+    }
+
+    let mut digest = [0u8; 16];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    fn digest_hex(message: &[u8]) -> String {
+        hex::encode(&md4(message)).to_lowercase()
+    }
+
+    #[test]
+    fn test_rfc1320_vectors() {
+        let vectors: [(&[u8], &str); 7] = [
+            (b"", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            (b"a", "bde52cb31de33e46245e05fbdbd6fb24"),
+            (b"abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            (b"message digest", "d9130a8164549fe818874806e1c7014b"),
+            (
+                b"abcdefghijklmnopqrstuvwxyz",
+                "d79e1c308aa5bbcdeea8ed63df412da9",
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "043f8582f241db351ce627e153e7f0e4",
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+        for (message, digest) in vectors {
+            assert_eq!(digest_hex(message), digest);
+        }
+    }
+
+    #[test]
+    fn test_step_values_end_in_the_state() {
+        let block = [0x5Au8; 64];
+        let values = step_values(&INITIAL_STATE, &words(&block));
+        let mut state = INITIAL_STATE;
+        compress(&mut state, &block);
+        assert_eq!(state[0], INITIAL_STATE[0].wrapping_add(values[48]));
+        assert_eq!(state[1], INITIAL_STATE[1].wrapping_add(values[51]));
+    }
+}
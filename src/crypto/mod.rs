@@ -2,4 +2,17 @@
 //!
 //! This module provides implementations of various cryptographic operations.
 
+pub mod aes;
+pub mod chacha20;
+pub mod chacha20_poly1305;
+pub mod ec;
+pub mod gcm;
+pub mod md4;
+pub mod pkcs7;
+pub mod poly1305;
+pub mod rc4;
+pub mod rsa;
+pub mod salsa20;
+pub mod sha256;
+pub mod stream;
 pub mod xor;
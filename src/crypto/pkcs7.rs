@@ -0,0 +1,87 @@
+//! PKCS#7 padding.
+//!
+//! Padding as specified in
+//! [RFC 5652 Section 6.3](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3):
+//! `n` bytes of value `n` are appended, where `n` is between 1 and the block
+//! length, so a full block is added when the input is already aligned.
+
+/// Pads the data to a multiple of `block_len`.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::pkcs7;
+///
+/// assert_eq!(pkcs7::pad(b"YELLOW SUBMARINE", 20), b"YELLOW SUBMARINE\x04\x04\x04\x04");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `block_len` is 0 or above 255.
+pub fn pad(data: &[u8], block_len: usize) -> Vec<u8> {
+    assert!(
+        (1..=255).contains(&block_len),
+        "block length is between 1 and 255"
+    );
+
+    let padding = block_len - data.len() % block_len;
+    let mut padded = data.to_vec();
+    padded.extend(std::iter::repeat_n(padding as u8, padding));
+    padded
+}
+
+/// Strips the padding, or returns `None` if it is malformed or the data is
+/// not a whole number of blocks.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::pkcs7;
+///
+/// assert_eq!(pkcs7::unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16), Some(&b"ICE ICE BABY"[..]));
+/// assert_eq!(pkcs7::unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16), None);
+/// ```
+pub fn unpad(data: &[u8], block_len: usize) -> Option<&[u8]> {
+    if data.is_empty() || block_len == 0 || !data.len().is_multiple_of(block_len) {
+        return None;
+    }
+
+    let padding = *data.last()? as usize;
+    if padding == 0 || padding > block_len {
+        return None;
+    }
+    let (unpadded, tail) = data.split_at(data.len() - padding);
+    tail.iter()
+        .all(|&byte| byte as usize == padding)
+        .then_some(unpadded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_aligned_input_gets_a_full_block() {
+        assert_eq!(pad(b"", 4), [4, 4, 4, 4]);
+        assert_eq!(pad(b"abcd", 4), b"abcd\x04\x04\x04\x04");
+    }
+
+    #[test]
+    fn test_unpad_rejects_malformed_padding() {
+        assert_eq!(unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16), None);
+        assert_eq!(unpad(b"abc\x00", 4), None);
+        assert_eq!(unpad(b"abc\x05", 4), None);
+        assert_eq!(unpad(b"abc\x01", 8), None);
+        assert_eq!(unpad(b"", 4), None);
+    }
+
+    proptest! {
+        #[test]
+        fn unpad_inverts_pad(data in proptest::collection::vec(any::<u8>(), 0..64), block_len in 1usize..=32) {
+            let padded = pad(&data, block_len);
+            prop_assert!(padded.len().is_multiple_of(block_len));
+            prop_assert_eq!(unpad(&padded, block_len), Some(&data[..]));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Poly1305 one-time authenticator.
+//!
+//! This module implements Poly1305 as specified in
+//! [RFC 8439 Section 2.5](https://datatracker.ietf.org/doc/html/rfc8439#section-2.5).
+//! The accumulator is kept in five 26-bit limbs so that products fit in `u64`.
+
+const LIMB_MASK: u64 = 0x3ff_ffff;
+
+fn le32(bytes: &[u8]) -> u64 {
+    u32::from_le_bytes(bytes[..4].try_into().expect("4 bytes")) as u64
+}
+
+/// Computes the 16-byte Poly1305 tag of a message.
+///
+/// The 32-byte key is `r || s` and must never be used for two messages.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::poly1305::poly1305;
+///
+/// let tag = poly1305(&[0; 32], b"any message");
+/// assert_eq!(tag, [0; 16]);
+/// ```
+pub fn poly1305(key: &[u8; 32], message: &[u8]) -> [u8; 16] {
+    // r is clamped as it is split into limbs
+    let r = [
+        le32(&key[0..]) & 0x3ff_ffff,
+        (le32(&key[3..]) >> 2) & 0x3ff_ff03,
+        (le32(&key[6..]) >> 4) & 0x3ff_c0ff,
+        (le32(&key[9..]) >> 6) & 0x3f0_3fff,
+        (le32(&key[12..]) >> 8) & 0x00f_ffff,
+    ];
+    // 2^130 = 5 (mod p), so limbs wrapping past the top are multiplied by 5
+    let s = [r[1] * 5, r[2] * 5, r[3] * 5, r[4] * 5];
+    let mut h = [0u64; 5];
+
+    for chunk in message.chunks(16) {
+        let mut block = [0u8; 17];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()] = 1;
+
+        h[0] += le32(&block[0..]) & LIMB_MASK;
+        h[1] += (le32(&block[3..]) >> 2) & LIMB_MASK;
+        h[2] += (le32(&block[6..]) >> 4) & LIMB_MASK;
+        h[3] += (le32(&block[9..]) >> 6) & LIMB_MASK;
+        h[4] += (le32(&block[12..]) >> 8) | (block[16] as u64) << 24;
+
+        let d = [
+            h[0] * r[0] + h[1] * s[3] + h[2] * s[2] + h[3] * s[1] + h[4] * s[0],
+            h[0] * r[1] + h[1] * r[0] + h[2] * s[3] + h[3] * s[2] + h[4] * s[1],
+            h[0] * r[2] + h[1] * r[1] + h[2] * r[0] + h[3] * s[3] + h[4] * s[2],
+            h[0] * r[3] + h[1] * r[2] + h[2] * r[1] + h[3] * r[0] + h[4] * s[3],
+            h[0] * r[4] + h[1] * r[3] + h[2] * r[2] + h[3] * r[1] + h[4] * r[0],
+        ];
+
+        let mut carry = 0;
+        for (limb, product) in h.iter_mut().zip(d) {
+            let value = product + carry;
+            *limb = value & LIMB_MASK;
+            carry = value >> 26;
+        }
+        h[0] += carry * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= LIMB_MASK;
+    }
+
+    // fully carry h
+    let mut carry = 0;
+    for limb in h.iter_mut().skip(1) {
+        *limb += carry;
+        carry = *limb >> 26;
+        *limb &= LIMB_MASK;
+    }
+    h[0] += carry * 5;
+    h[1] += h[0] >> 26;
+    h[0] &= LIMB_MASK;
+
+    // g = h + 5 - 2^130; keep g if it did not go negative, i.e. h >= p
+    let mut g = [0u64; 5];
+    let mut carry = 5;
+    for (g_limb, h_limb) in g.iter_mut().zip(h) {
+        let value = h_limb + carry;
+        *g_limb = value & LIMB_MASK;
+        carry = value >> 26;
+    }
+    let keep_g = (carry & 1).wrapping_neg();
+    for (h_limb, g_limb) in h.iter_mut().zip(g) {
+        *h_limb = (*h_limb & !keep_g) | (g_limb & keep_g);
+    }
+
+    // repack into 32-bit words and add s, dropping anything past 2^128
+    let words = [
+        h[0] | h[1] << 26,
+        h[1] >> 6 | h[2] << 20,
+        h[2] >> 12 | h[3] << 14,
+        h[3] >> 18 | h[4] << 8,
+    ];
+    let mut tag = [0u8; 16];
+    let mut carry = 0;
+    for (idx, word) in words.into_iter().enumerate() {
+        let value = (word & 0xffff_ffff) + le32(&key[16 + 4 * idx..]) + carry;
+        tag[4 * idx..4 * idx + 4].copy_from_slice(&(value as u32).to_le_bytes());
+        carry = value >> 32;
+    }
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    fn tag_hex(key: &str, message: &str) -> String {
+        let key = hex::decode(key).unwrap().try_into().unwrap();
+        hex::encode(&poly1305(&key, &hex::decode(message).unwrap())).to_lowercase()
+    }
+
+    #[test]
+    fn test_rfc_vector() {
+        // RFC 8439 Section 2.5.2
+        assert_eq!(
+            tag_hex(
+                "85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b",
+                &hex::encode_text("Cryptographic Forum Research Group")
+            ),
+            "a8061dc1305136c6c22b8baf0c0127a9"
+        );
+    }
+
+    #[test]
+    fn test_partial_and_full_blocks() {
+        let vectors = [
+            (
+                "52f22665a60c12d289185d950ee8813609166f6b113d178d6c0fd3901ff239a1",
+                "",
+                "09166f6b113d178d6c0fd3901ff239a1",
+            ),
+            (
+                "a095f20f9395650cf9380b8edb224a6b248a1e924e8fd0ae2e1a9492a3305f18",
+                "8c",
+                "b8fd5d3d27eaf3dbe139f04bd9160d8f",
+            ),
+            (
+                "b610900f9e347fae886dc6507795ec745c4c3fcb2eb2c73e14934c867ee057ba",
+                "72499bfa121e836b2ac15726ee7d6b",
+                "6f216123a9a34ef7eb616d992b17db36",
+            ),
+            (
+                "0af6ab13c38e92cae0d15057b159987f94cc7411d717f14579b2aa100fbbb34f",
+                "a593feaed27248b762e3ab5805f0765a",
+                "ce603d80ad0a944cac9e733ac9139152",
+            ),
+            (
+                "2b9c1d7e0f37c44921bd3f6564eadf7f142a72668c47e223d16edd8c47b46afc",
+                "5baee261f53b26152d263ba83b037cd496",
+                "f65277a0c9539c1d132b4c2ba4529083",
+            ),
+            (
+                "2e434801256b885e9c9051f320b0db83f39ea7adbd0d74e6dec7f3dfaecc8f64",
+                "6566641a7ba2660f3011fc3570291c57990d1a0091268919f25d9d0612df359d\
+                 6026a240f4589a5d791f1dd97cfefa777a7b4f15241abf57bd437ad4b1298405",
+                "3c646795445601a4a64e487848ce4fcc",
+            ),
+        ];
+
+        for (key, message, tag) in vectors {
+            assert_eq!(tag_hex(key, message), tag);
+        }
+    }
+
+    #[test]
+    fn test_final_reduction() {
+        // accumulators at or just above p = 2^130 - 5 must be reduced
+        assert_eq!(
+            tag_hex(
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+                 ffffffffffffffffffffffffffffffff"
+            ),
+            "5efc6a6b51fcec4c787c5075997c95e4"
+        );
+        assert_eq!(
+            tag_hex(
+                "0200000000000000000000000000000000000000000000000000000000000000",
+                "ffffffffffffffffffffffffffffffff"
+            ),
+            "03000000000000000000000000000000"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! RC4 stream cipher.
+//!
+//! This module implements the RC4 key-scheduling algorithm (KSA) and
+//! pseudo-random generation algorithm (PRGA). RC4 is long broken, but its
+//! keystream biases make it a good target for statistical attacks.
+
+use std::iter::zip;
+
+/// RC4 keystream generator.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::rc4::Rc4;
+///
+/// let mut cipher = Rc4::new(b"Key");
+/// let encrypted = cipher.apply_keystream(b"Plaintext");
+/// assert_eq!(encrypted, [0xBB, 0xF3, 0x16, 0xE8, 0xD9, 0x40, 0xAF, 0x0A, 0xD3]);
+/// ```
+#[derive(Clone)]
+pub struct Rc4 {
+    state: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    /// Runs the key-scheduling algorithm for the given key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is empty or longer than 256 bytes.
+    pub fn new(key: &[u8]) -> Self {
+        assert!(
+            !key.is_empty() && key.len() <= 256,
+            "key must be between 1 and 256 bytes"
+        );
+
+        let mut state: [u8; 256] = std::array::from_fn(|idx| idx as u8);
+        let mut j: u8 = 0;
+        for (i, &key_byte) in zip(0..256, key.iter().cycle()) {
+            j = j.wrapping_add(state[i]).wrapping_add(key_byte);
+            state.swap(i, j as usize);
+        }
+
+        Rc4 { state, i: 0, j: 0 }
+    }
+
+    /// Produces the next keystream byte.
+    pub fn next_byte(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.state[self.i as usize]);
+        self.state.swap(self.i as usize, self.j as usize);
+
+        let idx = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
+        self.state[idx as usize]
+    }
+
+    /// Produces the next `len` keystream bytes.
+    pub fn keystream(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_byte()).collect()
+    }
+
+    /// XORs the bytes with the keystream, encrypting or decrypting them.
+    pub fn apply_keystream(&mut self, bytes: &[u8]) -> Vec<u8> {
+        bytes.iter().map(|&byte| byte ^ self.next_byte()).collect()
+    }
+}
+
+/// Encrypts or decrypts the bytes with a fresh RC4 keystream for the key.
+pub fn rc4(bytes: &[u8], key: &[u8]) -> Vec<u8> {
+    Rc4::new(key).apply_keystream(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    #[test]
+    fn test_known_answers() {
+        assert_eq!(
+            hex::encode(&rc4(b"Plaintext", b"Key")),
+            "BBF316E8D940AF0AD3"
+        );
+        assert_eq!(hex::encode(&rc4(b"pedia", b"Wiki")), "1021BF0420");
+        assert_eq!(
+            hex::encode(&rc4(b"Attack at dawn", b"Secret")),
+            "45A01F645FC35B383552544B9BF5"
+        );
+    }
+
+    #[test]
+    fn test_keystream_matches_encryption() {
+        let keystream = Rc4::new(b"Key").keystream(9);
+        let encrypted = rc4(b"Plaintext", b"Key");
+
+        assert_eq!(
+            encrypted,
+            crate::crypto::xor::xor_bytes(b"Plaintext", &keystream)
+        );
+    }
+
+    #[test]
+    fn test_rc4_is_reversible() {
+        let encrypted = rc4(b"I am alpha", b"ICE");
+        assert_eq!(rc4(&encrypted, b"ICE"), b"I am alpha");
+    }
+}
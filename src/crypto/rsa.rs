@@ -0,0 +1,301 @@
+//! RSA signatures with PKCS#1 v1.5 padding.
+//!
+//! Keys and signatures follow [RFC 8017](https://datatracker.ietf.org/doc/html/rfc8017):
+//! a signature is the private-exponent power of the encoded message
+//! `00 01 FF .. FF 00 DigestInfo(SHA-256(m))` (section 9.2), and verification
+//! re-encodes the message and compares the whole block, so the padding
+//! cannot be forged the way a parser that stops at the digest can. The
+//! arithmetic is [`BigUint`], which does not run in constant time.
+//!
+//! Keys convert to and from the PKCS#1 structures of [`der`], which read and
+//! write them as DER and PEM.
+
+use crate::analysis::bignum::BigUint;
+use crate::analysis::number_theory::random_prime;
+use crate::crypto::sha256::sha256;
+use crate::encoding::der::{self, DigestInfo, RsaPrivateKey, RsaPublicKey};
+use crate::encoding::error::{DerError, DerErrorKind};
+use crate::util::SplitMix64;
+
+/// The public exponent of generated keys.
+const PUBLIC_EXPONENT: u64 = 65537;
+
+/// An RSA public key `(n, e)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    pub modulus: BigUint,
+    pub exponent: BigUint,
+}
+
+impl PublicKey {
+    /// The length of the modulus in bytes, which signatures are padded to.
+    pub fn size_in_bytes(&self) -> usize {
+        self.modulus.bits().div_ceil(8) as usize
+    }
+}
+
+impl From<&RsaPublicKey> for PublicKey {
+    fn from(key: &RsaPublicKey) -> Self {
+        PublicKey {
+            modulus: BigUint::from_be_bytes(&key.modulus),
+            exponent: BigUint::from_be_bytes(&key.public_exponent),
+        }
+    }
+}
+
+impl From<&PublicKey> for RsaPublicKey {
+    fn from(key: &PublicKey) -> Self {
+        RsaPublicKey {
+            modulus: key.modulus.to_be_bytes(),
+            public_exponent: key.exponent.to_be_bytes(),
+        }
+    }
+}
+
+/// An RSA key pair.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::rsa::{KeyPair, verify};
+/// use cryptopals::util::SplitMix64;
+///
+/// let keys = KeyPair::generate(512, &mut SplitMix64::new(1));
+/// let signature = keys.sign(b"hi mom").unwrap();
+/// assert!(verify(&keys.public, b"hi mom", &signature));
+/// assert!(!verify(&keys.public, b"hi dad", &signature));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyPair {
+    pub public: PublicKey,
+    private_exponent: BigUint,
+    primes: [BigUint; 2],
+}
+
+impl KeyPair {
+    /// A key pair with a modulus of `bits` bits and `e = 65537`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is below 16.
+    pub fn generate(bits: u64, rng: &mut SplitMix64) -> Self {
+        assert!(bits >= 16, "the modulus needs at least 16 bits");
+        loop {
+            let p = random_prime(bits.div_ceil(2), rng);
+            let q = random_prime(bits / 2, rng);
+            if let Some(keys) = KeyPair::from_primes(&p, &q, BigUint::from(PUBLIC_EXPONENT)) {
+                return keys;
+            }
+        }
+    }
+
+    /// The key pair with modulus `p * q` and public exponent `exponent`, or
+    /// `None` if the primes are equal or the exponent is not invertible
+    /// modulo `lcm(p - 1, q - 1)`.
+    ///
+    /// The primes are not checked for primality.
+    pub fn from_primes(p: &BigUint, q: &BigUint, exponent: BigUint) -> Option<Self> {
+        if p == q {
+            return None;
+        }
+        let one = BigUint::one();
+        let (p1, q1) = (p - &one, q - &one);
+        // the Carmichael function of pq
+        let lambda = &p1 / p1.gcd(&q1) * &q1;
+        let private_exponent = exponent.mod_inverse(&lambda)?;
+        Some(KeyPair {
+            public: PublicKey {
+                modulus: p * q,
+                exponent,
+            },
+            private_exponent,
+            primes: [p.clone(), q.clone()],
+        })
+    }
+
+    /// The signature of `message`, or `None` if the modulus is too short
+    /// for the encoding.
+    pub fn sign(&self, message: &[u8]) -> Option<BigUint> {
+        let encoded = encode(message, self.public.size_in_bytes())?;
+        Some(encoded.pow_mod(&self.private_exponent, &self.public.modulus))
+    }
+}
+
+impl From<&KeyPair> for RsaPrivateKey {
+    fn from(keys: &KeyPair) -> Self {
+        let [p, q] = &keys.primes;
+        let one = BigUint::one();
+        let d = &keys.private_exponent;
+        RsaPrivateKey {
+            modulus: keys.public.modulus.to_be_bytes(),
+            public_exponent: keys.public.exponent.to_be_bytes(),
+            private_exponent: d.to_be_bytes(),
+            prime1: p.to_be_bytes(),
+            prime2: q.to_be_bytes(),
+            exponent1: (d % (p - &one)).to_be_bytes(),
+            exponent2: (d % (q - &one)).to_be_bytes(),
+            coefficient: q
+                .mod_inverse(p)
+                .expect("distinct primes are coprime")
+                .to_be_bytes(),
+        }
+    }
+}
+
+impl TryFrom<&RsaPrivateKey> for KeyPair {
+    type Error = DerError;
+
+    /// The key pair of the key's primes and public exponent, as
+    /// [`KeyPair::from_primes`] builds it; the private exponent and CRT
+    /// values are recomputed rather than read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DerErrorKind::UnexpectedStructure`] if the primes do not
+    /// make up the modulus or [`KeyPair::from_primes`] rejects them.
+    fn try_from(key: &RsaPrivateKey) -> Result<Self, Self::Error> {
+        let p = BigUint::from_be_bytes(&key.prime1);
+        let q = BigUint::from_be_bytes(&key.prime2);
+        let exponent = BigUint::from_be_bytes(&key.public_exponent);
+        KeyPair::from_primes(&p, &q, exponent)
+            .filter(|keys| keys.public.modulus == BigUint::from_be_bytes(&key.modulus))
+            .ok_or(DerError {
+                kind: DerErrorKind::UnexpectedStructure,
+                offset: 0,
+            })
+    }
+}
+
+/// The EMSA-PKCS1-v1_5 encoding of `message` in `length` bytes, as an
+/// integer, or `None` if `length` leaves fewer than eight bytes of padding.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::rsa::encode;
+///
+/// let block = encode(b"hi mom", 64).unwrap().to_be_bytes();
+/// // the leading zero byte is not part of the integer
+/// assert_eq!(&block[..3], &[0x01, 0xff, 0xff]);
+/// assert!(encode(b"hi mom", 61).is_none());
+/// ```
+pub fn encode(message: &[u8], length: usize) -> Option<BigUint> {
+    let digest_info = DigestInfo {
+        algorithm: der::SHA256.to_vec(),
+        digest: sha256(message).to_vec(),
+    }
+    .to_der();
+    let padding = length.checked_sub(digest_info.len() + 3)?;
+    if padding < 8 {
+        return None;
+    }
+
+    let mut block = Vec::with_capacity(length);
+    block.extend_from_slice(&[0x00, 0x01]);
+    block.resize(2 + padding, 0xff);
+    block.push(0x00);
+    block.extend_from_slice(&digest_info);
+    Some(BigUint::from_be_bytes(&block))
+}
+
+/// Whether `signature` is a valid signature of `message` under `public`.
+pub fn verify(public: &PublicKey, message: &[u8], signature: &BigUint) -> bool {
+    if *signature >= public.modulus {
+        return false;
+    }
+    let Some(expected) = encode(message, public.size_in_bytes()) else {
+        return false;
+    };
+    signature.pow_mod(&public.exponent, &public.modulus) == expected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_small_key() {
+        // the textbook example: p = 61, q = 53, e = 17, d = 413 modulo
+        // lcm(60, 52) = 780
+        let keys = KeyPair::from_primes(
+            &BigUint::from(61u64),
+            &BigUint::from(53u64),
+            BigUint::from(17u64),
+        )
+        .unwrap();
+        assert_eq!(keys.public.modulus, BigUint::from(3233u64));
+        assert_eq!(keys.private_exponent, BigUint::from(413u64));
+        // too short for the encoding
+        assert_eq!(keys.sign(b"message"), None);
+
+        assert!(
+            KeyPair::from_primes(
+                &BigUint::from(61u64),
+                &BigUint::from(53u64),
+                BigUint::from(3u64)
+            )
+            .is_none()
+        );
+        assert!(
+            KeyPair::from_primes(
+                &BigUint::from(61u64),
+                &BigUint::from(61u64),
+                BigUint::from(17u64)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_rejects_tampered_signatures() {
+        let keys = KeyPair::generate(512, &mut SplitMix64::new(5));
+        assert_eq!(keys.public.modulus.bits(), 512);
+        let signature = keys.sign(b"message").unwrap();
+
+        assert!(verify(&keys.public, b"message", &signature));
+        assert!(!verify(
+            &keys.public,
+            b"message",
+            &(&signature + BigUint::one())
+        ));
+        assert!(!verify(
+            &keys.public,
+            b"message",
+            &(&signature + &keys.public.modulus)
+        ));
+    }
+
+    #[test]
+    fn test_pkcs1_conversions() {
+        let keys = KeyPair::generate(512, &mut SplitMix64::new(7));
+        let private = RsaPrivateKey::from_pem(&RsaPrivateKey::from(&keys).to_pem()).unwrap();
+        let public = RsaPublicKey::from_pem(&RsaPublicKey::from(&keys.public).to_pem()).unwrap();
+        assert_eq!(private.public_key(), public);
+        assert_eq!(PublicKey::from(&public), keys.public);
+
+        let restored = KeyPair::try_from(&private).unwrap();
+        assert_eq!(restored.public, keys.public);
+        assert_eq!(restored.sign(b"message"), keys.sign(b"message"));
+
+        let mismatched = RsaPrivateKey {
+            modulus: vec![0x01],
+            ..private
+        };
+        assert_eq!(
+            KeyPair::try_from(&mismatched).unwrap_err().kind,
+            DerErrorKind::UnexpectedStructure
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn signatures_verify(seed: u64, message: Vec<u8>) {
+            let keys = KeyPair::generate(512, &mut SplitMix64::new(seed));
+            let signature = keys.sign(&message).unwrap();
+            prop_assert!(verify(&keys.public, &message, &signature));
+        }
+    }
+}
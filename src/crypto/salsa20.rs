@@ -0,0 +1,173 @@
+//! Salsa20 stream cipher.
+//!
+//! This module implements Salsa20/20 with a 256-bit key, as described in
+//! [the Salsa20 specification](https://cr.yp.to/snuffle/spec.pdf): a 64-bit
+//! nonce and an explicit 64-bit block counter.
+
+use crate::crypto::stream::{SeekableStream, xor_with_blocks};
+
+/// "expand 32-byte k" as little-endian words.
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[b] ^= state[a].wrapping_add(state[d]).rotate_left(7);
+    state[c] ^= state[b].wrapping_add(state[a]).rotate_left(9);
+    state[d] ^= state[c].wrapping_add(state[b]).rotate_left(13);
+    state[a] ^= state[d].wrapping_add(state[c]).rotate_left(18);
+}
+
+/// Computes one 64-byte Salsa20 keystream block.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::crypto::salsa20::salsa20_block;
+///
+/// let mut key = [0; 32];
+/// key[0] = 0x80;
+/// let block = salsa20_block(&key, &[0; 8], 0);
+/// assert_eq!(block[..4], [0xE3, 0xBE, 0x8F, 0xDD]);
+/// ```
+pub fn salsa20_block(key: &[u8; 32], nonce: &[u8; 8], counter: u64) -> [u8; 64] {
+    let word = |bytes: &[u8], idx: usize| {
+        u32::from_le_bytes(bytes[4 * idx..4 * idx + 4].try_into().expect("4 bytes"))
+    };
+
+    let initial = [
+        CONSTANTS[0],
+        word(key, 0),
+        word(key, 1),
+        word(key, 2),
+        word(key, 3),
+        CONSTANTS[1],
+        word(nonce, 0),
+        word(nonce, 1),
+        counter as u32,
+        (counter >> 32) as u32,
+        CONSTANTS[2],
+        word(key, 4),
+        word(key, 5),
+        word(key, 6),
+        word(key, 7),
+        CONSTANTS[3],
+    ];
+
+    let mut state = initial;
+    for _ in 0..10 {
+        // column rounds
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 5, 9, 13, 1);
+        quarter_round(&mut state, 10, 14, 2, 6);
+        quarter_round(&mut state, 15, 3, 7, 11);
+        // row rounds
+        quarter_round(&mut state, 0, 1, 2, 3);
+        quarter_round(&mut state, 5, 6, 7, 4);
+        quarter_round(&mut state, 10, 11, 8, 9);
+        quarter_round(&mut state, 15, 12, 13, 14);
+    }
+
+    let mut block = [0u8; 64];
+    for (idx, (word, initial)) in state.iter().zip(initial).enumerate() {
+        block[4 * idx..4 * idx + 4].copy_from_slice(&word.wrapping_add(initial).to_le_bytes());
+    }
+    block
+}
+
+/// Seekable Salsa20 keystream.
+#[derive(Clone)]
+pub struct Salsa20 {
+    key: [u8; 32],
+    nonce: [u8; 8],
+    initial_counter: u64,
+    position: u64,
+}
+
+impl Salsa20 {
+    /// Creates a keystream whose first block uses `initial_counter`.
+    pub fn new(key: &[u8; 32], nonce: &[u8; 8], initial_counter: u64) -> Self {
+        Salsa20 {
+            key: *key,
+            nonce: *nonce,
+            initial_counter,
+            position: 0,
+        }
+    }
+}
+
+impl SeekableStream for Salsa20 {
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn seek(&mut self, offset: u64) {
+        self.position = offset;
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the 64-bit block counter would overflow.
+    fn apply_keystream(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let output = xor_with_blocks(bytes, self.position, |index| {
+            let counter = self
+                .initial_counter
+                .checked_add(index)
+                .expect("block counter does not overflow");
+            salsa20_block(&self.key, &self.nonce, counter)
+        });
+        self.position += bytes.len() as u64;
+        output
+    }
+}
+
+/// Encrypts or decrypts the bytes with Salsa20, starting at block `counter`.
+pub fn salsa20(bytes: &[u8], key: &[u8; 32], nonce: &[u8; 8], counter: u64) -> Vec<u8> {
+    Salsa20::new(key, nonce, counter).apply_keystream(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    #[test]
+    fn test_estream_vector() {
+        // eSTREAM Salsa20/20, 256-bit key, set 1 vector 0
+        let mut key = [0; 32];
+        key[0] = 0x80;
+
+        assert_eq!(
+            hex::encode(&salsa20(&[0; 64], &key, &[0; 8], 0)),
+            "E3BE8FDD8BECA2E3EA8EF9475B29A6E7003951E1097A5C38D23B7A5FAD9F6844\
+             B22C97559E2723C7CBBD3FE4FC8D9A0744652A83E72A9C461876AF4D7EF1A117"
+        );
+    }
+
+    #[test]
+    fn test_block_counter() {
+        let key = std::array::from_fn(|idx| idx as u8);
+        let nonce = std::array::from_fn(|idx| idx as u8);
+
+        assert_eq!(
+            hex::encode(&salsa20_block(&key, &nonce, 1)).to_lowercase(),
+            "a13a2b59d9047b8dbeb93ec4b78ce1a59bc210641318ccce694d30ff81d2afe7\
+             bdf5a3d58cb4f9a4ed5247823e14618f06dc61cfe4b8e2ba836783b280efedca"
+        );
+        // starting at counter 0 and seeking one block ahead is the same block
+        let mut cipher = Salsa20::new(&key, &nonce, 0);
+        cipher.seek(64);
+        assert_eq!(
+            cipher.apply_keystream(&[0; 64]),
+            salsa20_block(&key, &nonce, 1)
+        );
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut cipher = Salsa20::new(&[5; 32], &[6; 8], 0);
+        let ciphertext = cipher.apply_keystream(b"YELLOW SUBMARINE");
+
+        let edited = cipher.edit(&ciphertext, 7, b"SUBMERSIBLE");
+        cipher.seek(0);
+        assert_eq!(cipher.apply_keystream(&edited), b"YELLOW SUBMERSIBLE");
+    }
+}
@@ -0,0 +1,121 @@
+//! Seekable stream ciphers.
+//!
+//! Stream ciphers built from a block function (ChaCha20, Salsa20) or from a
+//! block cipher in CTR mode (AES-CTR) can jump to any offset of their
+//! keystream. That makes random-access "edit" operations cheap, and also
+//! makes reusing a key and nonce fatal.
+
+/// A keystream that can be positioned at any byte offset.
+pub trait SeekableStream {
+    /// Current byte offset into the keystream.
+    fn position(&self) -> u64;
+
+    /// Moves the keystream to the given byte offset.
+    fn seek(&mut self, offset: u64);
+
+    /// XORs the bytes with the keystream from the current position,
+    /// encrypting or decrypting them, and advances the position.
+    fn apply_keystream(&mut self, bytes: &[u8]) -> Vec<u8>;
+
+    /// Replaces the plaintext under `ciphertext[offset..]` with `newtext`.
+    ///
+    /// Returns the edited ciphertext, which grows if `newtext` runs past the
+    /// end. The keystream is left positioned after the edit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is past the end of the ciphertext.
+    fn edit(&mut self, ciphertext: &[u8], offset: usize, newtext: &[u8]) -> Vec<u8> {
+        assert!(offset <= ciphertext.len(), "offset is past the ciphertext");
+
+        self.seek(offset as u64);
+        let encrypted = self.apply_keystream(newtext);
+
+        let mut edited = ciphertext.to_vec();
+        let end = (offset + newtext.len()).min(ciphertext.len());
+        edited.splice(offset..end, encrypted);
+        edited
+    }
+}
+
+/// XORs the bytes with a keystream made of `N`-byte blocks, starting at
+/// byte `position`. `block` produces the keystream block with the given
+/// index.
+pub(crate) fn xor_with_blocks<const N: usize>(
+    bytes: &[u8],
+    position: u64,
+    block: impl Fn(u64) -> [u8; N],
+) -> Vec<u8> {
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut remaining = bytes;
+    let mut position = position;
+
+    while !remaining.is_empty() {
+        let keystream = block(position / N as u64);
+        let start = (position % N as u64) as usize;
+        let take = remaining.len().min(N - start);
+
+        output.extend(
+            remaining[..take]
+                .iter()
+                .zip(&keystream[start..start + take])
+                .map(|(a, b)| a ^ b),
+        );
+        remaining = &remaining[take..];
+        position += take as u64;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keystream whose every byte is its own offset, for checking alignment.
+    struct Counting {
+        position: u64,
+    }
+
+    impl SeekableStream for Counting {
+        fn position(&self) -> u64 {
+            self.position
+        }
+
+        fn seek(&mut self, offset: u64) {
+            self.position = offset;
+        }
+
+        fn apply_keystream(&mut self, bytes: &[u8]) -> Vec<u8> {
+            let output = xor_with_blocks::<64>(bytes, self.position, |index| {
+                std::array::from_fn(|idx| (index * 64 + idx as u64) as u8)
+            });
+            self.position += bytes.len() as u64;
+            output
+        }
+    }
+
+    #[test]
+    fn test_xor_with_blocks_crosses_block_boundaries() {
+        let keystream = xor_with_blocks(&[0; 100], 60, |index| [index as u8; 64]);
+
+        assert_eq!(keystream[..4], [0; 4]);
+        assert_eq!(keystream[4..68], [1; 64]);
+        assert_eq!(keystream[68..], [2; 32]);
+
+        let keystream = xor_with_blocks(&[0; 20], 14, |index| [index as u8; 16]);
+        assert_eq!(keystream, [&[0; 2][..], &[1; 16], &[2; 2]].concat());
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut stream = Counting { position: 0 };
+        let ciphertext = stream.apply_keystream(b"hello world");
+
+        let edited = stream.edit(&ciphertext, 6, b"there!");
+        assert_eq!(stream.position(), 12);
+
+        stream.seek(0);
+        assert_eq!(stream.apply_keystream(&edited), b"hello there!");
+    }
+}
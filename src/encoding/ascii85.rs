@@ -0,0 +1,208 @@
+//! Ascii85 encoding, as used by PostScript, PDF and `btoa`.
+//!
+//! Every 4 bytes are read as a big-endian `u32` and written as 5 base-85
+//! digits in the characters `!` to `u`. A group of four zero bytes is
+//! shortened to `z`, and a final group of `n` bytes is written as `n + 1`
+//! characters. The `<~` and `~>` delimiters of the Adobe variant are not
+//! written or expected.
+
+use crate::encoding::codec::Codec;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+
+/// Splits a group into its 5 base-85 digits, most significant first.
+pub(crate) fn to_digits(value: u32) -> [u8; 5] {
+    let mut digits = [0u8; 5];
+    let mut value = value;
+    for digit in digits.iter_mut().rev() {
+        *digit = (value % 85) as u8;
+        value /= 85;
+    }
+    digits
+}
+
+/// Joins 5 base-85 digits into a group, if the value fits in a `u32`.
+pub(crate) fn from_digits(digits: &[u8; 5]) -> Option<u32> {
+    let value = digits
+        .iter()
+        .fold(0u64, |acc, &digit| acc * 85 + digit as u64);
+    u32::try_from(value).ok()
+}
+
+/// Encodes a byte slice into an Ascii85 string.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::ascii85::encode;
+///
+/// assert_eq!(encode(b"Man is distinguished"), "9jqo^BlbD-BleB1DJ+*+F(f,q");
+/// assert_eq!(encode(&[0, 0, 0, 0, b'a']), "z@/");
+/// ```
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(4) * 5);
+    for chunk in bytes.chunks(4) {
+        if chunk == [0; 4] {
+            encoded.push('z');
+            continue;
+        }
+
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let digits = to_digits(u32::from_be_bytes(group));
+        encoded.extend(
+            digits[..chunk.len() + 1]
+                .iter()
+                .map(|&digit| (b'!' + digit) as char),
+        );
+    }
+    encoded
+}
+
+/// Decodes an Ascii85 string into bytes, skipping whitespace.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::ascii85::decode;
+///
+/// assert_eq!(decode("9jqo^BlbD-\nBleB1DJ+*+F(f,q").unwrap(), b"Man is distinguished");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ErrorKind::InvalidCharacter`] for a character outside `!` to
+/// `u`, a `z` inside a group, or the last character of a group whose value
+/// does not fit in 32 bits, and [`ErrorKind::InvalidLength`] if the input
+/// ends with a lone character.
+pub fn decode(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    let error = |kind| {
+        ParsingError::new(
+            ParsingDirection::Decoding,
+            Encoding::Ascii85,
+            kind,
+            encoded.to_owned(),
+        )
+    };
+
+    let mut bytes = Vec::with_capacity(encoded.len() / 5 * 4 + 4);
+    let mut digits = [0u8; 5];
+    let mut group_len = 0;
+    let mut significant = 0;
+    let mut last = (0, '!');
+
+    for (offset, char) in encoded.char_indices() {
+        if char.is_whitespace() {
+            continue;
+        }
+        significant += 1;
+
+        match char {
+            'z' if group_len == 0 => bytes.extend([0; 4]),
+            '!'..='u' => {
+                digits[group_len] = char as u8 - b'!';
+                group_len += 1;
+                last = (offset, char);
+
+                if group_len == 5 {
+                    let value = from_digits(&digits)
+                        .ok_or_else(|| error(ErrorKind::InvalidCharacter { offset, char }))?;
+                    bytes.extend(value.to_be_bytes());
+                    group_len = 0;
+                }
+            }
+            _ => return Err(error(ErrorKind::InvalidCharacter { offset, char })),
+        }
+    }
+
+    match group_len {
+        0 => {}
+        1 => {
+            return Err(error(ErrorKind::InvalidLength {
+                length: significant,
+            }));
+        }
+        _ => {
+            // pad with the highest digit so that truncation rounds back down
+            digits[group_len..].fill(84);
+            let (offset, char) = last;
+            let value = from_digits(&digits)
+                .ok_or_else(|| error(ErrorKind::InvalidCharacter { offset, char }))?;
+            bytes.extend_from_slice(&value.to_be_bytes()[..group_len - 1]);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Ascii85 as a [`Codec`].
+#[derive(Debug, Clone, Copy)]
+pub struct Ascii85;
+
+impl Codec for Ascii85 {
+    fn encoding(&self) -> Encoding {
+        Encoding::Ascii85
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        matches!(char, '!'..='u' | 'z')
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        Ok(encode(bytes))
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        decode(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn valid_ascii85_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..200)) {
+            assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        let vectors: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (b"\x00", "!!"),
+            (b"\xff", "rr"),
+            (b"\xff\xff\xff", "s8W*"),
+            (b"\xff\xff\xff\xff", "s8W-!"),
+            (b"\x00\x00\x00\x00abc", "z@:E^"),
+            (b"Hello, World!", "87cURD_*#4DfTZ)+T"),
+        ];
+
+        for (bytes, encoded) in vectors {
+            assert_eq!(encode(bytes), encoded);
+            assert_eq!(decode(encoded).unwrap(), bytes);
+        }
+        // a partial zero group is not shortened
+        assert_eq!(encode(&[0; 3]), "!!!!");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode("87cU~").unwrap_err().kind,
+            ErrorKind::InvalidCharacter {
+                offset: 4,
+                char: '~'
+            }
+        );
+        // z only stands for a whole group
+        assert_eq!(decode("87z").unwrap_err().kind.offset(), Some(2));
+        // one more than u32::MAX
+        assert_eq!(decode("s8W-\"").unwrap_err().kind.offset(), Some(4));
+        assert_eq!(
+            decode("87cURD").unwrap_err().kind,
+            ErrorKind::InvalidLength { length: 6 }
+        );
+    }
+}
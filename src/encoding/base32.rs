@@ -0,0 +1,274 @@
+//! Base32 encoding utilities following RFC 4648.
+//!
+//! This module implements Base32 as specified in
+//! [RFC 4648 Section 6](https://datatracker.ietf.org/doc/html/rfc4648#section-6),
+//! and the "base32hex" variant with the extended hex alphabet from
+//! [Section 7](https://datatracker.ietf.org/doc/html/rfc4648#section-7), which
+//! preserves the sort order of the encoded data. Output is always padded to a
+//! multiple of 8 characters.
+
+use crate::encoding::codec::Codec;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+use itertools::Itertools;
+use std::sync::LazyLock;
+
+/// Base32 character set: A-Z, 2-7
+static BASE32_CHARSET: LazyLock<[char; 32]> = LazyLock::new(|| {
+    ('A'..='Z')
+        .chain('2'..='7')
+        .collect::<Vec<char>>()
+        .try_into()
+        .expect("total count of characters is 32")
+});
+
+/// Extended hex Base32 character set: 0-9, A-V
+static BASE32_HEX_CHARSET: LazyLock<[char; 32]> = LazyLock::new(|| {
+    ('0'..='9')
+        .chain('A'..='V')
+        .collect::<Vec<char>>()
+        .try_into()
+        .expect("total count of characters is 32")
+});
+
+/// Encodes 1-5 bytes into 8 characters, padding with `=`.
+fn encode_quintet(charset: &[char; 32], bytes: &[u8], encoded: &mut String) {
+    debug_assert!((1..=5).contains(&bytes.len()));
+    let mut block = [0u8; 8];
+    block[3..3 + bytes.len()].copy_from_slice(bytes);
+    let value = u64::from_be_bytes(block);
+
+    let data_chars = (bytes.len() * 8).div_ceil(5);
+    for idx in 0..data_chars {
+        encoded.push(charset[(value >> (35 - 5 * idx)) as usize & 0b1_1111]);
+    }
+    encoded.extend(std::iter::repeat_n('=', 8 - data_chars));
+}
+
+fn encode_with(charset: &[char; 32], bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for chunk in bytes.chunks(5) {
+        encode_quintet(charset, chunk, &mut encoded);
+    }
+    encoded
+}
+
+fn decode_with(
+    charset: &[char; 32],
+    encoding: Encoding,
+    encoded: &str,
+) -> Result<Vec<u8>, ParsingError> {
+    let error = |kind| {
+        ParsingError::new(
+            ParsingDirection::Decoding,
+            encoding,
+            kind,
+            encoded.to_owned(),
+        )
+    };
+
+    let chars = encoded.char_indices().collect_vec();
+    let padding_len = chars.iter().rev().take_while(|(_, ch)| *ch == '=').count();
+    let data = &chars[..chars.len() - padding_len];
+
+    let values = data
+        .iter()
+        .map(|&(offset, char)| {
+            charset
+                .iter()
+                .position(|&ch| ch == char)
+                .map(|idx| idx as u8)
+                .ok_or_else(|| error(ErrorKind::InvalidCharacter { offset, char }))
+        })
+        .collect::<Result<Vec<u8>, ParsingError>>()?;
+
+    // 1, 3 and 6 characters leave a partial byte that no encoder writes
+    if matches!(values.len() % 8, 1 | 3 | 6) {
+        return Err(error(ErrorKind::InvalidLength {
+            length: values.len(),
+        }));
+    }
+    if padding_len != (8 - values.len() % 8) % 8 {
+        let offset = chars
+            .get(data.len())
+            .map_or(encoded.len(), |&(offset, _)| offset);
+        return Err(error(ErrorKind::InvalidPadding { offset }));
+    }
+
+    let mut bytes = Vec::with_capacity(values.len() * 5 / 8);
+    for (chunk, chars) in values.chunks(8).zip(data.chunks(8)) {
+        let value = chunk
+            .iter()
+            .fold(0u64, |acc, &value| acc << 5 | value as u64)
+            << (5 * (8 - chunk.len()));
+        let byte_count = chunk.len() * 5 / 8;
+
+        // the bits of the last character past the final byte must be zero
+        if value & ((1 << (40 - 8 * byte_count)) - 1) != 0 {
+            let (offset, char) = chars[chars.len() - 1];
+            return Err(error(ErrorKind::NonCanonicalTrailingBits { offset, char }));
+        }
+        bytes.extend_from_slice(&value.to_be_bytes()[3..3 + byte_count]);
+    }
+    Ok(bytes)
+}
+
+/// Encodes a byte slice into a padded Base32 string.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base32::encode;
+///
+/// assert_eq!(encode(b"foobar"), "MZXW6YTBOI======");
+/// ```
+pub fn encode(bytes: &[u8]) -> String {
+    encode_with(&BASE32_CHARSET, bytes)
+}
+
+/// Decodes a padded Base32 string into bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base32::decode;
+///
+/// assert_eq!(decode("MZXW6YTBOI======").unwrap(), b"foobar");
+/// assert_eq!(decode("MZXW6YTBOI").unwrap_err().kind.offset(), Some(10));
+/// ```
+///
+/// # Errors
+///
+/// Returns the same error kinds as [`base64::Engine::decode`](crate::encoding::base64::Engine::decode).
+pub fn decode(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    decode_with(&BASE32_CHARSET, Encoding::Base32, encoded)
+}
+
+/// Encodes a byte slice into a padded base32hex string.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base32::encode_hex;
+///
+/// assert_eq!(encode_hex(b"foobar"), "CPNMUOJ1E8======");
+/// ```
+pub fn encode_hex(bytes: &[u8]) -> String {
+    encode_with(&BASE32_HEX_CHARSET, bytes)
+}
+
+/// Decodes a padded base32hex string into bytes.
+///
+/// # Errors
+///
+/// Returns the same error kinds as [`decode`].
+pub fn decode_hex(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    decode_with(&BASE32_HEX_CHARSET, Encoding::Base32Hex, encoded)
+}
+
+/// Base32 as a [`Codec`].
+#[derive(Debug, Clone, Copy)]
+pub struct Base32;
+
+impl Codec for Base32 {
+    fn encoding(&self) -> Encoding {
+        Encoding::Base32
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        char == '=' || BASE32_CHARSET.contains(&char)
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        Ok(encode(bytes))
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        decode(encoded)
+    }
+}
+
+/// base32hex as a [`Codec`].
+#[derive(Debug, Clone, Copy)]
+pub struct Base32Hex;
+
+impl Codec for Base32Hex {
+    fn encoding(&self) -> Encoding {
+        Encoding::Base32Hex
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        char == '=' || BASE32_HEX_CHARSET.contains(&char)
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        Ok(encode_hex(bytes))
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        decode_hex(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn valid_base32_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..200)) {
+            assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+            assert_eq!(decode_hex(&encode_hex(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_rfc_4648_vectors() {
+        let vectors = [
+            ("", "", ""),
+            ("f", "MY======", "CO======"),
+            ("fo", "MZXQ====", "CPNG===="),
+            ("foo", "MZXW6===", "CPNMU==="),
+            ("foob", "MZXW6YQ=", "CPNMUOG="),
+            ("fooba", "MZXW6YTB", "CPNMUOJ1"),
+            ("foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+        ];
+
+        for (plain, base32, base32hex) in vectors {
+            assert_eq!(encode(plain.as_bytes()), base32);
+            assert_eq!(encode_hex(plain.as_bytes()), base32hex);
+            assert_eq!(decode(base32).unwrap(), plain.as_bytes());
+            assert_eq!(decode_hex(base32hex).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode("MZXW1===").unwrap_err().kind,
+            ErrorKind::InvalidCharacter {
+                offset: 4,
+                char: '1'
+            }
+        );
+        // lowercase is not part of the alphabet
+        assert_eq!(decode("My======").unwrap_err().kind.offset(), Some(1));
+        assert_eq!(
+            decode("MZX=====").unwrap_err().kind,
+            ErrorKind::InvalidLength { length: 3 }
+        );
+        assert_eq!(
+            decode("MZXQ===").unwrap_err().kind,
+            ErrorKind::InvalidPadding { offset: 4 }
+        );
+        // "MZ" would decode to "f" as well if trailing bits were ignored
+        assert_eq!(
+            decode("MZ======").unwrap_err().kind,
+            ErrorKind::NonCanonicalTrailingBits {
+                offset: 1,
+                char: 'Z'
+            }
+        );
+        assert_eq!(decode_hex("MY======").unwrap_err().kind.offset(), Some(1));
+    }
+}
@@ -0,0 +1,257 @@
+//! Base58 and Base58Check encoding, as used by Bitcoin.
+//!
+//! Base58 treats the input as one big-endian number and writes it in base 58,
+//! using an alphabet without the look-alike characters `0`, `O`, `I` and `l`.
+//! Each leading zero byte is written as a leading `1`.
+//!
+//! Base58Check appends the first four bytes of the double SHA-256 of the
+//! payload as a checksum before encoding.
+
+use crate::crypto::sha256::sha256;
+use crate::encoding::codec::Codec;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+use std::sync::LazyLock;
+
+/// Base58 character set: 1-9, A-Z and a-z without 0, O, I and l
+static BASE58_CHARSET: LazyLock<[char; 58]> = LazyLock::new(|| {
+    "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+        .chars()
+        .collect::<Vec<char>>()
+        .try_into()
+        .expect("total count of characters is 58")
+});
+
+/// Number of checksum bytes appended by Base58Check.
+const CHECKSUM_LEN: usize = 4;
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    sha256(&sha256(payload))[..CHECKSUM_LEN]
+        .try_into()
+        .expect("digest is longer than the checksum")
+}
+
+/// Encodes a byte slice into a Base58 string.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base58::encode;
+///
+/// assert_eq!(encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+/// assert_eq!(encode(&[0, 0, 1]), "112");
+/// ```
+pub fn encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+
+    // base 58 digits of the number, least significant first
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&digit| BASE58_CHARSET[digit as usize]),
+        )
+        .collect()
+}
+
+/// Decodes a Base58 string into bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base58::decode;
+///
+/// assert_eq!(decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+/// assert_eq!(decode("2NEpo0").unwrap_err().kind.offset(), Some(5));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ErrorKind::InvalidCharacter`] for the first character outside
+/// the alphabet.
+pub fn decode(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    let ones = encoded.chars().take_while(|&ch| ch == '1').count();
+
+    // bytes of the number, least significant first
+    let mut bytes: Vec<u8> = Vec::with_capacity(encoded.len() * 733 / 1000 + 1);
+    for (offset, char) in encoded.char_indices() {
+        let value = BASE58_CHARSET
+            .iter()
+            .position(|&ch| ch == char)
+            .ok_or_else(|| {
+                ParsingError::new(
+                    ParsingDirection::Decoding,
+                    Encoding::Base58,
+                    ErrorKind::InvalidCharacter { offset, char },
+                    encoded.to_owned(),
+                )
+            })?;
+
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    Ok(std::iter::repeat_n(0, ones)
+        .chain(bytes.into_iter().rev())
+        .collect())
+}
+
+/// Encodes a payload with a Base58Check checksum.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base58::encode_check;
+///
+/// // a version byte and an all-zero public key hash: the "burn" address
+/// assert_eq!(encode_check(&[0; 21]), "1111111111111111111114oLvT2");
+/// ```
+pub fn encode_check(payload: &[u8]) -> String {
+    let mut bytes = payload.to_vec();
+    bytes.extend(checksum(payload));
+    encode(&bytes)
+}
+
+/// Decodes a Base58Check string and verifies its checksum.
+///
+/// # Errors
+///
+/// Returns the errors of [`decode`], [`ErrorKind::InvalidLength`] if the
+/// input is too short to hold a checksum, or [`ErrorKind::InvalidChecksum`]
+/// if the checksum does not match.
+pub fn decode_check(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    let error = |kind| {
+        ParsingError::new(
+            ParsingDirection::Decoding,
+            Encoding::Base58,
+            kind,
+            encoded.to_owned(),
+        )
+    };
+
+    let mut payload = decode(encoded)?;
+    let Some(checksum_start) = payload.len().checked_sub(CHECKSUM_LEN) else {
+        return Err(error(ErrorKind::InvalidLength {
+            length: encoded.chars().count(),
+        }));
+    };
+
+    let expected = payload.split_off(checksum_start);
+    if checksum(&payload) != expected[..] {
+        return Err(error(ErrorKind::InvalidChecksum));
+    }
+    Ok(payload)
+}
+
+/// Base58 without a checksum as a [`Codec`].
+#[derive(Debug, Clone, Copy)]
+pub struct Base58;
+
+impl Codec for Base58 {
+    fn encoding(&self) -> Encoding {
+        Encoding::Base58
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        BASE58_CHARSET.contains(&char)
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        Ok(encode(bytes))
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        decode(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn valid_base58_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..100)) {
+            assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+            assert_eq!(decode_check(&encode_check(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        let vectors = [
+            ("", ""),
+            ("61", "2g"),
+            ("626262", "a3gV"),
+            ("636363", "aPEr"),
+            ("00000000000000000000", "1111111111"),
+            (
+                "00eb15231dfceb60925886b67d065299925915aeb172c06647",
+                "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+            ),
+            ("516b6fcd0f", "ABnLTmg"),
+            ("bf4f89001e670274dd", "3SEo3LWLoPntC"),
+            ("572e4794", "3EFU7m"),
+            ("ecac89cad93923c02321", "EJDM8drfXA6uyA"),
+        ];
+
+        for (bytes, encoded) in vectors {
+            let bytes = hex::decode(bytes).unwrap();
+            assert_eq!(encode(&bytes), encoded);
+            assert_eq!(decode(encoded).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_check_address() {
+        let payload = hex::decode("00f54a5851e9372b87810a8e60cdd2e7cfd80b6e31").unwrap();
+        let address = "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs";
+
+        assert_eq!(encode_check(&payload), address);
+        assert_eq!(decode_check(address).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        // the look-alike characters are not in the alphabet
+        for (encoded, offset) in [("0abc", 0), ("abOc", 2), ("abcI", 3), ("l", 0)] {
+            assert_eq!(decode(encoded).unwrap_err().kind.offset(), Some(offset));
+        }
+
+        // a typo in the address breaks the checksum
+        assert_eq!(
+            decode_check("1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAt")
+                .unwrap_err()
+                .kind,
+            ErrorKind::InvalidChecksum
+        );
+        assert_eq!(
+            decode_check("2g").unwrap_err().kind,
+            ErrorKind::InvalidLength { length: 2 }
+        );
+    }
+}
@@ -1,68 +1,644 @@
 //! Base64 encoding utilities following RFC 4648.
 //!
 //! This module implements Base64 encoding as specified in
-//! [RFC 4648 Section 4](https://datatracker.ietf.org/doc/html/rfc4648#section-4).
+//! [RFC 4648 Section 4](https://datatracker.ietf.org/doc/html/rfc4648#section-4),
+//! along with the common variants:
+//!
+//! - the URL- and filename-safe alphabet ([RFC 4648 Section 5](https://datatracker.ietf.org/doc/html/rfc4648#section-5)),
+//!   used by JWTs and cookies
+//! - unpadded and lenient-padding modes
+//! - MIME line wrapping ([RFC 2045 Section 6.8](https://datatracker.ietf.org/doc/html/rfc2045#section-6.8)),
+//!   used by PEM bodies and e-mail
+//!
+//! [`encode`] and [`decode`] use the standard alphabet with mandatory padding;
+//! the other variants are available through an [`Engine`]. [`Encoder`] and
+//! [`Decoder`] do the same over [`Write`] and [`Read`] streams.
 
-use crate::encoding::error::{Encoding, ParsingDirection, ParsingError};
+use crate::encoding::codec::Codec;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+use crate::encoding::{simd, stream};
 use itertools::Itertools;
-use std::sync::LazyLock;
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
 
-/// Base64 character set: A-Z, a-z, 0-9, +, /
-static BASE64_CHARSET: LazyLock<[char; 64]> = LazyLock::new(|| {
-    ('A'..='Z')
-        .chain('a'..='z')
-        .chain('0'..='9')
-        .chain(['+', '/'])
-        .collect::<Vec<char>>()
-        .try_into()
-        .expect("total count of characters is 64")
-});
+/// The 64 characters used to represent sextets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// `+` and `/` for values 62 and 63.
+    Standard,
+    /// `-` and `_` for values 62 and 63.
+    UrlSafe,
+}
 
-/// Converts a 6-bit value to its Base64 character representation.
-///
-/// # Panics
-///
-/// Panics if the value is greater than 63 (not representable in 6 bits).
-#[inline]
-fn encode_sextet(value: u8) -> char {
-    debug_assert!(value < 64, "value must be 6-bit (< 64)");
-    BASE64_CHARSET[value as usize]
+impl Alphabet {
+    /// The character of every sextet.
+    fn table(&self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard => STANDARD_TABLE,
+            Alphabet::UrlSafe => URL_SAFE_TABLE,
+        }
+    }
+
+    /// The sextet of every character, or [`INVALID`].
+    fn decode_table(&self) -> &'static [u8; 256] {
+        match self {
+            Alphabet::Standard => &STANDARD_DECODE_TABLE,
+            Alphabet::UrlSafe => &URL_SAFE_DECODE_TABLE,
+        }
+    }
+}
+
+const STANDARD_TABLE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_TABLE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Marks characters outside the alphabet in a decode table. Sextets never
+/// set its top bit.
+const INVALID: u8 = 0xFF;
+
+const fn decode_table(table: &[u8; 64]) -> [u8; 256] {
+    let mut decode = [INVALID; 256];
+    let mut sextet = 0;
+    while sextet < 64 {
+        decode[table[sextet] as usize] = sextet as u8;
+        sextet += 1;
+    }
+    decode
+}
+
+const STANDARD_DECODE_TABLE: [u8; 256] = decode_table(STANDARD_TABLE);
+const URL_SAFE_DECODE_TABLE: [u8; 256] = decode_table(URL_SAFE_TABLE);
+
+/// How the trailing `=` padding is written and checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// Padding is written, and decoding requires it.
+    Required,
+    /// Padding is not written, and decoding rejects it.
+    Omitted,
+    /// Padding is written, and decoding accepts input with or without it.
+    Lenient,
 }
 
-/// Encodes 1-3 bytes into Base64 characters.
+/// Line wrapping for the encoded output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineWrap {
+    /// Maximum number of characters per line.
+    pub width: usize,
+    /// Written between lines, never after the last one.
+    pub separator: &'static str,
+}
+
+/// A Base64 configuration: alphabet, padding and optional line wrapping.
 ///
-/// According to RFC 4648, Base64 encoding takes groups of 3 bytes (24 bits)
-/// and splits them into 4 groups of 6 bits, each encoded as a character.
+/// # Examples
 ///
-/// # Encoding Process
+/// ```
+/// use cryptopals::encoding::base64::URL_SAFE_NO_PAD;
 ///
-/// ```text
-/// 3 bytes:  [AAAAAAAA] [BBBBBBBB] [CCCCCCCC]
-///           ↓
-/// 4 sextets: [AAAAAA][AABBBBBB][BBBBCCCC][CCCCCC]
+/// // the header of a JWT
+/// let header = URL_SAFE_NO_PAD.decode("eyJhbGciOiJIUzI1NiJ9").unwrap();
+/// assert_eq!(header, br#"{"alg":"HS256"}"#);
+/// assert_eq!(URL_SAFE_NO_PAD.encode(&[0xFB, 0xFF]), "-_8");
 /// ```
-fn encode_triplet(byte_a: u8, byte_b: Option<u8>, byte_c: Option<u8>) -> Vec<char> {
-    let sextet_1 = byte_a >> 2;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Engine {
+    pub alphabet: Alphabet,
+    pub padding: Padding,
+    /// When set, encoding wraps lines and decoding skips CR and LF.
+    pub line_wrap: Option<LineWrap>,
+    /// When set, decoding skips ASCII whitespace.
+    pub ignore_whitespace: bool,
+}
+
+/// Standard alphabet with mandatory padding (RFC 4648 Section 4).
+pub const STANDARD: Engine = Engine::new(Alphabet::Standard, Padding::Required);
 
-    let sextet_2 = (byte_a & 0b0000_0011) << 4 | (byte_b.unwrap_or(0) & 0b1111_0000) >> 4;
+/// Standard alphabet without padding.
+pub const STANDARD_NO_PAD: Engine = Engine::new(Alphabet::Standard, Padding::Omitted);
 
-    let mut result = vec![encode_sextet(sextet_1), encode_sextet(sextet_2)];
+/// URL-safe alphabet with mandatory padding (RFC 4648 Section 5).
+pub const URL_SAFE: Engine = Engine::new(Alphabet::UrlSafe, Padding::Required);
 
-    if byte_b.is_none() {
-        return result;
+/// URL-safe alphabet without padding, as used by JWTs.
+pub const URL_SAFE_NO_PAD: Engine = Engine::new(Alphabet::UrlSafe, Padding::Omitted);
+
+/// Standard alphabet wrapped at 76 characters with CRLF (RFC 2045).
+pub const MIME: Engine = STANDARD.with_line_wrap(76, "\r\n");
+
+impl Engine {
+    pub const fn new(alphabet: Alphabet, padding: Padding) -> Self {
+        Engine {
+            alphabet,
+            padding,
+            line_wrap: None,
+            ignore_whitespace: false,
+        }
+    }
+
+    /// Returns the same engine, wrapping encoded lines at `width` characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub const fn with_line_wrap(self, width: usize, separator: &'static str) -> Self {
+        assert!(width > 0, "line width must be positive");
+        Engine {
+            line_wrap: Some(LineWrap { width, separator }),
+            ..self
+        }
     }
 
-    let sextet_3 = (byte_b.unwrap() & 0b0000_1111) << 2 | (byte_c.unwrap_or(0) & 0b1100_0000) >> 6;
-    result.push(encode_sextet(sextet_3));
+    /// Encodes a byte slice into a Base64 string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::{MIME, URL_SAFE};
+    ///
+    /// assert_eq!(URL_SAFE.encode(&[0xFB, 0xFF]), "-_8=");
+    /// assert_eq!(MIME.encode(&[0; 60]).split("\r\n").count(), 2);
+    /// ```
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let mut encoded = vec![0; self.encoded_len(bytes.len())];
+        self.encode_to_slice(bytes, &mut encoded);
+        String::from_utf8(encoded).expect("Base64 characters and separators are UTF-8")
+    }
+
+    /// Wraps encoded output into lines, if the engine wraps.
+    fn wrap(&self, encoded: String) -> String {
+        match self.line_wrap {
+            None => encoded,
+            Some(LineWrap { width, separator }) => encoded
+                .chars()
+                .chunks(width)
+                .into_iter()
+                .map(String::from_iter)
+                .join(separator),
+        }
+    }
 
-    if byte_c.is_none() {
-        return result;
+    /// Encodes a byte slice like [`Engine::encode`], in time that depends
+    /// only on its length.
+    ///
+    /// Characters are computed with arithmetic on masks instead of table
+    /// lookups, for keys and other secrets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::{MIME, URL_SAFE};
+    ///
+    /// assert_eq!(URL_SAFE.encode_constant_time(&[0xFB, 0xFF]), "-_8=");
+    /// assert_eq!(MIME.encode_constant_time(&[7; 100]), MIME.encode(&[7; 100]));
+    /// ```
+    pub fn encode_constant_time(&self, bytes: &[u8]) -> String {
+        self.encode_constant_time_with(bytes, encode_sextet_constant_time)
     }
 
-    let sextet_4 = byte_c.unwrap() & 0b0011_1111;
-    result.push(encode_sextet(sextet_4));
+    /// [`Engine::encode_constant_time`] with the sextet encoder passed in,
+    /// so that tests can count the characters it is called on.
+    fn encode_constant_time_with(
+        &self,
+        bytes: &[u8],
+        mut encode_sextet: impl FnMut(Alphabet, u8) -> u8,
+    ) -> String {
+        let mut encoded = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let sextets = split_triplet(chunk);
+            encoded.extend(
+                sextets[..chunk.len() + 1]
+                    .iter()
+                    .map(|&sextet| encode_sextet(self.alphabet, sextet)),
+            );
+        }
+        if self.padding != Padding::Omitted {
+            encoded.resize(bytes.len().div_ceil(3) * 4, b'=');
+        }
 
-    result
+        let encoded = String::from_utf8(encoded).expect("Base64 characters are ASCII");
+        self.wrap(encoded)
+    }
+
+    /// Encodes complete triplets without line wrapping into `out`, which is
+    /// resized to fit so that callers can reuse it.
+    fn encode_unwrapped(&self, bytes: &[u8], out: &mut Vec<u8>) {
+        debug_assert!(bytes.len().is_multiple_of(3));
+        let unwrapped = Engine {
+            line_wrap: None,
+            ..*self
+        };
+        out.resize(unwrapped.encoded_len(bytes.len()), 0);
+        unwrapped.encode_to_slice(bytes, out);
+    }
+
+    /// Returns the same engine, skipping ASCII whitespace while decoding.
+    pub const fn ignoring_whitespace(self) -> Self {
+        Engine {
+            ignore_whitespace: true,
+            ..self
+        }
+    }
+
+    /// Decodes a Base64 string into bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::{Alphabet, Engine, Padding};
+    ///
+    /// let lenient = Engine::new(Alphabet::Standard, Padding::Lenient);
+    /// assert_eq!(lenient.decode("SGk=").unwrap(), b"Hi");
+    /// assert_eq!(lenient.decode("SGk").unwrap(), b"Hi");
+    ///
+    /// let error = lenient.decode("SG!=").unwrap_err();
+    /// assert_eq!(error.kind.offset(), Some(2));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns, in order of precedence:
+    ///
+    /// - [`ErrorKind::InvalidCharacter`] for the first character outside the
+    ///   alphabet, including `=` before the end
+    /// - [`ErrorKind::InvalidLength`] if the data ends with a lone character
+    /// - [`ErrorKind::InvalidPadding`] if the padding does not match the
+    ///   engine's padding mode
+    /// - [`ErrorKind::NonCanonicalTrailingBits`] if the last character sets
+    ///   bits past the end of the data
+    pub fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        let mut decoded = vec![0; encoded.len() * 3 / 4];
+        let len = self.decode_to_slice(encoded, &mut decoded)?;
+        decoded.truncate(len);
+        Ok(decoded)
+    }
+
+    /// Decodes a character at a time, finding the first error that
+    /// [`Engine::decode`] reports.
+    fn decode_by_char(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        let chars = encoded
+            .char_indices()
+            .filter(|&(_, ch)| !self.is_skipped(ch));
+
+        self.decode_chars(chars, encoded.len()).map_err(|kind| {
+            ParsingError::new(
+                ParsingDirection::Decoding,
+                Encoding::Base64,
+                kind,
+                encoded.to_owned(),
+            )
+        })
+    }
+
+    /// Decodes significant `(offset, char)` pairs, where `end` is the offset
+    /// just past the input.
+    ///
+    /// Bytes are written as each quartet completes, so nothing but the output
+    /// grows with the input.
+    fn decode_chars(
+        &self,
+        chars: impl Iterator<Item = (usize, char)>,
+        end: usize,
+    ) -> Result<Vec<u8>, ErrorKind> {
+        let table = self.alphabet.decode_table();
+        let mut bytes = Vec::with_capacity(end / 4 * 3 + 2);
+        let mut quartet = [0; 4];
+        let mut data_len = 0;
+        // the offset of the first `=` and the number of `=` seen
+        let mut padding: Option<(usize, usize)> = None;
+        let mut last = None;
+
+        for (offset, char) in chars {
+            if char == '=' {
+                padding = Some(padding.map_or((offset, 1), |(first, len)| (first, len + 1)));
+                continue;
+            }
+            // data after padding makes the first `=` the culprit
+            if let Some((first, _)) = padding {
+                return Err(ErrorKind::InvalidCharacter {
+                    offset: first,
+                    char: '=',
+                });
+            }
+
+            let sextet = u8::try_from(char).map_or(INVALID, |byte| table[byte as usize]);
+            if sextet == INVALID {
+                return Err(ErrorKind::InvalidCharacter { offset, char });
+            }
+            quartet[data_len % 4] = sextet;
+            data_len += 1;
+            if data_len % 4 == 0 {
+                decode_quatret(&quartet, &mut bytes);
+            }
+            last = Some((offset, char, sextet));
+        }
+
+        // a single character carries only 6 bits, so it can never end the data
+        if data_len % 4 == 1 {
+            return Err(ErrorKind::InvalidLength { length: data_len });
+        }
+
+        let padding_len = padding.map_or(0, |(_, len)| len);
+        if !self.accepts_padding(data_len + padding_len, padding_len) {
+            let offset = padding.map_or(end, |(first, _)| first);
+            return Err(ErrorKind::InvalidPadding { offset });
+        }
+
+        // the bits of the last character past the final byte must be zero
+        let unused_bits = match data_len % 4 {
+            2 => 0b1111,
+            3 => 0b11,
+            _ => 0,
+        };
+        if let Some((offset, char, sextet)) = last
+            && sextet & unused_bits != 0
+        {
+            return Err(ErrorKind::NonCanonicalTrailingBits { offset, char });
+        }
+
+        if data_len % 4 != 0 {
+            decode_quatret(&quartet[..data_len % 4], &mut bytes);
+        }
+        Ok(bytes)
+    }
+
+    /// Length of the encoding of `len` bytes, padding and line breaks included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::{MIME, STANDARD, STANDARD_NO_PAD};
+    ///
+    /// assert_eq!(STANDARD.encoded_len(4), 8);
+    /// assert_eq!(STANDARD_NO_PAD.encoded_len(4), 6);
+    /// assert_eq!(MIME.encoded_len(60), 80 + 2);
+    /// ```
+    pub fn encoded_len(&self, len: usize) -> usize {
+        let unwrapped = match self.padding {
+            Padding::Omitted => (len * 4).div_ceil(3),
+            Padding::Required | Padding::Lenient => len.div_ceil(3) * 4,
+        };
+        match self.line_wrap {
+            None => unwrapped,
+            Some(LineWrap { width, separator }) => {
+                unwrapped + unwrapped.div_ceil(width).saturating_sub(1) * separator.len()
+            }
+        }
+    }
+
+    /// Encodes bytes into a caller-provided buffer, like [`Engine::encode`]
+    /// without allocating.
+    ///
+    /// Characters are looked up in a table, or encoded 12 or 24 bytes at a
+    /// time with SSSE3 or AVX2 when the CPU supports them. Returns the
+    /// number of bytes written, [`Engine::encoded_len`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::STANDARD;
+    ///
+    /// let mut out = [0; 8];
+    /// assert_eq!(STANDARD.encode_to_slice(b"Hello", &mut out), 8);
+    /// assert_eq!(&out, b"SGVsbG8=");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` holds fewer than [`Engine::encoded_len`] bytes.
+    pub fn encode_to_slice(&self, bytes: &[u8], out: &mut [u8]) -> usize {
+        let len = self.encoded_len(bytes.len());
+        assert!(
+            out.len() >= len,
+            "output buffer must hold the encoded length"
+        );
+        let out = &mut out[..len];
+        let table = self.alphabet.table();
+
+        let done = simd::base64_encode(bytes, out, table[62], table[63]);
+        let mut position = done / 3 * 4;
+        for chunk in bytes[done..].chunks(3) {
+            let sextets = split_triplet(chunk);
+            for &sextet in &sextets[..chunk.len() + 1] {
+                out[position] = table[sextet as usize];
+                position += 1;
+            }
+        }
+        if self.padding != Padding::Omitted {
+            out[position..bytes.len().div_ceil(3) * 4].fill(b'=');
+            position = bytes.len().div_ceil(3) * 4;
+        }
+
+        // spread the lines out from the last one, so that none is
+        // overwritten before it has moved
+        if let Some(LineWrap { width, separator }) = self.line_wrap {
+            let stride = width + separator.len();
+            for line in (1..position.div_ceil(width)).rev() {
+                let start = line * width;
+                out.copy_within(start..position.min(start + width), line * stride);
+                out[line * stride - separator.len()..line * stride]
+                    .copy_from_slice(separator.as_bytes());
+            }
+        }
+        len
+    }
+
+    /// Decodes Base64 into a caller-provided buffer, like [`Engine::decode`]
+    /// without allocating.
+    ///
+    /// Characters are looked up in a table, or, for the standard alphabet,
+    /// decoded 16 or 32 at a time with SSSE3 or AVX2 when the CPU supports
+    /// them. Returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::MIME;
+    ///
+    /// let mut out = [0; 8];
+    /// assert_eq!(MIME.decode_to_slice("SGVs\r\nbG8=", &mut out).unwrap(), 5);
+    /// assert_eq!(&out[..5], b"Hello");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Engine::decode`]. Part of `out` may have
+    /// been written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` holds fewer than `encoded.len() * 3 / 4` bytes.
+    pub fn decode_to_slice(&self, encoded: &str, out: &mut [u8]) -> Result<usize, ParsingError> {
+        assert!(
+            out.len() >= encoded.len() * 3 / 4,
+            "output buffer must hold three bytes per four characters"
+        );
+
+        let significant = if self.line_wrap.is_some() || self.ignore_whitespace {
+            Cow::Owned(
+                encoded
+                    .bytes()
+                    .filter(|&byte| !self.is_skipped(byte as char))
+                    .collect(),
+            )
+        } else {
+            Cow::Borrowed(encoded.as_bytes())
+        };
+
+        match self.decode_significant(&significant, out) {
+            Some(written) => Ok(written),
+            // decode again to find the error
+            None => {
+                let decoded = self.decode_by_char(encoded)?;
+                out[..decoded.len()].copy_from_slice(&decoded);
+                Ok(decoded.len())
+            }
+        }
+    }
+
+    /// Decodes significant characters with the table backends, or returns
+    /// `None` if they are not valid.
+    fn decode_significant(&self, encoded: &[u8], out: &mut [u8]) -> Option<usize> {
+        let padding_len = encoded
+            .iter()
+            .rev()
+            .take_while(|&&byte| byte == b'=')
+            .count();
+        if !self.accepts_padding(encoded.len(), padding_len) {
+            return None;
+        }
+        let data = &encoded[..encoded.len() - padding_len];
+        if data.len() % 4 == 1 {
+            return None;
+        }
+        let out = &mut out[..data.len() * 3 / 4];
+        let table = self.alphabet.decode_table();
+
+        let done = match self.alphabet {
+            Alphabet::Standard => simd::base64_decode(data, out),
+            Alphabet::UrlSafe => 0,
+        };
+        let mut merged = 0;
+        let mut trailing_bits = 0;
+        let mut position = done / 4 * 3;
+        for chunk in data[done..].chunks(4) {
+            let mut sextets = [0u8; 4];
+            for (sextet, &char) in sextets.iter_mut().zip(chunk) {
+                *sextet = table[char as usize];
+                merged |= *sextet;
+            }
+            let bytes = [
+                sextets[0] << 2 | sextets[1] >> 4,
+                sextets[1] << 4 | sextets[2] >> 2,
+                sextets[2] << 6 | sextets[3],
+            ];
+            out[position..position + chunk.len() - 1].copy_from_slice(&bytes[..chunk.len() - 1]);
+            position += chunk.len() - 1;
+
+            // the bits of the last character past the final byte must be zero
+            let unused_bits = match chunk.len() {
+                2 => 0b1111,
+                3 => 0b11,
+                _ => 0,
+            };
+            trailing_bits |= sextets[chunk.len() - 1] & unused_bits;
+        }
+
+        // only an invalid character sets the top bit
+        (merged & 0x80 == 0 && trailing_bits == 0).then_some(position)
+    }
+
+    /// Whether `padding_len` trailing `=` fit the padding mode, out of
+    /// `len` significant characters.
+    fn accepts_padding(&self, len: usize, padding_len: usize) -> bool {
+        let padding_is_valid = match self.padding {
+            Padding::Required => len.is_multiple_of(4),
+            Padding::Omitted => padding_len == 0,
+            Padding::Lenient => padding_len == 0 || len.is_multiple_of(4),
+        };
+        padding_is_valid && padding_len <= 2
+    }
+
+    /// Decodes a Base64 string like [`Engine::decode`], in time that depends
+    /// only on its length and padding.
+    ///
+    /// Sextets are computed with arithmetic on masks instead of table
+    /// lookups, and validity is only checked once the whole input has been
+    /// decoded. The padding is not treated as secret, since the length of
+    /// the output reveals it anyway. Line breaks and whitespace are never
+    /// skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cryptopals::encoding::base64::STANDARD;
+    ///
+    /// assert_eq!(STANDARD.decode_constant_time("SGVsbG8=").unwrap(), b"Hello");
+    /// assert_eq!(STANDARD.decode_constant_time("SG!sbG8=").unwrap_err().kind.offset(), Some(2));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Engine::decode`] for an engine that
+    /// skips nothing. Rejected input is decoded again with [`Engine::decode`]
+    /// to find the error, which does not run in constant time.
+    pub fn decode_constant_time(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        self.decode_constant_time_with(encoded, decode_sextet_constant_time)
+    }
+
+    /// [`Engine::decode_constant_time`] with the sextet decoder passed in,
+    /// so that tests can count the characters it is called on.
+    fn decode_constant_time_with(
+        &self,
+        encoded: &str,
+        mut decode_sextet: impl FnMut(Alphabet, u8) -> (u8, u8),
+    ) -> Result<Vec<u8>, ParsingError> {
+        let bytes = encoded.as_bytes();
+        let padding_len = bytes.iter().rev().take_while(|&&byte| byte == b'=').count();
+        let data = &bytes[..bytes.len() - padding_len];
+
+        let mut invalid = 0u8;
+        let sextets = data
+            .iter()
+            .map(|&char| {
+                let (sextet, valid) = decode_sextet(self.alphabet, char);
+                invalid |= !valid;
+                sextet
+            })
+            .collect_vec();
+
+        // the bits of the last character past the final byte must be zero
+        let unused_bits = match sextets.len() % 4 {
+            2 => 0b1111,
+            3 => 0b11,
+            _ => 0,
+        };
+        invalid |= sextets.last().map_or(0, |last| last & unused_bits);
+
+        if invalid != 0 || sextets.len() % 4 == 1 || !self.accepts_padding(bytes.len(), padding_len)
+        {
+            let strict = Engine {
+                line_wrap: None,
+                ignore_whitespace: false,
+                ..*self
+            };
+            return Err(strict
+                .decode(encoded)
+                .expect_err("constant-time decoding only rejects what decode rejects"));
+        }
+        let mut decoded = Vec::with_capacity(sextets.len() / 4 * 3 + 2);
+        for quartet in sextets.chunks(4) {
+            decode_quatret(quartet, &mut decoded);
+        }
+        Ok(decoded)
+    }
+
+    /// Whether the decoder drops this character before decoding.
+    fn is_skipped(&self, ch: char) -> bool {
+        (self.ignore_whitespace && ch.is_ascii_whitespace())
+            || (self.line_wrap.is_some() && (ch == '\r' || ch == '\n'))
+    }
 }
 
 /// Encodes a byte slice into a Base64 string.
@@ -81,112 +657,616 @@ fn encode_triplet(byte_a: u8, byte_b: Option<u8>, byte_c: Option<u8>) -> Vec<cha
 /// assert_eq!(encoded, "SGk=");
 /// ```
 pub fn encode(bytes: &[u8]) -> String {
-    // Process complete triplets (groups of 3 bytes)
-    let complete_triplets: String = bytes
-        .chunks_exact(3)
-        .flat_map(|chunk| encode_triplet(chunk[0], Some(chunk[1]), Some(chunk[2])))
-        .collect();
-
-    // Handle remaining bytes (0, 1, or 2 bytes)
-    let remainder = bytes.len() % 3;
-    let padding = match remainder {
-        0 => String::new(),
-        1 => {
-            // 1 byte → 2 Base64 chars + 2 padding chars
-            let mut chars = encode_triplet(bytes[bytes.len() - 1], None, None);
-            chars.extend(['=', '=']);
-            chars.into_iter().collect()
-        }
-        2 => {
-            // 2 bytes → 3 Base64 chars + 1 padding char
-            let mut chars =
-                encode_triplet(bytes[bytes.len() - 2], Some(bytes[bytes.len() - 1]), None);
-            chars.push('=');
-            chars.into_iter().collect()
-        }
-        _ => unreachable!(),
-    };
+    STANDARD.encode(bytes)
+}
 
-    format!("{}{}", complete_triplets, padding)
+/// Splits 1-3 bytes into the sextets of a full quartet, zero-filled.
+fn split_triplet(chunk: &[u8]) -> [u8; 4] {
+    let mut triplet = [0u8; 3];
+    triplet[..chunk.len()].copy_from_slice(chunk);
+    [
+        triplet[0] >> 2,
+        (triplet[0] & 0b11) << 4 | triplet[1] >> 4,
+        (triplet[1] & 0b1111) << 2 | triplet[2] >> 6,
+        triplet[2] & 0b11_1111,
+    ]
 }
 
-/// Converts a base64 representation to its u6 value, '=' is returned as value 65
-#[inline]
-fn decode_sextet(value: char) -> u8 {
-    if value == '=' {
-        return 65;
-    }
+/// The character for a sextet, without data-dependent branches or lookups.
+fn encode_sextet_constant_time(alphabet: Alphabet, sextet: u8) -> u8 {
+    // what to subtract past 61 and add past 62 to reach the last two characters
+    let (to_62, to_63) = match alphabet {
+        Alphabet::Standard => (15, 3),
+        Alphabet::UrlSafe => (13, 49),
+    };
 
-    BASE64_CHARSET
-        .iter()
-        .find_position(|&ch| ch.eq(&value))
-        .map(|(idx, _)| idx as u8)
-        .expect("value is a base64 character")
+    // each `(bound - sextet) >> 8` is all ones when the sextet is past `bound`
+    let sextet = sextet as i32;
+    let mut offset = b'A' as i32;
+    offset += ((25 - sextet) >> 8) & 6;
+    offset -= ((51 - sextet) >> 8) & 75;
+    offset -= ((61 - sextet) >> 8) & to_62;
+    offset += ((62 - sextet) >> 8) & to_63;
+    (sextet + offset) as u8
 }
 
-fn decode_quatret(encoded: &[char; 4]) -> Result<Vec<u8>, ParsingError> {
-    let chars: [Option<u8>; 4] = encoded
-        .iter()
-        .map(|&char| {
-            if char.eq(&'=') {
-                None
-            } else {
-                Some(decode_sextet(char))
-            }
-        })
-        .collect::<Vec<Option<u8>>>()
-        .try_into()
-        .expect("no filtering was done");
+/// The sextet of a character and a mask that is `0xFF` if the character is
+/// in the alphabet, without data-dependent branches or lookups.
+fn decode_sextet_constant_time(alphabet: Alphabet, char: u8) -> (u8, u8) {
+    let (char_62, char_63) = match alphabet {
+        Alphabet::Standard => (b'+' as i32, b'/' as i32),
+        Alphabet::UrlSafe => (b'-' as i32, b'_' as i32),
+    };
 
-    if chars[0].is_none() && chars[1].is_none() {
-        return Err(ParsingError::from_string(
-            ParsingDirection::Decoding,
-            Encoding::Base64,
-            String::from_iter(encoded.iter()),
-        ));
-    }
+    // `((low - char) & (char - high)) >> 8` is all ones for `low < char < high`;
+    // every range adds its sextet plus one, so nothing matched leaves -1
+    let char = char as i32;
+    let mut sextet = -1;
+    sextet += (((b'A' as i32 - 1 - char) & (char - b'Z' as i32 - 1)) >> 8) & (char - 64);
+    sextet += (((b'a' as i32 - 1 - char) & (char - b'z' as i32 - 1)) >> 8) & (char - 70);
+    sextet += (((b'0' as i32 - 1 - char) & (char - b'9' as i32 - 1)) >> 8) & (char + 5);
+    sextet += (((char_62 - 1 - char) & (char - char_62 - 1)) >> 8) & 63;
+    sextet += (((char_63 - 1 - char) & (char - char_63 - 1)) >> 8) & 64;
+    (sextet as u8, !(sextet >> 8) as u8)
+}
 
-    let (first_sextet, second_sextet) = (chars[0].unwrap(), chars[1].unwrap());
+/// Decodes 2-4 sextets into 1-3 bytes, appended to `out`.
+fn decode_quatret(sextets: &[u8], out: &mut Vec<u8>) {
+    debug_assert!((2..=4).contains(&sextets.len()));
+    let (first_sextet, second_sextet) = (sextets[0], sextets[1]);
 
     let first_byte = first_sextet << 2 | (second_sextet & 0b00110000) >> 4;
-    if chars[2].is_none() {
-        return Ok(vec![first_byte]);
+    if sextets.len() == 2 {
+        out.push(first_byte);
+        return;
     }
 
-    let third_sextet = chars[2].unwrap();
+    let third_sextet = sextets[2];
     let second_byte = (second_sextet & 0b00001111) << 4 | (third_sextet & 0b111100) >> 2;
-    if chars[3].is_none() {
-        return Ok(vec![first_byte, second_byte]);
+    if sextets.len() == 3 {
+        out.extend([first_byte, second_byte]);
+        return;
     }
 
-    let fourth_sextet = chars[3].unwrap();
+    let fourth_sextet = sextets[3];
     let third_byte = (third_sextet & 0b11) << 6 | fourth_sextet;
-    Ok(vec![first_byte, second_byte, third_byte])
+    out.extend([first_byte, second_byte, third_byte]);
 }
 
+/// Decodes a standard, padded Base64 string into bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base64::decode;
+///
+/// assert_eq!(decode("SGVsbG8=").unwrap(), b"Hello");
+/// ```
 pub fn decode(encoded: &str) -> Result<Vec<u8>, ParsingError> {
-    if encoded.len() % 4 != 0 {
-        return Err(ParsingError::from_string(
-            ParsingDirection::Decoding,
-            Encoding::Base64,
-            encoded.to_owned(),
-        ));
-    }
-
-    encoded
-        .chars()
-        .chunks(4)
-        .into_iter()
-        .try_fold(Vec::new(), |mut acc, chunk| {
-            let decoded = decode_quatret(&chunk.collect_array::<4>().unwrap())?;
-            acc.extend(decoded);
-            Ok(acc)
-        })
+    STANDARD.decode(encoded)
+}
+
+/// Decodes a standard, padded Base64 string, skipping ASCII whitespace.
+///
+/// Challenge files wrap their Base64 at 60 columns; this decodes them as-is.
+/// Offsets in errors still refer to the original input.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::base64::decode_ignoring_whitespace;
+///
+/// assert_eq!(decode_ignoring_whitespace("SGVs\nbG8=\n").unwrap(), b"Hello");
+/// ```
+pub fn decode_ignoring_whitespace(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    STANDARD.ignoring_whitespace().decode(encoded)
+}
+
+/// Bytes read from the wrapped reader, or encoded per write, at a time.
+const STREAM_CHUNK_SIZE: usize = 8192;
+
+fn invalid_data(error: ParsingError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Streaming Base64 encoder over a [`Write`] sink.
+///
+/// Up to two bytes that don't complete a triplet are held back between
+/// writes, so [`Encoder::finish`] must be called to write the final quartet
+/// and its padding, and to learn whether that write failed.
+///
+/// Dropping an unfinished encoder writes the final quartet on a best-effort
+/// basis only: errors are ignored, and nothing is written while the thread
+/// is panicking. Output that matters should go through `finish`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use cryptopals::encoding::base64::{Encoder, STANDARD};
+///
+/// let mut encoder = Encoder::new(Vec::new(), STANDARD);
+/// encoder.write_all(b"Hel").unwrap();
+/// encoder.write_all(b"lo").unwrap();
+/// assert_eq!(encoder.finish().unwrap(), b"SGVsbG8=");
+/// ```
+pub struct Encoder<W: Write> {
+    inner: Option<W>,
+    engine: Engine,
+    /// Bytes of an incomplete triplet.
+    pending: Vec<u8>,
+    /// Characters encoded by the last write, kept for the next one.
+    encoded: Vec<u8>,
+    /// Characters written on the current line.
+    column: usize,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(inner: W, engine: Engine) -> Self {
+        Encoder {
+            inner: Some(inner),
+            engine,
+            pending: Vec::with_capacity(3),
+            encoded: Vec::new(),
+            column: 0,
+        }
+    }
+
+    /// Writes the held-back bytes with padding and returns the wrapped writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing the final quartet.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_final_quartet()?;
+        Ok(self.inner.take().expect("encoder is not finished"))
+    }
+
+    fn write_final_quartet(&mut self) -> io::Result<()> {
+        let unwrapped = Engine {
+            line_wrap: None,
+            ..self.engine
+        };
+        let encoded = unwrapped.encode(&self.pending);
+        self.pending.clear();
+        self.write_wrapped(encoded.as_bytes())
+    }
+
+    /// Writes encoded characters, inserting separators as lines fill up.
+    fn write_wrapped(&mut self, encoded: &[u8]) -> io::Result<()> {
+        let inner = self.inner.as_mut().expect("encoder is not finished");
+        let Some(LineWrap { width, separator }) = self.engine.line_wrap else {
+            return inner.write_all(encoded);
+        };
+
+        let mut remaining = encoded;
+        while !remaining.is_empty() {
+            if self.column == width {
+                inner.write_all(separator.as_bytes())?;
+                self.column = 0;
+            }
+            let (line, rest) = remaining.split_at(remaining.len().min(width - self.column));
+            inner.write_all(line)?;
+            self.column += line.len();
+            remaining = rest;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let accepted = buf.len().min(STREAM_CHUNK_SIZE);
+        self.pending.extend_from_slice(&buf[..accepted]);
+
+        let complete = self.pending.len() - self.pending.len() % 3;
+        let mut encoded = std::mem::take(&mut self.encoded);
+        self.engine
+            .encode_unwrapped(&self.pending[..complete], &mut encoded);
+        self.pending.drain(..complete);
+        let written = self.write_wrapped(&encoded);
+        self.encoded = encoded;
+        written?;
+        Ok(accepted)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner
+            .as_mut()
+            .expect("encoder is not finished")
+            .flush()
+    }
+}
+
+impl<W: Write> Drop for Encoder<W> {
+    /// Finishes the encoding if [`Encoder::finish`] was not called, ignoring
+    /// errors. A panicking thread may have left the writer in a bad state,
+    /// so nothing is written then.
+    fn drop(&mut self) {
+        if self.inner.is_some() && !std::thread::panicking() {
+            let _ = self.write_final_quartet();
+        }
+    }
+}
+
+/// Streaming Base64 decoder over a [`Read`] source.
+///
+/// Quartets may be split across reads of the wrapped reader. The last
+/// quartet is held back until the end of the stream, where its padding is
+/// checked against the engine. Invalid input surfaces as an
+/// [`io::ErrorKind::InvalidData`] error wrapping a [`ParsingError`], whose
+/// offset counts bytes from the start of the stream. Bytes decoded before
+/// the error are returned first; the error comes with the next read.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use cryptopals::encoding::base64::{Decoder, MIME};
+///
+/// let mut decoded = Vec::new();
+/// Decoder::new("SGVs\r\nbG8=".as_bytes(), MIME)
+///     .read_to_end(&mut decoded)
+///     .unwrap();
+/// assert_eq!(decoded, b"Hello");
+/// ```
+pub struct Decoder<R: Read> {
+    inner: R,
+    engine: Engine,
+    buffer: Vec<u8>,
+    /// Significant characters not decoded yet, with their stream offsets.
+    pending: Vec<(usize, char)>,
+    /// The input from the first pending character on, for error excerpts.
+    pending_input: Vec<u8>,
+    /// Bytes consumed from the wrapped reader so far.
+    offset: usize,
+    /// Decoded bytes not handed out yet, starting at `decoded_position`.
+    decoded: Vec<u8>,
+    decoded_position: usize,
+    finished: bool,
+    /// An error held back until the bytes decoded before it are read.
+    error: Option<io::Error>,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(inner: R, engine: Engine) -> Self {
+        Decoder {
+            inner,
+            engine,
+            buffer: vec![0; STREAM_CHUNK_SIZE],
+            pending: Vec::new(),
+            pending_input: Vec::new(),
+            offset: 0,
+            decoded: Vec::new(),
+            decoded_position: 0,
+            finished: false,
+            error: None,
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decodes all complete quartets but the last, which may carry padding.
+    ///
+    /// An invalid character stops decoding; the quartets before it are kept
+    /// and the error is held back.
+    fn decode_pending(&mut self) {
+        let held_back = match self.pending.len() % 4 {
+            0 => 4,
+            remainder => remainder,
+        };
+        let complete = self.pending.len().saturating_sub(held_back);
+
+        let table = self.engine.alphabet.decode_table();
+        let mut invalid = None;
+        'quartets: for quartet in self.pending[..complete].chunks(4) {
+            let mut sextets = [0; 4];
+            for (sextet, &(offset, char)) in sextets.iter_mut().zip(quartet) {
+                *sextet = u8::try_from(char).map_or(INVALID, |byte| table[byte as usize]);
+                if *sextet == INVALID {
+                    invalid = Some(ErrorKind::InvalidCharacter { offset, char });
+                    break 'quartets;
+                }
+            }
+            decode_quatret(&sextets, &mut self.decoded);
+        }
+        if let Some(kind) = invalid {
+            self.error = Some(self.error(kind));
+            return;
+        }
+        self.pending.drain(..complete);
+
+        let input_offset = self.offset - self.pending_input.len();
+        let start = self
+            .pending
+            .first()
+            .map_or(self.offset, |&(offset, _)| offset);
+        self.pending_input.drain(..start - input_offset);
+    }
+
+    /// Decodes the held-back quartet, checking its padding.
+    fn decode_final_quartet(&mut self) -> io::Result<()> {
+        let bytes = self
+            .engine
+            .decode_chars(self.pending.iter().copied(), self.offset)
+            .map_err(|kind| self.error(kind))?;
+
+        self.decoded.extend(bytes);
+        self.pending.clear();
+        self.pending_input.clear();
+        Ok(())
+    }
+
+    /// The error for pending input rejected with `kind`.
+    ///
+    /// Characters were read a byte at a time; one outside ASCII is put back
+    /// together from the bytes that follow it before it is reported.
+    fn error(&mut self, kind: ErrorKind) -> io::Error {
+        let input_offset = self.offset - self.pending_input.len();
+        let kind = match kind {
+            ErrorKind::InvalidCharacter { offset, char } if !char.is_ascii() => {
+                let index = offset - input_offset;
+                let char = stream::char_at(
+                    self.pending_input[index],
+                    &self.pending_input[index + 1..],
+                    &mut self.inner,
+                );
+                ErrorKind::InvalidCharacter { offset, char }
+            }
+            kind => kind,
+        };
+
+        invalid_data(
+            ParsingError::new(
+                ParsingDirection::Decoding,
+                Encoding::Base64,
+                kind,
+                String::from_utf8_lossy(&self.pending_input).into_owned(),
+            )
+            .at_input_offset(input_offset),
+        )
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.decoded_position == self.decoded.len() && !self.finished {
+            if let Some(error) = self.error.take() {
+                return Err(error);
+            }
+            self.decoded.clear();
+            self.decoded_position = 0;
+
+            let read = self.inner.read(&mut self.buffer)?;
+            if read == 0 {
+                self.finished = true;
+                self.decode_final_quartet()?;
+                break;
+            }
+
+            for &byte in &self.buffer[..read] {
+                let ch = byte as char;
+                if !self.engine.is_skipped(ch) {
+                    self.pending.push((self.offset, ch));
+                }
+                if !self.pending.is_empty() {
+                    self.pending_input.push(byte);
+                }
+                self.offset += 1;
+            }
+            self.decode_pending();
+        }
+
+        let available = &self.decoded[self.decoded_position..];
+        let count = available.len().min(out.len());
+        out[..count].copy_from_slice(&available[..count]);
+        self.decoded_position += count;
+        Ok(count)
+    }
+}
+
+impl Codec for Engine {
+    fn encoding(&self) -> Encoding {
+        Encoding::Base64
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        char == '='
+            || u8::try_from(char)
+                .is_ok_and(|byte| self.alphabet.decode_table()[byte as usize] != INVALID)
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        Ok(Engine::encode(self, bytes))
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        Engine::decode(self, encoded)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn valid_base64_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..200)) {
+            for engine in [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD, MIME] {
+                let encoded = engine.encode(&bytes);
+                assert_eq!(engine.decode(&encoded).unwrap(), bytes);
+            }
+        }
+
+        #[test]
+        fn to_slice_matches(bytes in proptest::collection::vec(any::<u8>(), 0..200), s in "[A-Za-z0-9+/=\n]{0,40}") {
+            for engine in [STANDARD, STANDARD_NO_PAD, URL_SAFE, MIME] {
+                let mut out = vec![0; engine.encoded_len(bytes.len())];
+                engine.encode_to_slice(&bytes, &mut out);
+                assert_eq!(out, engine.encode(&bytes).as_bytes());
+
+                let mut decoded = vec![0; out.len()];
+                let written = engine.decode_to_slice(&engine.encode(&bytes), &mut decoded).unwrap();
+                assert_eq!(decoded[..written], bytes);
+
+                let mut decoded = vec![0; s.len()];
+                let result = engine.decode_to_slice(&s, &mut decoded);
+                assert_eq!(result.map(|written| decoded[..written].to_vec()), engine.decode(&s));
+            }
+        }
+
+        #[test]
+        fn constant_time_matches(bytes in proptest::collection::vec(any::<u8>(), 0..100), s in "[A-Za-z0-9+/_=-]{0,12}") {
+            let lenient = Engine::new(Alphabet::UrlSafe, Padding::Lenient);
+            for engine in [STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD, MIME, lenient] {
+                let encoded = engine.encode_constant_time(&bytes);
+                assert_eq!(encoded, engine.encode(&bytes));
+                if engine.line_wrap.is_none() {
+                    assert_eq!(engine.decode_constant_time(&encoded).unwrap(), bytes);
+                    assert_eq!(engine.decode_constant_time(&s), engine.decode(&s));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_slice() {
+        // long enough for the SIMD kernels, with a tail for the table
+        let bytes: Vec<u8> = (0..=u8::MAX).chain(0..77).collect();
+        let lenient = Engine::new(Alphabet::UrlSafe, Padding::Lenient);
+        for engine in [
+            STANDARD,
+            URL_SAFE_NO_PAD,
+            MIME,
+            lenient,
+            STANDARD.with_line_wrap(5, "\n"),
+        ] {
+            let encoded = engine.encode(&bytes);
+            let mut out = vec![0; encoded.len() + 3];
+            assert_eq!(engine.encode_to_slice(&bytes, &mut out), encoded.len());
+            assert_eq!(out[..encoded.len()], *encoded.as_bytes());
+            assert_eq!(out[encoded.len()..], [0; 3]);
+
+            let mut decoded = vec![0; encoded.len()];
+            let written = engine.decode_to_slice(&encoded, &mut decoded).unwrap();
+            assert_eq!(decoded[..written], bytes);
+        }
+
+        // errors in a SIMD block and in the tail
+        let encoded = encode(&bytes);
+        let mut out = vec![0; encoded.len()];
+        for offset in [5, encoded.len() - 3] {
+            let mut broken = encoded.clone();
+            broken.replace_range(offset..offset + 1, "*");
+            assert_eq!(
+                STANDARD.decode_to_slice(&broken, &mut out).unwrap_err(),
+                decode(&broken).unwrap_err()
+            );
+        }
+        assert_eq!(
+            STANDARD
+                .decode_to_slice("SGVsbG9=", &mut out)
+                .unwrap_err()
+                .kind,
+            ErrorKind::NonCanonicalTrailingBits {
+                offset: 6,
+                char: '9'
+            }
+        );
+        assert_eq!(
+            STANDARD_NO_PAD
+                .decode_to_slice("SGk=", &mut out)
+                .unwrap_err()
+                .kind,
+            ErrorKind::InvalidPadding { offset: 3 }
+        );
+
+        // whitespace outside ASCII is rejected, as by `decode`
+        let spaced = "SGVs\u{2003}bG8=";
+        assert_eq!(
+            STANDARD
+                .ignoring_whitespace()
+                .decode_to_slice(spaced, &mut out)
+                .unwrap_err(),
+            STANDARD.ignoring_whitespace().decode(spaced).unwrap_err()
+        );
+        assert_eq!(
+            STANDARD
+                .ignoring_whitespace()
+                .decode(spaced)
+                .unwrap_err()
+                .kind,
+            ErrorKind::InvalidCharacter {
+                offset: 4,
+                char: '\u{2003}'
+            }
+        );
+    }
+
+    #[test]
+    fn test_constant_time_codecs_handle_every_character() {
+        // the sextets go through the same arithmetic whatever their value,
+        // so the work depends on the input only through the calls made
+        for bytes in [[0; 7], [0xFF; 7], *b"\x00secret"] {
+            let mut sextets = Vec::new();
+            let encoded = STANDARD.encode_constant_time_with(&bytes, |alphabet, sextet| {
+                sextets.push(sextet);
+                encode_sextet_constant_time(alphabet, sextet)
+            });
+            assert_eq!(sextets.len(), 10);
+            assert_eq!(encoded, STANDARD.encode(&bytes));
+        }
+
+        for encoded in [
+            "AAAAAAAAAAA=",
+            "SGVsbG8gbW8=",
+            "!AAAAAAAAAA=",
+            "AAAAAAAAAA!=",
+            "AAAA\r\nAAAAA=",
+        ] {
+            let data = encoded.trim_end_matches('=');
+            let mut chars = Vec::new();
+            let result = STANDARD.decode_constant_time_with(encoded, |alphabet, char| {
+                chars.push(char);
+                decode_sextet_constant_time(alphabet, char)
+            });
+            assert_eq!(chars, data.as_bytes(), "{encoded:?}");
+            assert_eq!(result, STANDARD.decode(encoded));
+        }
+    }
+
+    #[test]
+    fn test_constant_time_sextets() {
+        for alphabet in [Alphabet::Standard, Alphabet::UrlSafe] {
+            for char in 0..=u8::MAX {
+                let (sextet, valid) = decode_sextet_constant_time(alphabet, char);
+                match alphabet.decode_table()[char as usize] {
+                    INVALID => assert_eq!(valid, 0, "{:?} is not in the alphabet", char as char),
+                    expected => assert_eq!((sextet, valid), (expected, 0xFF)),
+                }
+            }
+            for sextet in 0..64 {
+                assert_eq!(
+                    encode_sextet_constant_time(alphabet, sextet),
+                    alphabet.table()[sextet as usize]
+                );
+            }
+        }
+
+        // whitespace is never skipped
+        let wrapped = MIME.encode(&[0; 60]);
+        assert_eq!(
+            MIME.decode_constant_time(&wrapped).unwrap_err().kind,
+            ErrorKind::InvalidCharacter {
+                offset: 76,
+                char: '\r'
+            }
+        );
+    }
 
     #[test]
     fn test_encode_empty() {
@@ -225,4 +1305,328 @@ mod tests {
             b"I'm killing your brain like a poisonous mushroom"
         );
     }
+
+    #[test]
+    fn test_rfc_4648_vectors() {
+        let vectors = [
+            ("", "", ""),
+            ("f", "Zg==", "Zg"),
+            ("fo", "Zm8=", "Zm8"),
+            ("foo", "Zm9v", "Zm9v"),
+            ("foob", "Zm9vYg==", "Zm9vYg"),
+            ("fooba", "Zm9vYmE=", "Zm9vYmE"),
+            ("foobar", "Zm9vYmFy", "Zm9vYmFy"),
+        ];
+
+        for (plain, padded, unpadded) in vectors {
+            assert_eq!(STANDARD.encode(plain.as_bytes()), padded);
+            assert_eq!(STANDARD_NO_PAD.encode(plain.as_bytes()), unpadded);
+            assert_eq!(STANDARD.decode(padded).unwrap(), plain.as_bytes());
+            assert_eq!(STANDARD_NO_PAD.decode(unpadded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_url_safe_alphabet() {
+        let bytes = [0xFB, 0xEF, 0xFF];
+
+        assert_eq!(STANDARD.encode(&bytes), "++//");
+        assert_eq!(URL_SAFE.encode(&bytes), "--__");
+        assert_eq!(URL_SAFE.decode("--__").unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_padding_modes() {
+        let lenient = Engine::new(Alphabet::UrlSafe, Padding::Lenient);
+
+        assert!(STANDARD.decode("SGk").is_err());
+        assert!(STANDARD_NO_PAD.decode("SGk=").is_err());
+        assert_eq!(lenient.decode("SGk").unwrap(), b"Hi");
+        assert_eq!(lenient.decode("SGk=").unwrap(), b"Hi");
+        assert_eq!(lenient.encode(b"Hi"), "SGk=");
+
+        // padding that does not complete a quartet
+        assert!(lenient.decode("SG=").is_err());
+        assert!(STANDARD.decode("S===").is_err());
+        // a lone trailing character can't encode a byte
+        assert!(STANDARD_NO_PAD.decode("SGVsb").is_err());
+        // padding in the middle of the data
+        assert!(STANDARD.decode("SG==SGk=").is_err());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        assert_eq!(
+            STANDARD_NO_PAD.decode("SGVsb").unwrap_err().kind,
+            ErrorKind::InvalidLength { length: 5 }
+        );
+        // missing padding points at the end, unexpected padding at its start
+        assert_eq!(
+            STANDARD.decode("SGk").unwrap_err().kind,
+            ErrorKind::InvalidPadding { offset: 3 }
+        );
+        assert_eq!(
+            STANDARD_NO_PAD.decode("SGk=").unwrap_err().kind,
+            ErrorKind::InvalidPadding { offset: 3 }
+        );
+        assert_eq!(
+            STANDARD.decode("SGk==").unwrap_err().kind,
+            ErrorKind::InvalidPadding { offset: 3 }
+        );
+        // "SGl=" would decode to "Hi" as well if trailing bits were ignored
+        assert_eq!(
+            STANDARD.decode("SGl=").unwrap_err().kind,
+            ErrorKind::NonCanonicalTrailingBits {
+                offset: 2,
+                char: 'l'
+            }
+        );
+        assert_eq!(
+            STANDARD_NO_PAD.decode("qx").unwrap_err().kind,
+            ErrorKind::NonCanonicalTrailingBits {
+                offset: 1,
+                char: 'x'
+            }
+        );
+    }
+
+    #[test]
+    fn test_mime_line_wrapping() {
+        let bytes = (0..=255).collect_vec();
+        let encoded = MIME.encode(&bytes);
+        let lines = encoded.split("\r\n").collect_vec();
+
+        assert_eq!(lines.len(), 5);
+        assert!(lines[..4].iter().all(|line| line.len() == 76));
+        assert_eq!(lines.concat(), STANDARD.encode(&bytes));
+        assert_eq!(MIME.decode(&encoded).unwrap(), bytes);
+        // bare LF line endings decode as well
+        assert_eq!(MIME.decode(&lines.join("\n")).unwrap(), bytes);
+        // line breaks are only skipped when wrapping is configured
+        assert!(STANDARD.decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_invalid_characters_do_not_panic() {
+        let error = decode("SGVs*G8=").unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::InvalidCharacter {
+                offset: 4,
+                char: '*'
+            }
+        );
+
+        // characters from the other alphabet are invalid
+        let error = URL_SAFE.decode("++//").unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::InvalidCharacter {
+                offset: 0,
+                char: '+'
+            }
+        );
+
+        // padding before the end of the data
+        let error = decode("SG==SGk=").unwrap_err();
+        assert_eq!(error.kind.offset(), Some(2));
+
+        // non-ASCII input reports byte offsets
+        let error = decode("SG\u{e9}k").unwrap_err();
+        assert_eq!(error.kind.offset(), Some(2));
+    }
+
+    /// Reader that hands out at most `step` bytes per call.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.step.min(buf.len()).min(self.bytes.len());
+            buf[..count].copy_from_slice(&self.bytes[..count]);
+            self.bytes = &self.bytes[count..];
+            Ok(count)
+        }
+    }
+
+    fn stream_decode(encoded: &str, engine: Engine, step: usize) -> io::Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        Decoder::new(
+            Trickle {
+                bytes: encoded.as_bytes(),
+                step,
+            },
+            engine,
+        )
+        .read_to_end(&mut decoded)?;
+        Ok(decoded)
+    }
+
+    proptest! {
+        #[test]
+        fn streaming_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..300), step in 1..20usize) {
+            for engine in [STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD, MIME, STANDARD.with_line_wrap(5, "\n")] {
+                let mut encoder = Encoder::new(Vec::new(), engine);
+                for chunk in bytes.chunks(step) {
+                    encoder.write_all(chunk).unwrap();
+                }
+                let encoded = String::from_utf8(encoder.finish().unwrap()).unwrap();
+                assert_eq!(encoded, engine.encode(&bytes));
+
+                assert_eq!(stream_decode(&encoded, engine, step).unwrap(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_encoder_drop_finishes() {
+        let mut output = Vec::new();
+        {
+            let mut encoder = Encoder::new(&mut output, STANDARD);
+            encoder.write_all(b"Hi").unwrap();
+        }
+        assert_eq!(output, b"SGk=");
+    }
+
+    /// A writer that fails every write.
+    #[derive(Debug)]
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_streaming_encoder_finish_errors() {
+        let mut encoder = Encoder::new(Broken, STANDARD);
+        encoder.write_all(b"Hi").unwrap();
+        assert_eq!(encoder.finish().unwrap_err().to_string(), "broken");
+    }
+
+    #[test]
+    fn test_streaming_encoder_drop_while_panicking() {
+        let mut output = Vec::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut encoder = Encoder::new(&mut output, STANDARD);
+            encoder.write_all(b"Hi").unwrap();
+            panic!("unwinding past the encoder");
+        }));
+        assert!(result.is_err());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_streaming_decoder_keeps_bytes_before_error() {
+        let mut decoder = Decoder::new("SGVsbG8h*GVs".as_bytes(), STANDARD);
+        let mut decoded = [0; 16];
+        assert_eq!(decoder.read(&mut decoded).unwrap(), 6);
+        assert_eq!(decoded[..6], *b"Hello!");
+        let error = decoder.read(&mut decoded).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_streaming_decoder_non_ascii() {
+        let kind = |encoded: &str, step| {
+            stream_decode(encoded, STANDARD.ignoring_whitespace(), step)
+                .unwrap_err()
+                .into_inner()
+                .unwrap()
+                .downcast::<ParsingError>()
+                .unwrap()
+                .kind
+        };
+
+        for step in [1, 3, 100] {
+            for encoded in ["SGVs\u{e9}G8=", "SGVsbG\u{20ac}=", "SGVs\u{2003}bG8="] {
+                assert_eq!(
+                    kind(encoded, step),
+                    STANDARD
+                        .ignoring_whitespace()
+                        .decode(encoded)
+                        .unwrap_err()
+                        .kind
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_decoder_padding() {
+        for step in [1, 3, 100] {
+            assert_eq!(stream_decode("SGVsbG8=", STANDARD, step).unwrap(), b"Hello");
+            assert!(stream_decode("SGVsbG8", STANDARD, step).is_err());
+            assert!(stream_decode("SGVsbG8=", STANDARD_NO_PAD, step).is_err());
+            assert!(stream_decode("SGVsb", STANDARD_NO_PAD, step).is_err());
+        }
+    }
+
+    #[test]
+    fn test_streaming_decoder_offsets() {
+        let offset = |encoded, engine| {
+            let error = stream_decode(encoded, engine, 3).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            let error = error
+                .into_inner()
+                .unwrap()
+                .downcast::<ParsingError>()
+                .unwrap();
+            error.kind.offset().unwrap()
+        };
+
+        // in a complete quartet, in the final one, and padding mid-stream
+        assert_eq!(offset("SGVs*G8=", STANDARD), 4);
+        assert_eq!(offset("SGVsbG8*", STANDARD), 7);
+        assert_eq!(offset("SG==SGk=", STANDARD), 2);
+        // skipped characters still count towards the offset
+        assert_eq!(offset("SGVs\r\nbG!=", MIME), 8);
+        assert_eq!(offset(" SGVs\nbG!=\n", STANDARD.ignoring_whitespace()), 8);
+    }
+
+    #[test]
+    fn test_streaming_decoder_final_excerpt() {
+        let message = |encoded, engine, step| {
+            let error = stream_decode(encoded, engine, step).unwrap_err();
+            error
+                .into_inner()
+                .unwrap()
+                .downcast::<ParsingError>()
+                .unwrap()
+                .to_string()
+        };
+
+        for step in [1, 3, 100] {
+            let padding = message("SGVsbG8", STANDARD, step);
+            assert!(padding.ends_with("\n  | bG8\n  |    ^"), "{padding}");
+
+            let trailing = message("SGVs\nbG9=", STANDARD.ignoring_whitespace(), step);
+            assert!(trailing.ends_with("\n  | bG9=\n  |   ^"), "{trailing}");
+        }
+    }
+
+    #[test]
+    fn test_decode_ignoring_whitespace() {
+        assert_eq!(
+            decode_ignoring_whitespace(" SGVs\r\n\tbG8=\n").unwrap(),
+            b"Hello"
+        );
+        assert_eq!(
+            URL_SAFE_NO_PAD
+                .ignoring_whitespace()
+                .decode("-_ 8")
+                .unwrap(),
+            [0xFB, 0xFF]
+        );
+
+        let error = decode_ignoring_whitespace("SGVs\nbG!=").unwrap_err();
+        assert_eq!(error.kind.offset(), Some(7));
+    }
 }
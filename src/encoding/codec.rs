@@ -0,0 +1,207 @@
+//! A common interface over the encodings in this crate, and detection of
+//! which one an unknown string uses.
+
+use crate::analysis::frequency::shannon_entropy;
+use crate::encoding::base64::{Alphabet, Engine, Padding};
+use crate::encoding::error::{Encoding, ParsingError};
+use crate::encoding::{ascii85, base32, base58, hex, z85};
+use std::fmt::Debug;
+
+/// A text encoding for binary data.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::Codec;
+/// use cryptopals::encoding::{base32::Base32, hex::Hex};
+///
+/// let codecs: [&dyn Codec; 2] = [&Hex, &Base32];
+/// for codec in codecs {
+///     let encoded = codec.encode(b"hi").unwrap();
+///     assert_eq!(codec.decode(&encoded).unwrap(), b"hi");
+/// }
+/// ```
+pub trait Codec: Debug {
+    /// The encoding named in this codec's errors.
+    fn encoding(&self) -> Encoding;
+
+    /// Whether the character can appear in encoded output, padding included.
+    fn is_valid_char(&self, char: char) -> bool;
+
+    /// # Errors
+    ///
+    /// Only fails for encodings that restrict the input length.
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError>;
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError>;
+
+    /// Whether the whole string decodes.
+    fn is_valid(&self, encoded: &str) -> bool {
+        self.decode(encoded).is_ok()
+    }
+}
+
+/// One candidate encoding for a string, as returned by [`detect`].
+#[derive(Debug)]
+pub struct Guess {
+    pub codec: &'static dyn Codec,
+    /// Between 0 and 1; only comparable with other guesses for the same input.
+    pub score: f32,
+    /// The decoded bytes, if the input decodes with this codec.
+    pub decoded: Option<Vec<u8>>,
+}
+
+/// Base64 with either padding, since detection can't know which was used.
+const BASE64_LENIENT: Engine = Engine::new(Alphabet::Standard, Padding::Lenient);
+const BASE64_URL_LENIENT: Engine = Engine::new(Alphabet::UrlSafe, Padding::Lenient);
+
+/// Every codec [`detect`] tries, from the smallest alphabet to the largest.
+const CODECS: [&dyn Codec; 8] = [
+    &hex::Hex,
+    &base32::Base32,
+    &base32::Base32Hex,
+    &base58::Base58,
+    &BASE64_LENIENT,
+    &BASE64_URL_LENIENT,
+    &ascii85::Ascii85,
+    &z85::Z85,
+];
+
+/// Number of distinct characters each codec writes data with.
+fn alphabet_size(encoding: Encoding) -> f32 {
+    match encoding {
+        Encoding::Hex => 16.0,
+        Encoding::Base32 | Encoding::Base32Hex => 32.0,
+        Encoding::Base58 => 58.0,
+        Encoding::Base64 | Encoding::Pem => 64.0,
+        Encoding::Ascii85 | Encoding::Z85 => 85.0,
+    }
+}
+
+/// Scores an unknown string against every codec, best guess first.
+///
+/// Whitespace is ignored. Each score multiplies:
+///
+/// - the fraction of characters in the codec's charset
+/// - a penalty of one half if the input does not decode, e.g. because its
+///   length is impossible for the codec
+/// - the specificity of the alphabet: a string that fits in 16 characters is
+///   more likely hex than Base64, which would use all 64
+/// - a preference for decoded output with low entropy, such as text
+///
+/// Codecs without a single matching character are left out.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::detect;
+/// use cryptopals::encoding::error::Encoding;
+///
+/// let guesses = detect("SSdtIGtpbGxpbmcgeW91ciBicmFpbg==");
+/// assert_eq!(guesses[0].codec.encoding(), Encoding::Base64);
+/// assert_eq!(guesses[0].decoded.as_deref(), Some(&b"I'm killing your brain"[..]));
+///
+/// let guesses = detect("49276d206b696c6c696e67");
+/// assert_eq!(guesses[0].codec.encoding(), Encoding::Hex);
+/// ```
+pub fn detect(input: &str) -> Vec<Guess> {
+    let compact: String = input.chars().filter(|ch| !ch.is_whitespace()).collect();
+    if compact.is_empty() {
+        return Vec::new();
+    }
+    let length = compact.chars().count() as f32;
+
+    let mut guesses: Vec<Guess> = CODECS
+        .iter()
+        .filter_map(|&codec| {
+            let matching = compact
+                .chars()
+                .filter(|&ch| codec.is_valid_char(ch))
+                .count();
+            if matching == 0 {
+                return None;
+            }
+
+            let decoded = codec.decode(&compact).ok();
+            let decodes = if decoded.is_some() { 1.0 } else { 0.5 };
+            let specificity = 4.0 / alphabet_size(codec.encoding()).log2();
+            let entropy = decoded.as_deref().map_or(8.0, shannon_entropy);
+
+            Some(Guess {
+                codec,
+                score: matching as f32 / length * decodes * specificity * (1.0 - entropy / 16.0),
+                decoded,
+            })
+        })
+        .collect();
+
+    guesses.sort_by(|a, b| b.score.total_cmp(&a.score));
+    guesses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn best(input: &str) -> Encoding {
+        detect(input)[0].codec.encoding()
+    }
+
+    proptest! {
+        #[test]
+        fn every_codec_roundtrips(words in proptest::collection::vec(any::<[u8; 4]>(), 0..30)) {
+            let bytes = words.concat();
+            for codec in CODECS {
+                let encoded = codec.encode(&bytes).unwrap();
+                assert!(codec.is_valid(&encoded));
+                assert!(encoded.chars().all(|ch| codec.is_valid_char(ch)));
+                assert_eq!(codec.decode(&encoded).unwrap(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detects_text_in_each_encoding() {
+        let text = b"Cooking MC's like a pound of bacon";
+
+        assert_eq!(best(&hex::encode(text)), Encoding::Hex);
+        assert_eq!(best(&hex::encode(text).to_lowercase()), Encoding::Hex);
+        assert_eq!(best(&base32::encode(text)), Encoding::Base32);
+        assert_eq!(best(&base32::encode_hex(text)), Encoding::Base32Hex);
+        assert_eq!(best(&base58::encode(text)), Encoding::Base58);
+        assert_eq!(
+            best(&crate::encoding::base64::encode(text)),
+            Encoding::Base64
+        );
+        assert_eq!(best(&ascii85::encode(text)), Encoding::Ascii85);
+    }
+
+    #[test]
+    fn test_wrapped_and_partial_input() {
+        let wrapped = crate::encoding::base64::MIME.encode(&[b'x'; 100]);
+        assert_eq!(best(&wrapped), Encoding::Base64);
+
+        // a stray character lowers the score but still ranks the codec
+        let guesses = detect("48656c6c6f!");
+        let hex_guess = guesses
+            .iter()
+            .find(|guess| guess.codec.encoding() == Encoding::Hex)
+            .unwrap();
+        assert!(hex_guess.decoded.is_none());
+        assert!(hex_guess.score > 0.0);
+
+        assert!(detect(" \n").is_empty());
+    }
+
+    #[test]
+    fn test_scores_are_sorted() {
+        let guesses = detect("DEADBEEF");
+        assert!(
+            guesses
+                .windows(2)
+                .all(|pair| pair[0].score >= pair[1].score)
+        );
+        assert_eq!(guesses[0].codec.encoding(), Encoding::Hex);
+    }
+}
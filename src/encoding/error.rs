@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParsingDirection {
     Encoding,
     Decoding,
@@ -15,59 +15,330 @@ impl Display for ParsingDirection {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Encoding {
     Hex,
+    Base32,
+    Base32Hex,
+    Base58,
     Base64,
+    Ascii85,
+    Z85,
+    Pem,
 }
 
 impl Display for Encoding {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Encoding::Hex => f.write_str("hex"),
+            Encoding::Base32 => f.write_str("base32"),
+            Encoding::Base32Hex => f.write_str("base32hex"),
+            Encoding::Base58 => f.write_str("base58"),
             Encoding::Base64 => f.write_str("base64"),
+            Encoding::Ascii85 => f.write_str("ascii85"),
+            Encoding::Z85 => f.write_str("z85"),
+            Encoding::Pem => f.write_str("pem"),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum Value {
-    Bytes(Vec<u8>),
-    String(String),
+/// What is wrong with the input. Offsets are byte offsets into the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A character outside the alphabet, or not allowed at its position.
+    InvalidCharacter { offset: usize, char: char },
+    /// No encoder produces this many significant characters.
+    InvalidLength { length: usize },
+    /// Padding is missing, unexpected or too long; `offset` is where the
+    /// padding starts, or the end of the input if it is missing.
+    InvalidPadding { offset: usize },
+    /// The last character sets bits past the end of the data, so another
+    /// encoding of the same bytes exists.
+    NonCanonicalTrailingBits { offset: usize, char: char },
+    /// The embedded checksum does not match the decoded payload.
+    InvalidChecksum,
+    /// A PEM `-----BEGIN` or `-----END` line is missing, malformed or does
+    /// not match the other; `offset` is the start of the line, or the end of
+    /// the input if it is missing.
+    InvalidBoundary { offset: usize },
 }
 
-#[derive(Debug)]
+impl ErrorKind {
+    /// The offset this error points at, if it points at one.
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            ErrorKind::InvalidCharacter { offset, .. }
+            | ErrorKind::InvalidPadding { offset }
+            | ErrorKind::NonCanonicalTrailingBits { offset, .. }
+            | ErrorKind::InvalidBoundary { offset } => Some(offset),
+            ErrorKind::InvalidLength { .. } | ErrorKind::InvalidChecksum => None,
+        }
+    }
+
+    /// The same error for input that starts `by` bytes into a larger input.
+    pub(crate) fn shifted(self, by: usize) -> Self {
+        match self {
+            ErrorKind::InvalidCharacter { offset, char } => ErrorKind::InvalidCharacter {
+                offset: offset + by,
+                char,
+            },
+            ErrorKind::InvalidPadding { offset } => ErrorKind::InvalidPadding {
+                offset: offset + by,
+            },
+            ErrorKind::NonCanonicalTrailingBits { offset, char } => {
+                ErrorKind::NonCanonicalTrailingBits {
+                    offset: offset + by,
+                    char,
+                }
+            }
+            ErrorKind::InvalidBoundary { offset } => ErrorKind::InvalidBoundary {
+                offset: offset + by,
+            },
+            ErrorKind::InvalidLength { .. } | ErrorKind::InvalidChecksum => self,
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::InvalidCharacter { offset, char } => {
+                write!(f, "invalid character {:?} at offset {}", char, offset)
+            }
+            ErrorKind::InvalidLength { length } => {
+                write!(f, "invalid length of {} characters", length)
+            }
+            ErrorKind::InvalidPadding { offset } => {
+                write!(f, "invalid padding at offset {}", offset)
+            }
+            ErrorKind::NonCanonicalTrailingBits { offset, char } => write!(
+                f,
+                "non-zero trailing bits in {:?} at offset {}",
+                char, offset
+            ),
+            ErrorKind::InvalidChecksum => f.write_str("checksum mismatch"),
+            ErrorKind::InvalidBoundary { offset } => {
+                write!(f, "missing or mismatched boundary at offset {}", offset)
+            }
+        }
+    }
+}
+
+/// Bytes of context shown on either side of the offending position.
+const EXCERPT_CONTEXT: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsingError {
     pub direction: ParsingDirection,
     pub encoding: Encoding,
-    pub value: Value,
+    pub kind: ErrorKind,
+    /// The input, or the part of it that was at hand when parsing failed.
+    pub input: String,
+    /// Offset of `input` in the whole input; non-zero for streamed input.
+    pub input_offset: usize,
 }
 
 impl ParsingError {
-    pub fn from_bytes(direction: ParsingDirection, encoding: Encoding, bytes: Vec<u8>) -> Self {
+    pub fn new(
+        direction: ParsingDirection,
+        encoding: Encoding,
+        kind: ErrorKind,
+        input: String,
+    ) -> Self {
         ParsingError {
             direction,
             encoding,
-            value: Value::Bytes(bytes),
+            kind,
+            input,
+            input_offset: 0,
         }
     }
 
-    pub fn from_string(direction: ParsingDirection, encoding: Encoding, string: String) -> Self {
+    /// Returns the same error, with `input` starting at `offset` in the whole input.
+    pub fn at_input_offset(self, offset: usize) -> Self {
         ParsingError {
-            direction,
-            encoding,
-            value: Value::String(string),
+            input_offset: offset,
+            ..self
         }
     }
+
+    /// Writes the line of input around `position` with a caret below it.
+    fn write_excerpt(&self, f: &mut std::fmt::Formatter<'_>, position: usize) -> std::fmt::Result {
+        let floor = |mut idx: usize| {
+            while !self.input.is_char_boundary(idx) {
+                idx -= 1;
+            }
+            idx
+        };
+        let start = floor(position.saturating_sub(EXCERPT_CONTEXT));
+        let end = floor((position + EXCERPT_CONTEXT).min(self.input.len()));
+
+        let mut line = String::from(if start > 0 { "..." } else { "" });
+        let mut caret = line.chars().count();
+        for (idx, char) in self.input[start..end].char_indices() {
+            if start + idx == position {
+                caret = line.chars().count();
+            }
+            if char.is_control() {
+                line.extend(char.escape_debug());
+            } else {
+                line.push(char);
+            }
+        }
+        if position >= end {
+            caret = line.chars().count();
+        }
+        if end < self.input.len() {
+            line.push_str("...");
+        }
+
+        write!(f, "\n  | {}\n  | {}^", line, " ".repeat(caret))
+    }
 }
 
 impl Display for ParsingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&format!(
-            "{} failed {} for {:?}",
-            self.encoding, self.direction, self.value
-        ))
+        write!(
+            f,
+            "{} {} failed: {}",
+            self.encoding, self.direction, self.kind
+        )?;
+
+        // errors without an offset point past the end of the input
+        let position = self
+            .kind
+            .offset()
+            .unwrap_or(self.input_offset + self.input.len())
+            .checked_sub(self.input_offset);
+        match position {
+            Some(position) if !self.input.is_empty() && position <= self.input.len() => {
+                self.write_excerpt(f, position)
+            }
+            _ => Ok(()),
+        }
     }
 }
 
 impl std::error::Error for ParsingError {}
+
+/// What is wrong with a DER encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerErrorKind {
+    /// The input ends inside a value.
+    Truncated,
+    /// A length that is indefinite or not in its shortest form.
+    InvalidLength,
+    /// A tag for a type that is not supported.
+    UnsupportedTag(u8),
+    /// Content that is not the valid, shortest encoding of its type.
+    InvalidContent,
+    /// Bytes left over after the outermost value.
+    TrailingData,
+    /// SEQUENCEs nested deeper than [`der::MAX_DEPTH`](super::der::MAX_DEPTH).
+    TooDeep,
+    /// Well-formed values that do not make up the structure being read.
+    UnexpectedStructure,
+}
+
+impl Display for DerErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DerErrorKind::Truncated => f.write_str("truncated value"),
+            DerErrorKind::InvalidLength => f.write_str("invalid length"),
+            DerErrorKind::UnsupportedTag(tag) => write!(f, "unsupported tag 0x{:02x}", tag),
+            DerErrorKind::InvalidContent => f.write_str("invalid content"),
+            DerErrorKind::TrailingData => f.write_str("trailing data"),
+            DerErrorKind::TooDeep => f.write_str("nesting too deep"),
+            DerErrorKind::UnexpectedStructure => f.write_str("unexpected structure"),
+        }
+    }
+}
+
+/// A DER decoding error, at a byte offset into the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerError {
+    pub kind: DerErrorKind,
+    pub offset: usize,
+}
+
+impl Display for DerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "der decoding failed: {} at offset {}",
+            self.kind, self.offset
+        )
+    }
+}
+
+impl std::error::Error for DerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(kind: ErrorKind, input: &str) -> ParsingError {
+        ParsingError::new(
+            ParsingDirection::Decoding,
+            Encoding::Base64,
+            kind,
+            input.to_owned(),
+        )
+    }
+
+    #[test]
+    fn test_display_points_at_offset() {
+        let kind = ErrorKind::InvalidCharacter {
+            offset: 4,
+            char: '*',
+        };
+        assert_eq!(
+            error(kind, "SGVs*G8=").to_string(),
+            "base64 decoding failed: invalid character '*' at offset 4\n  | SGVs*G8=\n  |     ^"
+        );
+
+        // control characters are escaped, and the caret follows them
+        let kind = ErrorKind::InvalidPadding { offset: 6 };
+        assert_eq!(
+            error(kind, "SG\r\nk=").to_string(),
+            "base64 decoding failed: invalid padding at offset 6\n  | SG\\r\\nk=\n  |         ^"
+        );
+    }
+
+    #[test]
+    fn test_display_truncates_long_input() {
+        let input = "A".repeat(100) + "!" + &"B".repeat(100);
+        let kind = ErrorKind::InvalidCharacter {
+            offset: 100,
+            char: '!',
+        };
+        let message = error(kind, &input).to_string();
+        let lines: Vec<&str> = message.lines().collect();
+
+        assert_eq!(
+            lines[1],
+            format!("  | ...{}!{}...", "A".repeat(24), "B".repeat(23))
+        );
+        assert_eq!(lines[2], format!("  | {}^", " ".repeat(27)));
+    }
+
+    #[test]
+    fn test_display_streamed_input() {
+        // an error without an offset points at the end of the input
+        let kind = ErrorKind::InvalidLength { length: 3 };
+        assert!(
+            error(kind, "ABC")
+                .to_string()
+                .ends_with("  | ABC\n  |    ^")
+        );
+
+        let kind = ErrorKind::InvalidCharacter {
+            offset: 1000,
+            char: '!',
+        };
+        let streamed = error(kind, "!").at_input_offset(1000);
+        assert!(streamed.to_string().ends_with("  | !\n  | ^"));
+        // no excerpt when the input at hand does not cover the offset
+        assert!(!error(kind, "!").to_string().contains('|'));
+    }
+}
@@ -1,11 +1,16 @@
 //! Hexadecimal encoding and decoding utilities.
 //!
 //! This module provides functions for converting between hexadecimal strings
-//! and byte arrays, with proper validation.
+//! and byte arrays, with proper validation. This is the Base16 encoding of
+//! [RFC 4648 Section 8](https://datatracker.ietf.org/doc/html/rfc4648#section-8),
+//! except that decoding also accepts lowercase digits.
 
 use itertools::Itertools;
+use std::io::{self, Read, Write};
 
-use crate::encoding::error::{Encoding, ParsingDirection, ParsingError};
+use crate::encoding::codec::Codec;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+use crate::encoding::{simd, stream};
 
 /// Validates if a string is a valid hexadecimal encoding.
 ///
@@ -29,8 +34,6 @@ pub fn is_valid(hex: &str) -> bool {
 /// Decodes a hexadecimal string into a vector of bytes.
 ///
 /// Each pair of hex characters is converted to a single byte.
-/// If the input has odd length, the last character is treated as having
-/// a trailing zero (though this should be validated with `is_valid` first).
 ///
 /// # Examples
 ///
@@ -39,32 +42,168 @@ pub fn is_valid(hex: &str) -> bool {
 ///
 /// let bytes = decode("48656c6c6f").unwrap();
 /// assert_eq!(bytes, vec![0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+///
+/// let error = decode("48656x6c6f").unwrap_err();
+/// assert_eq!(error.kind.offset(), Some(5));
 /// ```
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if the string contains non-hexadecimal characters.
+/// Returns [`ErrorKind::InvalidCharacter`] for the first non-hexadecimal
+/// character, or [`ErrorKind::InvalidLength`] for an odd number of digits.
 pub fn decode(hex: &str) -> Result<Vec<u8>, ParsingError> {
-    if !is_valid(hex) {
-        return Err(ParsingError::from_string(
+    decode_chars(hex, hex.char_indices())
+}
+
+/// Decodes a hexadecimal string, skipping ASCII whitespace.
+///
+/// Useful for challenge files and tool output that wrap or group digits.
+/// Offsets in errors still refer to the original input.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hex::decode_ignoring_whitespace;
+///
+/// let bytes = decode_ignoring_whitespace("48 65 6c\n6c 6f\n").unwrap();
+/// assert_eq!(bytes, b"Hello");
+/// ```
+pub fn decode_ignoring_whitespace(hex: &str) -> Result<Vec<u8>, ParsingError> {
+    decode_chars(
+        hex,
+        hex.char_indices()
+            .filter(|(_, char)| !char.is_ascii_whitespace()),
+    )
+}
+
+/// Decodes a hexadecimal string in time that depends only on its length.
+///
+/// Digits are computed with arithmetic on masks instead of branching on
+/// their value, and validity is only checked once the whole input has been
+/// decoded, so this suits keys and other secrets.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hex::decode_constant_time;
+///
+/// assert_eq!(decode_constant_time("48656c6C6f").unwrap(), b"Hello");
+/// assert_eq!(decode_constant_time("48656x6c6f").unwrap_err().kind.offset(), Some(5));
+/// ```
+///
+/// # Errors
+///
+/// Returns the same errors as [`decode`]. Rejected input is decoded again
+/// with [`decode`] to find the error, which does not run in constant time.
+pub fn decode_constant_time(hex: &str) -> Result<Vec<u8>, ParsingError> {
+    decode_constant_time_with(hex, decode_digit_constant_time)
+}
+
+/// [`decode_constant_time`] with the digit decoder passed in, so that tests
+/// can count the characters it is called on.
+fn decode_constant_time_with(
+    hex: &str,
+    mut decode_digit: impl FnMut(u8) -> (u8, u8),
+) -> Result<Vec<u8>, ParsingError> {
+    let mut invalid = 0u8;
+    let bytes = hex
+        .as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            let (high, high_valid) = decode_digit(pair[0]);
+            let (low, low_valid) = decode_digit(pair[1]);
+            invalid |= !(high_valid & low_valid);
+            high << 4 | low
+        })
+        .collect_vec();
+
+    if invalid != 0 || !hex.len().is_multiple_of(2) {
+        return Err(
+            decode(hex).expect_err("constant-time decoding only rejects what decode rejects")
+        );
+    }
+    Ok(bytes)
+}
+
+/// The value of a hex digit and a mask that is `0xFF` if it is one, without
+/// data-dependent branches or lookups.
+fn decode_digit_constant_time(char: u8) -> (u8, u8) {
+    // `(value - bound) >> 8` keeps the borrow, all ones for `value < bound`
+    let char = char as u32;
+    let digit = char ^ 0x30;
+    let is_digit = digit.wrapping_sub(10) >> 8;
+    // clearing 0x20 folds lowercase onto uppercase
+    let letter = (char & !0x20).wrapping_sub(b'A' as u32 - 10);
+    let is_letter = (letter.wrapping_sub(10) ^ letter.wrapping_sub(16)) >> 8;
+
+    let value = (is_digit & digit) | (is_letter & letter);
+    (value as u8, (is_digit | is_letter) as u8)
+}
+
+/// The uppercase hex digit of a nibble, without data-dependent branches or
+/// lookups.
+fn encode_digit_constant_time(nibble: u8) -> u8 {
+    // `'0'` is 7 below `'A' - 10`; subtract it for nibbles below 10
+    let nibble = nibble as u32;
+    let below_ten = nibble.wrapping_sub(10) >> 8;
+    (nibble + (b'A' as u32 - 10) + (below_ten & 0xF9)) as u8
+}
+
+/// Encodes bytes as uppercase hex like [`encode`], in time that depends
+/// only on their length.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hex::encode_constant_time;
+///
+/// assert_eq!(encode_constant_time(&[0x48, 0x65, 0x6c, 0x6c, 0x6f]), "48656C6C6F");
+/// ```
+pub fn encode_constant_time(bytes: &[u8]) -> String {
+    let digits = bytes
+        .iter()
+        .flat_map(|&byte| {
+            [
+                encode_digit_constant_time(byte >> 4),
+                encode_digit_constant_time(byte & 0xF),
+            ]
+        })
+        .collect_vec();
+    String::from_utf8(digits).expect("hex digits are ASCII")
+}
+
+/// Decodes the given `(offset, char)` pairs of `hex` into bytes.
+pub(crate) fn decode_chars(
+    hex: &str,
+    chars: impl Iterator<Item = (usize, char)>,
+) -> Result<Vec<u8>, ParsingError> {
+    let error = |kind| {
+        ParsingError::new(
             ParsingDirection::Decoding,
             Encoding::Hex,
+            kind,
             hex.to_string(),
-        ));
+        )
+    };
+
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    let mut high = None;
+    let mut length = 0;
+    for (offset, char) in chars {
+        let digit = char
+            .to_digit(16)
+            .ok_or_else(|| error(ErrorKind::InvalidCharacter { offset, char }))?
+            as u8;
+        match high.take() {
+            Some(high) => bytes.push(high << 4 | digit),
+            None => high = Some(digit),
+        }
+        length += 1;
     }
 
-    let bytes = hex
-        .chars()
-        .map(|char| char.to_digit(16).unwrap() as u8)
-        .batching(|it| match it.next() {
-            None => None,
-            Some(x) => match it.next() {
-                Some(y) => Some((x, y)),
-                None => Some((x, 0)),
-            },
-        })
-        .map(|(a, b)| a << 4 | b)
-        .collect_vec();
+    if high.is_some() {
+        return Err(error(ErrorKind::InvalidLength { length }));
+    }
 
     Ok(bytes)
 }
@@ -72,7 +211,7 @@ pub fn decode(hex: &str) -> Result<Vec<u8>, ParsingError> {
 /// Encodes a vector of bytes as a hexadecimal string.
 ///
 /// Each byte is converted to two hexadecimal characters.
-/// The output is uppercase.
+/// The output is uppercase; see [`Formatter`] for other layouts.
 ///
 /// # Examples
 ///
@@ -83,17 +222,222 @@ pub fn decode(hex: &str) -> Result<Vec<u8>, ParsingError> {
 /// assert_eq!(hex, "48656C6C6F");
 /// ```
 pub fn encode(bytes: &[u8]) -> String {
-    let chars = bytes
+    Formatter::new().format(bytes)
+}
+
+/// The two uppercase digits of every byte.
+const ENCODE_TABLE: [[u8; 2]; 256] = {
+    let digits = b"0123456789ABCDEF";
+    let mut table = [[0; 2]; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        table[byte] = [digits[byte >> 4], digits[byte & 0xF]];
+        byte += 1;
+    }
+    table
+};
+
+/// Marks characters that are not hex digits in [`DECODE_TABLE`].
+const INVALID: u8 = 0xFF;
+
+/// The value of every hex digit, in either case.
+const DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut digit = 0;
+    while digit < 16 {
+        table[b"0123456789ABCDEF"[digit] as usize] = digit as u8;
+        table[b"0123456789abcdef"[digit] as usize] = digit as u8;
+        digit += 1;
+    }
+    table
+};
+
+/// Encodes bytes as uppercase hex into a caller-provided buffer, like
+/// [`encode`] without allocating.
+///
+/// Bytes are looked up in a table, or encoded 16 or 32 at a time with SSSE3
+/// or AVX2 when the CPU supports them. Returns the number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hex::encode_to_slice;
+///
+/// let mut out = [0; 10];
+/// assert_eq!(encode_to_slice(b"Hello", &mut out), 10);
+/// assert_eq!(&out, b"48656C6C6F");
+/// ```
+///
+/// # Panics
+///
+/// Panics if `out` holds fewer than `2 * bytes.len()` bytes.
+pub fn encode_to_slice(bytes: &[u8], out: &mut [u8]) -> usize {
+    assert!(
+        out.len() >= 2 * bytes.len(),
+        "output buffer must hold two digits per byte"
+    );
+    let out = &mut out[..2 * bytes.len()];
+
+    let done = simd::hex_encode(bytes, out);
+    for (&byte, digits) in bytes[done..]
         .iter()
-        .flat_map(|&byte| {
-            // Split byte into two 4-bit nibbles
-            let high = byte >> 4;
-            let low = byte & 0b1111;
-            vec![high, low]
-        })
-        .map(|digit| char::from_digit(digit as u32, 16).expect("nibble is always < 16"));
+        .zip(out[2 * done..].chunks_exact_mut(2))
+    {
+        digits.copy_from_slice(&ENCODE_TABLE[byte as usize]);
+    }
+    out.len()
+}
 
-    String::from_iter(chars).to_uppercase()
+/// Decodes hex into a caller-provided buffer, like [`decode`] without
+/// allocating.
+///
+/// Digits are looked up in a table, or decoded 32 or 64 at a time with
+/// SSSE3 or AVX2 when the CPU supports them. Returns the number of bytes
+/// written.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hex::decode_to_slice;
+///
+/// let mut out = [0; 5];
+/// assert_eq!(decode_to_slice("48656c6C6f", &mut out).unwrap(), 5);
+/// assert_eq!(&out, b"Hello");
+/// ```
+///
+/// # Errors
+///
+/// Returns the same errors as [`decode`]. Part of `out` may have been
+/// written.
+///
+/// # Panics
+///
+/// Panics if `out` holds fewer than `hex.len() / 2` bytes.
+pub fn decode_to_slice(hex: &str, out: &mut [u8]) -> Result<usize, ParsingError> {
+    assert!(
+        out.len() >= hex.len() / 2,
+        "output buffer must hold a byte per two digits"
+    );
+    let digits = hex.as_bytes();
+    let out = &mut out[..digits.len() / 2];
+
+    let done = simd::hex_decode(digits, out);
+    let mut invalid = false;
+    for (pair, byte) in digits[2 * done..]
+        .chunks_exact(2)
+        .zip(out[done..].iter_mut())
+    {
+        let (high, low) = (
+            DECODE_TABLE[pair[0] as usize],
+            DECODE_TABLE[pair[1] as usize],
+        );
+        invalid |= high == INVALID || low == INVALID;
+        *byte = high << 4 | low;
+    }
+
+    if invalid || !digits.len().is_multiple_of(2) {
+        return Err(decode(hex).expect_err("table decoding only rejects what decode rejects"));
+    }
+    Ok(out.len())
+}
+
+/// Hex output layout: digit case, prefixes and separators between groups.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hex::Formatter;
+///
+/// let bytes = [0xDE, 0xAD, 0xBE, 0xEF];
+/// assert_eq!(Formatter::new().lowercase().format(&bytes), "deadbeef");
+/// assert_eq!(Formatter::new().grouped(1, ":").format(&bytes), "DE:AD:BE:EF");
+/// assert_eq!(Formatter::new().grouped(2, " ").format(&bytes), "DEAD BEEF");
+///
+/// // a C array
+/// let c_array = Formatter::new().lowercase().prefixed("0x").grouped(1, ", ");
+/// assert_eq!(c_array.format(&bytes), "0xde, 0xad, 0xbe, 0xef");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Formatter {
+    pub lowercase: bool,
+    /// Written before every group.
+    pub prefix: &'static str,
+    /// Bytes per group; all bytes form one group when unset.
+    pub group_size: Option<usize>,
+    /// Written between groups, never after the last one.
+    pub separator: &'static str,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter {
+    /// Uppercase digits without prefixes or separators, like [`encode`].
+    pub const fn new() -> Self {
+        Formatter {
+            lowercase: false,
+            prefix: "",
+            group_size: None,
+            separator: "",
+        }
+    }
+
+    /// Returns the same formatter, writing digits `a`-`f` in lowercase.
+    pub const fn lowercase(self) -> Self {
+        Formatter {
+            lowercase: true,
+            ..self
+        }
+    }
+
+    /// Returns the same formatter, writing `prefix` before every group.
+    pub const fn prefixed(self, prefix: &'static str) -> Self {
+        Formatter { prefix, ..self }
+    }
+
+    /// Returns the same formatter, splitting the output into groups of
+    /// `size` bytes joined by `separator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub const fn grouped(self, size: usize, separator: &'static str) -> Self {
+        assert!(size > 0, "group size must be positive");
+        Formatter {
+            group_size: Some(size),
+            separator,
+            ..self
+        }
+    }
+
+    /// Formats the bytes; empty input gives an empty string.
+    pub fn format(&self, bytes: &[u8]) -> String {
+        let digits = if self.lowercase {
+            b"0123456789abcdef"
+        } else {
+            b"0123456789ABCDEF"
+        };
+        let group_size = self.group_size.unwrap_or(bytes.len()).max(1);
+
+        let mut formatted = String::with_capacity(
+            2 * bytes.len()
+                + bytes.len().div_ceil(group_size) * (self.prefix.len() + self.separator.len()),
+        );
+        for (idx, group) in bytes.chunks(group_size).enumerate() {
+            if idx > 0 {
+                formatted.push_str(self.separator);
+            }
+            formatted.push_str(self.prefix);
+            for &byte in group {
+                formatted.push(digits[(byte >> 4) as usize] as char);
+                formatted.push(digits[(byte & 0b1111) as usize] as char);
+            }
+        }
+        formatted
+    }
 }
 
 /// Convert regular text to hex encoded text
@@ -103,6 +447,214 @@ pub fn encode_text(text: &str) -> String {
     encode(bytes)
 }
 
+/// Bytes fed to the wrapped reader or writer per call.
+const STREAM_CHUNK_SIZE: usize = 8192;
+
+/// Streaming hex encoder over a [`Write`] sink.
+///
+/// Every byte written is forwarded as two uppercase hex digits, so nothing
+/// is held back and no `finish` step is needed.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use cryptopals::encoding::hex::Encoder;
+///
+/// let mut encoder = Encoder::new(Vec::new());
+/// encoder.write_all(b"Hel").unwrap();
+/// encoder.write_all(b"lo").unwrap();
+/// assert_eq!(encoder.into_inner(), b"48656C6C6F");
+/// ```
+pub struct Encoder<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(inner: W) -> Self {
+        Encoder { inner }
+    }
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let chunk = &buf[..buf.len().min(STREAM_CHUNK_SIZE / 2)];
+        self.inner.write_all(encode(chunk).as_bytes())?;
+        Ok(chunk.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Streaming hex decoder over a [`Read`] source.
+///
+/// Digit pairs may be split across reads of the wrapped reader. Invalid input
+/// surfaces as an [`io::ErrorKind::InvalidData`] error wrapping a
+/// [`ParsingError`], whose offset counts bytes from the start of the stream.
+/// Bytes decoded before the error are returned first; the error comes with
+/// the next read.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use cryptopals::encoding::hex::Decoder;
+///
+/// let mut decoded = Vec::new();
+/// Decoder::new("4865 6c6c\n6f".as_bytes())
+///     .ignoring_whitespace()
+///     .read_to_end(&mut decoded)
+///     .unwrap();
+/// assert_eq!(decoded, b"Hello");
+/// ```
+pub struct Decoder<R: Read> {
+    inner: R,
+    ignore_whitespace: bool,
+    /// High nibble of a byte whose low nibble has not been read yet, with
+    /// the digit and its offset.
+    pending_nibble: Option<(u8, u8, usize)>,
+    /// Digits decoded so far.
+    digits: usize,
+    /// Bytes consumed from the wrapped reader so far.
+    offset: usize,
+    buffer: Vec<u8>,
+    /// An error held back until the bytes decoded before it are read.
+    error: Option<io::Error>,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(inner: R) -> Self {
+        Decoder {
+            inner,
+            ignore_whitespace: false,
+            pending_nibble: None,
+            digits: 0,
+            offset: 0,
+            buffer: vec![0; STREAM_CHUNK_SIZE],
+            error: None,
+        }
+    }
+
+    /// Returns the same decoder, skipping ASCII whitespace in the input.
+    pub fn ignoring_whitespace(self) -> Self {
+        Decoder {
+            ignore_whitespace: true,
+            ..self
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+fn invalid_data(kind: ErrorKind, input: String, offset: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        ParsingError::new(ParsingDirection::Decoding, Encoding::Hex, kind, input)
+            .at_input_offset(offset),
+    )
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        if out.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            // two digits per output byte, so the output can never overflow
+            let wanted = (2 * out.len()).min(self.buffer.len());
+            let read = self.inner.read(&mut self.buffer[..wanted])?;
+            if read == 0 {
+                return match self.pending_nibble {
+                    None => Ok(0),
+                    Some((_, digit, offset)) => Err(invalid_data(
+                        ErrorKind::InvalidLength {
+                            length: self.digits,
+                        },
+                        (digit as char).to_string(),
+                        offset,
+                    )),
+                };
+            }
+
+            let mut written = 0;
+            for (index, &byte) in self.buffer[..read].iter().enumerate() {
+                let offset = self.offset + index;
+                if self.ignore_whitespace && byte.is_ascii_whitespace() {
+                    continue;
+                }
+                let Some(digit) = (byte as char).to_digit(16) else {
+                    let char =
+                        stream::char_at(byte, &self.buffer[index + 1..read], &mut self.inner);
+                    let error = invalid_data(
+                        ErrorKind::InvalidCharacter { offset, char },
+                        char.to_string(),
+                        offset,
+                    );
+                    if written == 0 {
+                        return Err(error);
+                    }
+                    self.error = Some(error);
+                    return Ok(written);
+                };
+                self.digits += 1;
+
+                match self.pending_nibble.take() {
+                    None => self.pending_nibble = Some((digit as u8, byte, offset)),
+                    Some((high, _, _)) => {
+                        out[written] = high << 4 | digit as u8;
+                        written += 1;
+                    }
+                }
+            }
+            self.offset += read;
+
+            if written > 0 {
+                return Ok(written);
+            }
+        }
+    }
+}
+
+/// Hex as a [`Codec`]: uppercase output, digits of either case as input.
+#[derive(Debug, Clone, Copy)]
+pub struct Hex;
+
+impl Codec for Hex {
+    fn encoding(&self) -> Encoding {
+        Encoding::Hex
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        char.is_ascii_hexdigit()
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        Ok(encode(bytes))
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        decode(encoded)
+    }
+
+    fn is_valid(&self, encoded: &str) -> bool {
+        is_valid(encoded)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +667,88 @@ mod tests {
             let encoded = encode(&decoded);
             assert_eq!(s.to_uppercase(), encoded);
         }
+
+        #[test]
+        fn to_slice_matches(bytes in proptest::collection::vec(any::<u8>(), 0..200)) {
+            let mut out = vec![0; 2 * bytes.len()];
+            encode_to_slice(&bytes, &mut out);
+            assert_eq!(out, encode(&bytes).as_bytes());
+            let mut decoded = vec![0; bytes.len()];
+            decode_to_slice(&encode(&bytes), &mut decoded).unwrap();
+            assert_eq!(decoded, bytes);
+        }
+
+        #[test]
+        fn constant_time_matches(bytes in proptest::collection::vec(any::<u8>(), 0..100), s in "[0-9A-Za-z ]{0,20}") {
+            assert_eq!(encode_constant_time(&bytes), encode(&bytes));
+            assert_eq!(decode_constant_time(&encode(&bytes)).unwrap(), bytes);
+            assert_eq!(decode_constant_time(&s), decode(&s));
+        }
+    }
+
+    #[test]
+    fn test_to_slice() {
+        // long enough for the SIMD kernels, with a tail for the table
+        let bytes: Vec<u8> = (0..=u8::MAX).chain(0..77).collect();
+        let mut out = vec![0; 2 * bytes.len() + 3];
+        assert_eq!(encode_to_slice(&bytes, &mut out), 2 * bytes.len());
+        assert_eq!(out[..2 * bytes.len()], *encode(&bytes).as_bytes());
+        assert_eq!(out[2 * bytes.len()..], [0; 3]);
+
+        let hex = encode(&bytes).to_lowercase();
+        let mut decoded = vec![0; bytes.len()];
+        assert_eq!(decode_to_slice(&hex, &mut decoded).unwrap(), bytes.len());
+        assert_eq!(decoded, bytes);
+
+        // errors in a SIMD block and in the tail
+        for offset in [5, 2 * bytes.len() - 1] {
+            let mut broken = hex.clone();
+            broken.replace_range(offset..offset + 1, "x");
+            assert_eq!(
+                decode_to_slice(&broken, &mut decoded).unwrap_err(),
+                decode(&broken).unwrap_err()
+            );
+        }
+        assert_eq!(
+            decode_to_slice("ABC", &mut decoded).unwrap_err().kind,
+            ErrorKind::InvalidLength { length: 3 }
+        );
+    }
+
+    #[test]
+    fn test_constant_time_digits() {
+        for char in 0..=u8::MAX {
+            let (value, valid) = decode_digit_constant_time(char);
+            match (char as char).to_digit(16) {
+                Some(digit) => assert_eq!((value, valid), (digit as u8, 0xFF)),
+                None => assert_eq!(valid, 0, "{:?} is not a digit", char as char),
+            }
+        }
+        for nibble in 0..16 {
+            assert_eq!(
+                encode_digit_constant_time(nibble),
+                b"0123456789ABCDEF"[nibble as usize]
+            );
+        }
+        assert_eq!(
+            decode_constant_time("éa").unwrap_err(),
+            decode("éa").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_constant_time_decoding_reads_every_digit() {
+        // the digits go through the same arithmetic whatever their value, so
+        // the work depends on the input only through the calls made
+        for hex in ["00000000", "fFfF1234", "x0000000", "0000000x", "é0000"] {
+            let mut digits = Vec::new();
+            let result = decode_constant_time_with(hex, |char| {
+                digits.push(char);
+                decode_digit_constant_time(char)
+            });
+            assert_eq!(digits, hex.as_bytes(), "{hex:?}");
+            assert_eq!(result, decode(hex));
+        }
     }
 
     #[test]
@@ -148,6 +782,201 @@ mod tests {
         assert_eq!(upper, lower);
     }
 
+    #[test]
+    fn test_decode_reports_invalid_character() {
+        let error = decode("DEADBEEG").unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::InvalidCharacter {
+                offset: 7,
+                char: 'G'
+            }
+        );
+
+        // offsets are byte offsets into the input
+        let error = decode("AB\u{e9}F").unwrap_err();
+        assert_eq!(error.kind.offset(), Some(2));
+
+        // odd length has no character to blame
+        let error = decode("ABC").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidLength { length: 3 });
+    }
+
+    #[test]
+    fn test_formatter() {
+        let bytes = [0x00, 0x1F, 0xA0, 0xFF, 0x7E];
+
+        assert_eq!(Formatter::new().format(&bytes), "001FA0FF7E");
+        assert_eq!(Formatter::new().lowercase().format(&bytes), "001fa0ff7e");
+        assert_eq!(
+            Formatter::new().prefixed("0x").format(&bytes),
+            "0x001FA0FF7E"
+        );
+        // the last group may be short
+        assert_eq!(
+            Formatter::new().grouped(2, " ").format(&bytes),
+            "001F A0FF 7E"
+        );
+        assert_eq!(
+            Formatter::new()
+                .prefixed("\\x")
+                .grouped(1, "")
+                .lowercase()
+                .format(&bytes[..2]),
+            "\\x00\\x1f"
+        );
+        assert_eq!(Formatter::new().prefixed("0x").format(&[]), "");
+    }
+
+    #[test]
+    fn test_decode_ignoring_whitespace() {
+        assert_eq!(
+            decode_ignoring_whitespace(" DE AD\r\nBE\tEF\n").unwrap(),
+            [0xDE, 0xAD, 0xBE, 0xEF]
+        );
+        assert!(decode("DE AD").is_err());
+
+        // odd digit count across whitespace still fails
+        assert!(decode_ignoring_whitespace("DE A").is_err());
+
+        let error = decode_ignoring_whitespace("DE\nAZ").unwrap_err();
+        assert_eq!(error.kind.offset(), Some(4));
+    }
+
+    /// Reader that hands out at most `step` bytes per call.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.step.min(buf.len()).min(self.bytes.len());
+            buf[..count].copy_from_slice(&self.bytes[..count]);
+            self.bytes = &self.bytes[count..];
+            Ok(count)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn streaming_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..300), step in 1..20usize) {
+            let mut encoder = Encoder::new(Vec::new());
+            for chunk in bytes.chunks(step) {
+                encoder.write_all(chunk).unwrap();
+            }
+            let encoded = encoder.into_inner();
+            assert_eq!(encoded, encode(&bytes).as_bytes());
+
+            let mut decoded = Vec::new();
+            Decoder::new(Trickle { bytes: &encoded, step })
+                .read_to_end(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, bytes);
+        }
+    }
+
+    #[test]
+    fn test_streaming_decoder_errors() {
+        let mut decoded = Vec::new();
+        let error = Decoder::new(Trickle {
+            bytes: b"DEAD\nBEEF",
+            step: 3,
+        })
+        .read_to_end(&mut decoded)
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let parsing_error = error
+            .into_inner()
+            .unwrap()
+            .downcast::<ParsingError>()
+            .unwrap();
+        assert_eq!(parsing_error.kind.offset(), Some(4));
+        // the byte decoded in the same read as the error is not lost
+        assert_eq!(decoded, [0xDE, 0xAD]);
+
+        // a dangling digit at the end of the stream
+        let mut decoded = Vec::new();
+        let error = Decoder::new(&b"DEADB"[..])
+            .read_to_end(&mut decoded)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(decoded, [0xDE, 0xAD]);
+        let parsing_error = error
+            .into_inner()
+            .unwrap()
+            .downcast::<ParsingError>()
+            .unwrap();
+        assert_eq!(parsing_error.kind, ErrorKind::InvalidLength { length: 5 });
+        assert_eq!(
+            (parsing_error.input.as_str(), parsing_error.input_offset),
+            ("B", 4)
+        );
+        assert!(parsing_error.to_string().contains('B'));
+    }
+
+    #[test]
+    fn test_streaming_decoder_non_ascii() {
+        // the character is put back together across reads
+        let mut decoded = Vec::new();
+        let error = Decoder::new(Trickle {
+            bytes: "AB\u{e9}F".as_bytes(),
+            step: 3,
+        })
+        .read_to_end(&mut decoded)
+        .unwrap_err();
+        let parsing_error = error
+            .into_inner()
+            .unwrap()
+            .downcast::<ParsingError>()
+            .unwrap();
+        assert_eq!(parsing_error.kind, decode("AB\u{e9}F").unwrap_err().kind);
+        assert_eq!(decoded, [0xAB]);
+
+        let mut decoded = Vec::new();
+        let error = Decoder::new(&b"AB\xFFF"[..])
+            .read_to_end(&mut decoded)
+            .unwrap_err();
+        let parsing_error = error
+            .into_inner()
+            .unwrap()
+            .downcast::<ParsingError>()
+            .unwrap();
+        assert_eq!(
+            parsing_error.kind,
+            ErrorKind::InvalidCharacter {
+                offset: 2,
+                char: '\u{FFFD}'
+            }
+        );
+    }
+
+    #[test]
+    fn test_whitespace_is_ascii() {
+        let spaced = "DE\u{2003}AD";
+        assert!(decode_ignoring_whitespace(spaced).is_err());
+        let mut decoded = Vec::new();
+        assert!(
+            Decoder::new(spaced.as_bytes())
+                .ignoring_whitespace()
+                .read_to_end(&mut decoded)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_streaming_decoder_small_reads() {
+        let mut decoder = Decoder::new(&b"DEADBEEF"[..]);
+        let mut byte = [0u8; 1];
+
+        let mut decoded = Vec::new();
+        while decoder.read(&mut byte).unwrap() == 1 {
+            decoded.push(byte[0]);
+        }
+        assert_eq!(decoded, [0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+
     #[test]
     fn test_text_encode() {
         assert_eq!(
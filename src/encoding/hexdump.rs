@@ -0,0 +1,306 @@
+//! Hex dumps in the layouts of `hexdump -C` and `xxd`.
+//!
+//! Both print 16 bytes per line after the offset of the first one, with an
+//! ASCII gutter showing printable bytes and `.` for the rest:
+//!
+//! ```text
+//! hexdump -C: 00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a        |Hello, World!.|
+//! xxd:        00000000: 4865 6c6c 6f2c 2057 6f72 6c64 210a  Hello, World!.
+//! ```
+//!
+//! `hexdump -C` also collapses runs of identical lines into a `*` and ends
+//! with a line holding only the total length. [`parse`] reads either layout
+//! back, whatever the grouping or line width.
+
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+use crate::encoding::hex::{self, Formatter};
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Which tool's output to imitate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// `hexdump -C`: byte groups, a `|`-delimited gutter and `*` for repeats.
+    Hexdump,
+    /// `xxd`: two-byte groups and a plain gutter.
+    Xxd,
+}
+
+fn gutter(line: &[u8]) -> String {
+    line.iter()
+        .map(|&byte| match byte {
+            0x20..=0x7E => byte as char,
+            _ => '.',
+        })
+        .collect()
+}
+
+/// Dumps the bytes in the given style, one `\n`-terminated line per 16 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hexdump::{Style, dump};
+///
+/// assert_eq!(
+///     dump(b"YELLOW SUBMARINE!", Style::Xxd),
+///     "00000000: 5945 4c4c 4f57 2053 5542 4d41 5249 4e45  YELLOW SUBMARINE\n\
+///      00000010: 21                                       !\n"
+/// );
+/// ```
+pub fn dump(bytes: &[u8], style: Style) -> String {
+    let mut dumped = String::new();
+    let mut previous: Option<&[u8]> = None;
+    let mut squeezing = false;
+
+    for (idx, line) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let offset = idx * BYTES_PER_LINE;
+        match style {
+            Style::Hexdump => {
+                if previous == Some(line) {
+                    if !squeezing {
+                        dumped.push_str("*\n");
+                        squeezing = true;
+                    }
+                    continue;
+                }
+                squeezing = false;
+                previous = Some(line);
+
+                let bytes = Formatter::new().lowercase().grouped(1, " ");
+                let (left, right) = line.split_at(line.len().min(8));
+                let mut hex = bytes.format(left);
+                if !right.is_empty() {
+                    hex.push_str("  ");
+                    hex.push_str(&bytes.format(right));
+                }
+                dumped.push_str(&format!(
+                    "{:08x}  {:<48}  |{}|\n",
+                    offset,
+                    hex,
+                    gutter(line)
+                ));
+            }
+            Style::Xxd => {
+                let hex = Formatter::new().lowercase().grouped(2, " ").format(line);
+                dumped.push_str(&format!("{:08x}: {:<39}  {}\n", offset, hex, gutter(line)));
+            }
+        }
+    }
+
+    if style == Style::Hexdump && !bytes.is_empty() {
+        dumped.push_str(&format!("{:08x}\n", bytes.len()));
+    }
+    dumped
+}
+
+/// Parses a `hexdump -C` or `xxd` dump back into bytes.
+///
+/// The gutter is ignored, so any grouping and line width is accepted.
+/// Offsets must continue where the previous line ended.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::hexdump::parse;
+///
+/// let pasted = "\
+/// 00000000  41 41 41 41 41 41 41 41  41 41 41 41 41 41 41 41  |AAAAAAAAAAAAAAAA|
+/// *
+/// 00000020  42 0a                                             |B.|
+/// 00000022
+/// ";
+/// let bytes = parse(pasted).unwrap();
+/// assert_eq!(bytes.len(), 0x22);
+/// assert!(bytes.ends_with(b"AAB\n"));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ErrorKind::InvalidCharacter`] for a non-hexadecimal character
+/// in an offset or in the bytes, and [`ErrorKind::InvalidLength`] with the
+/// number of bytes read so far if an offset does not follow on from them,
+/// or if a squeezed run is too long to allocate.
+pub fn parse(dump: &str) -> Result<Vec<u8>, ParsingError> {
+    let error = |kind| {
+        ParsingError::new(
+            ParsingDirection::Decoding,
+            Encoding::Hex,
+            kind,
+            dump.to_owned(),
+        )
+    };
+
+    let mut bytes = Vec::new();
+    let mut previous_line: Vec<u8> = Vec::new();
+    let mut squeezed = false;
+    let mut line_start = 0;
+
+    for line in dump.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "*" {
+            squeezed = true;
+            continue;
+        }
+
+        let (offset_field, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let is_xxd = offset_field.ends_with(':');
+        let offset_field = offset_field.trim_end_matches(':');
+        let offset = usize::from_str_radix(offset_field, 16).map_err(|_| {
+            let (idx, char) = offset_field
+                .char_indices()
+                .find(|(_, char)| !char.is_ascii_hexdigit())
+                .unwrap_or((0, offset_field.chars().next().unwrap_or(' ')));
+            error(ErrorKind::InvalidCharacter {
+                offset: start + idx,
+                char,
+            })
+        })?;
+
+        // a squeezed run repeats the line before it up to this offset
+        if squeezed {
+            squeezed = false;
+            let gap = offset.checked_sub(bytes.len()).unwrap_or(usize::MAX);
+            // the offset comes from the input, so it may be far too large
+            if previous_line.is_empty()
+                || gap % previous_line.len() != 0
+                || bytes.try_reserve(gap).is_err()
+            {
+                return Err(error(ErrorKind::InvalidLength {
+                    length: bytes.len(),
+                }));
+            }
+            for _ in 0..gap / previous_line.len() {
+                bytes.extend_from_slice(&previous_line);
+            }
+        }
+        if offset != bytes.len() {
+            return Err(error(ErrorKind::InvalidLength {
+                length: bytes.len(),
+            }));
+        }
+
+        // the bytes end where the gutter starts
+        let hex_end = if is_xxd {
+            rest.find("  ")
+        } else {
+            rest.find('|')
+        }
+        .unwrap_or(rest.len());
+        let hex_start = start + offset_field.len() + if is_xxd { 2 } else { 1 };
+
+        let line_bytes = hex::decode_chars(
+            dump,
+            rest[..hex_end]
+                .char_indices()
+                .filter(|(_, char)| !char.is_whitespace())
+                .map(|(idx, char)| (hex_start + idx, char)),
+        )?;
+        bytes.extend_from_slice(&line_bytes);
+        previous_line = line_bytes;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const TEXT: &[u8] = b"Hello, World!\nThis is a test of the dump format\x00\x01\xff";
+
+    proptest! {
+        #[test]
+        fn dump_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..200)) {
+            assert_eq!(parse(&dump(&bytes, Style::Hexdump)).unwrap(), bytes);
+            assert_eq!(parse(&dump(&bytes, Style::Xxd)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_xxd_layout() {
+        // output of `xxd`
+        let expected = concat!(
+            "00000000: 4865 6c6c 6f2c 2057 6f72 6c64 210a 5468  Hello, World!.Th\n",
+            "00000010: 6973 2069 7320 6120 7465 7374 206f 6620  is is a test of \n",
+            "00000020: 7468 6520 6475 6d70 2066 6f72 6d61 7400  the dump format.\n",
+            "00000030: 01ff                                     ..\n",
+        );
+        assert_eq!(dump(TEXT, Style::Xxd), expected);
+        assert_eq!(parse(expected).unwrap(), TEXT);
+        assert_eq!(dump(&[], Style::Xxd), "");
+    }
+
+    #[test]
+    fn test_hexdump_layout() {
+        // output of `hexdump -C`
+        let expected = "\
+00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a 54 68  |Hello, World!.Th|
+00000010  69 73 20 69 73 20 61 20  74 65 73 74 20 6f 66 20  |is is a test of |
+00000020  74 68 65 20 64 75 6d 70  20 66 6f 72 6d 61 74 00  |the dump format.|
+00000030  01 ff                                             |..|
+00000032
+";
+        assert_eq!(dump(TEXT, Style::Hexdump), expected);
+        assert_eq!(parse(expected).unwrap(), TEXT);
+        assert_eq!(dump(&[], Style::Hexdump), "");
+    }
+
+    #[test]
+    fn test_hexdump_squeezes_repeats() {
+        let bytes = [[b'A'; 48].as_slice(), b"BBBBB"].concat();
+        let expected = "\
+00000000  41 41 41 41 41 41 41 41  41 41 41 41 41 41 41 41  |AAAAAAAAAAAAAAAA|
+*
+00000030  42 42 42 42 42                                    |BBBBB|
+00000035
+";
+        assert_eq!(dump(&bytes, Style::Hexdump), expected);
+        assert_eq!(parse(expected).unwrap(), bytes);
+
+        // a squeezed run at the very end is closed by the length line
+        let zeros = [0; 64];
+        assert_eq!(parse(&dump(&zeros, Style::Hexdump)).unwrap(), zeros);
+    }
+
+    #[test]
+    fn test_parse_other_groupings() {
+        // `xxd -g 1 -c 8` and CRLF line endings
+        let dump = "00000000: 59 45 4c 4c 4f 57 20 53  YELLOW S\r\n00000008: 55 42  UB\r\n";
+        assert_eq!(parse(dump).unwrap(), b"YELLOW SUB");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("00000000: 4865 6c6g  Hel?\n").unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::InvalidCharacter {
+                offset: 18,
+                char: 'g'
+            }
+        );
+
+        let error = parse("0000000x: 4865\n").unwrap_err();
+        assert_eq!(error.kind.offset(), Some(7));
+
+        // a missing line
+        let error = parse("00000000: 4865  He\n00000010: 6c6c  ll\n").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidLength { length: 2 });
+
+        // a squeezed run up to an offset that cannot be allocated
+        let dump = "\
+00000000  41 41 41 41 41 41 41 41  41 41 41 41 41 41 41 41  |AAAAAAAAAAAAAAAA|
+*
+fffffffffffffff0
+";
+        let error = parse(dump).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidLength { length: 16 });
+    }
+}
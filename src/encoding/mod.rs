@@ -1,11 +1,27 @@
 //! Encoding and decoding utilities.
 //!
-//! This module provides conversions between different data representations,
-//! including hexadecimal and Base64 encoding.
+//! This module provides conversions between different data representations:
+//! hexadecimal, Base32, Base58, Base64, Ascii85 and Z85. Each implements
+//! [`Codec`], and [`detect`] guesses which one an unknown string uses.
+//!
+//! [`pem`] and [`der`] read and write RSA keys in the formats of standard
+//! tooling.
 
+pub mod ascii85;
+pub mod base32;
+pub mod base58;
 pub mod base64;
+mod codec;
+pub mod der;
 pub mod error;
 pub mod hex;
+pub mod hexdump;
+pub mod pem;
+mod simd;
+mod stream;
+pub mod z85;
+
+pub use codec::{Codec, Guess, detect};
 
 /// Converts a hexadecimal string to Base64 encoding.
 ///
@@ -0,0 +1,230 @@
+//! PEM, the text armor around keys and certificates.
+//!
+//! This module implements the textual encoding of
+//! [RFC 7468](https://datatracker.ietf.org/doc/html/rfc7468): binary data,
+//! usually DER, written as Base64 in lines of 64 characters between a
+//! `-----BEGIN <label>-----` and a matching `-----END <label>-----` line.
+//! Text outside these lines is ignored, and the RFC 1421 headers of
+//! encrypted keys are not supported.
+
+use crate::encoding::base64::Engine;
+use crate::encoding::base64::STANDARD;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+
+/// Base64 as written in PEM bodies.
+const BODY: Engine = STANDARD.with_line_wrap(64, "\n");
+
+const DASHES: &str = "-----";
+
+/// One block of a PEM file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pem {
+    /// The label of the boundary lines, such as `RSA PRIVATE KEY`.
+    pub label: String,
+    pub contents: Vec<u8>,
+}
+
+/// Encodes the contents as a PEM block with the given label.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::pem::encode;
+///
+/// assert_eq!(
+///     encode("MESSAGE", b"hello"),
+///     "-----BEGIN MESSAGE-----\naGVsbG8=\n-----END MESSAGE-----\n"
+/// );
+/// ```
+pub fn encode(label: &str, contents: &[u8]) -> String {
+    let mut encoded = format!("{DASHES}BEGIN {label}{DASHES}\n");
+    if !contents.is_empty() {
+        encoded.push_str(&BODY.encode(contents));
+        encoded.push('\n');
+    }
+    encoded.push_str(&format!("{DASHES}END {label}{DASHES}\n"));
+    encoded
+}
+
+/// Decodes the first PEM block in the input.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::pem::decode;
+///
+/// let pem = decode("Subject: greeting\n-----BEGIN MESSAGE-----\naGVsbG8=\n-----END MESSAGE-----\n").unwrap();
+/// assert_eq!(pem.label, "MESSAGE");
+/// assert_eq!(pem.contents, b"hello");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ErrorKind::InvalidBoundary`] if there is no block, or its
+/// boundary lines are malformed or do not match, and the errors of
+/// [`base64::Engine::decode`](crate::encoding::base64::Engine::decode) for
+/// the body.
+pub fn decode(input: &str) -> Result<Pem, ParsingError> {
+    match decode_from(input, 0)? {
+        Some((pem, _)) => Ok(pem),
+        None => Err(error(input, ErrorKind::InvalidBoundary { offset: 0 })),
+    }
+}
+
+/// Decodes every PEM block in the input, such as a certificate chain.
+///
+/// # Errors
+///
+/// Returns the same errors as [`decode`] for the first malformed block.
+pub fn decode_all(input: &str) -> Result<Vec<Pem>, ParsingError> {
+    let mut blocks = Vec::new();
+    let mut position = 0;
+    while let Some((pem, end)) = decode_from(input, position)? {
+        blocks.push(pem);
+        position = end;
+    }
+    Ok(blocks)
+}
+
+fn error(input: &str, kind: ErrorKind) -> ParsingError {
+    ParsingError::new(
+        ParsingDirection::Decoding,
+        Encoding::Pem,
+        kind,
+        input.to_owned(),
+    )
+}
+
+/// Decodes the first block at or after `position`, returning it with the
+/// offset just past its END line.
+fn decode_from(input: &str, position: usize) -> Result<Option<(Pem, usize)>, ParsingError> {
+    let begin_marker = format!("{DASHES}BEGIN ");
+    let Some(begin) = input[position..]
+        .find(&begin_marker)
+        .map(|idx| position + idx)
+    else {
+        return Ok(None);
+    };
+
+    let label_start = begin + begin_marker.len();
+    let line_end = input[label_start..]
+        .find('\n')
+        .map_or(input.len(), |idx| label_start + idx);
+    let label = input[label_start..line_end]
+        .trim_end()
+        .strip_suffix(DASHES)
+        .ok_or_else(|| error(input, ErrorKind::InvalidBoundary { offset: begin }))?;
+
+    let body_start = (line_end + 1).min(input.len());
+    let end_marker = format!("{DASHES}END ");
+    let end = input[body_start..]
+        .find(&end_marker)
+        .map(|idx| body_start + idx)
+        .ok_or_else(|| {
+            error(
+                input,
+                ErrorKind::InvalidBoundary {
+                    offset: input.len(),
+                },
+            )
+        })?;
+    let end_line_end = input[end..].find('\n').map_or(input.len(), |idx| end + idx);
+    if input[end..end_line_end].trim_end() != format!("{end_marker}{label}{DASHES}") {
+        return Err(error(input, ErrorKind::InvalidBoundary { offset: end }));
+    }
+
+    let body = &input[body_start..end];
+    let contents = BODY.ignoring_whitespace().decode(body).map_err(|error| {
+        ParsingError {
+            encoding: Encoding::Pem,
+            kind: error.kind.shifted(body_start),
+            ..error
+        }
+        .at_input_offset(body_start)
+    })?;
+
+    let pem = Pem {
+        label: label.to_owned(),
+        contents,
+    };
+    Ok(Some((pem, end_line_end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn valid_pem_roundtrip(bytes in proptest::collection::vec(any::<u8>(), 0..300)) {
+            let pem = decode(&encode("DATA", &bytes)).unwrap();
+            assert_eq!(pem.label, "DATA");
+            assert_eq!(pem.contents, bytes);
+        }
+    }
+
+    #[test]
+    fn test_wraps_at_64_columns() {
+        let encoded = encode("DATA", &[0xAB; 100]);
+        let lengths: Vec<usize> = encoded.lines().map(str::len).collect();
+        assert_eq!(lengths, [20, 64, 64, 8, 18]);
+        assert_eq!(
+            encode("EMPTY", &[]),
+            "-----BEGIN EMPTY-----\n-----END EMPTY-----\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_all_with_crlf() {
+        let input = format!(
+            "first\r\n{}between\r\n{}",
+            encode("A", b"one").replace('\n', "\r\n"),
+            encode("B", b"two")
+        );
+        let blocks = decode_all(&input).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            (blocks[0].label.as_str(), &blocks[0].contents[..]),
+            ("A", &b"one"[..])
+        );
+        assert_eq!(
+            (blocks[1].label.as_str(), &blocks[1].contents[..]),
+            ("B", &b"two"[..])
+        );
+        assert!(decode_all("no blocks here").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode("nothing").unwrap_err().kind,
+            ErrorKind::InvalidBoundary { offset: 0 }
+        );
+        // END with another label
+        let input = "-----BEGIN A-----\nb25l\n-----END B-----\n";
+        assert_eq!(
+            decode(input).unwrap_err().kind,
+            ErrorKind::InvalidBoundary { offset: 23 }
+        );
+        let input = "-----BEGIN A-----\nb25l\n";
+        assert_eq!(
+            decode(input).unwrap_err().kind,
+            ErrorKind::InvalidBoundary { offset: 23 }
+        );
+
+        // body errors point into the whole input
+        let input = "-----BEGIN A-----\nb2*l\n-----END A-----\n";
+        let error = decode(input).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ErrorKind::InvalidCharacter {
+                offset: 20,
+                char: '*'
+            }
+        );
+        assert!(error.to_string().starts_with("pem decoding failed"));
+        assert!(error.to_string().contains("  | b2*l"));
+        assert!(error.to_string().ends_with("\n  |   ^"));
+    }
+}
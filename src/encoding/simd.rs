@@ -0,0 +1,590 @@
+//! SSSE3 and AVX2 kernels for the hex and Base64 `_to_slice` functions.
+//!
+//! Each kernel handles a prefix of whole blocks and returns how far it got;
+//! the caller finishes the rest with lookup tables. The widest instruction
+//! set the CPU supports is picked at runtime, and on other architectures, or
+//! without the `simd` feature, every kernel handles nothing.
+//!
+//! Decoding kernels stop at the first block holding a character they do not
+//! decode, so that the table code reports it or handles the padding.
+//!
+//! The Base64 kernels follow Wojciech Muła and Daniel Lemire, "Faster Base64
+//! Encoding and Decoding Using AVX2 Instructions" (2018).
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// The instruction sets the kernels can use.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(crate) enum Level {
+        Ssse3,
+        Avx2,
+    }
+
+    pub(crate) fn detect() -> Option<Level> {
+        if is_x86_feature_detected!("avx2") {
+            Some(Level::Avx2)
+        } else if is_x86_feature_detected!("ssse3") {
+            Some(Level::Ssse3)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the CPU supports the level, for tests that force one.
+    #[cfg(test)]
+    pub(crate) fn supports(level: Level) -> bool {
+        match level {
+            Level::Ssse3 => is_x86_feature_detected!("ssse3"),
+            Level::Avx2 => is_x86_feature_detected!("avx2"),
+        }
+    }
+
+    const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+    /// Loads 16 bytes from the start of the slice.
+    #[target_feature(enable = "ssse3")]
+    fn load(bytes: &[u8]) -> __m128i {
+        assert!(bytes.len() >= 16);
+        // SAFETY: the slice holds at least 16 bytes, and the load is unaligned
+        unsafe { _mm_loadu_si128(bytes.as_ptr().cast()) }
+    }
+
+    /// Stores 16 bytes at the start of the slice.
+    #[target_feature(enable = "ssse3")]
+    fn store(out: &mut [u8], value: __m128i) {
+        assert!(out.len() >= 16);
+        // SAFETY: the slice holds at least 16 bytes, and the store is unaligned
+        unsafe { _mm_storeu_si128(out.as_mut_ptr().cast(), value) }
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn load256(bytes: &[u8]) -> __m256i {
+        assert!(bytes.len() >= 32);
+        // SAFETY: the slice holds at least 32 bytes, and the load is unaligned
+        unsafe { _mm256_loadu_si256(bytes.as_ptr().cast()) }
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn store256(out: &mut [u8], value: __m256i) {
+        assert!(out.len() >= 32);
+        // SAFETY: the slice holds at least 32 bytes, and the store is unaligned
+        unsafe { _mm256_storeu_si256(out.as_mut_ptr().cast(), value) }
+    }
+
+    /// Encodes 16 bytes at a time into 32 uppercase digits.
+    #[target_feature(enable = "ssse3")]
+    pub(crate) fn hex_encode_ssse3(bytes: &[u8], out: &mut [u8]) -> usize {
+        let digits = load(HEX_DIGITS);
+        let low_nibbles = _mm_set1_epi8(0x0F);
+
+        let mut done = 0;
+        for (block, out) in bytes.chunks_exact(16).zip(out.chunks_exact_mut(32)) {
+            let block = load(block);
+            let high =
+                _mm_shuffle_epi8(digits, _mm_and_si128(_mm_srli_epi16(block, 4), low_nibbles));
+            let low = _mm_shuffle_epi8(digits, _mm_and_si128(block, low_nibbles));
+            store(&mut out[..16], _mm_unpacklo_epi8(high, low));
+            store(&mut out[16..], _mm_unpackhi_epi8(high, low));
+            done += 16;
+        }
+        done
+    }
+
+    /// Encodes 32 bytes at a time into 64 uppercase digits.
+    #[target_feature(enable = "avx2")]
+    pub(crate) fn hex_encode_avx2(bytes: &[u8], out: &mut [u8]) -> usize {
+        let digits = _mm256_broadcastsi128_si256(load(HEX_DIGITS));
+        let low_nibbles = _mm256_set1_epi8(0x0F);
+
+        let mut done = 0;
+        for (block, out) in bytes.chunks_exact(32).zip(out.chunks_exact_mut(64)) {
+            let block = load256(block);
+            let high = _mm256_shuffle_epi8(
+                digits,
+                _mm256_and_si256(_mm256_srli_epi16(block, 4), low_nibbles),
+            );
+            let low = _mm256_shuffle_epi8(digits, _mm256_and_si256(block, low_nibbles));
+            // unpacking works within 128-bit lanes, so put the lanes back in order
+            let first = _mm256_unpacklo_epi8(high, low);
+            let second = _mm256_unpackhi_epi8(high, low);
+            store256(
+                &mut out[..32],
+                _mm256_permute2x128_si256(first, second, 0x20),
+            );
+            store256(
+                &mut out[32..],
+                _mm256_permute2x128_si256(first, second, 0x31),
+            );
+            done += 32;
+        }
+        done
+    }
+
+    /// The values of 16 hex digits, or `None` if one is not a digit.
+    #[target_feature(enable = "ssse3")]
+    fn hex_digits_ssse3(chars: __m128i) -> Option<__m128i> {
+        // a digit is at most 9 past '0', a letter at most 5 past 'a' once lowercased
+        let digit = _mm_sub_epi8(chars, _mm_set1_epi8(b'0' as i8));
+        let is_digit = _mm_cmpeq_epi8(_mm_min_epu8(digit, _mm_set1_epi8(9)), digit);
+        let letter = _mm_sub_epi8(
+            _mm_or_si128(chars, _mm_set1_epi8(0x20)),
+            _mm_set1_epi8(b'a' as i8),
+        );
+        let is_letter = _mm_cmpeq_epi8(_mm_min_epu8(letter, _mm_set1_epi8(5)), letter);
+
+        if _mm_movemask_epi8(_mm_or_si128(is_digit, is_letter)) != 0xFFFF {
+            return None;
+        }
+        let letter = _mm_add_epi8(letter, _mm_set1_epi8(10));
+        Some(_mm_or_si128(
+            _mm_and_si128(is_digit, digit),
+            _mm_and_si128(is_letter, letter),
+        ))
+    }
+
+    /// Decodes 32 digits at a time into 16 bytes, stopping before the first
+    /// block that is not all digits. Returns the number of bytes written.
+    #[target_feature(enable = "ssse3")]
+    pub(crate) fn hex_decode_ssse3(hex: &[u8], out: &mut [u8]) -> usize {
+        // multiply the high digit of each pair by 16 and add the low one
+        let weights = _mm_set1_epi16(0x0110);
+
+        let mut done = 0;
+        for (block, out) in hex.chunks_exact(32).zip(out.chunks_exact_mut(16)) {
+            let (Some(first), Some(second)) = (
+                hex_digits_ssse3(load(&block[..16])),
+                hex_digits_ssse3(load(&block[16..])),
+            ) else {
+                break;
+            };
+            let first = _mm_maddubs_epi16(first, weights);
+            let second = _mm_maddubs_epi16(second, weights);
+            store(out, _mm_packus_epi16(first, second));
+            done += 16;
+        }
+        done
+    }
+
+    /// The values of 32 hex digits, or `None` if one is not a digit.
+    #[target_feature(enable = "avx2")]
+    fn hex_digits_avx2(chars: __m256i) -> Option<__m256i> {
+        let digit = _mm256_sub_epi8(chars, _mm256_set1_epi8(b'0' as i8));
+        let is_digit = _mm256_cmpeq_epi8(_mm256_min_epu8(digit, _mm256_set1_epi8(9)), digit);
+        let letter = _mm256_sub_epi8(
+            _mm256_or_si256(chars, _mm256_set1_epi8(0x20)),
+            _mm256_set1_epi8(b'a' as i8),
+        );
+        let is_letter = _mm256_cmpeq_epi8(_mm256_min_epu8(letter, _mm256_set1_epi8(5)), letter);
+
+        if _mm256_movemask_epi8(_mm256_or_si256(is_digit, is_letter)) != -1 {
+            return None;
+        }
+        let letter = _mm256_add_epi8(letter, _mm256_set1_epi8(10));
+        Some(_mm256_or_si256(
+            _mm256_and_si256(is_digit, digit),
+            _mm256_and_si256(is_letter, letter),
+        ))
+    }
+
+    /// Decodes 64 digits at a time into 32 bytes, stopping before the first
+    /// block that is not all digits. Returns the number of bytes written.
+    #[target_feature(enable = "avx2")]
+    pub(crate) fn hex_decode_avx2(hex: &[u8], out: &mut [u8]) -> usize {
+        let weights = _mm256_set1_epi16(0x0110);
+
+        let mut done = 0;
+        for (block, out) in hex.chunks_exact(64).zip(out.chunks_exact_mut(32)) {
+            let (Some(first), Some(second)) = (
+                hex_digits_avx2(load256(&block[..32])),
+                hex_digits_avx2(load256(&block[32..])),
+            ) else {
+                break;
+            };
+            let first = _mm256_maddubs_epi16(first, weights);
+            let second = _mm256_maddubs_epi16(second, weights);
+            // packing interleaves the lanes of both halves
+            let packed = _mm256_packus_epi16(first, second);
+            store256(out, _mm256_permute4x64_epi64(packed, 0b11_01_10_00));
+            done += 32;
+        }
+        done
+    }
+
+    /// What to add to each class of sextet to reach its character: `a`-`z`,
+    /// ten times `0`-`9`, the two characters of the alphabet, and `A`-`Z`.
+    #[target_feature(enable = "ssse3")]
+    fn base64_shifts(char_62: u8, char_63: u8) -> __m128i {
+        let digits = b'0'.wrapping_sub(52) as i8;
+        _mm_setr_epi8(
+            (b'a' - 26) as i8,
+            digits,
+            digits,
+            digits,
+            digits,
+            digits,
+            digits,
+            digits,
+            digits,
+            digits,
+            digits,
+            char_62.wrapping_sub(62) as i8,
+            char_63.wrapping_sub(63) as i8,
+            b'A' as i8,
+            0,
+            0,
+        )
+    }
+
+    /// Splits the first 12 bytes of each 16-byte lane into 16 sextets, one
+    /// per byte.
+    macro_rules! split_sextets {
+        ($and:ident, $shuffle:ident, $mulhi:ident, $mullo:ident, $or:ident, $set1_32:ident, $block:expr, $order:expr) => {{
+            // each 32-bit word gets the bytes [b, a, c, b] of a triplet
+            let block = $shuffle($block, $order);
+            let high = $mulhi($and(block, $set1_32(0x0FC0_FC00)), $set1_32(0x0400_0040));
+            let low = $mullo($and(block, $set1_32(0x003F_03F0)), $set1_32(0x0100_0010));
+            $or(high, low)
+        }};
+    }
+
+    /// Encodes 12 bytes at a time into 16 characters. Reads 16 bytes per
+    /// block, so stops 4 bytes early. Returns the number of bytes encoded.
+    #[target_feature(enable = "ssse3")]
+    pub(crate) fn base64_encode_ssse3(
+        bytes: &[u8],
+        out: &mut [u8],
+        char_62: u8,
+        char_63: u8,
+    ) -> usize {
+        let shifts = base64_shifts(char_62, char_63);
+        let order = _mm_setr_epi8(1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10);
+
+        let mut done = 0;
+        while bytes.len() - done >= 16 && out.len() - done / 3 * 4 >= 16 {
+            let sextets = split_sextets!(
+                _mm_and_si128,
+                _mm_shuffle_epi8,
+                _mm_mulhi_epu16,
+                _mm_mullo_epi16,
+                _mm_or_si128,
+                _mm_set1_epi32,
+                load(&bytes[done..]),
+                order
+            );
+            // 13 for A-Z, 0 for a-z and 1-12 for the rest
+            let classes = _mm_or_si128(
+                _mm_subs_epu8(sextets, _mm_set1_epi8(51)),
+                _mm_and_si128(
+                    _mm_cmpgt_epi8(_mm_set1_epi8(26), sextets),
+                    _mm_set1_epi8(13),
+                ),
+            );
+            let chars = _mm_add_epi8(_mm_shuffle_epi8(shifts, classes), sextets);
+            store(&mut out[done / 3 * 4..], chars);
+            done += 12;
+        }
+        done
+    }
+
+    /// Encodes 24 bytes at a time into 32 characters. Reads 28 bytes per
+    /// block, so stops 4 bytes early. Returns the number of bytes encoded.
+    #[target_feature(enable = "avx2")]
+    pub(crate) fn base64_encode_avx2(
+        bytes: &[u8],
+        out: &mut [u8],
+        char_62: u8,
+        char_63: u8,
+    ) -> usize {
+        let shifts = _mm256_broadcastsi128_si256(base64_shifts(char_62, char_63));
+        let order = _mm256_setr_epi8(
+            1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7, 10, 9, 11, 10, 1, 0, 2, 1, 4, 3, 5, 4, 7, 6, 8, 7,
+            10, 9, 11, 10,
+        );
+
+        let mut done = 0;
+        while bytes.len() - done >= 28 && out.len() - done / 3 * 4 >= 32 {
+            let block = _mm256_set_m128i(load(&bytes[done + 12..]), load(&bytes[done..]));
+            let sextets = split_sextets!(
+                _mm256_and_si256,
+                _mm256_shuffle_epi8,
+                _mm256_mulhi_epu16,
+                _mm256_mullo_epi16,
+                _mm256_or_si256,
+                _mm256_set1_epi32,
+                block,
+                order
+            );
+            let classes = _mm256_or_si256(
+                _mm256_subs_epu8(sextets, _mm256_set1_epi8(51)),
+                _mm256_and_si256(
+                    _mm256_cmpgt_epi8(_mm256_set1_epi8(26), sextets),
+                    _mm256_set1_epi8(13),
+                ),
+            );
+            let chars = _mm256_add_epi8(_mm256_shuffle_epi8(shifts, classes), sextets);
+            store256(&mut out[done / 3 * 4..], chars);
+            done += 24;
+        }
+        done
+    }
+
+    /// Lookup tables indexed by the low and high nibble of a character; a
+    /// character is valid Base64 if the two entries share no bit.
+    const BASE64_LOW_NIBBLE_CLASSES: [u8; 16] = [
+        0x15, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x13, 0x1A, 0x1B, 0x1B, 0x1B,
+        0x1A,
+    ];
+    const BASE64_HIGH_NIBBLE_CLASSES: [u8; 16] = [
+        0x10, 0x10, 0x01, 0x02, 0x04, 0x08, 0x04, 0x08, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+        0x10,
+    ];
+    /// What to add to a character to reach its sextet, by high nibble, with
+    /// `/` moved to index 1.
+    const BASE64_ROLLS: [u8; 16] = [0, 16, 19, 4, 191, 191, 185, 185, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    /// Decodes 16 standard-alphabet characters at a time into 12 bytes,
+    /// stopping before the first block with another character. Writes 16
+    /// bytes per block. Returns the number of characters decoded.
+    #[target_feature(enable = "ssse3")]
+    pub(crate) fn base64_decode_ssse3(encoded: &[u8], out: &mut [u8]) -> usize {
+        let low_classes = load(&BASE64_LOW_NIBBLE_CLASSES);
+        let high_classes = load(&BASE64_HIGH_NIBBLE_CLASSES);
+        let rolls = load(&BASE64_ROLLS);
+        let slash = _mm_set1_epi8(b'/' as i8);
+        let order = _mm_setr_epi8(2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1);
+
+        let mut done = 0;
+        while encoded.len() - done >= 16 && out.len() - done / 4 * 3 >= 16 {
+            let chars = load(&encoded[done..]);
+            // 0x2F keeps the nibble for the shuffles, and `/` for the comparison
+            let high_nibbles = _mm_and_si128(_mm_srli_epi32(chars, 4), slash);
+            let low_nibbles = _mm_and_si128(chars, slash);
+            let high = _mm_shuffle_epi8(high_classes, high_nibbles);
+            let low = _mm_shuffle_epi8(low_classes, low_nibbles);
+            let clear = _mm_cmpeq_epi8(_mm_and_si128(low, high), _mm_setzero_si128());
+            if _mm_movemask_epi8(clear) != 0xFFFF {
+                break;
+            }
+
+            let is_slash = _mm_cmpeq_epi8(chars, slash);
+            let roll = _mm_shuffle_epi8(rolls, _mm_add_epi8(is_slash, high_nibbles));
+            let sextets = _mm_add_epi8(chars, roll);
+
+            // join pairs of sextets, then pairs of those, then drop the gaps
+            let pairs = _mm_maddubs_epi16(sextets, _mm_set1_epi32(0x0140_0140));
+            let words = _mm_madd_epi16(pairs, _mm_set1_epi32(0x0001_1000));
+            store(&mut out[done / 4 * 3..], _mm_shuffle_epi8(words, order));
+            done += 16;
+        }
+        done
+    }
+
+    /// Decodes 32 standard-alphabet characters at a time into 24 bytes,
+    /// stopping before the first block with another character. Writes 32
+    /// bytes per block. Returns the number of characters decoded.
+    #[target_feature(enable = "avx2")]
+    pub(crate) fn base64_decode_avx2(encoded: &[u8], out: &mut [u8]) -> usize {
+        let low_classes = _mm256_broadcastsi128_si256(load(&BASE64_LOW_NIBBLE_CLASSES));
+        let high_classes = _mm256_broadcastsi128_si256(load(&BASE64_HIGH_NIBBLE_CLASSES));
+        let rolls = _mm256_broadcastsi128_si256(load(&BASE64_ROLLS));
+        let slash = _mm256_set1_epi8(b'/' as i8);
+        let order = _mm256_setr_epi8(
+            2, 1, 0, 6, 5, 4, 10, 9, 8, 14, 13, 12, -1, -1, -1, -1, 2, 1, 0, 6, 5, 4, 10, 9, 8, 14,
+            13, 12, -1, -1, -1, -1,
+        );
+
+        let mut done = 0;
+        while encoded.len() - done >= 32 && out.len() - done / 4 * 3 >= 32 {
+            let chars = load256(&encoded[done..]);
+            let high_nibbles = _mm256_and_si256(_mm256_srli_epi32(chars, 4), slash);
+            let low_nibbles = _mm256_and_si256(chars, slash);
+            let high = _mm256_shuffle_epi8(high_classes, high_nibbles);
+            let low = _mm256_shuffle_epi8(low_classes, low_nibbles);
+            if _mm256_testz_si256(low, high) == 0 {
+                break;
+            }
+
+            let is_slash = _mm256_cmpeq_epi8(chars, slash);
+            let roll = _mm256_shuffle_epi8(rolls, _mm256_add_epi8(is_slash, high_nibbles));
+            let sextets = _mm256_add_epi8(chars, roll);
+
+            let pairs = _mm256_maddubs_epi16(sextets, _mm256_set1_epi32(0x0140_0140));
+            let words = _mm256_madd_epi16(pairs, _mm256_set1_epi32(0x0001_1000));
+            let lanes = _mm256_shuffle_epi8(words, order);
+            // move the 12 bytes of the upper lane next to those of the lower one
+            let packed =
+                _mm256_permutevar8x32_epi32(lanes, _mm256_setr_epi32(0, 1, 2, 4, 5, 6, 3, 7));
+            store256(&mut out[done / 4 * 3..], packed);
+            done += 32;
+        }
+        done
+    }
+}
+
+/// Encodes a prefix of `bytes` as uppercase hex into `out`, which holds at
+/// least twice as many bytes. Returns the number of bytes encoded.
+pub(crate) fn hex_encode(bytes: &[u8], out: &mut [u8]) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    match x86::detect() {
+        // SAFETY: the CPU supports the instruction set
+        Some(x86::Level::Avx2) => return unsafe { x86::hex_encode_avx2(bytes, out) },
+        Some(x86::Level::Ssse3) => return unsafe { x86::hex_encode_ssse3(bytes, out) },
+        None => {}
+    }
+    let _ = (bytes, out);
+    0
+}
+
+/// Decodes a prefix of `hex` into `out`. Returns the number of bytes
+/// written, each from two digits.
+pub(crate) fn hex_decode(hex: &[u8], out: &mut [u8]) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    match x86::detect() {
+        // SAFETY: the CPU supports the instruction set
+        Some(x86::Level::Avx2) => return unsafe { x86::hex_decode_avx2(hex, out) },
+        Some(x86::Level::Ssse3) => return unsafe { x86::hex_decode_ssse3(hex, out) },
+        None => {}
+    }
+    let _ = (hex, out);
+    0
+}
+
+/// Encodes a prefix of whole triplets of `bytes` into `out`, with `char_62`
+/// and `char_63` as the last two characters of the alphabet. Returns the
+/// number of bytes encoded.
+pub(crate) fn base64_encode(bytes: &[u8], out: &mut [u8], char_62: u8, char_63: u8) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    match x86::detect() {
+        // SAFETY: the CPU supports the instruction set
+        Some(x86::Level::Avx2) => {
+            let done = unsafe { x86::base64_encode_avx2(bytes, out, char_62, char_63) };
+            let (bytes, out) = (&bytes[done..], &mut out[done / 3 * 4..]);
+            return done + unsafe { x86::base64_encode_ssse3(bytes, out, char_62, char_63) };
+        }
+        Some(x86::Level::Ssse3) => {
+            return unsafe { x86::base64_encode_ssse3(bytes, out, char_62, char_63) };
+        }
+        None => {}
+    }
+    let _ = (bytes, out, char_62, char_63);
+    0
+}
+
+/// Decodes a prefix of whole quartets of standard-alphabet `encoded` into
+/// `out`. Returns the number of characters decoded.
+pub(crate) fn base64_decode(encoded: &[u8], out: &mut [u8]) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    match x86::detect() {
+        // SAFETY: the CPU supports the instruction set
+        Some(x86::Level::Avx2) => {
+            let done = unsafe { x86::base64_decode_avx2(encoded, out) };
+            let (encoded, out) = (&encoded[done..], &mut out[done / 4 * 3..]);
+            return done + unsafe { x86::base64_decode_ssse3(encoded, out) };
+        }
+        Some(x86::Level::Ssse3) => return unsafe { x86::base64_decode_ssse3(encoded, out) },
+        None => {}
+    }
+    let _ = (encoded, out);
+    0
+}
+
+#[cfg(all(test, feature = "simd", target_arch = "x86_64"))]
+mod tests {
+    use super::x86::*;
+    use crate::encoding::{base64, hex};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn kernels_match_tables(bytes in proptest::collection::vec(any::<u8>(), 0..300)) {
+            for level in [Level::Ssse3, Level::Avx2] {
+                if !supports(level) {
+                    continue;
+                }
+
+                let hex = hex::encode(&bytes);
+                let mut out = vec![0; bytes.len() * 2];
+                // SAFETY: the CPU supports the instruction set
+                let done = unsafe {
+                    match level {
+                        Level::Ssse3 => hex_encode_ssse3(&bytes, &mut out),
+                        Level::Avx2 => hex_encode_avx2(&bytes, &mut out),
+                    }
+                };
+                assert_eq!(out[..done * 2], hex.as_bytes()[..done * 2]);
+
+                let lower = hex.to_lowercase();
+                let mut out = vec![0; bytes.len()];
+                let done = unsafe {
+                    match level {
+                        Level::Ssse3 => hex_decode_ssse3(lower.as_bytes(), &mut out),
+                        Level::Avx2 => hex_decode_avx2(lower.as_bytes(), &mut out),
+                    }
+                };
+                assert_eq!(out[..done], bytes[..done]);
+
+                for (engine, char_62, char_63) in [(base64::STANDARD, b'+', b'/'), (base64::URL_SAFE, b'-', b'_')] {
+                    let encoded = engine.encode(&bytes);
+                    let mut out = vec![0; encoded.len()];
+                    let done = unsafe {
+                        match level {
+                            Level::Ssse3 => base64_encode_ssse3(&bytes, &mut out, char_62, char_63),
+                            Level::Avx2 => base64_encode_avx2(&bytes, &mut out, char_62, char_63),
+                        }
+                    };
+                    assert_eq!(done % 3, 0);
+                    assert_eq!(out[..done / 3 * 4], encoded.as_bytes()[..done / 3 * 4]);
+                }
+
+                let encoded = base64::encode(&bytes);
+                let mut out = vec![0; bytes.len() + 32];
+                let done = unsafe {
+                    match level {
+                        Level::Ssse3 => base64_decode_ssse3(encoded.as_bytes(), &mut out),
+                        Level::Avx2 => base64_decode_avx2(encoded.as_bytes(), &mut out),
+                    }
+                };
+                assert_eq!(done % 4, 0);
+                assert_eq!(out[..done / 4 * 3], bytes[..done / 4 * 3]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoding_stops_at_invalid_blocks() {
+        for level in [Level::Ssse3, Level::Avx2] {
+            if !supports(level) {
+                continue;
+            }
+            let mut hex = "00".repeat(64);
+            hex.replace_range(70..71, "g");
+            let mut out = [0; 64];
+            // SAFETY: the CPU supports the instruction set
+            let done = unsafe {
+                match level {
+                    Level::Ssse3 => hex_decode_ssse3(hex.as_bytes(), &mut out),
+                    Level::Avx2 => hex_decode_avx2(hex.as_bytes(), &mut out),
+                }
+            };
+            assert_eq!(done, 32);
+
+            // every byte that is not in the alphabet stops the block
+            for invalid in (0..=u8::MAX)
+                .filter(|&byte| !byte.is_ascii_alphanumeric() && byte != b'+' && byte != b'/')
+            {
+                let mut encoded = [b'A'; 64];
+                encoded[40] = invalid;
+                let mut out = [0; 64];
+                let done = unsafe {
+                    match level {
+                        Level::Ssse3 => base64_decode_ssse3(&encoded, &mut out),
+                        Level::Avx2 => base64_decode_avx2(&encoded, &mut out),
+                    }
+                };
+                assert_eq!(done, 32, "{:?} decoded", invalid as char);
+            }
+        }
+    }
+}
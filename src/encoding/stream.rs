@@ -0,0 +1,54 @@
+//! Helpers shared by the streaming decoders.
+
+use std::io::Read;
+
+/// The character that starts with `first`, for error reports.
+///
+/// Streaming decoders look at one byte at a time, so a multi-byte character
+/// outside the alphabet has to be put back together before it is reported.
+/// Its continuation bytes come from `following`, the rest of the buffer at
+/// hand, and then from `inner`. An invalid or truncated sequence is
+/// reported as U+FFFD.
+pub(crate) fn char_at(first: u8, following: &[u8], inner: &mut impl Read) -> char {
+    let width = match first {
+        0x00..=0x7F => return first as char,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return char::REPLACEMENT_CHARACTER,
+    };
+
+    let mut sequence = vec![first];
+    sequence.extend(following.iter().take(width - 1));
+    let missing = width - sequence.len();
+    if missing > 0 {
+        // the stream is abandoned after the error, so reading ahead is harmless
+        let _ = inner.take(missing as u64).read_to_end(&mut sequence);
+    }
+
+    std::str::from_utf8(&sequence)
+        .ok()
+        .and_then(|decoded| decoded.chars().next())
+        .unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_at() {
+        let mut empty: &[u8] = &[];
+        assert_eq!(char_at(b'x', &[], &mut empty), 'x');
+        assert_eq!(char_at(0xC3, &[0xA9, b'A'], &mut empty), 'é');
+
+        // continuation bytes split between the buffer and the reader
+        let mut rest: &[u8] = &[0xAC, b'A'];
+        assert_eq!(char_at(0xE2, &[0x82], &mut rest), '€');
+        assert_eq!(rest, b"A");
+
+        assert_eq!(char_at(0xE9, b"A", &mut empty), '\u{FFFD}');
+        assert_eq!(char_at(0xC3, &[], &mut empty), '\u{FFFD}');
+        assert_eq!(char_at(0xC3, b"A", &mut empty), '\u{FFFD}');
+    }
+}
@@ -0,0 +1,178 @@
+//! Z85 encoding from ZeroMQ.
+//!
+//! This module implements Z85 as specified in
+//! [ZeroMQ RFC 32](https://rfc.zeromq.org/spec/32/): the base-85 grouping of
+//! Ascii85 with an alphabet that avoids quotes and backslashes, so the output
+//! can be embedded in source code. There is no padding, so the input of the
+//! encoder must be a multiple of 4 bytes.
+
+use crate::encoding::ascii85::{from_digits, to_digits};
+use crate::encoding::codec::Codec;
+use crate::encoding::error::{Encoding, ErrorKind, ParsingDirection, ParsingError};
+use std::sync::LazyLock;
+
+/// Z85 character set: 0-9, a-z, A-Z and 23 punctuation characters
+static Z85_CHARSET: LazyLock<[char; 85]> = LazyLock::new(|| {
+    ('0'..='9')
+        .chain('a'..='z')
+        .chain('A'..='Z')
+        .chain(".-:+=^!/*?&<>()[]{}@%$#".chars())
+        .collect::<Vec<char>>()
+        .try_into()
+        .expect("total count of characters is 85")
+});
+
+/// Encodes a byte slice into a Z85 string.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::z85::encode;
+///
+/// let bytes = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+/// assert_eq!(encode(&bytes).unwrap(), "HelloWorld");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ErrorKind::InvalidLength`] if the input is not a multiple of 4
+/// bytes long.
+pub fn encode(bytes: &[u8]) -> Result<String, ParsingError> {
+    if !bytes.len().is_multiple_of(4) {
+        return Err(ParsingError::new(
+            ParsingDirection::Encoding,
+            Encoding::Z85,
+            ErrorKind::InvalidLength {
+                length: bytes.len(),
+            },
+            String::new(),
+        ));
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .flat_map(|chunk| {
+            to_digits(u32::from_be_bytes(chunk.try_into().expect("4 bytes")))
+                .map(|digit| Z85_CHARSET[digit as usize])
+        })
+        .collect())
+}
+
+/// Decodes a Z85 string into bytes.
+///
+/// # Examples
+///
+/// ```
+/// use cryptopals::encoding::z85::decode;
+///
+/// assert_eq!(decode("HelloWorld").unwrap(), [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ErrorKind::InvalidCharacter`] for a character outside the
+/// alphabet or the last character of a group whose value does not fit in 32
+/// bits, and [`ErrorKind::InvalidLength`] if the input is not a multiple of 5
+/// characters long.
+pub fn decode(encoded: &str) -> Result<Vec<u8>, ParsingError> {
+    let error = |kind| {
+        ParsingError::new(
+            ParsingDirection::Decoding,
+            Encoding::Z85,
+            kind,
+            encoded.to_owned(),
+        )
+    };
+
+    let chars = encoded.char_indices().collect::<Vec<_>>();
+    if !chars.len().is_multiple_of(5) {
+        return Err(error(ErrorKind::InvalidLength {
+            length: chars.len(),
+        }));
+    }
+
+    let mut bytes = Vec::with_capacity(chars.len() / 5 * 4);
+    for group in chars.chunks_exact(5) {
+        let mut digits = [0u8; 5];
+        for (digit, &(offset, char)) in digits.iter_mut().zip(group) {
+            *digit = Z85_CHARSET
+                .iter()
+                .position(|&ch| ch == char)
+                .ok_or_else(|| error(ErrorKind::InvalidCharacter { offset, char }))?
+                as u8;
+        }
+
+        let (offset, char) = group[4];
+        let value = from_digits(&digits)
+            .ok_or_else(|| error(ErrorKind::InvalidCharacter { offset, char }))?;
+        bytes.extend(value.to_be_bytes());
+    }
+    Ok(bytes)
+}
+
+/// Z85 as a [`Codec`].
+#[derive(Debug, Clone, Copy)]
+pub struct Z85;
+
+impl Codec for Z85 {
+    fn encoding(&self) -> Encoding {
+        Encoding::Z85
+    }
+
+    fn is_valid_char(&self, char: char) -> bool {
+        Z85_CHARSET.contains(&char)
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, ParsingError> {
+        encode(bytes)
+    }
+
+    fn decode(&self, encoded: &str) -> Result<Vec<u8>, ParsingError> {
+        decode(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn valid_z85_roundtrip(words in proptest::collection::vec(any::<[u8; 4]>(), 0..50)) {
+            let bytes = words.concat();
+            assert_eq!(decode(&encode(&bytes).unwrap()).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_spec_vector() {
+        let bytes = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+        assert_eq!(encode(&bytes).unwrap(), "HelloWorld");
+        assert_eq!(decode("HelloWorld").unwrap(), bytes);
+
+        assert_eq!(encode(&[0; 4]).unwrap(), "00000");
+        assert_eq!(encode(&[0xFF; 4]).unwrap(), "%nSc0");
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = encode(&[1, 2, 3]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidLength { length: 3 });
+
+        assert_eq!(decode("Hello").unwrap(), [0x86, 0x4F, 0xD2, 0x6F]);
+        assert_eq!(
+            decode("Hell").unwrap_err().kind,
+            ErrorKind::InvalidLength { length: 4 }
+        );
+        assert_eq!(
+            decode("Hel\"o").unwrap_err().kind,
+            ErrorKind::InvalidCharacter {
+                offset: 3,
+                char: '"'
+            }
+        );
+        // one more than u32::MAX
+        assert_eq!(decode("%nSc1").unwrap_err().kind.offset(), Some(4));
+    }
+}
@@ -0,0 +1,100 @@
+//! Crate-wide error type.
+//!
+//! Each module reports its own error type; [`Error`] collects them so that
+//! code chaining several modules can use `?` throughout.
+
+use std::fmt::Display;
+
+use crate::encoding::error::{DerError, ParsingError};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Malformed input in one of the text encodings.
+    Parsing(ParsingError),
+    /// Malformed DER, or DER that is not the expected structure.
+    Der(DerError),
+    /// Reading or writing a stream failed.
+    Io(std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parsing(error) => error.fmt(f),
+            Error::Der(error) => error.fmt(f),
+            Error::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parsing(error) => Some(error),
+            Error::Der(error) => Some(error),
+            Error::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParsingError> for Error {
+    fn from(error: ParsingError) -> Self {
+        Error::Parsing(error)
+    }
+}
+
+impl From<DerError> for Error {
+    fn from(error: DerError) -> Self {
+        Error::Der(error)
+    }
+}
+
+/// Streaming decoders report malformed input as [`std::io::ErrorKind::InvalidData`]
+/// wrapping a [`ParsingError`]; that error is unwrapped again here.
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        if error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<ParsingError>())
+        {
+            let inner = error.into_inner().expect("error has an inner error");
+            return Error::Parsing(*inner.downcast().expect("inner error is a ParsingError"));
+        }
+        Error::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{base64, error::ErrorKind, hex};
+    use std::io::Read;
+
+    fn decode_and_reencode(input: &str) -> Result<String> {
+        let mut bytes = Vec::new();
+        base64::Decoder::new(input.as_bytes(), base64::STANDARD).read_to_end(&mut bytes)?;
+        Ok(hex::encode(&hex::decode(&hex::encode(&bytes))?))
+    }
+
+    #[test]
+    fn test_streamed_parsing_errors_are_unwrapped() {
+        assert_eq!(decode_and_reencode("SGk=").unwrap(), "4869");
+
+        match decode_and_reencode("SG*=") {
+            Err(Error::Parsing(error)) => assert_eq!(
+                error.kind,
+                ErrorKind::InvalidCharacter {
+                    offset: 2,
+                    char: '*'
+                }
+            ),
+            other => panic!("expected a parsing error, got {:?}", other),
+        }
+
+        let io_error = std::io::Error::other("disk on fire");
+        assert!(matches!(Error::from(io_error), Error::Io(_)));
+    }
+}
@@ -2,13 +2,26 @@
 //!
 //! This crate contains solutions and utilities for the [Cryptopals Crypto Challenges](https://cryptopals.com/).
 //!
-//! The codebase is organized into three main modules:
+//! The codebase is organized into five main modules:
 //!
-//! - [`encoding`] - Encoding/decoding utilities (hex, base64)
-//! - [`crypto`] - Cryptographic operations (XOR, etc.)
+//! - [`encoding`] - Encoding/decoding utilities (hex, base32, base58, base64, ascii85, z85)
+//! - [`crypto`] - Cryptographic operations (XOR, AES, etc.)
 //! - [`analysis`] - Cryptanalysis tools (frequency analysis, etc.)
+//! - [`attacks`] - Attacks on local servers (CBC-MAC forgery, etc.)
+//! - [`compression`] - Compression (DEFLATE)
+//!
+//! [`util`] holds the pseudorandom generator the attacks and examples share.
+//!
+//! [`error::Error`] gathers the errors of the encoding modules: malformed
+//! text encodings, malformed DER and failed I/O. The `crypto`, `analysis` and
+//! `attacks` modules have no error types of their own yet; they report rejected input
+//! with `Option`.
 pub mod analysis;
+pub mod attacks;
+pub mod compression;
 pub mod crypto;
 pub mod encoding;
+pub mod error;
+pub mod util;
 
 pub use encoding::hex_to_base64;